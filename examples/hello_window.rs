@@ -4,11 +4,15 @@
 
 // We use block_on as Renderer creation requires async, but our app isn't configured to use async.
 use futures::executor::block_on;
-use rusty_gui::{components::{Button, Label}, gui::{GUI}, layout::Layout, rendering::{Renderer, ScreenMode, Transform, WindowBuilder}};
-use winit::event::{ElementState, Event};
+use rusty_gui::{AppState, Color, components::{Button, Label}, event::{GuiEvent, MouseButton}, gui::{GUI}, layout::Layout, rendering::{RendererBuilder, ScreenMode, Transform, WindowBuilder}};
+
+/// The app's own data. Callbacks can change it directly, through the `AppState` they're given
+struct AppData{
+    clicks: u32,
+}
 
 /// A simple callback handler. Shows how it works, so you can extend it
-fn event_callback_handler(_event: &winit::event::Event<()>, _window: &mut winit::window::Window, _renderer: &mut rusty_gui::rendering::Renderer){
+fn event_callback_handler(_event: &GuiEvent, _window: &mut winit::window::Window, _renderer: &mut rusty_gui::rendering::Renderer){
     // Handle events
     
 }
@@ -38,15 +42,16 @@ fn _from_scratch(){
 
     window.set_event_handler(Box::new(event_callback_handler));
 
-    let renderer = block_on(Renderer::new(&window.window));
+    // The 128px text below doesn't fit in the default glyph cache, so start with a bigger one
+    let renderer = block_on(RendererBuilder::new().set_glyph_cache_size((1024, 1024)).build(&window.window));
 
-    let mut gui = GUI::new(window, renderer, wgpu::Color::WHITE);
+    let mut gui = GUI::new(window, renderer, Color::WHITE);
 
     let mut layout = Layout::new();
 
     let label = Label::new("Hello, world!", 128.0, [100.0, 100.0]);
 
-    layout.add_text_component(Box::new(label));
+    layout.add_component(Box::new(label));
 
     gui.set_render_layout(layout);
 
@@ -54,33 +59,22 @@ fn _from_scratch(){
 }
 
 // Simple button function that disables a button if the mouse is hovering and clicking over it
-fn test_button_func(event: &winit::event::Event<()>, window: &winit::window::Window, cursor_in_bounds: &bool, _button_enabled: &mut bool){
+fn test_button_func(event: &GuiEvent, _window: &winit::window::Window, cursor_in_bounds: &bool, _button_enabled: &mut bool, state: &mut AppState){
     if cursor_in_bounds == &true{
         match event{
-            Event::WindowEvent{
-                ref event,
-                window_id
-            } if window_id == &window.id() => {
-                match event{
-                    winit::event::WindowEvent::MouseInput{
-                        button: winit::event::MouseButton::Left,
-                        state, 
-                        ..
-                    } => {
-                        if state == &ElementState::Pressed{
-                            println!("Button pressed!");
-                            *_button_enabled = false;
-                        }else{
-                            println!("Button released!");
-                            *_button_enabled = true;
-                        }
-                    }
-                    _ => {}
+            GuiEvent::MouseDown{ button: MouseButton::Left, .. } => {
+                println!("Button pressed!");
+                *_button_enabled = false;
+            }
+            GuiEvent::MouseUp{ button: MouseButton::Left, .. } => {
+                if let Some(data) = state.get_mut::<AppData>(){
+                    data.clicks += 1;
+                    println!("Button released! ({} clicks)", data.clicks);
                 }
+                *_button_enabled = true;
             }
             _ => {}
         }
-        
     }
 }
 
@@ -89,6 +83,7 @@ fn _from_default(){
     let mut gui = GUI::default(); // Create the gui with default values (which inits the window and renderer)
 
     gui.set_event_handler(Box::new(event_callback_handler)); // Set the event handler to our custom event handler
+    gui.set_app_state(AppData{ clicks: 0 }); // Our own data, which the callbacks can change
 
     let mut layout = Layout::new(); // We initialize a new layout
 
@@ -99,9 +94,9 @@ fn _from_default(){
 
     // Add the components to the layout - the order only matters if you want the components to render in a specific way
     // Text will ALWAYS be rendered on top of everything else, that is something to fix
-    layout.add_text_component(Box::new(label));
-    layout.add_text_component(Box::new(label_1));
-    layout.add_text_component(Box::new(label_2));
+    layout.add_component(Box::new(label));
+    layout.add_component(Box::new(label_1));
+    layout.add_component(Box::new(label_2));
 
 
 
@@ -109,9 +104,9 @@ fn _from_default(){
     let button = Button::new(
         // The transformation of the button
 Transform::new(
-        cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0), 
+        cgmath::Vector3::<f32>::new(400.0, 300.0, 0.0), 
         cgmath::Quaternion::<f32>::new(0.0, 0.0, 0.0, 0.0), 
-        cgmath::Vector3::<f32>::new(0.2, 0.2, 0.2), gui.borrow_render_device()),
+        cgmath::Vector3::<f32>::new(160.0, 120.0, 1.0), gui.borrow_renderer().transform_pool()),
 
         // Function that should be called when the button is pressed
 Some(Box::new(test_button_func)),
@@ -122,13 +117,11 @@ Some(Box::new(test_button_func)),
         // Define the text
     Some("Hello, Button!"),
     
-32.0,
-        // Borrow the layout
-        &mut layout
+32.0
     );
 
     // Add the button to the layout
-    layout.add_event_component(Box::new(button));
+    layout.add_component(Box::new(button));
 
 
     // Set the renderer render layout to our layout - this will consume our layout, so to access it,
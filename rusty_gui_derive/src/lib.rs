@@ -0,0 +1,301 @@
+//! This crate provides the macros for writing `rusty_gui` components, so a custom component doesn't have to repeat the
+//! same impl blocks as every other one. Use them through `rusty_gui` (with its `derive` feature), not directly.
+//!
+//! * `#[derive(GuiComponent)]` writes the whole `Component` impl - `as_any` and `as_any_mut`, the methods marked fields
+//!   stand in for, and any methods forwarded to the struct's own (inherent) methods.
+//! * `#[gui_component]` goes on a hand-written `impl Component for ...` block, and adds `as_any` and `as_any_mut` to it.
+//!
+//! ```ignore
+//! use rusty_gui::{components::Component, GuiComponent};
+//!
+//! #[derive(GuiComponent)]
+//! #[component(update)]             // forward `Component::update` to `Counter::update`
+//! struct Counter{
+//!     #[component(pos)]            // `get_pos`
+//!     pos: [f32; 2],
+//!     #[component(visible)]        // `is_visible` and `set_visible`
+//!     visible: bool,
+//!     #[component(text)]           // `get_text`
+//!     text: String,
+//!     ticks: u32,
+//! }
+//!
+//! impl Counter{
+//!     fn update(&mut self, _delta_time: std::time::Duration){
+//!         self.ticks += 1;
+//!         self.text = self.ticks.to_string();
+//!     }
+//! }
+//! ```
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields, ImplItem, ItemImpl, Member};
+
+/// Derive `Component` for a struct (or enum). See the crate docs for the attributes it takes.
+///
+/// On the struct, `#[component(...)]` lists the `Component` methods to forward to the struct's own methods of the same
+/// name and signature: `render`, `queue_text`, `handle_event`, `update`, `prerender`, `on_attach`, `on_detach`,
+/// `apply_theme`, `apply_locale`, `save_state`, `restore_state`, `bounds`, `access_info` and `access_action`.
+///
+/// On a field, `#[component(...)]` makes it stand in for part of the trait:
+/// * `pos` - a `[f32; 2]` returned by `get_pos`
+/// * `visible` - a `bool` for `is_visible` and `set_visible`
+/// * `enabled` - a `bool` for `is_enabled` and `set_enabled`
+/// * `focus` - a `bool` for `has_focus` and `set_focus`, which also makes the component focusable
+/// * `text` - a `String` returned by `get_text`
+#[proc_macro_derive(GuiComponent, attributes(component))]
+pub fn derive_gui_component(input: TokenStream) -> TokenStream{
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_derive(input){
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Add `as_any` and `as_any_mut` to an `impl Component for ...` block, so only the methods the component uses are written
+/// out. Either one that's already there is left alone.
+#[proc_macro_attribute]
+pub fn gui_component(args: TokenStream, input: TokenStream) -> TokenStream{
+    if !args.is_empty(){
+        let args = TokenStream2::from(args);
+        return syn::Error::new(args.span(), "`gui_component` doesn't take any arguments").to_compile_error().into();
+    }
+    let mut item = parse_macro_input!(input as ItemImpl);
+    if item.trait_.is_none(){
+        return syn::Error::new(item.self_ty.span(), "`gui_component` goes on an `impl Component for ...` block").to_compile_error().into();
+    }
+
+    let has = |name: &str| item.items.iter().any(|item| matches!(item, ImplItem::Fn(f) if f.sig.ident == name));
+    let (has_as_any, has_as_any_mut) = (has("as_any"), has("as_any_mut"));
+    if !has_as_any{
+        item.items.push(parse_quote!{
+            fn as_any(&self) -> &dyn ::std::any::Any{
+                self
+            }
+        });
+    }
+    if !has_as_any_mut{
+        item.items.push(parse_quote!{
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any{
+                self
+            }
+        });
+    }
+    quote!(#item).into()
+}
+
+// The fields standing in for parts of the trait
+#[derive(Default)]
+struct Roles{
+    pos: Option<Member>,
+    visible: Option<Member>,
+    enabled: Option<Member>,
+    focus: Option<Member>,
+    text: Option<Member>,
+}
+
+fn expand_derive(mut input: DeriveInput) -> syn::Result<TokenStream2>{
+    if let Data::Union(data) = &input.data{
+        return Err(syn::Error::new(data.union_token.span, "`GuiComponent` can't be derived for unions"));
+    }
+
+    let mut forwarded = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("component")){
+        attr.parse_nested_meta(|meta| {
+            let ident = meta.path.get_ident().ok_or_else(|| meta.error("expected a `Component` method name"))?;
+            let method = forward(ident).ok_or_else(|| meta.error(format!("`{}` can't be forwarded", ident)))?;
+            forwarded.push(method);
+            Ok(())
+        })?;
+    }
+
+    let mut roles = Roles::default();
+    if let Data::Struct(data) = &input.data{
+        let fields = match &data.fields{
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+            Fields::Unit => Vec::new(),
+        };
+        for (i, field) in fields.into_iter().enumerate(){
+            let member = match &field.ident{
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("component")){
+                attr.parse_nested_meta(|meta| {
+                    let slot = if meta.path.is_ident("pos"){
+                        &mut roles.pos
+                    }else if meta.path.is_ident("visible"){
+                        &mut roles.visible
+                    }else if meta.path.is_ident("enabled"){
+                        &mut roles.enabled
+                    }else if meta.path.is_ident("focus"){
+                        &mut roles.focus
+                    }else if meta.path.is_ident("text"){
+                        &mut roles.text
+                    }else{
+                        return Err(meta.error("expected `pos`, `visible`, `enabled`, `focus` or `text`"));
+                    };
+                    if slot.is_some(){
+                        return Err(meta.error("another field already has this role"));
+                    }
+                    *slot = Some(member.clone());
+                    Ok(())
+                })?;
+            }
+        }
+    }
+
+    let mut methods = forwarded;
+    if let Some(field) = roles.pos{
+        methods.push(quote!{
+            fn get_pos(&self) -> [f32; 2]{
+                self.#field
+            }
+        });
+    }
+    if let Some(field) = roles.visible{
+        methods.push(quote!{
+            fn is_visible(&self) -> bool{
+                self.#field
+            }
+            fn set_visible(&mut self, visible: bool){
+                self.#field = visible;
+            }
+        });
+    }
+    if let Some(field) = roles.enabled{
+        methods.push(quote!{
+            fn is_enabled(&self) -> bool{
+                self.#field
+            }
+            fn set_enabled(&mut self, enabled: bool){
+                self.#field = enabled;
+            }
+        });
+    }
+    if let Some(field) = roles.focus{
+        methods.push(quote!{
+            fn is_focusable(&self) -> bool{
+                true
+            }
+            fn set_focus(&mut self, focused: bool){
+                self.#field = focused;
+            }
+            fn has_focus(&self) -> bool{
+                self.#field
+            }
+        });
+    }
+    if let Some(field) = roles.text{
+        methods.push(quote!{
+            fn get_text(&self) -> ::std::option::Option<&str>{
+                ::std::option::Option::Some(&self.#field)
+            }
+        });
+    }
+
+    // `as_any` needs `Self: 'static`, so every type parameter has to be too
+    let params: Vec<_> = input.generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in params{
+        where_clause.predicates.push(parse_quote!(#param: 'static));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote!{
+        impl #impl_generics ::rusty_gui::components::Component for #name #type_generics #where_clause{
+            fn as_any(&self) -> &dyn ::std::any::Any{
+                self
+            }
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any{
+                self
+            }
+            #(#methods)*
+        }
+    })
+}
+
+// A trait method that calls the type's own method of the same name (inherent methods are found before trait ones)
+fn forward(method: &syn::Ident) -> Option<TokenStream2>{
+    let private = quote!(::rusty_gui::__private);
+    let tokens = match method.to_string().as_str(){
+        "render" => quote!{
+            fn render<'a, 'b>(&'a self, render_pass: &'b mut #private::wgpu::RenderPass<'a>) where 'a: 'b{
+                Self::render(self, render_pass)
+            }
+        },
+        "queue_text" => quote!{
+            fn queue_text<'a, 'b>(&'a self, queue: &'b mut ::rusty_gui::rendering::TextQueue) where 'a: 'b{
+                Self::queue_text(self, queue)
+            }
+        },
+        "handle_event" => quote!{
+            fn handle_event(&mut self, event: &::rusty_gui::event::GuiEvent, window: &#private::winit::window::Window, state: &mut ::rusty_gui::AppState) -> ::rusty_gui::event::EventResult{
+                Self::handle_event(self, event, window, state)
+            }
+        },
+        "update" => quote!{
+            fn update(&mut self, delta_time: ::std::time::Duration){
+                Self::update(self, delta_time)
+            }
+        },
+        "prerender" => quote!{
+            fn prerender(&mut self, context: &mut ::rusty_gui::rendering::RenderContext){
+                Self::prerender(self, context)
+            }
+        },
+        "on_attach" => quote!{
+            fn on_attach(&mut self, renderer: &::rusty_gui::rendering::Renderer){
+                Self::on_attach(self, renderer)
+            }
+        },
+        "on_detach" => quote!{
+            fn on_detach(&mut self){
+                Self::on_detach(self)
+            }
+        },
+        "apply_theme" => quote!{
+            fn apply_theme(&mut self, theme: &::rusty_gui::Theme) -> bool{
+                Self::apply_theme(self, theme)
+            }
+        },
+        "apply_locale" => quote!{
+            fn apply_locale(&mut self, locale: &::rusty_gui::Locale) -> bool{
+                Self::apply_locale(self, locale)
+            }
+        },
+        "save_state" => quote!{
+            fn save_state(&self) -> ::std::option::Option<::rusty_gui::persistence::ComponentState>{
+                Self::save_state(self)
+            }
+        },
+        "restore_state" => quote!{
+            fn restore_state(&mut self, state: &::rusty_gui::persistence::ComponentState){
+                Self::restore_state(self, state)
+            }
+        },
+        "bounds" => quote!{
+            fn bounds(&self, screen: (u32, u32)) -> ::std::option::Option<[f32; 4]>{
+                Self::bounds(self, screen)
+            }
+        },
+        "access_info" => quote!{
+            fn access_info(&self) -> ::std::option::Option<::rusty_gui::accessibility::AccessInfo>{
+                Self::access_info(self)
+            }
+        },
+        "access_action" => quote!{
+            fn access_action(&mut self, action: &::rusty_gui::accessibility::AccessAction, window: &#private::winit::window::Window, state: &mut ::rusty_gui::AppState) -> bool{
+                Self::access_action(self, action, window, state)
+            }
+        },
+        _ => return None,
+    };
+    Some(tokens)
+}
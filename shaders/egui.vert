@@ -0,0 +1,28 @@
+#version 450
+
+layout(location=0) in vec2 position;
+layout(location=1) in vec2 tex_coords;
+layout(location=2) in vec4 color;
+
+layout(location=0) out vec2 v_tex_coords;
+layout(location=1) out vec4 v_color;
+
+layout(set=0, binding=0) 
+uniform Screen {
+    mat4 proj;
+};
+
+// egui's colors are sRGB, but we blend in linear space (the target converts back when it's written)
+vec3 linear_from_srgb(vec3 srgb) {
+    bvec3 cutoff = lessThan(srgb, vec3(0.04045));
+    vec3 lower = srgb / vec3(12.92);
+    vec3 higher = pow((srgb + vec3(0.055)) / vec3(1.055), vec3(2.4));
+    return mix(higher, lower, cutoff);
+}
+
+void main() {
+    // The projection maps egui's points to pixels in the area, and then to clip space
+    gl_Position = proj * vec4(position, 0.0, 1.0);
+    v_tex_coords = tex_coords;
+    v_color = vec4(linear_from_srgb(color.rgb), color.a);
+}
@@ -3,6 +3,11 @@ layout (location = 0) in vec2 v_tex_coords;
 
 layout(location = 0) out vec4 f_color;
 
+layout(set=2, binding=0) 
+uniform Tint {
+    vec4 tint;
+};
+
 void main() {
-    f_color = vec4(1,0,0,1);
+    f_color = tint;
 }
\ No newline at end of file
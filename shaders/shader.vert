@@ -16,6 +16,7 @@ uniform Transform {
 };
 
 void main() {
-    gl_Position = transform * vec4(position, 1.0);
+    // The transform places the quad in pixels from the top left, and the camera maps pixels to clip space
+    gl_Position = proj * transform * vec4(position, 1.0);
     v_tex_coords = tex_coords;
 }
\ No newline at end of file
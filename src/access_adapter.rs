@@ -0,0 +1,351 @@
+//! This module hands the accessibility tree to the OS's screen readers through AccessKit (enabled with the `accesskit`
+//! feature). AccessKit talks to AT-SPI on Linux and the BSDs, UI Automation on Windows and NSAccessibility on macOS.
+//!
+//! Whenever the layout's accessibility tree (see `Layout::accessibility_tree`) changes, the main loop turns it into an
+//! AccessKit `TreeUpdate`. The actions screen readers ask for (focus, click, set value) come back on another thread, and
+//! are carried out on the main thread with `Layout::access_action`, so they go through the layout like real input.
+//! Enable it with `GUI::enable_accessibility`.
+
+use std::{collections::HashMap, sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver, Sender}}};
+
+use accesskit::{Action, ActionData, ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Node, NodeId, Rect, Tree, TreeUpdate};
+use winit::window::Window;
+
+use crate::{accessibility::{AccessAction, AccessNode, Role}, layout::Layout, sender::EventSender, state::AppState};
+
+/// The ID of the node standing for the window, which every component is under
+pub const ROOT_ID: NodeId = NodeId(0);
+
+/// # NodeIds
+///
+/// The AccessKit node IDs given to components, by their path in the layout (see `AccessNode::path`). A component keeps its ID
+/// for as long as it stays in the tree, so screen readers can follow it from one update to the next.
+#[derive(Debug)]
+pub struct NodeIds{
+    ids: HashMap<Vec<usize>, NodeId>,
+    paths: HashMap<NodeId, Vec<usize>>,
+    next: u64,
+}
+
+impl Default for NodeIds{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
+impl NodeIds{
+    pub fn new() -> Self{
+        Self{
+            ids: HashMap::new(),
+            paths: HashMap::new(),
+            // 0 is the root
+            next: 1,
+        }
+    }
+
+    /// The node ID of the component at `path`, giving it a new one if it doesn't have one yet
+    pub fn id(&mut self, path: &[usize]) -> NodeId{
+        if let Some(id) = self.ids.get(path){
+            return *id;
+        }
+        let id = NodeId(self.next);
+        self.next += 1;
+        self.ids.insert(path.to_vec(), id);
+        self.paths.insert(id, path.to_vec());
+        id
+    }
+
+    /// The node ID of the component at `path`, if it has one
+    pub fn get(&self, path: &[usize]) -> Option<NodeId>{
+        self.ids.get(path).copied()
+    }
+
+    /// The path of the component with the node ID `id`, if it's in the tree
+    pub fn path(&self, id: NodeId) -> Option<&[usize]>{
+        self.paths.get(&id).map(Vec::as_slice)
+    }
+
+    // Forget the components that aren't in `nodes` any more, so a new component in the same place gets a new ID
+    fn retain(&mut self, nodes: &[(NodeId, Node)]){
+        let kept = nodes.iter().map(|(id, _)| *id).collect::<std::collections::HashSet<_>>();
+        self.ids.retain(|_, id| kept.contains(id));
+        self.paths.retain(|id, _| kept.contains(id));
+    }
+}
+
+/// Turn an accessibility tree (see `Layout::accessibility_tree`) into a complete AccessKit tree, with the components under a
+/// window node (`ROOT_ID`). Components are given node IDs from `ids`.
+pub fn tree_update(nodes: &[AccessNode], ids: &mut NodeIds) -> TreeUpdate{
+    let mut built = Vec::new();
+    let mut focus = ROOT_ID;
+    let children = add_nodes(nodes, ids, &mut built, &mut focus);
+
+    let mut root = Node::new(accesskit::Role::Window);
+    root.set_children(children);
+    built.push((ROOT_ID, root));
+    ids.retain(&built);
+
+    let mut tree = Tree::new(ROOT_ID);
+    tree.toolkit_name = Some("rusty_gui".to_string());
+    tree.toolkit_version = Some(env!("CARGO_PKG_VERSION").to_string());
+    TreeUpdate{
+        nodes: built,
+        tree: Some(tree),
+        focus,
+    }
+}
+
+// Add the AccessKit nodes for `nodes` and everything under them to `built`, returning their IDs. The focused one is stored in `focus`.
+fn add_nodes(nodes: &[AccessNode], ids: &mut NodeIds, built: &mut Vec<(NodeId, Node)>, focus: &mut NodeId) -> Vec<NodeId>{
+    let mut added = Vec::with_capacity(nodes.len());
+    for node in nodes{
+        let id = ids.id(&node.path);
+        let mut access_node = Node::new(role(node.role));
+        if let Some(name) = &node.name{
+            access_node.set_label(name.as_str());
+        }
+        if let Some(value) = &node.value{
+            access_node.set_value(value.as_str());
+        }
+        if let Some(description) = &node.description{
+            access_node.set_description(description.as_str());
+        }
+        if let Some([left, top, width, height]) = node.bounds{
+            access_node.set_bounds(Rect::new(left as f64, top as f64, (left + width) as f64, (top + height) as f64));
+        }
+
+        // Disabled components can't be acted on
+        if !node.enabled{
+            access_node.set_disabled();
+        }else{
+            if node.focusable{
+                access_node.add_action(Action::Focus);
+            }
+            match node.role{
+                Role::Button => access_node.add_action(Action::Click),
+                Role::TextInput => access_node.add_action(Action::SetValue),
+                _ => {}
+            }
+        }
+        if node.focused{
+            *focus = id;
+        }
+
+        let children = add_nodes(&node.children, ids, built, focus);
+        access_node.set_children(children);
+        built.push((id, access_node));
+        added.push(id);
+    }
+    added
+}
+
+// The AccessKit role for a component's role
+fn role(role: Role) -> accesskit::Role{
+    match role{
+        Role::Button => accesskit::Role::Button,
+        Role::Label => accesskit::Role::Label,
+        Role::TextInput => accesskit::Role::TextInput,
+        Role::Group => accesskit::Role::Group,
+        Role::Unknown => accesskit::Role::Unknown,
+    }
+}
+
+/// The component (by its path in the layout) and action an AccessKit action request is for. Returns `None` if the request
+/// isn't for a component in `ids`, or asks for something the layout can't do.
+pub fn access_action(request: &ActionRequest, ids: &NodeIds) -> Option<(Vec<usize>, AccessAction)>{
+    let path = ids.path(request.target)?.to_vec();
+    let action = match (request.action, &request.data){
+        (Action::Focus, _) => AccessAction::Focus,
+        (Action::Click, _) => AccessAction::Click,
+        (Action::SetValue, Some(ActionData::Value(value))) => AccessAction::SetValue(value.to_string()),
+        _ => return None,
+    };
+    Some((path, action))
+}
+
+// Wake the main loop, if it's running, so it sees what a handler did
+fn wake(sender: &Mutex<Option<EventSender>>){
+    if let Some(sender) = sender.lock().unwrap().as_ref(){
+        let _ = sender.wake();
+    }
+}
+
+// Passes the actions screen readers ask for to the main loop
+struct Actions{
+    requests: Sender<ActionRequest>,
+    sender: Arc<Mutex<Option<EventSender>>>,
+}
+
+impl ActionHandler for Actions{
+    fn do_action(&mut self, request: ActionRequest){
+        let _ = self.requests.send(request);
+        wake(&self.sender);
+    }
+}
+
+// Asks the main loop for the tree when a screen reader starts listening. The layout is only on the main thread, so the
+// tree is sent with the next update instead of here.
+struct Activation{
+    activated: Arc<AtomicBool>,
+    sender: Arc<Mutex<Option<EventSender>>>,
+}
+
+impl ActivationHandler for Activation{
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate>{
+        self.activated.store(true, Ordering::SeqCst);
+        wake(&self.sender);
+        None
+    }
+}
+
+// Nothing needs to be done when screen readers stop listening - updates are dropped until they start again
+struct Deactivation;
+
+impl DeactivationHandler for Deactivation{
+    fn deactivate_accessibility(&mut self){}
+}
+
+/// # AccessAdapter
+///
+/// Connects the main window to the OS's accessibility API, sending it the layout's accessibility tree and carrying out
+/// the actions screen readers ask for. The main loop drives it.
+pub struct AccessAdapter{
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    adapter: accesskit_unix::Adapter,
+    #[cfg(target_os = "windows")]
+    adapter: accesskit_windows::SubclassingAdapter,
+    #[cfg(target_os = "macos")]
+    adapter: accesskit_macos::SubclassingAdapter,
+    requests: Receiver<ActionRequest>,
+    // Set by the activation handler when the whole tree needs sending
+    activated: Arc<AtomicBool>,
+    sender: Arc<Mutex<Option<EventSender>>>,
+    ids: NodeIds,
+    // Set when the layout changed since the last update, as its own dirty flag is cleared when it's drawn
+    stale: bool,
+    // The tree last sent, so it's only sent again when it changes
+    last: Option<Vec<AccessNode>>,
+}
+
+impl AccessAdapter{
+    /// Connect `window` to the OS's accessibility API.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on platforms AccessKit doesn't support, and on Windows if the window has already been shown.
+    #[allow(unused_variables)]
+    pub fn new(window: &Window) -> Result<Self, &'static str>{
+        let (requests_sender, requests) = mpsc::channel();
+        let activated = Arc::new(AtomicBool::new(false));
+        let sender = Arc::new(Mutex::new(None));
+        let actions = Actions{ requests: requests_sender, sender: sender.clone() };
+        let activation = Activation{ activated: activated.clone(), sender: sender.clone() };
+
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        let adapter = accesskit_unix::Adapter::new(activation, actions, Deactivation);
+
+        #[cfg(target_os = "windows")]
+        let adapter = {
+            use winit::platform::windows::WindowExtWindows;
+            // AccessKit has to be hooked up before the window is first shown (it panics otherwise), so check first
+            if window.is_visible() != Some(false){
+                return Err("Error, accessibility has to be enabled before the window is shown!");
+            }
+            accesskit_windows::SubclassingAdapter::new(accesskit_windows::HWND(window.hwnd()), activation, actions)
+        };
+
+        #[cfg(target_os = "macos")]
+        let adapter = {
+            use winit::platform::macos::WindowExtMacOS;
+            // The view is the window's own, so it's valid for as long as the window is
+            unsafe{ accesskit_macos::SubclassingAdapter::new(window.ns_view(), activation, actions) }
+        };
+
+        #[cfg(not(any(all(unix, not(any(target_os = "ios", target_os = "android"))), target_os = "windows")))]
+        return Err("Error, accessibility isn't supported on this platform!");
+        #[cfg(any(all(unix, not(any(target_os = "ios", target_os = "android"))), target_os = "windows"))]
+        Ok(Self{
+            adapter,
+            requests,
+            activated,
+            sender,
+            ids: NodeIds::new(),
+            stale: true,
+            last: None,
+        })
+    }
+
+    /// Let the adapter wake the main loop when a screen reader asks for something
+    pub fn set_event_sender(&self, sender: EventSender){
+        *self.sender.lock().unwrap() = Some(sender);
+    }
+
+    /// Note that the layout changed, so the next `update` builds the tree again. The layout's dirty flag is cleared when
+    /// it's drawn, so the main loop calls this before drawing a dirty layout.
+    pub fn mark_stale(&mut self){
+        self.stale = true;
+    }
+
+    /// Send the accessibility tree of `layout` (on a window of `screen` pixels) to the OS. The tree is only built when the
+    /// layout is dirty or was marked stale (see `mark_stale`), and only sent if it changed - unless a screen reader has just
+    /// started listening, which always gets the whole tree.
+    #[allow(unused_variables)]
+    pub fn update(&mut self, layout: &Layout, screen: (u32, u32), window: &Window){
+        let activated = self.activated.swap(false, Ordering::SeqCst);
+        if !activated && !std::mem::take(&mut self.stale) && !layout.is_dirty(){
+            return;
+        }
+        let nodes = layout.accessibility_tree(screen);
+        if !activated && self.last.as_ref() == Some(&nodes){
+            return;
+        }
+
+        let ids = &mut self.ids;
+        let update = || tree_update(&nodes, ids);
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        {
+            // AT-SPI places the tree on the screen itself, so it needs to know where the window is
+            if let (Ok(outer), Ok(inner)) = (window.outer_position(), window.inner_position()){
+                let (outer_size, inner_size) = (window.outer_size(), window.inner_size());
+                let rect = |x: i32, y: i32, width: u32, height: u32| Rect::new(x as f64, y as f64, x as f64 + width as f64, y as f64 + height as f64);
+                self.adapter.set_root_window_bounds(
+                    rect(outer.x, outer.y, outer_size.width, outer_size.height),
+                    rect(inner.x, inner.y, inner_size.width, inner_size.height),
+                );
+            }
+            self.adapter.update_if_active(update);
+        }
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        if let Some(events) = self.adapter.update_if_active(update){
+            events.raise();
+        }
+        self.last = Some(nodes);
+    }
+
+    /// Tell the OS whether the window has keyboard focus, so screen readers know whether to follow the focused component
+    #[allow(unused_variables)]
+    pub fn set_window_focused(&mut self, focused: bool){
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        self.adapter.update_window_focus_state(focused);
+        // On Windows, the adapter follows the window's focus messages itself
+        #[cfg(target_os = "macos")]
+        if let Some(events) = self.adapter.update_view_focus_state(focused){
+            events.raise();
+        }
+    }
+
+    /// Carry out the actions screen readers have asked for since the last call on `layout` (see `Layout::access_action`).
+    /// Returns true if any of them were carried out.
+    pub fn process(&mut self, layout: &mut Layout, window: &Window, state: &mut AppState) -> bool{
+        let mut done = false;
+        while let Ok(request) = self.requests.try_recv(){
+            if let Some((path, action)) = access_action(&request, &self.ids){
+                match layout.access_action(&path, &action, window, state){
+                    Ok(v) => done |= v,
+                    Err(e) => tracing::debug!(error = e, ?action, "Failed to carry out an accessibility action"),
+                }
+            }
+        }
+        done
+    }
+}
@@ -0,0 +1,141 @@
+//! This module describes the components in a layout for assistive technology, like screen readers. Components say what
+//! they are (their role), what they're called and what value they hold (see `Component::access_info`), and the layout
+//! puts that together with what it knows (enabled, focused, bounds) into a tree of `AccessNode`s.
+//!
+//! Assistive technology can also act on components - focus them, click them, or set their value - through
+//! `Layout::access_action`. Focus goes through the layout's own focus system, so it behaves exactly like Tab does.
+//!
+//! The tree is plain data, so it can be handed to a platform accessibility API by an adapter - with the `accesskit` feature,
+//! `GUI::enable_accessibility` hands it to the OS through AccessKit (see the `access_adapter` module).
+
+use crate::layout::Layout;
+
+/// What kind of thing a component is, for assistive technology
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role{
+    /// A component that can be clicked to do something
+    Button,
+    /// Text that can be read, but not edited
+    Label,
+    /// A box text can be typed into
+    TextInput,
+    /// A component grouping other components (eg, a `Panel`)
+    Group,
+    /// Anything else
+    Unknown,
+}
+
+/// # AccessInfo
+///
+/// How a component describes itself to assistive technology (see `Component::access_info`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessInfo{
+    pub role: Role,
+    /// What the component is called (eg, a button's label). Screen readers read this out.
+    pub name: Option<String>,
+    /// The value the component holds (eg, the text in a text input)
+    pub value: Option<String>,
+    /// More about the component, read after its name (eg, a hint for what to type)
+    pub description: Option<String>,
+}
+
+impl AccessInfo{
+    /// Describe a component with `role`, with no name or value
+    pub fn new(role: Role) -> Self{
+        Self{
+            role,
+            name: None,
+            value: None,
+            description: None,
+        }
+    }
+
+    /// Describe a component with `role`, called `name`
+    pub fn named<S: Into<String>>(role: Role, name: S) -> Self{
+        Self{
+            role,
+            name: Some(name.into()),
+            value: None,
+            description: None,
+        }
+    }
+}
+
+/// Something assistive technology asks a component to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessAction{
+    /// Give the component keyboard focus. This is handled by the layout, not the component.
+    Focus,
+    /// Click the component (eg, press a button)
+    Click,
+    /// Replace the component's value (eg, the text in a text input)
+    SetValue(String),
+}
+
+/// # AccessNode
+///
+/// A component in the accessibility tree (see `Layout::accessibility_tree`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode{
+    /// The IDs leading to the component - its ID in the layout, then its ID in that component's child layout, and so on.
+    /// Pass this to `Layout::access_action` to act on it.
+    pub path: Vec<usize>,
+    pub role: Role,
+    pub name: Option<String>,
+    pub value: Option<String>,
+    pub description: Option<String>,
+    /// The area the component covers, as [left, top, width, height] in pixels from the top left of the window
+    pub bounds: Option<[f32; 4]>,
+    pub enabled: bool,
+    pub focusable: bool,
+    pub focused: bool,
+    /// The components inside this one (see `Component::children`)
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode{
+    /// Find the node at `path` among this node and its descendants
+    pub fn find(&self, path: &[usize]) -> Option<&AccessNode>{
+        if self.path == path{
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(path))
+    }
+}
+
+/// Build the accessibility tree for `layout`, on a window of `screen` pixels (see `Layout::accessibility_tree`)
+pub(crate) fn build_tree(layout: &Layout, screen: (u32, u32), parent: &[usize]) -> Vec<AccessNode>{
+    let mut nodes = Vec::new();
+    // Components are listed in reading order, which is the order Tab moves focus in
+    for id in layout.reading_order(){
+        let comp = &layout.components[id];
+        if !comp.is_visible(){
+            continue;
+        }
+        let mut path = parent.to_vec();
+        path.push(id);
+        let children = match comp.children(){
+            Some(children) => build_tree(children, screen, &path),
+            None => Vec::new(),
+        };
+
+        match comp.access_info(){
+            Some(info) => nodes.push(AccessNode{
+                path,
+                role: info.role,
+                // Names and descriptions given to the layout replace the component's own
+                name: layout.accessible_name(id).map(str::to_string).or(info.name),
+                value: info.value,
+                description: layout.description(id).map(str::to_string).or(info.description),
+                bounds: comp.bounds(screen),
+                enabled: comp.is_enabled(),
+                focusable: comp.is_focusable(),
+                focused: comp.has_focus(),
+                children,
+            }),
+            // Components that don't describe themselves are left out, but their children aren't
+            None => nodes.extend(children),
+        }
+    }
+    nodes
+}
@@ -0,0 +1,346 @@
+//! This module contains the `Animator`, which tweens component properties - position, scale, opacity and color - over time.
+//! The renderer owns one (`Renderer::animator_mut`), and steps it in its prepass with the time since the last frame,
+//! so animations run at the same speed whatever the frame rate. While any are running, the main loop keeps drawing frames.
+//!
+//! Components say which properties they can animate through `Component::animated_value` and `Component::set_animated_value`:
+//!
+//! ```ignore
+//! let mut animation = Animation::new(button, AnimatedValue::Scale([0.6, 0.6]), Duration::from_millis(250));
+//! animation.set_easing(Easing::EaseOut).on_complete(|_state| println!("Grown"));
+//! renderer.animator_mut().start(animation);
+//! ```
+//!
+//! Transitions (`Transition`) are built on top, so menus and panels fade, slide or grow in when they're shown
+//! (`Renderer::show_component`) rather than popping in, and out again when they're hidden (`Renderer::hide_component`).
+
+use std::time::Duration;
+
+use crate::{color::Color, layout::Layout, state::AppState};
+
+pub use crate::easing::Easing;
+
+/// The signature of an animation's start and complete callbacks. They get the app's state (see `AppState`).
+pub type AnimationCallback = Box<dyn FnMut(&mut AppState)>;
+
+/// A property of a component that can be animated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnimatedProperty{
+    Position,
+    Scale,
+    Opacity,
+    Color,
+}
+
+/// The value of an animated property
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimatedValue{
+    /// Where the component is, in pixels from the top left of the window (a button's center, or a label's anchor)
+    Position([f32; 2]),
+    /// The component's size, in pixels for buttons
+    Scale([f32; 2]),
+    /// How opaque the component is, from 0 (invisible) to 1
+    Opacity(f32),
+    /// The component's main color (a button's tint, or a label's text)
+    Color(Color),
+}
+
+impl AnimatedValue{
+    /// The property this is a value of
+    pub fn property(&self) -> AnimatedProperty{
+        match self{
+            AnimatedValue::Position(_) => AnimatedProperty::Position,
+            AnimatedValue::Scale(_) => AnimatedProperty::Scale,
+            AnimatedValue::Opacity(_) => AnimatedProperty::Opacity,
+            AnimatedValue::Color(_) => AnimatedProperty::Color,
+        }
+    }
+
+    /// The value `t` of the way from this to `to` (0 is this, 1 is `to`). Values of different properties don't mix,
+    /// so that returns `to`.
+    pub fn lerp(&self, to: &AnimatedValue, t: f32) -> AnimatedValue{
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        match (self, to){
+            (AnimatedValue::Position(a), AnimatedValue::Position(b)) => AnimatedValue::Position([mix(a[0], b[0]), mix(a[1], b[1])]),
+            (AnimatedValue::Scale(a), AnimatedValue::Scale(b)) => AnimatedValue::Scale([mix(a[0], b[0]), mix(a[1], b[1])]),
+            (AnimatedValue::Opacity(a), AnimatedValue::Opacity(b)) => AnimatedValue::Opacity(mix(*a, *b)),
+            (AnimatedValue::Color(a), AnimatedValue::Color(b)) => AnimatedValue::Color(a.mix(*b, t)),
+            _ => *to,
+        }
+    }
+}
+
+/// # Transition
+///
+/// How a component is animated as it's shown or hidden (see `Animator::show` and `Animator::hide`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition{
+    /// Fade in from transparent, and out to it
+    Fade,
+    /// Slide in from this far from the component's position, and out to it (in the component's position units)
+    Slide([f32; 2]),
+    /// Grow from this fraction of the component's scale, and shrink to it
+    Scale(f32),
+}
+
+impl Transition{
+    /// The property the transition animates
+    pub fn property(&self) -> AnimatedProperty{
+        match self{
+            Transition::Fade => AnimatedProperty::Opacity,
+            Transition::Slide(_) => AnimatedProperty::Position,
+            Transition::Scale(_) => AnimatedProperty::Scale,
+        }
+    }
+
+    /// The value a component is shown from (and hidden to), given the value it has while it's shown
+    pub fn hidden_value(&self, shown: AnimatedValue) -> AnimatedValue{
+        match (self, shown){
+            (Transition::Fade, _) => AnimatedValue::Opacity(0.0),
+            (Transition::Slide(offset), AnimatedValue::Position(pos)) => AnimatedValue::Position([pos[0] + offset[0], pos[1] + offset[1]]),
+            (Transition::Scale(factor), AnimatedValue::Scale(scale)) => AnimatedValue::Scale([scale[0] * factor, scale[1] * factor]),
+            (_, value) => value,
+        }
+    }
+}
+
+/// Identifies a running animation, so it can be cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationId(u64);
+
+/// # Animation
+///
+/// A property of a component (by its ID in the main layout) moving to a value over a duration. It starts from the
+/// component's current value, unless it's given one with `set_from`.
+pub struct Animation{
+    component: usize,
+    from: Option<AnimatedValue>,
+    to: AnimatedValue,
+    duration: Duration,
+    delay: Duration,
+    easing: Easing,
+    on_start: Option<AnimationCallback>,
+    on_complete: Option<AnimationCallback>,
+}
+
+impl Animation{
+    /// Animate a property of `component` to `to` over `duration`
+    pub fn new(component: usize, to: AnimatedValue, duration: Duration) -> Self{
+        Self{
+            component,
+            from: None,
+            to,
+            duration,
+            delay: Duration::from_secs(0),
+            easing: Easing::default(),
+            on_start: None,
+            on_complete: None,
+        }
+    }
+
+    /// Start from `from`, instead of the component's value when the animation starts
+    pub fn set_from(&mut self, from: AnimatedValue) -> &mut Self{
+        self.from = Some(from);
+        self
+    }
+
+    /// Wait for `delay` before starting
+    pub fn set_delay(&mut self, delay: Duration) -> &mut Self{
+        self.delay = delay;
+        self
+    }
+
+    /// Change how the animation is eased (linear by default)
+    pub fn set_easing(&mut self, easing: Easing) -> &mut Self{
+        self.easing = easing;
+        self
+    }
+
+    /// Run `callback` when the animation starts (after its delay)
+    pub fn on_start<F: FnMut(&mut AppState) + 'static>(&mut self, callback: F) -> &mut Self{
+        self.on_start = Some(Box::new(callback));
+        self
+    }
+
+    /// Run `callback` when the animation finishes. It doesn't run if the animation is cancelled.
+    pub fn on_complete<F: FnMut(&mut AppState) + 'static>(&mut self, callback: F) -> &mut Self{
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+// An animation that has been started, and how far through it is
+struct Running{
+    id: AnimationId,
+    animation: Animation,
+    // Time since the animation was started, including the delay. `None` until its first step, which counts as
+    // time zero - the frame before may have been a long time ago, if the loop was sleeping.
+    elapsed: Option<Duration>,
+    started: bool,
+    // Set for show and hide transitions - the value the component has while it's shown. Hide transitions put it
+    // back to this after hiding the component, so it's shown as it was the next time.
+    shown: Option<AnimatedValue>,
+    hide: bool,
+}
+
+/// # Animator
+///
+/// The animations that are running. Starting an animation of a property that's already animating on the same component
+/// replaces the old one, so it picks up from wherever that got to.
+#[derive(Default)]
+pub struct Animator{
+    running: Vec<Running>,
+    next_id: u64,
+}
+
+impl Animator{
+    /// Create an animator with nothing running
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Start an animation. It takes its first step the next time the animator is stepped.
+    pub fn start(&mut self, animation: Animation) -> AnimationId{
+        self.push(animation, None, false)
+    }
+
+    /// Show a component in `layout` with a transition - it's made visible now, and animates in from the transition's
+    /// hidden value over `duration`. Interrupting a hide transition goes back to how the component was before it (and
+    /// the same the other way around).
+    pub fn show(&mut self, layout: &mut Layout, component: usize, transition: Transition, duration: Duration) -> AnimationId{
+        let shown = self.shown_value(layout, component, transition);
+        if let Some(comp) = layout.get_component_mut(component){
+            comp.set_visible(true);
+        }
+        let mut animation = Animation::new(component, shown, duration);
+        animation.set_from(transition.hidden_value(shown));
+        self.push(animation, Some(shown), false)
+    }
+
+    /// Hide a component in `layout` with a transition - it animates out to the transition's hidden value over
+    /// `duration`, and is hidden at the end (its property goes back to how it was, ready to be shown again).
+    pub fn hide(&mut self, layout: &mut Layout, component: usize, transition: Transition, duration: Duration) -> AnimationId{
+        let shown = self.shown_value(layout, component, transition);
+        let animation = Animation::new(component, transition.hidden_value(shown), duration);
+        self.push(animation, Some(shown), true)
+    }
+
+    /// Stop every animation (eg, when the layout they're animating is replaced)
+    pub fn clear(&mut self){
+        self.running.clear();
+    }
+
+    // The value a component has while it's shown - from a transition that's still running, or the component itself
+    fn shown_value(&self, layout: &Layout, component: usize, transition: Transition) -> AnimatedValue{
+        let property = transition.property();
+        let hiding = self.running.iter()
+            .filter(|running| running.animation.component == component && running.animation.to.property() == property)
+            .find_map(|running| running.shown);
+        let fallback = match property{
+            AnimatedProperty::Opacity => AnimatedValue::Opacity(1.0),
+            AnimatedProperty::Position => AnimatedValue::Position([0.0, 0.0]),
+            AnimatedProperty::Scale => AnimatedValue::Scale([1.0, 1.0]),
+            AnimatedProperty::Color => AnimatedValue::Color(Color::WHITE),
+        };
+        hiding
+            .or_else(|| layout.get_component(component).and_then(|comp| comp.animated_value(property)))
+            .unwrap_or(fallback)
+    }
+
+    fn push(&mut self, animation: Animation, shown: Option<AnimatedValue>, hide: bool) -> AnimationId{
+        let id = AnimationId(self.next_id);
+        self.next_id += 1;
+        let property = animation.to.property();
+        self.running.retain(|running| running.animation.component != animation.component || running.animation.to.property() != property);
+        self.running.push(Running{
+            id,
+            animation,
+            elapsed: None,
+            started: false,
+            shown,
+            hide,
+        });
+        id
+    }
+
+    /// Stop an animation where it is, without running its complete callback. Returns false if it had already finished.
+    pub fn cancel(&mut self, id: AnimationId) -> bool{
+        let count = self.running.len();
+        self.running.retain(|running| running.id != id);
+        self.running.len() != count
+    }
+
+    /// Stop every animation of a component (eg, before removing it from the layout)
+    pub fn cancel_component(&mut self, component: usize){
+        self.running.retain(|running| running.animation.component != component);
+    }
+
+    /// Returns true if the animation hasn't finished (or been cancelled)
+    pub fn is_running(&self, id: AnimationId) -> bool{
+        self.running.iter().any(|running| running.id == id)
+    }
+
+    /// The number of animations running, including ones waiting out their delay
+    pub fn len(&self) -> usize{
+        self.running.len()
+    }
+
+    /// Returns true if nothing is animating
+    pub fn is_empty(&self) -> bool{
+        self.running.is_empty()
+    }
+
+    /// Move every animation on by `delta_time`, setting the properties of the components in `layout`. The renderer
+    /// calls this in its prepass, so it only needs calling by hand for a layout the renderer doesn't draw.
+    pub fn step(&mut self, delta_time: Duration, layout: &mut Layout, state: &mut AppState){
+        let mut finished = Vec::new();
+        for (index, running) in self.running.iter_mut().enumerate(){
+            let elapsed = running.elapsed.map_or(Duration::from_secs(0), |elapsed| elapsed + delta_time);
+            running.elapsed = Some(elapsed);
+            let animation = &mut running.animation;
+            if elapsed < animation.delay{
+                continue;
+            }
+            let component = match layout.get_component_mut(animation.component){
+                Some(component) => component,
+                None => {
+                    // The component was removed
+                    finished.push(index);
+                    continue;
+                }
+            };
+
+            if !running.started{
+                running.started = true;
+                if animation.from.is_none(){
+                    // A component without the property jumps straight to the end
+                    animation.from = Some(component.animated_value(animation.to.property()).unwrap_or(animation.to));
+                }
+                if let Some(callback) = &mut animation.on_start{
+                    callback(state);
+                }
+            }
+
+            let progress = if animation.duration.as_secs_f32() > 0.0{
+                ((elapsed - animation.delay).as_secs_f32() / animation.duration.as_secs_f32()).min(1.0)
+            }else{
+                1.0
+            };
+            let from = animation.from.unwrap_or(animation.to);
+            component.set_animated_value(from.lerp(&animation.to, animation.easing.apply(progress)));
+
+            if progress >= 1.0{
+                if let (true, Some(shown)) = (running.hide, running.shown){
+                    component.set_visible(false);
+                    component.set_animated_value(shown);
+                }
+                if let Some(callback) = &mut animation.on_complete{
+                    callback(state);
+                }
+                finished.push(index);
+            }
+        }
+        for index in finished.into_iter().rev(){
+            self.running.remove(index);
+        }
+    }
+}
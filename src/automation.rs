@@ -0,0 +1,310 @@
+//! This module contains an optional automation interface (enabled with the `automation` feature).
+//! It lets external tools - UI test runners, accessibility checkers, scripts - drive a running app
+//! over a local socket, using line-delimited JSON-RPC 2.0. Tools can list the components in the
+//! active layout, read text back, and send clicks, key presses and typed text.
+//!
+//! Requests are received on a background thread, but are always executed on the main thread (inside
+//! the main loop), so they see the layout exactly as the app does. Synthesized input goes through the
+//! same path as real input, so buttons and the user's event handler can't tell the difference.
+
+use std::{io::{BufRead, BufReader, ErrorKind, Write}, net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs}, sync::{Arc, Mutex, mpsc::{self, Receiver, Sender}}, thread};
+
+use serde_json::{json, Value};
+use winit::{event_loop::EventLoopProxy, window::Window};
+
+use crate::{event::{GuiEvent, ModifiersState, VirtualKeyCode}, rendering::Renderer, sender::EventLoopMessage};
+
+/// JSON-RPC error code for a request that isn't valid JSON
+pub const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code for a request that isn't a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code for an unknown method
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for missing or invalid parameters
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// # AutomationRequest
+///
+/// A request an automation client can make. These map to the JSON-RPC methods:
+///
+/// * `list_components` - returns every component in the active layout (kind, id, enabled, visible, position and text)
+/// * `get_text` `{"id": usize}` - returns the text of a component (eg, a label or a button)
+/// * `click` `{"x": f64, "y": f64}` - clicks at a position in window pixels (from the top left)
+/// * `click_component` `{"id": usize}` - clicks the center of a component (eg, a button)
+/// * `key` `{"key": "Return"}` - presses and releases a key
+/// * `type` `{"text": "..."}` - types text, one character at a time
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutomationRequest{
+    ListComponents,
+    GetText{ id: usize },
+    Click{ x: f64, y: f64 },
+    ClickComponent{ id: usize },
+    Key{ key: VirtualKeyCode },
+    Type{ text: String },
+}
+
+impl AutomationRequest{
+    /// Build a request from a JSON-RPC method name and its parameters
+    pub fn from_method(method: &str, params: &Value) -> Result<Self, (i64, &'static str)>{
+        let invalid = (INVALID_PARAMS, "Error, missing or invalid parameters!");
+        let get_usize = |name: &str| params.get(name).and_then(Value::as_u64).map(|v| v as usize).ok_or(invalid);
+        let get_f64 = |name: &str| params.get(name).and_then(Value::as_f64).ok_or(invalid);
+        let get_str = |name: &str| params.get(name).and_then(Value::as_str).ok_or(invalid);
+
+        match method{
+            "list_components" => Ok(AutomationRequest::ListComponents),
+            "get_text" => Ok(AutomationRequest::GetText{ id: get_usize("id")? }),
+            "click" => Ok(AutomationRequest::Click{ x: get_f64("x")?, y: get_f64("y")? }),
+            "click_component" => Ok(AutomationRequest::ClickComponent{ id: get_usize("id")? }),
+            "key" => match parse_key(get_str("key")?){
+                Some(key) => Ok(AutomationRequest::Key{ key }),
+                None => Err((INVALID_PARAMS, "Error, unknown key name!")),
+            },
+            "type" => Ok(AutomationRequest::Type{ text: get_str("text")?.to_string() }),
+            _ => Err((METHOD_NOT_FOUND, "Error, unknown method!")),
+        }
+    }
+
+    /// Parse a single line of JSON-RPC. On success, returns the request ID and the request,
+    /// otherwise returns the error response that should be sent back.
+    pub fn parse(line: &str) -> Result<(Value, Self), Value>{
+        let request: Value = match serde_json::from_str(line){
+            Ok(v) => v,
+            Err(_) => return Err(error_response(Value::Null, PARSE_ERROR, "Error, request is not valid JSON!")),
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = match request.get("method").and_then(Value::as_str){
+            Some(v) => v,
+            None => return Err(error_response(id, INVALID_REQUEST, "Error, request has no method!")),
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        match Self::from_method(method, &params){
+            Ok(v) => Ok((id, v)),
+            Err((code, message)) => Err(error_response(id, code, message)),
+        }
+    }
+}
+
+/// Build a successful JSON-RPC response
+pub fn result_response(id: Value, result: Value) -> Value{
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Build a JSON-RPC error response
+pub fn error_response(id: Value, code: i64, message: &str) -> Value{
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Turn a key name (the `VirtualKeyCode` name, like `A`, `Key1`, `Return` or `Escape`) into a key code.
+/// A few common aliases (`Enter`, `Backspace`, `Esc` and single digits) are accepted too.
+pub fn parse_key(name: &str) -> Option<VirtualKeyCode>{
+    use VirtualKeyCode::*;
+    const LETTERS: [VirtualKeyCode; 26] = [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z];
+    const DIGITS: [VirtualKeyCode; 10] = [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+
+    let upper = name.to_ascii_uppercase();
+    let upper = upper.strip_prefix("KEY").filter(|v| v.len() == 1).unwrap_or(&upper);
+    if upper.len() == 1{
+        let c = upper.as_bytes()[0];
+        return match c{
+            b'A'..=b'Z' => Some(LETTERS[(c - b'A') as usize]),
+            b'0'..=b'9' => Some(DIGITS[(c - b'0') as usize]),
+            _ => None,
+        };
+    }
+
+    match upper{
+        "RETURN" | "ENTER" => Some(Return),
+        "NUMPADENTER" => Some(NumpadEnter),
+        "ESCAPE" | "ESC" => Some(Escape),
+        "TAB" => Some(Tab),
+        "SPACE" => Some(Space),
+        "BACK" | "BACKSPACE" => Some(Back),
+        "DELETE" => Some(Delete),
+        "LEFT" => Some(Left),
+        "RIGHT" => Some(Right),
+        "UP" => Some(Up),
+        "DOWN" => Some(Down),
+        "HOME" => Some(Home),
+        "END" => Some(End),
+        "PAGEUP" => Some(PageUp),
+        "PAGEDOWN" => Some(PageDown),
+        _ => None,
+    }
+}
+
+// A parsed request waiting for the main thread, and where to send the response
+type PendingRequest = (Value, AutomationRequest, Sender<Value>);
+
+/// # AutomationServer
+///
+/// Listens for automation clients on a local TCP socket (on a loopback address, so only this machine can connect). Each
+/// client sends one JSON-RPC request per line, and gets one response per line back.
+///
+/// ## Usage
+///
+/// Enable the `automation` feature, then call `GUI::enable_automation("127.0.0.1:7878")` before starting the
+/// main loop. The main loop executes requests as they arrive.
+pub struct AutomationServer{
+    receiver: Receiver<PendingRequest>,
+    proxy: Arc<Mutex<Option<EventLoopProxy<EventLoopMessage>>>>,
+    address: SocketAddr,
+}
+
+impl AutomationServer{
+    /// Start listening on `address`. Anyone who can connect can control the app, so it must be a loopback address
+    /// (eg, "127.0.0.1:7878") - other addresses are refused with `PermissionDenied`.
+    pub fn start<A: ToSocketAddrs>(address: A) -> std::io::Result<Self>{
+        let refused = || std::io::Error::new(ErrorKind::PermissionDenied, "Error, the automation server can only listen on loopback addresses!");
+        // Checked before binding, so the port is never open to other machines
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if addresses.iter().any(|address| !address.ip().is_loopback()){
+            return Err(refused());
+        }
+        let listener = TcpListener::bind(&addresses[..])?;
+        let address = listener.local_addr()?;
+        if !address.ip().is_loopback(){
+            return Err(refused());
+        }
+        let (sender, receiver) = mpsc::channel();
+        let proxy: Arc<Mutex<Option<EventLoopProxy<EventLoopMessage>>>> = Arc::new(Mutex::new(None));
+
+        let thread_proxy = proxy.clone();
+        thread::Builder::new().name("rusty_gui automation".into()).spawn(move ||{
+            for stream in listener.incoming().flatten(){
+                let sender = sender.clone();
+                let proxy = thread_proxy.clone();
+                thread::spawn(move || handle_client(stream, sender, proxy));
+            }
+        })?;
+
+        Ok(Self{
+            receiver,
+            proxy,
+            address,
+        })
+    }
+
+    /// The address the server is listening on (useful when binding to port 0)
+    pub fn address(&self) -> SocketAddr{
+        self.address
+    }
+
+    /// Give the server a way to wake up the event loop when a request arrives, so requests are handled
+    /// straight away instead of waiting for the next event
+    pub fn set_event_loop_proxy(&self, proxy: EventLoopProxy<EventLoopMessage>){
+        *self.proxy.lock().unwrap() = Some(proxy);
+    }
+
+    /// Execute every pending request. `dispatch` is used to deliver synthesized events, and should handle
+    /// them the same way the main loop handles real events.
+    pub fn process<F>(&self, window: &mut Window, renderer: &mut Renderer, mut dispatch: F)
+    where F: FnMut(&GuiEvent, &mut Window, &mut Renderer){
+        while let Ok((id, request, reply)) = self.receiver.try_recv(){
+            let response = match execute(request, window, renderer, &mut dispatch){
+                Ok(result) => result_response(id, result),
+                Err((code, message)) => error_response(id, code, message),
+            };
+            // The client may have disconnected in the meantime, which is fine
+            let _ = reply.send(response);
+        }
+    }
+}
+
+// Read requests from a client until it disconnects
+fn handle_client(stream: TcpStream, sender: Sender<PendingRequest>, proxy: Arc<Mutex<Option<EventLoopProxy<EventLoopMessage>>>>){
+    let mut writer = match stream.try_clone(){
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    for line in BufReader::new(stream).lines(){
+        let line = match line{
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        if line.trim().is_empty(){
+            continue;
+        }
+
+        let response = match AutomationRequest::parse(&line){
+            Ok((id, request)) => {
+                let (reply_sender, reply_receiver) = mpsc::channel();
+                if sender.send((id, request, reply_sender)).is_err(){
+                    // The app has shut down
+                    return;
+                }
+                if let Some(proxy) = proxy.lock().unwrap().as_ref(){
+                    let _ = proxy.send_event(EventLoopMessage::wake());
+                }
+                match reply_receiver.recv(){
+                    Ok(v) => v,
+                    Err(_) => return,
+                }
+            }
+            Err(error) => error,
+        };
+
+        if writeln!(writer, "{}", response).is_err(){
+            return;
+        }
+    }
+}
+
+// Run a single request on the main thread
+fn execute<F>(request: AutomationRequest, window: &mut Window, renderer: &mut Renderer, dispatch: &mut F) -> Result<Value, (i64, &'static str)>
+where F: FnMut(&GuiEvent, &mut Window, &mut Renderer){
+    match request{
+        AutomationRequest::ListComponents => Ok(list_components(renderer)),
+        AutomationRequest::GetText{ id } => {
+            match renderer.layout.components.get(id){
+                Some(comp) => Ok(comp.get_text().map(Value::from).unwrap_or(Value::Null)),
+                None => Err((INVALID_PARAMS, "Error, no component with that ID!")),
+            }
+        }
+        AutomationRequest::Click{ x, y } => {
+            click(x, y, window, renderer, dispatch);
+            Ok(Value::Bool(true))
+        }
+        AutomationRequest::ClickComponent{ id } => {
+            let pos = match renderer.layout.components.get(id){
+                Some(comp) => comp.get_pos(),
+                None => return Err((INVALID_PARAMS, "Error, no component with that ID!")),
+            };
+            // Component positions are in pixels from the top left of the window
+            click(pos[0] as f64, pos[1] as f64, window, renderer, dispatch);
+            Ok(Value::Bool(true))
+        }
+        AutomationRequest::Key{ key } => {
+            for event in crate::test::key_press(key, ModifiersState::empty()){
+                dispatch(&event, window, renderer);
+            }
+            Ok(Value::Bool(true))
+        }
+        AutomationRequest::Type{ text } => {
+            for event in crate::test::type_text(&text){
+                dispatch(&event, window, renderer);
+            }
+            Ok(Value::Bool(true))
+        }
+    }
+}
+
+// Describe every component in the active layout
+fn list_components(renderer: &Renderer) -> Value{
+    let layout = &renderer.layout;
+    let mut components = Vec::new();
+    for (id, comp) in layout.components.iter(){
+        components.push(json!({ "id": id, "enabled": comp.is_enabled(), "visible": comp.is_visible(), "pos": comp.get_pos(), "text": comp.get_text() }));
+    }
+    Value::Array(components)
+}
+
+// Move the cursor to (x, y), then press and release the left mouse button
+fn click<F>(x: f64, y: f64, window: &mut Window, renderer: &mut Renderer, dispatch: &mut F)
+where F: FnMut(&GuiEvent, &mut Window, &mut Renderer){
+    for event in crate::test::click((x, y)){
+        dispatch(&event, window, renderer);
+    }
+}
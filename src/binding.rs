@@ -0,0 +1,137 @@
+//! This module contains `Observable`, a value shared between the app and its components. The app changes it, and the
+//! components bound to it (eg, a label showing a score) pick up the change before the next frame is drawn, instead of
+//! being looked up by ID and changed by hand.
+//!
+//! Components subscribe with a `Binding`, which remembers the last change it saw. They check it in `Component::update`,
+//! which runs during the renderer's prepass. Changing an observable also asks for the next frame, so the change is drawn
+//! even if nothing else happened.
+
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
+thread_local!{
+    // Whether any observable changed since the main loop last checked
+    static CHANGED: Cell<bool> = const { Cell::new(false) };
+}
+
+// Returns true if any observable changed since the last call, clearing the flag
+pub(crate) fn take_changes() -> bool{
+    CHANGED.with(|changed| changed.replace(false))
+}
+
+// The value, and a count of the changes made to it
+struct Shared<T>{
+    value: T,
+    version: u64,
+}
+
+/// # Observable
+///
+/// A value that components can be bound to. Clones are cheap, and share the same value - keep one in the app's state,
+/// and give the others to the components.
+pub struct Observable<T>{
+    inner: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Clone for Observable<T>{
+    fn clone(&self) -> Self{
+        Self{
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Observable<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("Observable").field("value", &self.inner.borrow().value).finish()
+    }
+}
+
+impl<T> Observable<T>{
+    /// Create a new observable holding `value`
+    pub fn new(value: T) -> Self{
+        Self{
+            inner: Rc::new(RefCell::new(Shared{
+                value,
+                version: 0,
+            })),
+        }
+    }
+
+    /// Replace the value. Every binding sees the change, even if the new value is the same.
+    pub fn set(&self, value: T){
+        let mut shared = self.inner.borrow_mut();
+        shared.value = value;
+        shared.version += 1;
+        CHANGED.with(|changed| changed.set(true));
+    }
+
+    /// Change the value in place
+    pub fn update<F: FnOnce(&mut T)>(&self, change: F){
+        let mut shared = self.inner.borrow_mut();
+        change(&mut shared.value);
+        shared.version += 1;
+        CHANGED.with(|changed| changed.set(true));
+    }
+
+    /// Read the value without cloning it
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, read: F) -> R{
+        read(&self.inner.borrow().value)
+    }
+
+    /// Subscribe to the value. The binding's first `poll` returns the current value, so it's applied straight away.
+    pub fn bind(&self) -> Binding<T>{
+        Binding{
+            observable: self.clone(),
+            seen: None,
+        }
+    }
+
+    fn version(&self) -> u64{
+        self.inner.borrow().version
+    }
+}
+
+impl<T: Clone> Observable<T>{
+    /// A copy of the value
+    pub fn get(&self) -> T{
+        self.inner.borrow().value.clone()
+    }
+}
+
+/// # Binding
+///
+/// A component's subscription to an `Observable`. It remembers the last change it saw, so the component only
+/// applies each change once.
+pub struct Binding<T>{
+    observable: Observable<T>,
+    // The version last returned by `poll`, or `None` if it hasn't been polled yet
+    seen: Option<u64>,
+}
+
+impl<T> Binding<T>{
+    /// The observable this binding is subscribed to
+    pub fn observable(&self) -> &Observable<T>{
+        &self.observable
+    }
+
+    /// Returns true if the value has changed since it was last polled
+    pub fn has_changed(&self) -> bool{
+        self.seen != Some(self.observable.version())
+    }
+
+    /// Read the new value with `read`, if it has changed since it was last polled
+    pub fn poll_with<R, F: FnOnce(&T) -> R>(&mut self, read: F) -> Option<R>{
+        if !self.has_changed(){
+            return None;
+        }
+        self.seen = Some(self.observable.version());
+        Some(self.observable.with(read))
+    }
+}
+
+impl<T: Clone> Binding<T>{
+    /// The new value, if it has changed since it was last polled
+    pub fn poll(&mut self) -> Option<T>{
+        self.poll_with(T::clone)
+    }
+}
@@ -0,0 +1,122 @@
+//! This module contains `Clipboard`, a small wrapper around the system clipboard (using arboard).
+//! The renderer owns one, so it's available from event callbacks, and text inputs use it for cut, copy and paste.
+//! It holds images too, as RGBA buffers - the same `image::RgbaImage` a `Texture` is made from, and a `Snapshot` converts to.
+
+use std::{cell::RefCell, rc::Rc};
+
+/// # Clipboard
+///
+/// A handle to the system clipboard. Handles are cheap to clone, and all share the same connection -
+/// on X11 and Wayland the text we copy only stays available while that connection is alive.
+///
+/// The connection is opened the first time the clipboard is used, so apps that never touch it don't pay for it.
+///
+/// `Clipboard::in_memory` makes one that isn't connected to the system clipboard at all, for tests.
+#[derive(Clone, Default)]
+pub struct Clipboard{
+    inner: Rc<RefCell<Backend>>,
+}
+
+// Where a clipboard's contents are kept
+enum Backend{
+    // The system clipboard, once it's been opened
+    System(Option<arboard::Clipboard>),
+    // The contents of an in-memory clipboard (which holds text or an image, like the system one)
+    Memory(Option<Content>),
+}
+
+enum Content{
+    Text(String),
+    Image(image::RgbaImage),
+}
+
+impl Default for Backend{
+    fn default() -> Self{
+        Backend::System(None)
+    }
+}
+
+impl std::fmt::Debug for Clipboard{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        match &*self.inner.borrow(){
+            Backend::System(clipboard) => f.debug_struct("Clipboard").field("connected", &clipboard.is_some()).finish(),
+            Backend::Memory(_) => f.debug_struct("Clipboard").field("in_memory", &true).finish(),
+        }
+    }
+}
+
+impl Clipboard{
+    /// Create a new clipboard handle
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Create a clipboard that keeps what's put on it in memory, without touching the system clipboard (eg, to test
+    /// copy and paste). Its handles share the contents, like the system clipboard's do.
+    pub fn in_memory() -> Self{
+        Self{
+            inner: Rc::new(RefCell::new(Backend::Memory(None))),
+        }
+    }
+
+    /// Get the text on the clipboard. Fails if the clipboard is empty, holds something other than text, or can't be opened.
+    pub fn get_text(&self) -> Result<String, &'static str>{
+        const EMPTY: &str = "Error, the clipboard doesn't contain any text!";
+        match &mut *self.inner.borrow_mut(){
+            Backend::Memory(Some(Content::Text(text))) => Ok(text.clone()),
+            Backend::Memory(_) => Err(EMPTY),
+            Backend::System(clipboard) => Self::connect(clipboard)?.get_text().map_err(|_| EMPTY),
+        }
+    }
+
+    /// Put text on the clipboard, replacing what was there
+    pub fn set_text<S: Into<String>>(&self, text: S) -> Result<(), &'static str>{
+        let text = text.into();
+        match &mut *self.inner.borrow_mut(){
+            Backend::Memory(content) => {
+                *content = Some(Content::Text(text));
+                Ok(())
+            }
+            Backend::System(clipboard) => Self::connect(clipboard)?.set_text(text).map_err(|_| "Error, failed to set the clipboard text!"),
+        }
+    }
+
+    /// Get the image on the clipboard (eg, a screenshot), as RGBA pixels. Fails if the clipboard is empty, holds something
+    /// other than an image, or can't be opened.
+    pub fn get_image(&self) -> Result<image::RgbaImage, &'static str>{
+        const EMPTY: &str = "Error, the clipboard doesn't contain an image!";
+        let data = match &mut *self.inner.borrow_mut(){
+            Backend::Memory(Some(Content::Image(image))) => return Ok(image.clone()),
+            Backend::Memory(_) => return Err(EMPTY),
+            Backend::System(clipboard) => Self::connect(clipboard)?.get_image().map_err(|_| EMPTY)?,
+        };
+        image::RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+            .ok_or("Error, the clipboard image has the wrong number of pixels!")
+    }
+
+    /// Put an image on the clipboard, replacing what was there
+    pub fn set_image(&self, image: &image::RgbaImage) -> Result<(), &'static str>{
+        match &mut *self.inner.borrow_mut(){
+            Backend::Memory(content) => {
+                *content = Some(Content::Image(image.clone()));
+                Ok(())
+            }
+            Backend::System(clipboard) => {
+                let data = arboard::ImageData{
+                    width: image.width() as usize,
+                    height: image.height() as usize,
+                    bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+                };
+                Self::connect(clipboard)?.set_image(data).map_err(|_| "Error, failed to set the clipboard image!")
+            }
+        }
+    }
+
+    // The system clipboard, connecting to it first if we haven't already
+    fn connect(clipboard: &mut Option<arboard::Clipboard>) -> Result<&mut arboard::Clipboard, &'static str>{
+        if clipboard.is_none(){
+            *clipboard = Some(arboard::Clipboard::new().map_err(|_| "Error, failed to open the clipboard!")?);
+        }
+        Ok(clipboard.as_mut().unwrap())
+    }
+}
@@ -0,0 +1,185 @@
+//! This module contains the `Color` type used throughout the crate - for the clear color,
+//! label text and component tints. Colors are written the way you'd pick them in an image editor
+//! (sRGB), and get converted to linear space when they're handed to the GPU, as the swapchain is sRGB.
+
+/// # Color
+///
+/// An RGBA color, with each channel stored as an sRGB value between 0.0 and 1.0.
+///
+/// Colors can be made from hex strings (`Color::from_hex("#336699")`), 8 bit channels,
+/// HSV values or one of the named constants.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color{
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color{
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+    pub const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+    pub const GRAY: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+    pub const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
+    pub const GREEN: Color = Color::new(0.0, 1.0, 0.0, 1.0);
+    pub const BLUE: Color = Color::new(0.0, 0.0, 1.0, 1.0);
+    pub const YELLOW: Color = Color::new(1.0, 1.0, 0.0, 1.0);
+    pub const CYAN: Color = Color::new(0.0, 1.0, 1.0, 1.0);
+    pub const MAGENTA: Color = Color::new(1.0, 0.0, 1.0, 1.0);
+
+    /// Create a new color from sRGB channels between 0.0 and 1.0
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self{
+        Self{
+            r,
+            g,
+            b,
+            a,
+        }
+    }
+
+    /// Create a new, fully opaque color from sRGB channels between 0.0 and 1.0
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self{
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// Create a new color from 8 bit sRGB channels
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self{
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    /// Parse a hex color. Accepts `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA`, with or without the `#`.
+    pub fn from_hex(hex: &str) -> Result<Self, &'static str>{
+        let hex = hex.trim().trim_start_matches('#');
+        // from_str_radix would also take a sign, so "+f" would parse as a channel
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()){
+            return Err("Error, hex color contains invalid characters!");
+        }
+
+        let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| "Error, hex color contains invalid characters!");
+        // Short hand colors repeat each digit, so #abc is the same as #aabbcc
+        let short_channel = |s: &str| channel(s).map(|v| v * 17);
+
+        match hex.len(){
+            3 => Ok(Self::from_rgba8(short_channel(&hex[0..1])?, short_channel(&hex[1..2])?, short_channel(&hex[2..3])?, 255)),
+            4 => Ok(Self::from_rgba8(short_channel(&hex[0..1])?, short_channel(&hex[1..2])?, short_channel(&hex[2..3])?, short_channel(&hex[3..4])?)),
+            6 => Ok(Self::from_rgba8(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?, 255)),
+            8 => Ok(Self::from_rgba8(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?, channel(&hex[6..8])?)),
+            _ => Err("Error, hex color has an invalid length!"),
+        }
+    }
+
+    /// Convert the color back into a `#RRGGBBAA` hex string
+    pub fn to_hex(&self) -> String{
+        let [r, g, b, a] = self.to_rgba8();
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+
+    /// Get the color as 8 bit sRGB channels
+    pub fn to_rgba8(&self) -> [u8; 4]{
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [to_u8(self.r), to_u8(self.g), to_u8(self.b), to_u8(self.a)]
+    }
+
+    /// Create a color from hue (in degrees), saturation and value (between 0.0 and 1.0)
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self{
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32{
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        Self::rgb(r + m, g + m, b + m)
+    }
+
+    /// Convert the color into hue (in degrees), saturation and value
+    pub fn to_hsv(&self) -> (f32, f32, f32){
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0{
+            0.0
+        }else if max == self.r{
+            60.0 * ((self.g - self.b) / delta).rem_euclid(6.0)
+        }else if max == self.g{
+            60.0 * ((self.b - self.r) / delta + 2.0)
+        }else{
+            60.0 * ((self.r - self.g) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        (hue, saturation, max)
+    }
+
+    /// Return the same color with a different alpha
+    pub fn with_alpha(&self, a: f32) -> Self{
+        Self::new(self.r, self.g, self.b, a)
+    }
+
+    /// Mix this color with `other`, by `amount` (0.0 gives this color, 1.0 gives `other`)
+    pub fn mix(&self, other: Color, amount: f32) -> Self{
+        let amount = amount.clamp(0.0, 1.0);
+        let mix = |from: f32, to: f32| from + (to - from) * amount;
+        Self::new(mix(self.r, other.r), mix(self.g, other.g), mix(self.b, other.b), mix(self.a, other.a))
+    }
+
+    /// Convert the color into linear space, which is what the GPU expects when drawing to an sRGB target
+    pub fn to_linear(&self) -> [f32; 4]{
+        [srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a]
+    }
+
+    /// Premultiply the color by its alpha (in linear space, where blending happens). Transparent windows
+    /// expect premultiplied colors, otherwise semi transparent areas come out too bright.
+    pub fn premultiplied(&self) -> Self{
+        let [r, g, b, a] = self.to_linear();
+        Self::from_linear(r * a, g * a, b * a, a)
+    }
+
+    /// Create a color from linear channels (for example, read back from a linear texture)
+    pub fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self{
+        Self::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+    }
+}
+
+impl Default for Color{
+    fn default() -> Color{
+        Color::BLACK
+    }
+}
+
+/// wgpu expects linear colors (eg, for the clear color), so we convert on the way in
+impl From<Color> for wgpu::Color{
+    fn from(color: Color) -> wgpu::Color{
+        let [r, g, b, a] = color.to_linear();
+        wgpu::Color{
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: a as f64,
+        }
+    }
+}
+
+fn srgb_to_linear(v: f32) -> f32{
+    if v <= 0.04045{
+        v / 12.92
+    }else{
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(v: f32) -> f32{
+    if v <= 0.003_130_8{
+        v * 12.92
+    }else{
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
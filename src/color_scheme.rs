@@ -0,0 +1,85 @@
+//! This module contains `ColorScheme` - light mode, dark mode, or following the OS - and detects which one the OS prefers.
+//! `GUI::set_color_scheme` picks the matching `Theme`, and with `ColorScheme::System` the GUI restyles itself when the OS switches.
+//!
+//! winit only reports scheme changes on Windows (as `GuiEvent::ColorSchemeChanged`), so on other platforms the GUI
+//! checks again whenever the main window gains focus. Detection asks the OS's settings tools (`defaults` on macOS,
+//! `reg` on Windows, `gsettings` or `GTK_THEME` elsewhere), and falls back to light when it can't tell.
+
+use std::process::Command;
+
+use crate::theme::Theme;
+
+/// # ColorScheme
+///
+/// Whether the GUI is drawn in light or dark colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorScheme{
+    /// Dark text on a light background (the default)
+    #[default]
+    Light,
+    /// Light text on a dark background
+    Dark,
+    /// Whatever the OS prefers, following it when it changes
+    System,
+}
+
+impl ColorScheme{
+    /// The scheme the OS prefers, `Light` or `Dark`. This runs a settings tool, so it's best not to call it every frame.
+    pub fn detect() -> ColorScheme{
+        if detect_dark(){
+            ColorScheme::Dark
+        }else{
+            ColorScheme::Light
+        }
+    }
+
+    /// `Light` or `Dark`, using `system` for `System`
+    pub fn resolve(self, system: ColorScheme) -> ColorScheme{
+        match self{
+            ColorScheme::System if system == ColorScheme::Dark => ColorScheme::Dark,
+            ColorScheme::System => ColorScheme::Light,
+            scheme => scheme,
+        }
+    }
+
+    /// The theme for the scheme, using `system` for `System`
+    pub fn theme(self, system: ColorScheme) -> Theme{
+        match self.resolve(system){
+            ColorScheme::Dark => Theme::dark(),
+            _ => Theme::light(),
+        }
+    }
+}
+
+// Run a settings tool, returning what it printed (or `None` if it isn't there, or failed)
+fn query(program: &str, args: &[&str]) -> Option<String>{
+    let output = Command::new(program).args(args).output().ok()?;
+    if output.status.success(){
+        String::from_utf8(output.stdout).ok()
+    }else{
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect_dark() -> bool{
+    // The key only exists while dark mode is on
+    query("defaults", &["read", "-g", "AppleInterfaceStyle"]).is_some_and(|style| style.trim() == "Dark")
+}
+
+#[cfg(target_os = "windows")]
+fn detect_dark() -> bool{
+    query("reg", &["query", r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize", "/v", "AppsUseLightTheme"])
+        .is_some_and(|output| output.split_whitespace().last() == Some("0x0"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn detect_dark() -> bool{
+    // GNOME (and anything that follows its settings) has a preference, older desktops only have a dark GTK theme
+    if let Some(scheme) = query("gsettings", &["get", "org.gnome.desktop.interface", "color-scheme"]){
+        if scheme.contains("prefer-dark"){
+            return true;
+        }
+    }
+    std::env::var("GTK_THEME").is_ok_and(|theme| theme.to_lowercase().contains("dark"))
+}
@@ -0,0 +1,268 @@
+//! This module defines `Badge`, a small count bubble pinned to a corner of another component (like the number of unread
+//! messages on a button).
+//!
+//! The badge's transform is a child of the host's (see `Transform::set_parent`), so it follows the host as it moves,
+//! turns and zooms, without the host knowing it's there. It hides itself while the count is zero.
+
+use std::{any::Any, rc::Rc, time::Duration};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{accessibility::{AccessInfo, Role}, color::Color, rendering::{RenderContext, Renderer, ResourceReport, TextQueue, Tracked, Transform}};
+
+use super::{Component, Label};
+
+/// The corner of its host a `Badge` is pinned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeCorner{
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl BadgeCorner{
+    // Which way the corner is from the host's center, as -1 or 1 on each axis (y going down)
+    fn direction(&self) -> [f32; 2]{
+        match self{
+            BadgeCorner::TopLeft => [-1.0, -1.0],
+            BadgeCorner::TopRight => [1.0, -1.0],
+            BadgeCorner::BottomLeft => [-1.0, 1.0],
+            BadgeCorner::BottomRight => [1.0, 1.0],
+        }
+    }
+}
+
+/// # Badge
+///
+/// A count drawn in a bubble over a corner of another component. Add it to the layout after its host, so it's drawn on
+/// top. Clicks go through it to the host.
+///
+/// The badge is placed when it's anchored (with `new` or `anchor_to`), from the host's position and scale at the time.
+/// It follows the host as it moves from then on, but if the host is resized, anchor the badge to it again.
+pub struct Badge{
+    count: u32,
+    max: u32,
+    corner: BadgeCorner,
+    text_size: f32,
+    color: Color,
+    visible: bool,
+    // Set when the color changed, which is written to the tint buffer in `prerender`
+    restyled: bool,
+    transform: Transform,
+    label: Label,
+
+    // The renderer's shared quad
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+    tint: (Tracked<wgpu::Buffer>, wgpu::BindGroup),
+}
+
+impl Badge{
+    /// The color of the bubble unless it's changed
+    pub const DEFAULT_COLOR: Color = Color::new(0.86, 0.15, 0.15, 1.0);
+
+    /// Create a badge pinned to `corner` of the component `host` is the transform of, showing 0 (so it starts hidden)
+    pub fn new(host: &Transform, corner: BadgeCorner, text_size: f32, renderer: &Renderer) -> Self{
+        let transform = Transform::new(
+            cgmath::Vector3::new(0.0, 0.0, 0.0),
+            cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            cgmath::Vector3::new(text_size, text_size, 1.0),
+            renderer.transform_pool(),
+        );
+        let mut label = Label::new("0", text_size * 0.75, [0.0, 0.0]);
+        label.align_horizontal(HorizontalAlign::Center);
+        label.align_vertical(VerticalAlign::Center);
+        label.set_color(Color::WHITE);
+        let (tint_buffer, tint_bind_group, _) = renderer.bind_group_cache().create_uniform(wgpu::ShaderStage::FRAGMENT, 0, &Self::DEFAULT_COLOR.to_linear(), "Badge tint");
+
+        let mut badge = Self{
+            count: 0,
+            max: 99,
+            corner,
+            text_size,
+            color: Self::DEFAULT_COLOR,
+            visible: true,
+            restyled: false,
+            transform,
+            label,
+            vertex_buffer: renderer.quad_buffer(),
+            tint: (tint_buffer, tint_bind_group),
+        };
+        badge.anchor_to(host, corner);
+        badge
+    }
+
+    /// Pin the badge to `corner` of the component `host` is the transform of, centering it on the corner
+    pub fn anchor_to(&mut self, host: &Transform, corner: BadgeCorner){
+        self.corner = corner;
+        // The position is in the host's frame, which its content scale zooms
+        let [x, y] = corner.direction();
+        let scale = host.scale() / host.content_scale();
+        // `host` can't be the badge's own transform, or one of its children, so this always succeeds
+        let _ = self.transform.set_parent(Some(host));
+        self.transform.set_position(cgmath::Vector3::new(x * scale.x / 2.0, y * scale.y / 2.0, 0.0));
+    }
+
+    /// The corner of the host the badge is pinned to
+    pub fn corner(&self) -> BadgeCorner{
+        self.corner
+    }
+
+    /// Show `count` in the badge, or hide it with 0
+    pub fn set_count(&mut self, count: u32){
+        self.count = count;
+        self.label.set_text(self.text());
+    }
+
+    /// The count the badge shows
+    pub fn count(&self) -> u32{
+        self.count
+    }
+
+    /// Show counts above `max` as "`max`+" (eg, "99+", the default), to keep the badge small
+    pub fn set_max(&mut self, max: u32){
+        self.max = max;
+        self.label.set_text(self.text());
+    }
+
+    /// The highest count shown as it is
+    pub fn max(&self) -> u32{
+        self.max
+    }
+
+    /// The text drawn in the badge
+    pub fn text(&self) -> String{
+        Self::count_text(self.count, self.max)
+    }
+
+    // The text a badge shows for `count`, with counts above `max` shown as "`max`+"
+    pub(crate) fn count_text(count: u32, max: u32) -> String{
+        if count > max{
+            format!("{}+", max)
+        }else{
+            count.to_string()
+        }
+    }
+
+    /// Change the color of the bubble
+    pub fn set_color(&mut self, color: Color){
+        self.color = color;
+        self.restyled = true;
+    }
+
+    /// Get the color of the bubble
+    pub fn get_color(&self) -> Color{
+        self.color
+    }
+
+    /// Change the color of the count
+    pub fn set_text_color(&mut self, color: Color){
+        self.label.set_color(color);
+    }
+
+    /// Show or hide the badge. A badge with a count of 0 is hidden either way.
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+
+    /// Borrow the badge's transform, which is a child of the host's
+    pub fn transform(&self) -> &Transform{
+        &self.transform
+    }
+}
+
+impl Component for Badge{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {
+        self.transform.bind(render_pass);
+        render_pass.set_bind_group(2, &self.tint.1, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..6, 0..1);
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue) where 'a: 'b {
+        self.label.queue_text(queue);
+    }
+
+    fn update(&mut self, delta_time: Duration){
+        self.label.update(delta_time);
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        if std::mem::take(&mut self.restyled){
+            context.queue.write_buffer(&self.tint.0, 0, bytemuck::cast_slice(&[self.color.to_linear()]));
+        }
+        // A round bubble for a single digit, growing into a pill for longer counts
+        let height = self.text_size;
+        let width = (context.measure_text(&self.text(), self.label.get_size()).0 + height / 2.0).max(height);
+        if self.transform.scale().x != width{
+            self.transform.set_scale(cgmath::Vector3::new(width, height, 1.0));
+        }
+        let rect = self.transform.rect();
+        if self.label.get_rect() != Some(rect){
+            self.label.set_rect(Some(rect));
+        }
+        self.label.prerender(context);
+        self.transform.write_uniform();
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible && self.count > 0
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        Badge::set_visible(self, visible);
+    }
+
+    fn is_hit_testable(&self) -> bool{
+        false
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        let position = self.transform.world_position();
+        [position.x, position.y]
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.transform.rect())
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64);
+    }
+
+    fn glyph_count(&self) -> usize{
+        if Component::is_visible(self) { self.label.glyph_count() } else { 0 }
+    }
+
+    fn get_text(&self) -> Option<&str>{
+        Some(self.label.text())
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(AccessInfo::named(Role::Label, self.text()))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that badges show their count as it is up to the maximum, and as the maximum with a "+" above it
+    #[test]
+    fn count_text_test(){
+        assert_eq!(Badge::count_text(0, 99), "0");
+        assert_eq!(Badge::count_text(3, 99), "3");
+        assert_eq!(Badge::count_text(99, 99), "99");
+        assert_eq!(Badge::count_text(120, 99), "99+");
+        assert_eq!(Badge::count_text(10, 9), "9+");
+    }
+}
@@ -4,53 +4,210 @@
 //! so that the crate remains as modular and user extendable as possible.
 
 use wgpu::util::DeviceExt;
-use winit::window::Window;
+use winit::window::{CursorIcon, Window};
 use wgpu_glyph::{HorizontalAlign, VerticalAlign};
 
-use crate::{layout::Layout, rendering::{Renderer, Transform}};
+use crate::{accessibility::{AccessAction, AccessInfo, Role}, animation::{AnimatedProperty, AnimatedValue}, binding::Observable, color::Color, event::{ClickTracker, EventResult, GuiEvent, MouseButton, NavAction, VirtualKeyCode}, layout::Layout, locale::{Locale, TextKey}, persistence::{ComponentState, TransformState}, rendering::{MemoryKind, RenderContext, Renderer, ResourceReport, TextQueue, TextureRegion, Tracked, Transform}, state::AppState, theme::Theme};
 
-use std::{any::Any};
+use std::{any::Any, rc::Rc, time::Duration};
 
-/// # GUIComponent
+/// The signature of a click handler (see `Button::on_click`)
+pub type ClickCallback = Box<dyn FnMut(&mut ClickContext)>;
+
+/// What a click handler gets when its button is clicked
+pub struct ClickContext<'a>{
+    pub window: &'a Window,
+    /// The app's state (see `AppState`)
+    pub state: &'a mut AppState,
+    /// Whether the button is enabled - set it to false to disable the button
+    pub enabled: &'a mut bool,
+    /// Where the click was released, in pixels from the top left of the window. Keyboard activation clicks the button's center.
+    pub pos: (f64, f64),
+}
+
+/// The signature of a button callback. It takes the event, the window, whether the cursor is within the button,
+/// a mutable reference to the button's enabled state and the app's state (see `AppState`).
+pub type ButtonCallback = Box<dyn FnMut(&GuiEvent, &Window, &bool, &mut bool, &mut AppState)>;
+
+/// # Component
+///
+/// This trait defines a component. Anything that implements it can be added to a layout, and will be
+/// rendered as long as it's in a layout that is currently getting drawn by the renderer.
 ///
-/// This trait defines a GUIComponent
-/// The component will be rendered as long as it is in a layout
-/// that is currently getting drawn by the renderer.
+/// Only `as_any` and `as_any_mut` have to be implemented - everything else defaults to doing nothing,
+/// so a component only implements the parts it uses:
+/// * `render` draws images and quads in the main pass, in the order the components were added.
+/// * `queue_text` queues text, which is drawn on top of every component in a separate pass.
+/// * `handle_event` gets the window's events. Useful for buttons and other event driven components.
 ///
-/// It has a single function - render, which will define how the component gets
-/// renderered onto the screen.
+/// Events go to the topmost component first (the one under the cursor, or the focused one for keyboard events), then
+/// down through the rest. Return `EventResult::Consumed` to stop an event there. Components get the app's state with
+/// each event, so they can pass it on to their callbacks.
 ///
-/// You use this function to add images to bind groups, or to draw text etc.
+/// Three flags control how a component takes part, and both the renderer and the layout honor them:
+/// * `is_visible` - hidden components aren't drawn, and don't get any events.
+/// * `is_enabled` - disabled components are still drawn (usually greyed out), but can't be hovered or focused.
+/// * `is_hit_testable` - components that aren't hit testable let the mouse through to whatever is below them.
 ///
 /// Lastly, the user should define a new function to easily create a new struct.
-pub trait GUIComponent{
-    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b;
-    fn as_any(&self) -> &dyn Any;
-    fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn get_text_id(&self) -> Option<usize>;
-    fn is_enabled(&self) -> bool;
-    fn get_pos(&self) -> [f32; 2];
-}
-
-/// Similar to the `GUIComponent`, except every event gets passed to the component. Useful for buttons
-/// and other event driven components.
-pub trait EventGUIComponent{
-    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b;
-    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window);
-    fn as_any(&self) -> &dyn Any;
-    fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn get_text_id(&self) -> Option<usize>;
-    fn is_enabled(&self) -> bool;
-    fn get_pos(&self) -> [f32; 2];
-}
-
-
-/// Similar to a GUI component, but renders text rather than an image.
-/// Exists because labels require it.
-pub trait TextGUIComponent{
-    fn render_text<'a, 'b>(&'a self, brush: &'b mut wgpu_glyph::GlyphBrush<()>) where 'a: 'b;
+///
+/// With the `derive` feature, `#[derive(GuiComponent)]` writes this impl instead (`as_any`, `as_any_mut`, and simple methods
+/// like `get_pos` and `is_visible` from marked fields), and `#[gui_component]` adds `as_any` and `as_any_mut` to a
+/// hand-written one.
+pub trait Component{
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Draw the component in the main render pass
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    /// The child components this component owns (see `Panel`), if any. The renderer draws them (and queues their text) straight
+    /// after the component, the same way it draws a layout - everything else (events, updates, attaching) is up to the component
+    /// to pass on.
+    fn children(&self) -> Option<&Layout>{
+        None
+    }
+    /// The child components, borrowed mutably (used to reach them from outside, eg, for `Layout::access_action`)
+    fn children_mut(&mut self) -> Option<&mut Layout>{
+        None
+    }
+    /// Queue the component's text (see `TextQueue`). Text is drawn after (so on top of) every component, cut off at the
+    /// clip rect of the layout the component is in.
+    fn queue_text<'a, 'b>(&'a self, _queue: &'b mut TextQueue) where 'a: 'b {}
+    /// Handle a window event, returning `EventResult::Consumed` to stop it going to the components below
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    /// Return false if the component can't be interacted with. It's still drawn, so it can show that it's disabled.
+    fn is_enabled(&self) -> bool{
+        true
+    }
+    /// Return false to hide the component. Hidden components aren't drawn, and don't get any events.
+    fn is_visible(&self) -> bool{
+        true
+    }
+    /// Show or hide the component, if it can be hidden (used by show and hide transitions, see `Animator::hide`)
+    fn set_visible(&mut self, _visible: bool){}
+    /// Enable or disable the component, if it can be disabled (used by containers, see `Panel::set_enabled`)
+    fn set_enabled(&mut self, _enabled: bool){}
+    /// Return false to let the mouse pass through the component - it's never hovered, and doesn't get mouse events.
+    fn is_hit_testable(&self) -> bool{
+        true
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    /// Called when the component enters a layout the renderer draws, before it's first drawn. Components can create their
+    /// GPU resources here (rather than needing a `&Renderer` to be created), and draw nothing until they have.
+    fn on_attach(&mut self, _renderer: &Renderer){}
+    /// Called when the component leaves the layout it was attached to - it was removed, or the layout was dropped or
+    /// replaced. Free anything created in `on_attach` here.
+    fn on_detach(&mut self){}
+    /// Called once per frame, before rendering, with the time since the last frame
+    fn update(&mut self, _delta_time: Duration){}
+    /// Return true while the component needs to be drawn again without anything else changing (eg, it's playing an
+    /// animation of its own). Changes made through the layout already redraw it (see `Layout::is_dirty`).
+    fn needs_redraw(&self) -> bool{
+        false
+    }
+    /// The current value of a property the `Animator` can animate, or `None` if the component doesn't have it
+    fn animated_value(&self, _property: AnimatedProperty) -> Option<AnimatedValue>{
+        None
+    }
+    /// Set a property the `Animator` is animating (see `animated_value`). This is called once per frame while it runs.
+    fn set_animated_value(&mut self, _value: AnimatedValue){}
+    /// Called before the main render pass starts, so the component can draw anything it needs offscreen
+    fn prerender(&mut self, _context: &mut RenderContext){}
+    /// Restyle the component with `theme`. This is called on every component when the theme changes (see `GUI::set_theme`).
+    /// Returns true if the component's appearance changed, so anything caching it knows to draw it again.
+    fn apply_theme(&mut self, _theme: &Theme) -> bool{
+        false
+    }
+    /// Called on every component when the locale changes (see `GUI::set_locale`), so text keys can be looked up again.
+    /// Returns true if the component's appearance may have changed, so anything caching it knows to draw it again.
+    fn apply_locale(&mut self, _locale: &Locale) -> bool{
+        false
+    }
+    /// Save the parts of the component worth keeping between sessions (see `persistence`), or `None` (the default) to save nothing
+    fn save_state(&self) -> Option<ComponentState>{
+        None
+    }
+    /// Put back a state saved with `save_state`. Only the parts that are there are changed.
+    fn restore_state(&mut self, _state: &ComponentState){}
+    /// Record the GPU resources this component holds (buffers, textures, nested layouts) in a resource report
+    fn report_resources(&self, _report: &mut ResourceReport){}
+    /// The number of glyphs this component queues in `queue_text` (used for frame statistics)
+    fn glyph_count(&self) -> usize{
+        0
+    }
+    /// The text this component shows, if it can be read back (used by automation tools)
+    fn get_text(&self) -> Option<&str>{
+        None
+    }
+    /// How the component is described to assistive technology, like screen readers (see `accessibility`), or `None` (the default)
+    /// to leave it out of the accessibility tree. Its children are still included.
+    fn access_info(&self) -> Option<AccessInfo>{
+        None
+    }
+    /// Carry out an action assistive technology asked for (eg, clicking a button). Return true if it was carried out.
+    /// Focus is handled by the layout, so it never gets here.
+    fn access_action(&mut self, _action: &AccessAction, _window: &winit::window::Window, _state: &mut AppState) -> bool{
+        false
+    }
+    /// The cursor to show while the mouse is over this component, or `None` to leave it alone
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        None
+    }
+    /// Return true to ask for the window to close (eg, a close button in a custom title bar). This is treated like the
+    /// OS close button, so the close requested callback can veto it. It's checked after every event, so clear the request once it's been taken.
+    fn take_close_request(&mut self) -> bool{
+        false
+    }
+    /// Return true if this component can hold keyboard focus (Tab and Shift+Tab move focus between these).
+    /// Focusable components need to implement `set_focus` and `has_focus` too.
+    fn is_focusable(&self) -> bool{
+        false
+    }
+    /// Called by the layout when the component gains or loses keyboard focus
+    fn set_focus(&mut self, _focused: bool){}
+    /// Return true if the component has keyboard focus. Components can take focus themselves (eg, when clicked),
+    /// the layout checks this after every event and moves focus to follow them.
+    fn has_focus(&self) -> bool{
+        false
+    }
+    /// The area to draw the focus ring around, as [left, top, width, height] in pixels from the top left
+    /// of a window of `screen` pixels, or `None` for no ring. Defaults to the component's `bounds`.
+    fn focus_rect(&self, screen: (u32, u32)) -> Option<[f32; 4]>{
+        self.bounds(screen)
+    }
+    /// Return true if this component takes typed text (like a text input). While it has focus, keyboard shortcuts
+    /// that would get in the way of typing are suppressed.
+    fn accepts_text(&self) -> bool{
+        false
+    }
+    /// The area the component covers, as [left, top, width, height] in pixels from the top left of a window of `screen` pixels.
+    /// The layout hit tests these to work out which component the cursor is over (the last one added wins where they overlap).
+    /// Return `None` (the default) to never be hovered.
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        None
+    }
+    /// The area the component draws in, in the same pixels as `bounds`. Components entirely outside the window (or their
+    /// layout's clip rect, see `Layout::set_clip_rect`) aren't drawn. Defaults to `bounds` - components that draw outside
+    /// their bounds should return more, and `None` means the component is always drawn.
+    fn draw_bounds(&self, screen: (u32, u32)) -> Option<[f32; 4]>{
+        self.bounds(screen)
+    }
+    /// Called by the layout when the cursor moves over the component (see `bounds`)
+    fn on_hover_enter(&mut self){}
+    /// Called by the layout when the cursor moves off the component, or leaves the window
+    fn on_hover_exit(&mut self){}
+    /// Called by the layout when a mouse button is pressed on the component (see `bounds`). Return true to start dragging it -
+    /// it then captures the mouse, getting every mouse event (and no other component getting any) until the button is released.
+    fn on_drag_start(&mut self, _button: MouseButton, _pos: (f64, f64)) -> bool{
+        false
+    }
+    /// Called while the component is being dragged, with the cursor position and how far it moved since the last call
+    fn on_drag_move(&mut self, _pos: (f64, f64), _delta: (f64, f64)){}
+    /// Called when the drag ends - the button was released, or the window lost focus
+    fn on_drag_end(&mut self, _pos: (f64, f64)){}
 }
 
 // This part now shows some of the base components, and may help when designing your own custom components
@@ -60,62 +217,358 @@ pub trait TextGUIComponent{
 ///
 /// This works like many labels in GUI libraries - renders
 /// text to the screen, using a specified size, pos and font.
+///
+/// The text and size can be changed at any time (eg, for a score counter). The label measures itself again the next time
+/// it's drawn, so `measured_size` always matches what's on screen.
+///
+/// A label is either anchored to a point (its `pos`), or aligned within a rect with `set_rect` - eg, a button keeps its
+/// label centered in its bounds. Text that doesn't fit in a rect's width wraps.
+///
+/// Disabled labels are drawn greyed out, hidden ones (see `set_visible`) aren't drawn at all.
+///
+/// The text can be bound to an `Observable` with `bind_text` (or `bind_text_with`, to show any other value), so it
+/// follows the app's data without the label being looked up and changed every frame.
 pub struct Label{
     content: String,
     size: f32,
     pos: [f32; 2], // x and y coords
+    rect: Option<[f32; 4]>, // the rect the text is aligned in, replacing `pos`
+    measured: Option<(f32, f32)>, // the size of the text in pixels, cleared when the text or size changes
 
     alignment: (wgpu_glyph::VerticalAlign, wgpu_glyph::HorizontalAlign),
+    color: Color,
+    opacity: f32, // multiplied into the color's alpha
     enabled: bool,
+    visible: bool,
+    text_binding: Option<TextBinding>, // the value the text follows, if it's bound
+    text_key: Option<TextKey>, // the locale string shown instead of fixed text
+    key_resolved: bool, // false until the key has been looked up in the current locale
+    section: Option<SectionLayout>, // where and how the text is drawn, cleared when a property it depends on changes
+}
+
+// Returns the label's new text, if the value it's bound to has changed
+type TextBinding = Box<dyn FnMut() -> Option<String>>;
+
+// The parts of a label's section that don't change from frame to frame, worked out in `prerender` so drawing the text
+// doesn't redo them (the glyph brush caches the glyph positions itself, as long as the section stays the same)
+#[derive(Clone, Copy)]
+struct SectionLayout{
+    screen_position: (f32, f32),
+    bounds: (f32, f32),
+    color: [f32; 4],
+    layout: wgpu_glyph::Layout<wgpu_glyph::BuiltInLineBreaker>,
 }
 
 impl Label{
     /// Create a new `Label` struct
-    pub fn new<S: Into<String> + Copy>(content: S, size: f32, pos: [f32; 2]) -> Self{
+    pub fn new<S: Into<String>>(content: S, size: f32, pos: [f32; 2]) -> Self{
         Self{
             content: content.into(),
             size,
             pos,
+            rect: None,
+            measured: None,
             alignment: (wgpu_glyph::VerticalAlign::Top, wgpu_glyph::HorizontalAlign::Left),
+            color: Color::BLACK,
+            opacity: 1.0,
             enabled: true,
+            visible: true,
+            text_binding: None,
+            text_key: None,
+            key_resolved: false,
+            section: None,
+        }
+    }
+
+    /// Change the text of the label. It's measured again the next time it's drawn (if it actually changed).
+    pub fn set_text<S: Into<String>>(&mut self, content: S){
+        self.text_key = None;
+        self.replace_text(content);
+    }
+
+    // Change the text, keeping the text key
+    fn replace_text<S: Into<String>>(&mut self, content: S){
+        let content = content.into();
+        if content != self.content{
+            self.content = content;
+            self.measured = None;
         }
     }
 
+    /// Get the text of the label
+    pub fn text(&self) -> &str{
+        &self.content
+    }
+
+    /// Show the string for `key` in the renderer's locale (see `locale`), instead of fixed text. It's looked up before the
+    /// label is next drawn, and again whenever the locale changes. Setting text with `set_text` replaces the key.
+    pub fn set_text_key<K: Into<TextKey>>(&mut self, key: K){
+        self.text_key = Some(key.into());
+        self.key_resolved = false;
+    }
+
+    /// The text key the label shows, if it has one
+    pub fn text_key(&self) -> Option<&TextKey>{
+        self.text_key.as_ref()
+    }
+
+    /// Make the text follow `text`. Changes are applied before the next frame is drawn, replacing any text set since.
+    pub fn bind_text(&mut self, text: &Observable<String>){
+        self.bind_text_with(text, String::clone);
+    }
+
+    /// Make the text follow `value`, shown with `format` (eg, a score as `format!("Score: {}", score)`)
+    pub fn bind_text_with<T: 'static, F: Fn(&T) -> String + 'static>(&mut self, value: &Observable<T>, format: F){
+        let mut binding = value.bind();
+        self.text_binding = Some(Box::new(move || binding.poll_with(&format)));
+    }
+
+    /// Stop the text following a value. It keeps the text it has.
+    pub fn unbind_text(&mut self){
+        self.text_binding = None;
+    }
+
+    /// Change the size (in pixels) the text is drawn at
+    pub fn set_size(&mut self, size: f32){
+        if size != self.size{
+            self.size = size;
+            self.measured = None;
+        }
+    }
+
+    /// Get the size (in pixels) the text is drawn at
+    pub fn get_size(&self) -> f32{
+        self.size
+    }
+
+    /// The size of the text in pixels, as measured the last time the label was drawn. Returns `None` if the text or size
+    /// has changed since (or it hasn't been drawn yet).
+    pub fn measured_size(&self) -> Option<(f32, f32)>{
+        self.measured
+    }
+
     /// Change the vertical alignment of the label
     pub fn align_vertical(&mut self, alignment: wgpu_glyph::VerticalAlign){
         self.alignment.0 = alignment;
+        self.section = None;
     }
 
     /// Change the horizontal alignment of the label
     pub fn align_horizontal(&mut self, alignment: wgpu_glyph::HorizontalAlign){
         self.alignment.1 = alignment;
+        self.section = None;
+    }
+
+    /// Change the color of the label's text
+    pub fn set_color(&mut self, color: Color){
+        self.color = color;
+        self.section = None;
+    }
+
+    /// Get the color of the label's text
+    pub fn get_color(&self) -> Color{
+        self.color
+    }
+
+    /// Change how opaque the text is, from 0 (invisible) to 1 (the default). This is multiplied with the color's alpha.
+    pub fn set_opacity(&mut self, opacity: f32){
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self.section = None;
+    }
+
+    /// Get how opaque the text is
+    pub fn get_opacity(&self) -> f32{
+        self.opacity
     }
 
     pub fn enable(&mut self){
         self.enabled = true;
+        self.section = None;
     }
 
     pub fn disable(&mut self){
         self.enabled = false;
+        self.section = None;
+    }
+
+    /// Show or hide the label
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+
+    // The color the text is drawn in - greyed out like a disabled button's tint while disabled
+    fn text_color(&self) -> Color{
+        let color = if self.enabled{
+            self.color
+        }else{
+            self.color.mix(Color::GRAY, 0.75).with_alpha(self.color.a * 0.5)
+        };
+        color.with_alpha(color.a * self.opacity)
+    }
+
+    /// Move the label's anchor to `pos`, in pixels from the top left of the window
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.section = None;
+    }
+
+    /// Align the text within a rect, as [left, top, width, height] in pixels from the top left of the window
+    /// (using the label's alignment), or go back to anchoring it at its position with `None`
+    pub fn set_rect(&mut self, rect: Option<[f32; 4]>){
+        self.rect = rect;
+        self.section = None;
     }
 
-    pub fn set_pos(&mut self, pos: [f32; 2], screen_dim: (u32, u32)){
-        self.pos = [(pos[0] + (screen_dim.0/2) as f32), (pos[1] + (screen_dim.1/2) as f32)];
+    /// The rect the text is aligned within, if any
+    pub fn get_rect(&self) -> Option<[f32; 4]>{
+        self.rect
+    }
+
+    /// The point the text is anchored at, in pixels from the top left of the window - the position, or the point of
+    /// the rect picked by the alignment (eg, the center of the rect for centered text)
+    pub fn anchor(&self) -> [f32; 2]{
+        let [left, top, width, height] = match self.rect{
+            Some(rect) => rect,
+            None => return self.pos,
+        };
+        let x = match self.alignment.1{
+            HorizontalAlign::Left => left,
+            HorizontalAlign::Center => left + width / 2.0,
+            HorizontalAlign::Right => left + width,
+        };
+        let y = match self.alignment.0{
+            VerticalAlign::Top => top,
+            VerticalAlign::Center => top + height / 2.0,
+            VerticalAlign::Bottom => top + height,
+        };
+        [x, y]
+    }
+
+    // Work out where and how the text is drawn
+    fn section_layout(&self) -> SectionLayout{
+        let anchor = self.anchor();
+        SectionLayout{
+            screen_position: (anchor[0], anchor[1]),
+            // Text wraps at the rect's width
+            bounds: self.rect.map_or((f32::INFINITY, f32::INFINITY), |rect| (rect[2], rect[3])),
+            color: self.text_color().to_linear(),
+            layout: wgpu_glyph::Layout::default().v_align(self.alignment.0).h_align(self.alignment.1),
+        }
     }
 }
 
-impl TextGUIComponent for Label{
-    fn render_text<'a, 'b>(&'a self, brush: &'b mut wgpu_glyph::GlyphBrush<()>)
+impl Component for Label{
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        let changed = self.color != theme.colors.text;
+        self.set_color(theme.colors.text);
+        changed
+    }
+
+    fn update(&mut self, _delta_time: Duration){
+        if let Some(text) = self.text_binding.as_mut().and_then(|binding| binding()){
+            self.set_text(text);
+        }
+    }
+
+    fn animated_value(&self, property: AnimatedProperty) -> Option<AnimatedValue>{
+        match property{
+            AnimatedProperty::Position => Some(AnimatedValue::Position(self.pos)),
+            AnimatedProperty::Opacity => Some(AnimatedValue::Opacity(self.opacity)),
+            AnimatedProperty::Color => Some(AnimatedValue::Color(self.color)),
+            AnimatedProperty::Scale => None,
+        }
+    }
+
+    fn set_animated_value(&mut self, value: AnimatedValue){
+        match value{
+            AnimatedValue::Position(pos) => self.set_pos(pos),
+            AnimatedValue::Opacity(opacity) => self.set_opacity(opacity),
+            AnimatedValue::Color(color) => self.set_color(color),
+            AnimatedValue::Scale(_) => {}
+        }
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        Some(ComponentState{
+            // Text shown from a key is looked up again instead
+            text: if self.text_key.is_none() { Some(self.content.clone()) } else { None },
+            position: Some(self.pos),
+            color: Some(self.color),
+            visible: Some(self.visible),
+            enabled: Some(self.enabled),
+            ..ComponentState::default()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        if let Some(text) = &state.text{
+            self.set_text(text.as_str());
+        }
+        if let Some(position) = state.position{
+            self.set_pos(position);
+        }
+        if let Some(color) = state.color{
+            self.set_color(color);
+        }
+        if let Some(visible) = state.visible{
+            self.set_visible(visible);
+        }
+        if let Some(enabled) = state.enabled{
+            if enabled { self.enable() } else { self.disable() }
+        }
+    }
+
+    fn apply_locale(&mut self, _locale: &Locale) -> bool{
+        // The key is looked up in `prerender`, which gets the new locale
+        self.key_resolved = false;
+        self.text_key.is_some()
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        if let (Some(key), false) = (&self.text_key, self.key_resolved){
+            let text = context.locale.resolve(key);
+            self.replace_text(text);
+            self.key_resolved = true;
+        }
+        if self.measured.is_none(){
+            self.measured = Some(context.measure_text(&self.content, self.size));
+        }
+        if self.section.is_none(){
+            self.section = Some(self.section_layout());
+        }
+    }
+
+    fn draw_bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        if let Some(rect) = self.rect{
+            return Some(rect);
+        }
+        // Until it's measured, it's drawn wherever it is
+        let (width, height) = self.measured?;
+        let left = match self.alignment.1{
+            HorizontalAlign::Left => self.pos[0],
+            HorizontalAlign::Center => self.pos[0] - width / 2.0,
+            HorizontalAlign::Right => self.pos[0] - width,
+        };
+        let top = match self.alignment.0{
+            VerticalAlign::Top => self.pos[1],
+            VerticalAlign::Center => self.pos[1] - height / 2.0,
+            VerticalAlign::Bottom => self.pos[1] - height,
+        };
+        Some([left, top, width, height])
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue)
     where 'a: 'b {
-        if self.enabled{
-            brush.queue(
+        if self.visible{
+            // Labels drawn without a `prerender` (eg, inside a component that doesn't forward it) work it out here
+            let section = self.section.unwrap_or_else(|| self.section_layout());
+            queue.queue(
                 wgpu_glyph::Section {
-                    screen_position: (self.pos[0], self.pos[1]),
-                    text: vec![wgpu_glyph::Text::new(&self.content).with_color([0.0, 0.0, 0.0, 1.0]).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size))],
-                    layout: wgpu_glyph::Layout::default().v_align(self.alignment.0).h_align(self.alignment.1),
-                    ..wgpu_glyph::Section::default()
+                    screen_position: section.screen_position,
+                    bounds: section.bounds,
+                    text: vec![wgpu_glyph::Text::new(&self.content).with_color(section.color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size))],
+                    layout: section.layout,
                 }
-                
             )
         }
     }
@@ -127,50 +580,231 @@ impl TextGUIComponent for Label{
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        Label::set_visible(self, visible);
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        if enabled { self.enable() } else { self.disable() }
+    }
+
+    fn glyph_count(&self) -> usize{
+        if self.visible{
+            self.content.chars().filter(|c| !c.is_whitespace()).count()
+        }else{
+            0
+        }
+    }
+
+    fn get_text(&self) -> Option<&str>{
+        Some(&self.content)
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(AccessInfo::named(Role::Label, self.content.clone()))
+    }
+}
+
+
+/// The state a button is drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState{
+    Normal,
+    /// The cursor is over the button
+    Hovered,
+    /// The button is being clicked - the left mouse button was pressed on it, and the cursor is still over it
+    Pressed,
+    /// The button is disabled, so it ignores clicks
+    Disabled,
+}
+
+/// # ButtonStyle
+///
+/// The tint a button is drawn with in each of its states (see `ButtonState`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonStyle{
+    pub normal: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+}
+
+impl ButtonStyle{
+    /// Create a style from a single tint - lighter when hovered, darker when pressed, and greyed out when disabled
+    pub fn from_tint(tint: Color) -> Self{
+        Self{
+            normal: tint,
+            hovered: tint.mix(Color::WHITE, 0.25),
+            pressed: tint.mix(Color::BLACK, 0.25),
+            disabled: tint.mix(Color::GRAY, 0.75).with_alpha(tint.a * 0.5),
+        }
+    }
+
+    /// The tint for a state
+    pub fn tint(&self, state: ButtonState) -> Color{
+        match state{
+            ButtonState::Normal => self.normal,
+            ButtonState::Hovered => self.hovered,
+            ButtonState::Pressed => self.pressed,
+            ButtonState::Disabled => self.disabled,
+        }
+    }
+}
+
+impl Default for ButtonStyle{
+    fn default() -> Self{
+        Self::from_tint(Color::RED)
+    }
+}
+
+/// # ButtonSkin
+///
+/// Images to draw a button with, instead of a flat tint (see `Button::set_skin`). A state without its own image uses the normal one.
+///
+/// The button's tint for the state (see `ButtonStyle`) is multiplied over the image, so give the button a white style
+/// (`ButtonStyle::from_tint(Color::WHITE)`) to draw the images as they are, and still darken them when pressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ButtonSkin{
+    normal: TextureRegion,
+    hovered: Option<TextureRegion>,
+    pressed: Option<TextureRegion>,
+    disabled: Option<TextureRegion>,
+}
+
+impl ButtonSkin{
+    /// Create a skin that draws every state with `normal` (a texture, or a region of one)
+    pub fn new<R: Into<TextureRegion>>(normal: R) -> Self{
+        Self{
+            normal: normal.into(),
+            hovered: None,
+            pressed: None,
+            disabled: None,
+        }
+    }
+
+    /// Set the image drawn while the cursor is over the button
+    pub fn set_hovered<R: Into<TextureRegion>>(&mut self, region: R) -> &mut Self{
+        self.hovered = Some(region.into());
+        self
+    }
+
+    /// Set the image drawn while the button is pressed
+    pub fn set_pressed<R: Into<TextureRegion>>(&mut self, region: R) -> &mut Self{
+        self.pressed = Some(region.into());
+        self
+    }
+
+    /// Set the image drawn while the button is disabled
+    pub fn set_disabled<R: Into<TextureRegion>>(&mut self, region: R) -> &mut Self{
+        self.disabled = Some(region.into());
+        self
+    }
+
+    /// The image drawn in a state
+    pub fn region(&self, state: ButtonState) -> &TextureRegion{
+        let region = match state{
+            ButtonState::Normal => None,
+            ButtonState::Hovered => self.hovered.as_ref(),
+            ButtonState::Pressed => self.pressed.as_ref(),
+            ButtonState::Disabled => self.disabled.as_ref(),
+        };
+        region.unwrap_or(&self.normal)
+    }
 }
 
+// The GPU side of a button's skin - a bind group and quad (with the region's texture coordinates) for each state
+struct SkinParts{
+    skin: ButtonSkin,
+    pipeline: Rc<wgpu::RenderPipeline>,
+    states: Vec<(wgpu::BindGroup, Tracked<wgpu::Buffer>)>,
+}
+
+const BUTTON_STATES: [ButtonState; 4] = [ButtonState::Normal, ButtonState::Hovered, ButtonState::Pressed, ButtonState::Disabled];
+
 
 /// # Button
 ///
-/// A button component. It implements a label struct as a child.
+/// A button component. It owns a label as a child, which it keeps centered on the button.
 /// All buttons run through the event handler (not the user defined one),
 /// so inputs are registered.
 ///
-/// The button also contains the callback to run when the button is pressed.
+/// The button also contains the callback to run when the button is pressed. Most buttons only need to know when they're
+/// clicked, so `on_click` takes a handler that runs once per completed click (pressed and released on the button).
+/// The raw callback, which gets every event, is there for anything more advanced.
+///
+/// Buttons can take keyboard focus. Pressing Space (or a gamepad's activate button) while one has focus runs the
+/// callbacks as if it was clicked - with a left `MouseUp` at the button's center, and the cursor in bounds.
+///
+/// The button is tinted by its state - normal, hovered, pressed or disabled (see `ButtonStyle`). Disabled buttons
+/// are still drawn, but don't take clicks. Hide a button with `set_visible`, or let clicks through it with `set_hit_test`. Buttons can be drawn with images instead of a flat tint (see `ButtonSkin`).
 ///
 /// This is designed to be a simple, no frills button. If you want to implement animated buttons,
 /// feel free to make your own components
 pub struct Button{
     transform: Transform, // position scale and rot
-    callback: Option<Box<dyn Fn(&winit::event::Event<()>, &Window, &bool, &mut bool) -> ()>>, // func to run when clicked
-    cursor_in_bounds: bool, // tells us if the cursor is in bounds of the button
-    vertex_buffer: wgpu::Buffer, // the vertex buffer that stores the verticies of,
+    callback: Option<ButtonCallback>, // func to run with every event
+    click_handler: Option<ClickCallback>, // func to run when clicked
+    cursor_in_bounds: bool, // tells us if the cursor is in bounds of the button (set by the layout's hit test)
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>, // the renderer's shared quad (see `Renderer::quad_buffer`)
+    style: ButtonStyle, // the colors the button is drawn with in each state
+    opacity: f32, // multiplied into the tint's alpha (and the label's)
+    applied_tint: Color, // the tint currently in the tint buffer
+    tint_buffer: Tracked<wgpu::Buffer>,
+    tint_bind_group: wgpu::BindGroup,
+    hover_cursor: Option<CursorIcon>, // the cursor shown while hovering the button
     enabled: bool,
-    attached_text_id: Option<usize>,
+    visible: bool,
+    hit_test: bool, // whether the button can be hovered and clicked
+    clicks: ClickTracker, // tracks presses, to find completed clicks
+    focused: bool,
+    label: Option<Label>,
+    skin: Option<SkinParts>,
 }
 
 
 
 impl Button{
-    pub fn new(transform: Transform, callback: Option<Box<dyn Fn(&winit::event::Event<()>, &Window, &bool, &mut bool) -> ()>>, renderer: &Renderer, text: Option<&str>, text_size: f32, layout: &mut Layout) -> Self{
-        let mut attached_text_id = None;
-        // We now define the text to render with the button
-        if let Some(button_text) = text{
+    pub fn new(transform: Transform, callback: Option<ButtonCallback>, renderer: &Renderer, text: Option<&str>, text_size: f32) -> Self{
+        // We now define the text to render with the button - it gets moved to the button's position every frame
+        let label = text.map(|button_text| {
             let mut text_label = Label::new(button_text, text_size, [0.0, 0.0]);
             text_label.align_horizontal(HorizontalAlign::Center);
             text_label.align_vertical(VerticalAlign::Center);
-    
-            // We add the text to our layout - make sure we grab the ID!
-            attached_text_id = Some(layout.add_text_component(Box::new(text_label)));
-        }
+            text_label.set_color(renderer.theme().colors.text);
+            text_label
+        });
+        
+        let style = ButtonStyle::from_tint(renderer.theme().colors.accent);
+        let (tint_buffer, tint_bind_group, _) = renderer.bind_group_cache().create_uniform(wgpu::ShaderStage::FRAGMENT, 0, &style.normal.to_linear(), "Button tint");
         
         Self{
             transform,
             callback,
+            click_handler: None,
             cursor_in_bounds: false,
-            vertex_buffer: create_buffers(&renderer.device),
+            vertex_buffer: renderer.quad_buffer(),
+            style,
+            opacity: 1.0,
+            applied_tint: style.normal,
+            tint_buffer,
+            tint_bind_group,
+            hover_cursor: Some(CursorIcon::Hand),
             enabled: true,
-            attached_text_id
+            visible: true,
+            hit_test: true,
+            clicks: ClickTracker::new(),
+            focused: false,
+            label,
+            skin: None,
         }
     }
 
@@ -179,70 +813,320 @@ impl Button{
     }
     pub fn disable(&mut self){
         self.enabled = false;
+        self.clicks.cancel();
+    }
+
+    /// Show or hide the button (and its text)
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+        if !visible{
+            self.clicks.cancel();
+        }
     }
 
-    pub fn update_text(&self, layout: &mut Layout, screen_dim: (u32, u32)){
-        if self.attached_text_id.is_some(){
-            layout.borrow_text_component_as_type_mut::<Label>(self.attached_text_id.unwrap()).unwrap().pos = [(self.transform.position.x + (screen_dim.0 / 2) as f32), (self.transform.position.y + (screen_dim.1 / 2) as f32)];
+    /// Choose whether the button can be hovered and clicked. With `false`, the mouse goes through it to whatever is below.
+    pub fn set_hit_test(&mut self, hit_test: bool){
+        self.hit_test = hit_test;
+        if !hit_test{
+            self.clicks.cancel();
         }
     }
 
     pub fn has_text(&self) -> bool{
-        self.attached_text_id.is_some()
+        self.label.is_some()
+    }
+
+    /// Borrow the button's transform
+    pub fn transform(&self) -> &Transform{
+        &self.transform
+    }
+
+    /// Borrow the button's transform mutably, to move, rotate or scale it
+    pub fn transform_mut(&mut self) -> &mut Transform{
+        &mut self.transform
+    }
+
+    /// Borrow the button's label, if it has text
+    pub fn label(&self) -> Option<&Label>{
+        self.label.as_ref()
+    }
+
+    /// Borrow the button's label mutably (eg, to change its color), if it has text
+    pub fn label_mut(&mut self) -> Option<&mut Label>{
+        self.label.as_mut()
+    }
+
+    /// Show the string for `key` in the renderer's locale on the button (see `Label::set_text_key`). Buttons created
+    /// without text have no label to show it, so this does nothing for them.
+    pub fn set_text_key<K: Into<TextKey>>(&mut self, key: K){
+        if let Some(label) = &mut self.label{
+            label.set_text_key(key);
+        }
+    }
+
+    /// Change the color the button is drawn with. The other states get lighter, darker and greyed out versions of it
+    /// (see `ButtonStyle::from_tint`).
+    pub fn set_tint(&mut self, tint: Color, renderer: &Renderer){
+        self.set_style(ButtonStyle::from_tint(tint), renderer);
+    }
+
+    /// Get the color the button is drawn with, in its current state
+    pub fn get_tint(&self) -> Color{
+        self.style.tint(self.state())
+    }
+
+    /// Change the colors the button is drawn with in each state
+    pub fn set_style(&mut self, style: ButtonStyle, renderer: &Renderer){
+        self.style = style;
+        self.apply_tint(&renderer.queue);
+    }
+
+    /// Get the colors the button is drawn with in each state
+    pub fn get_style(&self) -> ButtonStyle{
+        self.style
+    }
+
+    /// Change how opaque the button and its text are, from 0 (invisible) to 1 (the default). The new tint is written
+    /// the next time the button is drawn.
+    pub fn set_opacity(&mut self, opacity: f32){
+        self.opacity = opacity.clamp(0.0, 1.0);
+        if let Some(label) = &mut self.label{
+            label.set_opacity(self.opacity);
+        }
+    }
+
+    /// Get how opaque the button is
+    pub fn get_opacity(&self) -> f32{
+        self.opacity
+    }
+
+    /// Run `handler` once every time the button is clicked - pressed and released with the left mouse button while the
+    /// cursor is over it, or activated from the keyboard while it has focus. This replaces any previous click handler.
+    pub fn on_click<F: FnMut(&mut ClickContext) + 'static>(&mut self, handler: F) -> &mut Self{
+        self.click_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Draw the button with images instead of a flat tint, or go back to the flat tint with `None`
+    pub fn set_skin(&mut self, skin: Option<ButtonSkin>, renderer: &Renderer){
+        self.skin = skin.map(|skin| {
+            let states = BUTTON_STATES.iter().map(|state| {
+                let region = skin.region(*state);
+                let bind_group = renderer.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &renderer.skin_bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(region.texture().view()),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(region.texture().sampler()),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Buffer(self.tint_buffer.slice(..)),
+                        },
+                    ],
+                    label: Some("Button skin bind group"),
+                });
+                (bind_group, create_region_buffer(&renderer.device, region.uv()))
+            }).collect();
+
+            SkinParts{
+                skin,
+                pipeline: renderer.skin_pipeline.clone(),
+                states,
+            }
+        });
+    }
+
+    /// Get the images the button is drawn with, if it has a skin
+    pub fn get_skin(&self) -> Option<&ButtonSkin>{
+        self.skin.as_ref().map(|parts| &parts.skin)
+    }
+
+    /// The state the button is drawn in
+    pub fn state(&self) -> ButtonState{
+        if !self.enabled{
+            ButtonState::Disabled
+        }else if self.clicks.is_pressed() && self.cursor_in_bounds{
+            ButtonState::Pressed
+        }else if self.cursor_in_bounds{
+            ButtonState::Hovered
+        }else{
+            ButtonState::Normal
+        }
+    }
+
+    /// Returns true if the cursor is over the button
+    pub fn is_cursor_in_bounds(&self) -> bool{
+        self.cursor_in_bounds
+    }
+
+    // Keyboard (and accessibility) activation pretends the button was clicked at its center, so callbacks don't need to know about it
+    fn activate(&mut self, window: &Window, state: &mut AppState){
+        let pos = (self.transform.world_position().x as f64, self.transform.world_position().y as f64);
+        if let Some(v) = &mut self.callback{
+            v(&GuiEvent::MouseUp{ button: MouseButton::Left, pos }, window, &true, &mut self.enabled, state);
+        }
+        if let (true, Some(handler)) = (self.enabled, &mut self.click_handler){
+            handler(&mut ClickContext{ window, state, enabled: &mut self.enabled, pos });
+        }
+    }
+
+    /// Set the cursor shown while the mouse is over the button (a pointing hand by default), or `None` to keep the normal cursor
+    pub fn set_hover_cursor(&mut self, cursor: Option<CursorIcon>){
+        self.hover_cursor = cursor;
+    }
+
+    // Write the tint for the current state to the tint buffer, if it changed
+    fn apply_tint(&mut self, queue: &wgpu::Queue){
+        let tint = self.get_tint();
+        let tint = tint.with_alpha(tint.a * self.opacity);
+        if tint != self.applied_tint{
+            queue.write_buffer(&self.tint_buffer, 0, bytemuck::cast_slice(&[tint.to_linear()]));
+            self.applied_tint = tint;
+        }
     }
 }
 
 
-impl EventGUIComponent for Button{
+impl Component for Button{
     fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
     where 'a: 'b {
-        if self.enabled{
-            render_pass.set_bind_group(1, &self.transform.bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.draw(0..6, 0..1);
-        }
-    }
-
-    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
-        match event{
-            winit::event::Event::WindowEvent {
-                ref event,
-                window_id,
-                ..
-            } if (&window.id() == window_id) => {
-                match event{
-                    winit::event::WindowEvent::CursorMoved{mut position, ..} => {
-                        // Convert window space into WGPU (dx) space
-                        position.x -= (window.inner_size().width/2) as f64;
-                        position.y -= (window.inner_size().height/2) as f64;
-
-                        // Simple and fast check for collision with mouse - I don't know how I got these values,
-                        // I was trying anything to see what stuck
-                        if ((self.transform.position.x - ((self.transform.scale.x*2.0) * (window.inner_size().width/2) as f32) / 2.0) as f64) < position.x 
-                        && ((self.transform.position.y - ((self.transform.scale.y*2.0) * (window.inner_size().height/2) as f32) / 2.0) as f64) < position.y{
-                            self.cursor_in_bounds = ((self.transform.position.x + ((self.transform.scale.x*2.0) * (window.inner_size().width/2) as f32) / 2.0) as f64) > position.x 
-                                                 && ((self.transform.position.y + ((self.transform.scale.y*2.0) * (window.inner_size().height/2) as f32) / 2.0) as f64) > position.y;
-
-                        }else{
-                            self.cursor_in_bounds = false;
-                        }
-        
-                                    
+        self.transform.bind(render_pass);
+        match &self.skin{
+            Some(parts) => {
+                let state = BUTTON_STATES.iter().position(|state| *state == self.state()).unwrap_or(0);
+                let (bind_group, vertex_buffer) = &parts.states[state];
+                render_pass.set_pipeline(&parts.pipeline);
+                render_pass.set_bind_group(2, bind_group, &[]);
+                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            }
+            None => {
+                render_pass.set_bind_group(2, &self.tint_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            }
+        }
+        render_pass.draw(0..6, 0..1);
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue)
+    where 'a: 'b {
+        if let Some(label) = &self.label{
+            label.queue_text(queue);
+        }
+    }
+
+    fn update(&mut self, delta_time: Duration){
+        if let Some(label) = &mut self.label{
+            label.update(delta_time);
+        }
+    }
 
-                        
-                    }
-                
-                    _ => {}
+    fn animated_value(&self, property: AnimatedProperty) -> Option<AnimatedValue>{
+        let value = match property{
+            AnimatedProperty::Position => AnimatedValue::Position(self.get_pos()),
+            AnimatedProperty::Scale => AnimatedValue::Scale([self.transform.scale().x, self.transform.scale().y]),
+            AnimatedProperty::Opacity => AnimatedValue::Opacity(self.opacity),
+            AnimatedProperty::Color => AnimatedValue::Color(self.style.normal),
+        };
+        Some(value)
+    }
+
+    fn set_animated_value(&mut self, value: AnimatedValue){
+        // The transform and tint are written in `prerender`
+        match value{
+            AnimatedValue::Position([x, y]) => {
+                let z = self.transform.position().z;
+                self.transform.set_position(cgmath::Vector3::new(x, y, z));
+            }
+            AnimatedValue::Scale([x, y]) => {
+                let z = self.transform.scale().z;
+                self.transform.set_scale(cgmath::Vector3::new(x, y, z));
             }
+            AnimatedValue::Opacity(opacity) => self.set_opacity(opacity),
+            AnimatedValue::Color(color) => self.style = ButtonStyle::from_tint(color),
+        }
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        // The new tint is written in `prerender`
+        let style = ButtonStyle::from_tint(theme.colors.accent);
+        let mut changed = self.style != style;
+        self.style = style;
+        if let Some(label) = &mut self.label{
+            changed |= label.apply_theme(theme);
+        }
+        changed
+    }
+
+    fn apply_locale(&mut self, locale: &Locale) -> bool{
+        self.label.as_mut().is_some_and(|label| label.apply_locale(locale))
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        Some(ComponentState{
+            text: self.label.as_ref().filter(|label| label.text_key().is_none()).map(|label| label.text().to_string()),
+            transform: Some(TransformState::from_transform(&self.transform)),
+            visible: Some(self.visible),
+            enabled: Some(self.enabled),
+            ..ComponentState::default()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        if let (Some(text), Some(label)) = (&state.text, &mut self.label){
+            label.set_text(text.as_str());
+        }
+        if let Some(transform) = &state.transform{
+            transform.apply(&mut self.transform);
+        }
+        if let Some(visible) = state.visible{
+            self.set_visible(visible);
         }
+        if let Some(enabled) = state.enabled{
+            if enabled { self.enable() } else { self.disable() }
+        }
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        // Keep the label centered in the button, which moves with the window size
+        let bounds = self.bounds(context.size);
+        if let Some(label) = &mut self.label{
+            label.set_rect(bounds);
+            label.prerender(context);
+        }
+        self.apply_tint(context.queue);
+        self.transform.write_uniform();
+    }
 
-            _ => {}
+    fn handle_event(&mut self, event: &GuiEvent, window: &winit::window::Window, state: &mut AppState) -> EventResult{
+        // The layout keeps `cursor_in_bounds` up to date (see `on_hover_enter`) before the event gets here
+        let activated = self.focused && matches!(event, GuiEvent::KeyDown{ key: VirtualKeyCode::Space, .. } | GuiEvent::Navigate(NavAction::Activate));
+        // Clicks on the button don't go through it to whatever is behind
+        let clicked = self.enabled && self.cursor_in_bounds && matches!(event,
+            GuiEvent::MouseDown{ .. } | GuiEvent::MouseUp{ .. } | GuiEvent::DoubleClick{ .. } | GuiEvent::TripleClick{ .. }
+        );
+        // Track the press, so the button can be drawn pressed, and knows when a click completes
+        let completed = self.clicks.update(event, self.enabled && self.cursor_in_bounds);
+
+        if activated{
+            self.activate(window, state);
+        }else{
+            // We now callback the user callback
+            if let Some(v) = &mut self.callback{
+                v(event, window, &self.cursor_in_bounds, &mut self.enabled, state);
+            }
+            if let (true, true, Some(handler), GuiEvent::MouseUp{ pos, .. }) = (completed, self.enabled, &mut self.click_handler, event){
+                handler(&mut ClickContext{ window, state, enabled: &mut self.enabled, pos: *pos });
+            }
         }
-        // We now callback the user callback
-        match &self.callback{
-            Some(v) => { v(event, &window, &self.cursor_in_bounds, &mut self.enabled);},
-            None => {}
-        };       
+        if !self.enabled{
+            self.clicks.cancel();
+        }
+        EventResult::consumed_if(activated || clicked)
     }
 
     fn as_any(&self) -> &dyn Any{
@@ -253,29 +1137,124 @@ impl EventGUIComponent for Button{
         self
     }
 
-    fn get_text_id(&self) -> Option<usize> {
-        self.attached_text_id
-    }
-
     fn is_enabled(&self) -> bool{
         self.enabled
     }
 
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        Button::set_visible(self, visible);
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        if enabled { self.enable() } else { self.disable() }
+    }
+
+    fn is_hit_testable(&self) -> bool{
+        self.hit_test
+    }
+
     fn get_pos(&self) -> [f32; 2]{
-        [self.transform.position.x, self.transform.position.y]
+        [self.transform.position().x, self.transform.position().y]
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        // The plain quad is the renderer's, so only the tint is ours
+        report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64); // Tint
+        if let Some(parts) = &self.skin{
+            // The skin's textures can be shared with other components, so only its quads are counted
+            for _ in parts.states.iter(){
+                report.add_buffer(std::mem::size_of_val(crate::rendering::QUAD) as u64);
+            }
+        }
+    }
+
+    fn glyph_count(&self) -> usize{
+        self.label.as_ref().map_or(0, |label| label.glyph_count())
+    }
+
+    fn get_text(&self) -> Option<&str>{
+        self.label.as_ref().and_then(|label| label.get_text())
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(match self.get_text(){
+            Some(text) => AccessInfo::named(Role::Button, text),
+            None => AccessInfo::new(Role::Button),
+        })
+    }
+
+    fn access_action(&mut self, action: &AccessAction, window: &winit::window::Window, state: &mut AppState) -> bool{
+        if *action != AccessAction::Click || !self.enabled{
+            return false;
+        }
+        self.activate(window, state);
+        true
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        if self.enabled && self.cursor_in_bounds{
+            self.hover_cursor
+        }else{
+            None
+        }
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.transform.rect())
+    }
+
+    fn on_hover_enter(&mut self){
+        self.cursor_in_bounds = true;
+    }
+
+    fn on_hover_exit(&mut self){
+        self.cursor_in_bounds = false;
+    }
+
+    fn is_focusable(&self) -> bool{
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+
+    fn has_focus(&self) -> bool{
+        self.focused
     }
 }
 
+// A quad buffer that maps `uv` (a [left, top, width, height] part of a texture) onto the quad
+fn create_region_buffer(device: &wgpu::Device, uv: [f32; 4]) -> Tracked<wgpu::Buffer>{
+    let mut vertices = crate::rendering::QUAD.to_vec();
+    for vertex in vertices.iter_mut(){
+        vertex.tex_coords = [uv[0] + vertex.tex_coords[0] * uv[2], uv[1] + vertex.tex_coords[1] * uv[3]];
+    }
+    let buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Region Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsage::VERTEX,
+        }
+    );
+    Tracked::new(buffer, MemoryKind::VertexBuffer, std::mem::size_of_val(vertices.as_slice()) as u64)
+}
+
 /// Helpful function to automatically create a new quad buffer for all your GUI needs.
-pub fn create_buffers(device: &wgpu::Device) -> wgpu::Buffer{
+/// Components drawing the plain quad should share `Renderer::quad_buffer` instead.
+pub fn create_buffers(device: &wgpu::Device) -> Tracked<wgpu::Buffer>{
     // Create the vertex buffer (so we can draw to it)
-    device.create_buffer_init(
+    let buffer = device.create_buffer_init(
         &wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
             contents: bytemuck::cast_slice(crate::rendering::QUAD),
             usage: wgpu::BufferUsage::VERTEX,
         }
-    )
-
+    );
+    Tracked::new(buffer, MemoryKind::VertexBuffer, std::mem::size_of_val(crate::rendering::QUAD) as u64)
 }
 
@@ -0,0 +1,223 @@
+//! This module defines `ButtonBar`, a helper that lays out dialog buttons (OK, Cancel, Apply and so on).
+//! It orders them the way the current platform expects, gives them all the same size (based on the
+//! widest label), and lets Enter/Escape trigger the default and cancel buttons.
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use winit::window::Window;
+
+use crate::{event::{EventResult, GuiEvent, NavAction, VirtualKeyCode}, layout::Layout, rendering::{Renderer, Transform}, state::AppState};
+
+use super::{Button, Component};
+
+/// The action a button bar button runs, either when clicked or through its keyboard shortcut.
+/// It's shared between the button and the shortcut, so it's kept in a `RefCell` to be called mutably.
+pub type ButtonBarAction = Rc<RefCell<dyn FnMut(&Window, &mut AppState)>>;
+
+/// The role of a button in a button bar. This decides where it's placed and which key triggers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonRole{
+    /// The affirmative button (OK, Save, Yes). Triggered by Enter.
+    Accept,
+    /// The button that dismisses the dialog (Cancel, No). Triggered by Escape.
+    Cancel,
+    /// Applies changes without closing the dialog
+    Apply,
+    /// Any other button (Help, Reset...). These go before the standard buttons.
+    Other,
+}
+
+/// The order buttons are placed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonOrder{
+    /// OK, Cancel, Apply - affirmative button first
+    Windows,
+    /// Apply, Cancel, OK - affirmative button last (also used by most Linux desktops)
+    MacOS,
+}
+
+impl ButtonOrder{
+    /// The order used by the platform we're running on
+    pub fn platform_default() -> Self{
+        if cfg!(target_os = "windows"){
+            ButtonOrder::Windows
+        }else{
+            ButtonOrder::MacOS
+        }
+    }
+
+    // Where a role goes in the bar, from left to right
+    fn rank(&self, role: ButtonRole) -> u8{
+        match (self, role){
+            (_, ButtonRole::Other) => 0,
+            (ButtonOrder::Windows, ButtonRole::Accept) => 1,
+            (ButtonOrder::Windows, ButtonRole::Cancel) => 2,
+            (ButtonOrder::Windows, ButtonRole::Apply) => 3,
+            (ButtonOrder::MacOS, ButtonRole::Apply) => 1,
+            (ButtonOrder::MacOS, ButtonRole::Cancel) => 2,
+            (ButtonOrder::MacOS, ButtonRole::Accept) => 3,
+        }
+    }
+}
+
+/// # ButtonBar
+///
+/// Builds a row of dialog buttons. Add buttons with their role and action, then call `build`
+/// to create the buttons in a layout.
+///
+/// ## Usage
+///
+/// Create a bar with `ButtonBar::new()`, add each button with `add_button(text, role, action)`,
+/// then call `build` with the layout the buttons should be added to.
+///
+/// The text size, padding and spacing come from the renderer's theme, unless they're set on the bar.
+pub struct ButtonBar{
+    buttons: Vec<(String, ButtonRole, ButtonBarAction)>,
+    order: ButtonOrder,
+    text_size: Option<f32>,
+    padding: Option<f32>,
+    spacing: Option<f32>,
+}
+
+impl Default for ButtonBar{
+    fn default() -> ButtonBar{
+        Self{
+            buttons: Vec::new(),
+            order: ButtonOrder::platform_default(),
+            text_size: None,
+            padding: None,
+            spacing: None,
+        }
+    }
+}
+
+impl ButtonBar{
+    /// Create a new, empty button bar using the platform's button order
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Add a button to the bar, with the action it runs. The action gets the window and the app's state.
+    pub fn add_button<S: Into<String>, F: FnMut(&Window, &mut AppState) + 'static>(&mut self, text: S, role: ButtonRole, action: F) -> &mut Self{
+        self.buttons.push((text.into(), role, Rc::new(RefCell::new(action))));
+        self
+    }
+
+    /// Override the platform button order
+    pub fn set_order(&mut self, order: ButtonOrder) -> &mut Self{
+        self.order = order;
+        self
+    }
+
+    /// Set the text size of the button labels
+    pub fn set_text_size(&mut self, text_size: f32) -> &mut Self{
+        self.text_size = Some(text_size);
+        self
+    }
+
+    /// Set the padding (in pixels) between a label and the edge of its button
+    pub fn set_padding(&mut self, padding: f32) -> &mut Self{
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Set the space (in pixels) between buttons
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self{
+        self.spacing = Some(spacing);
+        self
+    }
+
+    /// Returns the button labels and roles in the order they'll be placed, left to right
+    pub fn ordered(&self) -> Vec<(&str, ButtonRole)>{
+        let mut buttons: Vec<(&str, ButtonRole)> = self.buttons.iter().map(|(text, role, _)| (text.as_str(), *role)).collect();
+        // sort_by_key is stable, so buttons with the same role keep the order they were added in
+        buttons.sort_by_key(|(_, role)| self.order.rank(*role));
+        buttons
+    }
+
+    /// Create the buttons in `layout`, centered on `center` (in pixels from the top left of the window).
+    ///
+    /// Returns the component IDs of the buttons (left to right), followed by the ID of the component
+    /// that handles the Enter/Escape shortcuts.
+    pub fn build(&self, center: [f32; 2], renderer: &mut Renderer, layout: &mut Layout) -> Vec<usize>{
+        let mut buttons: Vec<&(String, ButtonRole, ButtonBarAction)> = self.buttons.iter().collect();
+        buttons.sort_by_key(|(_, role, _)| self.order.rank(*role));
+        let theme = renderer.theme();
+        let text_size = self.text_size.unwrap_or(theme.text_sizes.body);
+        let padding = self.padding.unwrap_or(theme.spacing);
+        let spacing = self.spacing.unwrap_or(theme.spacing);
+
+        // Every button gets the size of the widest label
+        let mut widest = 0.0f32;
+        for (text, _, _) in buttons.iter(){
+            widest = widest.max(renderer.measure_text(text, text_size).0);
+        }
+        let button_width = widest + padding * 2.0;
+        let button_height = text_size + padding * 2.0;
+        let total_width = button_width * buttons.len() as f32 + spacing * buttons.len().saturating_sub(1) as f32;
+
+        let scale = cgmath::Vector3::<f32>::new(button_width, button_height, 1.0);
+
+        let mut ids = Vec::new();
+        let mut accept = None;
+        let mut cancel = None;
+        for (i, (text, role, action)) in buttons.iter().enumerate(){
+            let x = center[0] - total_width / 2.0 + button_width / 2.0 + (button_width + spacing) * i as f32;
+            let transform = Transform::new(
+                cgmath::Vector3::<f32>::new(x, center[1], 0.0),
+                cgmath::Quaternion::<f32>::new(0.0, 0.0, 0.0, 0.0),
+                scale,
+                renderer.transform_pool()
+            );
+
+            let click_action = action.clone();
+            let mut button = Button::new(transform, None, renderer, Some(text.as_str()), text_size);
+            button.on_click(move |click| (click_action.borrow_mut())(click.window, click.state));
+            ids.push(layout.add_component(Box::new(button)));
+
+            match role{
+                ButtonRole::Accept if accept.is_none() => accept = Some(action.clone()),
+                ButtonRole::Cancel if cancel.is_none() => cancel = Some(action.clone()),
+                _ => {}
+            }
+        }
+
+        ids.push(layout.add_component(Box::new(ButtonBarKeys{
+            accept,
+            cancel,
+        })));
+
+        ids
+    }
+}
+
+/// Invisible component that runs the default button on Enter, and the cancel button on Escape (or a gamepad's back button)
+pub struct ButtonBarKeys{
+    accept: Option<ButtonBarAction>,
+    cancel: Option<ButtonBarAction>,
+}
+
+impl Component for ButtonBarKeys{
+    fn handle_event(&mut self, event: &GuiEvent, window: &Window, state: &mut AppState) -> EventResult{
+        let action = match event{
+            GuiEvent::KeyDown{ key: VirtualKeyCode::Return, .. } | GuiEvent::KeyDown{ key: VirtualKeyCode::NumpadEnter, .. } => &self.accept,
+            GuiEvent::KeyDown{ key: VirtualKeyCode::Escape, .. } | GuiEvent::Navigate(NavAction::Back) => &self.cancel,
+            _ => &None,
+        };
+        match action{
+            Some(action) => {
+                (action.borrow_mut())(window, state);
+                EventResult::Consumed
+            }
+            None => EventResult::Ignored,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+}
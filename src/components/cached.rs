@@ -0,0 +1,268 @@
+//! This module defines `CachedLayout`, a component that renders a whole layout into a texture
+//! and then draws that texture as a single quad. It's meant for expensive widgets (lots of text,
+//! plots and the like) where redrawing every frame costs far more than the VRAM the texture takes.
+
+use std::{any::Any, rc::Rc};
+
+use crate::{color::Color, layout::Layout, locale::Locale, persistence::ComponentState, rendering::{Camera, RenderContext, Renderer, MemoryKind, ResourceReport, Tracked, Transform}, theme::Theme};
+
+use super::Component;
+
+/// # CachedLayout
+///
+/// Wraps a layout, renders it into a texture of `size` pixels, and draws that texture
+/// with `transform` (usually the same size, so it isn't stretched) instead of redrawing the layout every frame.
+/// The components in the layout are positioned in pixels from the top left of the texture.
+///
+/// The texture is only redrawn when the content changes (borrowing the layout mutably, or calling `invalidate`)
+/// or when the size changes.
+pub struct CachedLayout{
+    layout: Layout,
+    transform: Transform,
+    size: (u32, u32),
+    clear_color: Color,
+    // The renderer's shared quad
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+    enabled: bool,
+    visible: bool,
+
+    dirty: bool,
+    cache: Option<RenderCache>,
+}
+
+// The GPU side of the cache, created lazily on the first frame
+struct RenderCache{
+    size: (u32, u32),
+    _texture: Tracked<wgpu::Texture>,
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    pipeline: Rc<wgpu::RenderPipeline>,
+    camera: Camera, // maps the texture's pixels, for the layout drawn into it
+}
+
+impl CachedLayout{
+    /// Create a new cached layout. The layout will be rendered into a texture of `size` pixels, cleared with `clear_color`.
+    pub fn new(layout: Layout, transform: Transform, size: (u32, u32), clear_color: Color, renderer: &Renderer) -> Self{
+        Self{
+            layout,
+            transform,
+            size,
+            clear_color,
+            vertex_buffer: renderer.quad_buffer(),
+            enabled: true,
+            visible: true,
+            dirty: true,
+            cache: None,
+        }
+    }
+
+    /// Borrow the cached layout
+    pub fn borrow_layout(&self) -> &Layout{
+        &self.layout
+    }
+
+    /// Borrow the cached layout mutably. This marks the cache as dirty, so it gets redrawn next frame.
+    pub fn borrow_layout_mut(&mut self) -> &mut Layout{
+        self.dirty = true;
+        &mut self.layout
+    }
+
+    /// Force the cache to be redrawn next frame
+    pub fn invalidate(&mut self){
+        self.dirty = true;
+    }
+
+    /// Returns true if the cache will be redrawn next frame
+    pub fn is_dirty(&self) -> bool{
+        self.dirty
+    }
+
+    /// Change the size (in pixels) of the cached texture. The cache is only rebuilt if the size actually changed.
+    pub fn set_size(&mut self, size: (u32, u32)){
+        if self.size != size{
+            self.size = size;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get_size(&self) -> (u32, u32){
+        self.size
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    /// Show or hide the cached layout
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+
+    // (Re)create the texture the layout gets rendered into
+    fn create_cache(&self, context: &RenderContext) -> RenderCache{
+        let (width, height) = (self.size.0.max(1), self.size.1.max(1));
+        let texture = context.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Cached layout texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: context.format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = context.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let bind_group = context.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: context.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("Cached layout bind group"),
+        });
+
+        RenderCache{
+            size: (width, height),
+            _texture: Tracked::new(texture, MemoryKind::RenderTarget, 4 * width as u64 * height as u64),
+            view,
+            bind_group,
+            pipeline: context.texture_pipeline.clone(),
+            camera: Camera::new((width, height), context.bind_group_cache),
+        }
+    }
+}
+
+impl Component for CachedLayout{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if let Some(cache) = &self.cache{
+            render_pass.set_pipeline(&cache.pipeline);
+            self.transform.bind(render_pass);
+            render_pass.set_bind_group(2, &cache.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+
+    fn on_attach(&mut self, renderer: &Renderer){
+        // Components added to the nested layout after this are attached the next time the cached layout is
+        self.layout.attach(renderer);
+    }
+
+    fn on_detach(&mut self){
+        self.layout.detach();
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        // Only draw the texture again if something in it was restyled
+        let changed = self.layout.apply_theme(theme);
+        self.dirty |= changed;
+        changed
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        Some(ComponentState{
+            visible: Some(self.visible),
+            enabled: Some(self.enabled),
+            children: Some(self.layout.save_state()),
+            ..ComponentState::default()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        if let Some(children) = &state.children{
+            self.layout.restore_state(children);
+            self.dirty = true;
+        }
+        if let Some(visible) = state.visible{
+            self.visible = visible;
+        }
+        if let Some(enabled) = state.enabled{
+            self.enabled = enabled;
+        }
+    }
+
+    fn apply_locale(&mut self, locale: &Locale) -> bool{
+        // Only draw the texture again if something in it shows a text key
+        let changed = self.layout.apply_locale(locale);
+        self.dirty |= changed;
+        changed
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        self.transform.write_uniform();
+        let size = (self.size.0.max(1), self.size.1.max(1));
+        let needs_new_texture = match &self.cache{
+            Some(cache) => cache.size != size,
+            None => true,
+        };
+        if needs_new_texture{
+            self.cache = Some(self.create_cache(context));
+            self.dirty = true;
+        }
+
+        if self.dirty{
+            let cache = self.cache.as_ref().unwrap();
+            context.draw_layout(&mut self.layout, &cache.view, &cache.camera, self.clear_color);
+            self.dirty = false;
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        CachedLayout::set_visible(self, visible);
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        if enabled { self.enable() } else { self.disable() }
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        [self.transform.position().x, self.transform.position().y]
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        if let Some(cache) = &self.cache{
+            report.add_texture(cache.size.0, cache.size.1, 4);
+            cache.camera.report_resources(report);
+        }
+        self.layout.report_resources(report);
+    }
+}
@@ -0,0 +1,671 @@
+//! This module defines `EguiArea`, a component that hosts an egui context in an area of a layout (with the `egui` feature).
+//! It's meant for the panels egui is good at - debug views, inspectors, property editors - inside an app whose chrome is
+//! drawn with rusty_gui.
+//!
+//! The area translates the events it gets into egui's input, runs the UI closure before each frame, and draws egui's meshes
+//! with the renderer's device and queue, in the layout's render pass. egui's text is part of its meshes, so it's drawn
+//! with the area rather than on top of everything like the text of other components.
+
+use std::{any::Any, collections::HashMap, rc::Rc, time::Instant};
+
+use wgpu::util::DeviceExt;
+use winit::window::{CursorIcon, Window};
+
+use crate::{clipboard::Clipboard, event::{EventResult, GuiEvent, ModifiersState, MouseButton, ScrollDelta, VirtualKeyCode}, rendering::{Blend, PipelineKey, RenderContext, MemoryKind, Renderer, ResourceReport, Tracked}, state::AppState};
+
+use super::Component;
+
+/// The signature of the closure that builds an `EguiArea`'s UI. It runs once per frame.
+pub type EguiCallback = Box<dyn FnMut(&egui::Context)>;
+
+/// # EguiArea
+///
+/// Runs an egui UI in `rect` ([left, top, width, height] in pixels from the top left of the window). egui's (0, 0) is the
+/// top left of the area, and it sees the area as its whole screen - so use `egui::Window`s or `egui::CentralPanel` as usual.
+///
+/// The area takes keyboard focus when it's clicked, and egui gets key presses and typed text while it has it.
+/// Drags that start in the area keep going to egui until the button is released, even outside the area.
+pub struct EguiArea{
+    context: egui::Context,
+    ui: EguiCallback,
+    rect: [f32; 4],
+    pixels_per_point: f32,
+    visible: bool,
+    enabled: bool,
+    focused: bool,
+    hovered: bool,
+
+    // Input gathered since the last frame
+    events: Vec<egui::Event>,
+    modifiers: egui::Modifiers,
+    start: Instant,
+    // What egui asked for on the last frame
+    cursor: Option<CursorIcon>,
+    wants_pointer: bool,
+    wants_keyboard: bool,
+    // Whether egui wants another frame straight away (eg, it's animating)
+    repaint: bool,
+
+    clipboard: Option<Clipboard>,
+    gpu: Option<EguiGpu>,
+}
+
+// The GPU side of the area, created when it's attached
+struct EguiGpu{
+    pipeline: Rc<wgpu::RenderPipeline>,
+    texture_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    screen_buffer: Tracked<wgpu::Buffer>,
+    screen_bind_group: wgpu::BindGroup,
+    textures: HashMap<egui::TextureId, EguiTexture>,
+    // Textures egui freed, which the last frame may still have drawn with
+    pending_free: Vec<egui::TextureId>,
+    meshes: Vec<EguiMesh>,
+    // The scissor rect the layout was drawn with, put back after the meshes
+    clip: [u32; 4],
+}
+
+struct EguiTexture{
+    texture: Tracked<wgpu::Texture>,
+    bind_group: wgpu::BindGroup,
+    size: (u32, u32),
+}
+
+struct EguiMesh{
+    vertex_buffer: Tracked<wgpu::Buffer>,
+    index_buffer: Tracked<wgpu::Buffer>,
+    vertex_count: u32,
+    index_count: u32,
+    texture: egui::TextureId,
+    // [left, top, width, height] in pixels of the target
+    scissor: [u32; 4],
+}
+
+// The layout of egui's vertices, as the shader reads them
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct EguiVertex{
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    color: [u8; 4],
+}
+
+impl EguiArea{
+    /// Create an area covering `rect` ([left, top, width, height] in pixels from the top left of the window), which runs `ui` every frame
+    pub fn new<F: FnMut(&egui::Context) + 'static>(rect: [f32; 4], ui: F) -> Self{
+        Self{
+            context: egui::Context::default(),
+            ui: Box::new(ui),
+            rect,
+            pixels_per_point: 1.0,
+            visible: true,
+            enabled: true,
+            focused: false,
+            hovered: false,
+            events: Vec::new(),
+            modifiers: egui::Modifiers::default(),
+            start: Instant::now(),
+            cursor: None,
+            wants_pointer: false,
+            wants_keyboard: false,
+            repaint: true,
+            clipboard: None,
+            gpu: None,
+        }
+    }
+
+    /// The egui context, eg, to change its style or fonts
+    pub fn context(&self) -> &egui::Context{
+        &self.context
+    }
+
+    /// Move or resize the area ([left, top, width, height] in pixels from the top left of the window)
+    pub fn set_rect(&mut self, rect: [f32; 4]){
+        self.rect = rect;
+    }
+
+    /// The area egui draws in
+    pub fn rect(&self) -> [f32; 4]{
+        self.rect
+    }
+
+    /// Set how many pixels one of egui's points covers (eg, the window's scale factor). Defaults to 1.
+    pub fn set_pixels_per_point(&mut self, pixels_per_point: f32){
+        self.pixels_per_point = pixels_per_point.max(0.1);
+    }
+
+    // Convert a position in the window to egui's points in the area
+    fn to_points(&self, pos: (f64, f64)) -> egui::Pos2{
+        egui::pos2((pos.0 as f32 - self.rect[0]) / self.pixels_per_point, (pos.1 as f32 - self.rect[1]) / self.pixels_per_point)
+    }
+
+    fn contains(&self, pos: (f64, f64)) -> bool{
+        let [left, top, width, height] = self.rect;
+        let (x, y) = (pos.0 as f32, pos.1 as f32);
+        x >= left && x < left + width && y >= top && y < top + height
+    }
+
+    // Pass a key press on to egui, along with the clipboard events egui expects integrations to send
+    fn key_event(&mut self, key: VirtualKeyCode, pressed: bool){
+        if pressed && self.modifiers.command{
+            match key{
+                VirtualKeyCode::C => self.events.push(egui::Event::Copy),
+                VirtualKeyCode::X => self.events.push(egui::Event::Cut),
+                VirtualKeyCode::V => {
+                    if let Some(text) = self.clipboard.as_ref().and_then(|clipboard| clipboard.get_text().ok()){
+                        self.events.push(egui::Event::Paste(text));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(key) = egui_key(key){
+            self.events.push(egui::Event::Key{ key, physical_key: None, pressed, repeat: false, modifiers: self.modifiers });
+        }
+    }
+
+    // Run the UI for this frame, and turn what it drew into meshes
+    fn run_frame(&mut self, context: &mut RenderContext){
+        let size = egui::vec2(self.rect[2], self.rect[3]) / self.pixels_per_point;
+        let mut input = egui::RawInput{
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+            time: Some(self.start.elapsed().as_secs_f64()),
+            modifiers: self.modifiers,
+            events: std::mem::take(&mut self.events),
+            focused: self.focused,
+            ..egui::RawInput::default()
+        };
+        input.viewports.entry(input.viewport_id).or_default().native_pixels_per_point = Some(self.pixels_per_point);
+
+        let ui = &mut self.ui;
+        let output = self.context.run(input, |ctx| ui(ctx));
+        self.wants_pointer = self.context.is_using_pointer() || self.context.is_pointer_over_area();
+        self.wants_keyboard = self.context.wants_keyboard_input();
+        self.repaint = output.viewport_output.values().any(|viewport| viewport.repaint_delay.is_zero());
+        self.cursor = cursor_icon(output.platform_output.cursor_icon);
+        for command in output.platform_output.commands{
+            if let (egui::OutputCommand::CopyText(text), Some(clipboard)) = (command, &self.clipboard){
+                let _ = clipboard.set_text(text);
+            }
+        }
+
+        let primitives = self.context.tessellate(output.shapes, output.pixels_per_point);
+        let gpu = match &mut self.gpu{
+            Some(v) => v,
+            None => return,
+        };
+
+        // Textures freed last frame can go now, as that frame has been drawn
+        for id in gpu.pending_free.drain(..){
+            gpu.textures.remove(&id);
+        }
+        for (id, delta) in output.textures_delta.set{
+            gpu.update_texture(context, id, &delta);
+        }
+        gpu.pending_free = output.textures_delta.free;
+
+        // Points in the area to clip space of the target
+        let (width, height) = (context.size.0.max(1) as f32, context.size.1.max(1) as f32);
+        let scale = output.pixels_per_point;
+        let proj: [[f32; 4]; 4] = [
+            [2.0 * scale / width, 0.0, 0.0, 0.0],
+            [0.0, -2.0 * scale / height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [2.0 * self.rect[0] / width - 1.0, 1.0 - 2.0 * self.rect[1] / height, 0.0, 1.0],
+        ];
+        context.queue.write_buffer(&gpu.screen_buffer, 0, bytemuck::cast_slice(&proj));
+        gpu.clip = context.clip_rect();
+
+        gpu.meshes.clear();
+        for primitive in primitives{
+            let mesh = match primitive.primitive{
+                egui::epaint::Primitive::Mesh(mesh) => mesh,
+                // Paint callbacks are for egui's own wgpu integration, which we don't use
+                egui::epaint::Primitive::Callback(_) => continue,
+            };
+            if mesh.indices.is_empty(){
+                continue;
+            }
+            let scissor = match clip_to_pixels(primitive.clip_rect, self.rect, scale, gpu.clip){
+                Some(v) => v,
+                None => continue,
+            };
+            let vertices: Vec<EguiVertex> = mesh.vertices.iter().map(|v| EguiVertex{
+                position: [v.pos.x, v.pos.y],
+                tex_coords: [v.uv.x, v.uv.y],
+                color: v.color.to_array(),
+            }).collect();
+            gpu.meshes.push(EguiMesh{
+                vertex_buffer: Tracked::new(context.device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+                    label: Some("egui vertex buffer"),
+                    contents: bytemuck::cast_slice(&vertices),
+                    usage: wgpu::BufferUsage::VERTEX,
+                }), MemoryKind::VertexBuffer, std::mem::size_of_val(vertices.as_slice()) as u64),
+                index_buffer: Tracked::new(context.device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+                    label: Some("egui index buffer"),
+                    contents: bytemuck::cast_slice(&mesh.indices),
+                    usage: wgpu::BufferUsage::INDEX,
+                }), MemoryKind::VertexBuffer, std::mem::size_of_val(mesh.indices.as_slice()) as u64),
+                vertex_count: vertices.len() as u32,
+                index_count: mesh.indices.len() as u32,
+                texture: mesh.texture_id,
+                scissor,
+            });
+        }
+    }
+}
+
+impl EguiGpu{
+    fn new(renderer: &Renderer) -> Self{
+        let device = &*renderer.device;
+        let (screen_buffer, screen_bind_group, screen_layout) = renderer.bind_group_cache().create_uniform(wgpu::ShaderStage::VERTEX, 0, &[[0.0f32; 4]; 4], "egui screen");
+        let texture_layout = Renderer::create_texture_bind_group_layout(device);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        // Layouts with the same entries are the same layout to wgpu, so every area can share the first one's pipeline
+        let key = PipelineKey::new("rusty_gui::egui", Blend::PREMULTIPLIED, renderer.sc_desc.format);
+        let pipeline = renderer.pipeline_cache().get_or_create(key, |key| create_pipeline(device, &screen_layout, &texture_layout, key.format));
+        Self{
+            pipeline,
+            texture_layout,
+            sampler,
+            screen_buffer,
+            screen_bind_group,
+            textures: HashMap::new(),
+            pending_free: Vec::new(),
+            meshes: Vec::new(),
+            clip: [0, 0, 1, 1],
+        }
+    }
+
+    // Create a texture egui asked for, or update part of one
+    fn update_texture(&mut self, context: &RenderContext, id: egui::TextureId, delta: &egui::epaint::ImageDelta){
+        let egui::ImageData::Color(image) = &delta.image;
+        let size = (image.size[0] as u32, image.size[1] as u32);
+        if size.0 == 0 || size.1 == 0{
+            return;
+        }
+        let origin = match delta.pos{
+            Some([x, y]) => (x as u32, y as u32),
+            // A whole new image replaces the texture
+            None => {
+                self.textures.insert(id, self.create_texture(context.device, size));
+                (0, 0)
+            }
+        };
+        let texture = match self.textures.get(&id){
+            Some(v) => v,
+            None => return,
+        };
+        let pixels: Vec<u8> = image.pixels.iter().flat_map(|color| color.to_array()).collect();
+        context.queue.write_texture(
+            wgpu::TextureCopyView{
+                texture: &texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d{ x: origin.0, y: origin.1, z: 0 },
+            },
+            &pixels,
+            wgpu::TextureDataLayout{
+                offset: 0,
+                bytes_per_row: 4 * size.0,
+                rows_per_image: size.1,
+            },
+            wgpu::Extent3d{ width: size.0, height: size.1, depth: 1 },
+        );
+    }
+
+    fn create_texture(&self, device: &wgpu::Device, size: (u32, u32)) -> EguiTexture{
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("egui texture"),
+            size: wgpu::Extent3d{ width: size.0, height: size.1, depth: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("egui texture bind group"),
+        });
+        EguiTexture{
+            texture: Tracked::new(texture, MemoryKind::Texture, 4 * size.0 as u64 * size.1 as u64),
+            bind_group,
+            size,
+        }
+    }
+}
+
+// The pipeline egui's meshes are drawn with. egui's colors are premultiplied, and its meshes aren't consistently wound.
+fn create_pipeline(device: &wgpu::Device, screen_layout: &wgpu::BindGroupLayout, texture_layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat) -> wgpu::RenderPipeline{
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("egui pipeline layout"),
+        bind_group_layouts: &[screen_layout, texture_layout],
+        push_constant_ranges: &[],
+    });
+    let vs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/egui.vert.spv"));
+    let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/egui.frag.spv"));
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("egui pipeline"),
+        layout: Some(&layout),
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+            clamp_depth: false,
+        }),
+        color_states: &[
+            wgpu::ColorStateDescriptor {
+                format,
+                color_blend: Blend::PREMULTIPLIED.color,
+                alpha_blend: Blend::PREMULTIPLIED.alpha,
+                write_mask: wgpu::ColorWrite::ALL
+            }
+        ],
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        depth_stencil_state: None,
+        vertex_state: wgpu::VertexStateDescriptor {
+            index_format: wgpu::IndexFormat::Uint32,
+            vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                stride: std::mem::size_of::<EguiVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::InputStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttributeDescriptor { offset: 0, shader_location: 0, format: wgpu::VertexFormat::Float2 },
+                    wgpu::VertexAttributeDescriptor { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float2 },
+                    wgpu::VertexAttributeDescriptor { offset: 16, shader_location: 2, format: wgpu::VertexFormat::Uchar4Norm },
+                ],
+            }],
+        },
+        sample_count: 1,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+// The scissor rect (in pixels of the target) for a clip rect in points, clamped to the area and to the layout's clip
+// rect `bounds`, or `None` if nothing is left of it
+fn clip_to_pixels(clip: egui::Rect, area: [f32; 4], pixels_per_point: f32, bounds: [u32; 4]) -> Option<[u32; 4]>{
+    // The area is relative to the layout's clip rect, which is the window of an embedded renderer
+    let (x, y) = (bounds[0] as f32, bounds[1] as f32);
+    let left = (x + area[0] + clip.min.x * pixels_per_point).max(x + area[0]).max(x).round();
+    let top = (y + area[1] + clip.min.y * pixels_per_point).max(y + area[1]).max(y).round();
+    let right = (x + area[0] + clip.max.x * pixels_per_point).min(x + area[0] + area[2]).min(x + bounds[2] as f32).round();
+    let bottom = (y + area[1] + clip.max.y * pixels_per_point).min(y + area[1] + area[3]).min(y + bounds[3] as f32).round();
+    if right <= left || bottom <= top{
+        return None;
+    }
+    Some([left as u32, top as u32, (right - left) as u32, (bottom - top) as u32])
+}
+
+fn egui_modifiers(modifiers: ModifiersState) -> egui::Modifiers{
+    egui::Modifiers{
+        alt: modifiers.alt(),
+        ctrl: modifiers.ctrl(),
+        shift: modifiers.shift(),
+        mac_cmd: cfg!(target_os = "macos") && modifiers.logo(),
+        command: if cfg!(target_os = "macos"){ modifiers.logo() }else{ modifiers.ctrl() },
+    }
+}
+
+// egui's key for a winit key. Most share a name, the rest are renamed here.
+fn egui_key(key: VirtualKeyCode) -> Option<egui::Key>{
+    let name = match key{
+        VirtualKeyCode::Back => "Backspace".to_string(),
+        VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => "Enter".to_string(),
+        key => {
+            let name = format!("{:?}", key);
+            // Key0 to Key9 are the number row
+            match name.strip_prefix("Key"){
+                Some(digit) => digit.to_string(),
+                None => name,
+            }
+        }
+    };
+    egui::Key::from_name(&name)
+}
+
+fn cursor_icon(icon: egui::CursorIcon) -> Option<CursorIcon>{
+    Some(match icon{
+        egui::CursorIcon::Default => return None,
+        egui::CursorIcon::PointingHand => CursorIcon::Hand,
+        egui::CursorIcon::Text => CursorIcon::Text,
+        egui::CursorIcon::Grab => CursorIcon::Grab,
+        egui::CursorIcon::Grabbing => CursorIcon::Grabbing,
+        egui::CursorIcon::Move => CursorIcon::Move,
+        egui::CursorIcon::ResizeHorizontal | egui::CursorIcon::ResizeColumn => CursorIcon::EwResize,
+        egui::CursorIcon::ResizeVertical | egui::CursorIcon::ResizeRow => CursorIcon::NsResize,
+        egui::CursorIcon::ResizeNwSe => CursorIcon::NwseResize,
+        egui::CursorIcon::ResizeNeSw => CursorIcon::NeswResize,
+        egui::CursorIcon::NotAllowed | egui::CursorIcon::NoDrop => CursorIcon::NotAllowed,
+        _ => CursorIcon::Default,
+    })
+}
+
+impl Component for EguiArea{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        let gpu = match &self.gpu{
+            Some(v) => v,
+            None => return,
+        };
+        render_pass.set_pipeline(&gpu.pipeline);
+        render_pass.set_bind_group(0, &gpu.screen_bind_group, &[]);
+        for mesh in gpu.meshes.iter(){
+            let texture = match gpu.textures.get(&mesh.texture){
+                Some(v) => v,
+                None => continue,
+            };
+            let [x, y, width, height] = mesh.scissor;
+            render_pass.set_scissor_rect(x, y, width, height);
+            render_pass.set_bind_group(1, &texture.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..));
+            render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+        // Don't clip the components drawn after us
+        let [x, y, width, height] = gpu.clip;
+        render_pass.set_scissor_rect(x, y, width, height);
+    }
+
+    fn handle_event(&mut self, event: &GuiEvent, _window: &Window, _state: &mut AppState) -> EventResult{
+        if !self.enabled{
+            return EventResult::Ignored;
+        }
+        let consumed = match event{
+            GuiEvent::MouseMove{ pos } => {
+                if self.contains(*pos) || self.wants_pointer{
+                    self.events.push(egui::Event::PointerMoved(self.to_points(*pos)));
+                }else{
+                    self.events.push(egui::Event::PointerGone);
+                }
+                false
+            }
+            GuiEvent::MouseLeave => {
+                self.events.push(egui::Event::PointerGone);
+                false
+            }
+            GuiEvent::MouseDown{ button, pos } | GuiEvent::MouseUp{ button, pos } => {
+                let pressed = matches!(event, GuiEvent::MouseDown{ .. });
+                let button = match button{
+                    MouseButton::Left => egui::PointerButton::Primary,
+                    MouseButton::Right => egui::PointerButton::Secondary,
+                    MouseButton::Middle => egui::PointerButton::Middle,
+                    MouseButton::Other(_) => return EventResult::Ignored,
+                };
+                // Presses have to land on the area, releases go to egui wherever they are so drags end
+                if pressed && !self.hovered{
+                    return EventResult::Ignored;
+                }
+                if pressed{
+                    self.focused = true;
+                }
+                self.events.push(egui::Event::PointerButton{ pos: self.to_points(*pos), button, pressed, modifiers: self.modifiers });
+                true
+            }
+            GuiEvent::Scroll{ delta } if self.hovered => {
+                let (unit, delta) = match delta{
+                    ScrollDelta::Lines{ x, y } => (egui::MouseWheelUnit::Line, egui::vec2(*x, *y)),
+                    ScrollDelta::Pixels{ x, y } => (egui::MouseWheelUnit::Point, egui::vec2(*x as f32, *y as f32) / self.pixels_per_point),
+                };
+                self.events.push(egui::Event::MouseWheel{ unit, delta, modifiers: self.modifiers });
+                true
+            }
+            GuiEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = egui_modifiers(*modifiers);
+                false
+            }
+            GuiEvent::KeyDown{ key, modifiers, .. } | GuiEvent::KeyUp{ key, modifiers, .. } if self.focused => {
+                self.modifiers = egui_modifiers(*modifiers);
+                self.key_event(*key, matches!(event, GuiEvent::KeyDown{ .. }));
+                self.wants_keyboard
+            }
+            GuiEvent::TextInput(c) if self.focused && !c.is_control() => {
+                self.events.push(egui::Event::Text(c.to_string()));
+                true
+            }
+            GuiEvent::ImePreedit(text) if self.focused => {
+                self.events.push(egui::Event::Ime(egui::ImeEvent::Preedit(text.clone())));
+                true
+            }
+            GuiEvent::ImeCommit(text) if self.focused => {
+                self.events.push(egui::Event::Ime(egui::ImeEvent::Commit(text.clone())));
+                true
+            }
+            GuiEvent::Focused(focused) => {
+                self.events.push(egui::Event::WindowFocused(*focused));
+                false
+            }
+            _ => false,
+        };
+        EventResult::consumed_if(consumed)
+    }
+
+    fn on_attach(&mut self, renderer: &Renderer){
+        self.clipboard = Some(renderer.clipboard().clone());
+        self.gpu = Some(EguiGpu::new(renderer));
+    }
+
+    fn on_detach(&mut self){
+        self.gpu = None;
+    }
+
+    fn needs_redraw(&self) -> bool{
+        // Input only reaches egui when it runs, in `prerender`
+        self.repaint || !self.events.is_empty()
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        self.run_frame(context);
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        if let Some(gpu) = &self.gpu{
+            report.add_buffer(std::mem::size_of::<[[f32; 4]; 4]>() as u64);
+            for texture in gpu.textures.values(){
+                report.add_texture(texture.size.0, texture.size.1, 4);
+            }
+            for mesh in gpu.meshes.iter(){
+                report.add_buffer(mesh.vertex_count as u64 * std::mem::size_of::<EguiVertex>() as u64);
+                report.add_buffer(mesh.index_count as u64 * 4);
+            }
+        }
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        self.enabled = enabled;
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        [self.rect[0], self.rect[1]]
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        if self.hovered{
+            self.cursor
+        }else{
+            None
+        }
+    }
+
+    fn is_focusable(&self) -> bool{
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+
+    fn accepts_text(&self) -> bool{
+        self.focused && self.wants_keyboard
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+
+    fn on_hover_enter(&mut self){
+        self.hovered = true;
+    }
+
+    fn on_hover_exit(&mut self){
+        self.hovered = false;
+    }
+
+    fn on_drag_start(&mut self, _button: MouseButton, _pos: (f64, f64)) -> bool{
+        // Keep getting the mouse until the button is released, so egui's drags (sliders, windows) work past the area
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+}
@@ -1,3 +1,21 @@
 pub mod base_components;
+pub mod cached;
+pub mod button_bar;
+pub mod title_bar;
+pub mod text_input;
+pub mod password_input;
+pub mod panel;
+pub mod badge;
+#[cfg(feature = "egui")]
+pub mod egui_area;
 
-pub use base_components::{GUIComponent, TextGUIComponent, EventGUIComponent, Label, Button};
\ No newline at end of file
+pub use base_components::{Component, Label, Button, ButtonCallback, ButtonSkin, ButtonState, ButtonStyle, ClickCallback, ClickContext};
+pub use cached::CachedLayout;
+pub use button_bar::{ButtonBar, ButtonBarAction, ButtonOrder, ButtonRole};
+pub use title_bar::{DragArea, DragRegion, WindowControl, WindowControlButton};
+pub use text_input::{TextEdit, TextInput};
+pub use password_input::PasswordInput;
+pub use panel::Panel;
+pub use badge::{Badge, BadgeCorner};
+#[cfg(feature = "egui")]
+pub use egui_area::{EguiArea, EguiCallback};
@@ -0,0 +1,355 @@
+//! This module defines `Panel`, a container that owns a group of child components. It draws an optional background
+//! behind them, moves them along with itself, and passes its enabled state and opacity on to them - the same way a
+//! button does for its label, but for any components.
+
+use std::{any::Any, rc::Rc, time::Duration};
+
+use winit::window::CursorIcon;
+
+use crate::{accessibility::{AccessInfo, Role}, animation::{AnimatedProperty, AnimatedValue}, color::Color, event::{EventResult, GuiEvent}, layout::Layout, locale::Locale, persistence::ComponentState, rendering::{RenderContext, Renderer, ResourceReport, Tracked, Transform}, state::AppState, theme::Theme};
+
+use super::Component;
+
+/// # Panel
+///
+/// A container for child components, covering a rect of [left, top, width, height] pixels from the top left of the window.
+///
+/// Children are positioned relative to the panel - `add_child` moves them by the panel's top left, and they move with
+/// the panel when it does (through their animated position, see `Component::animated_value`). A label at [10, 10] ends up
+/// 10 pixels into the panel, and a button at [50, 20] is centered 50 pixels in. Children without a position stay where they are.
+///
+/// The panel keeps its children in a layout of their own, so they have their own z order, hover and focus. It draws
+/// them (and their text) straight after itself (see `Component::children`), and passes events on to them. Disabling the panel disables
+/// every child (enabling it enables them all again), and its opacity is set on every child. Hidden panels hide their children.
+///
+/// A panel with a background is solid - clicks on it don't go through to the components behind it. Without one, only
+/// the children take clicks.
+pub struct Panel{
+    layout: Layout,
+    rect: [f32; 4],
+    background: Option<Color>,
+    opacity: f32,
+    enabled: bool,
+    visible: bool,
+    hovered: bool,
+
+    transform: Transform, // the background's quad, fitted to the rect in `prerender`
+    // The renderer's shared quad
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+    applied_tint: Color, // the tint currently in the tint buffer
+    tint_buffer: Tracked<wgpu::Buffer>,
+    tint_bind_group: wgpu::BindGroup,
+}
+
+impl Panel{
+    /// Create an empty panel covering `rect` ([left, top, width, height] in pixels from the top left), with no background
+    pub fn new(rect: [f32; 4], renderer: &Renderer) -> Self{
+        let transform = Transform::new(
+            cgmath::Vector3::new(0.0, 0.0, 0.0),
+            cgmath::Quaternion::<f32>::new(0.0, 0.0, 0.0, 0.0),
+            cgmath::Vector3::new(0.0, 0.0, 1.0),
+            renderer.transform_pool(),
+        );
+        let (tint_buffer, tint_bind_group, _) = renderer.bind_group_cache().create_uniform(wgpu::ShaderStage::FRAGMENT, 0, &Color::TRANSPARENT.to_linear(), "Panel tint");
+
+        Self{
+            layout: Layout::new(),
+            rect,
+            background: None,
+            opacity: 1.0,
+            enabled: true,
+            visible: true,
+            hovered: false,
+            transform,
+            vertex_buffer: renderer.quad_buffer(),
+            applied_tint: Color::TRANSPARENT,
+            tint_buffer,
+            tint_bind_group,
+        }
+    }
+
+    /// Add a child, positioned relative to the panel's top left. Returns its ID in the panel's layout.
+    pub fn add_child<T: Component + 'static>(&mut self, mut child: Box<T>) -> usize{
+        offset_child(child.as_mut(), [self.rect[0], self.rect[1]]);
+        if !self.enabled{
+            child.set_enabled(false);
+        }
+        if self.opacity < 1.0{
+            child.set_animated_value(AnimatedValue::Opacity(self.opacity));
+        }
+        self.layout.add_component(child)
+    }
+
+    /// Borrow the layout holding the panel's children
+    pub fn layout(&self) -> &Layout{
+        &self.layout
+    }
+
+    /// Borrow the layout holding the panel's children mutably (eg, to remove one, or change their z order).
+    /// Components added to it directly aren't moved to the panel, like they are with `add_child`.
+    pub fn layout_mut(&mut self) -> &mut Layout{
+        &mut self.layout
+    }
+
+    /// Move and resize the panel, moving its children with it
+    pub fn set_rect(&mut self, rect: [f32; 4]){
+        let offset = [rect[0] - self.rect[0], rect[1] - self.rect[1]];
+        if offset != [0.0, 0.0]{
+            for (_, child) in self.layout.components.iter_mut(){
+                offset_child(child.as_mut(), offset);
+            }
+        }
+        self.rect = rect;
+    }
+
+    /// The rect the panel covers, as [left, top, width, height] in pixels from the top left
+    pub fn get_rect(&self) -> [f32; 4]{
+        self.rect
+    }
+
+    /// Move the panel's top left to `pos`, moving its children with it
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.set_rect([pos[0], pos[1], self.rect[2], self.rect[3]]);
+    }
+
+    /// Draw a background behind the children, or nothing with `None` (the default). The tint is written the next time the panel is drawn.
+    pub fn set_background(&mut self, background: Option<Color>) -> &mut Self{
+        self.background = background;
+        self
+    }
+
+    /// The background drawn behind the children, if any
+    pub fn get_background(&self) -> Option<Color>{
+        self.background
+    }
+
+    /// Change how opaque the panel and its children are, from 0 (invisible) to 1 (the default)
+    pub fn set_opacity(&mut self, opacity: f32){
+        self.opacity = opacity.clamp(0.0, 1.0);
+        for (_, child) in self.layout.components.iter_mut(){
+            child.set_animated_value(AnimatedValue::Opacity(self.opacity));
+        }
+    }
+
+    /// Get how opaque the panel is
+    pub fn get_opacity(&self) -> f32{
+        self.opacity
+    }
+
+    pub fn enable(&mut self){
+        self.set_enabled(true);
+    }
+
+    pub fn disable(&mut self){
+        self.set_enabled(false);
+    }
+
+    /// Enable or disable the panel and every child
+    pub fn set_enabled(&mut self, enabled: bool){
+        self.enabled = enabled;
+        for (_, child) in self.layout.components.iter_mut(){
+            child.set_enabled(enabled);
+        }
+    }
+
+    /// Show or hide the panel (and its children)
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+
+    // Write the background's tint to the tint buffer, if it changed
+    fn apply_tint(&mut self, queue: &wgpu::Queue){
+        let tint = self.background.unwrap_or(Color::TRANSPARENT);
+        let tint = tint.with_alpha(tint.a * self.opacity);
+        if tint != self.applied_tint{
+            queue.write_buffer(&self.tint_buffer, 0, bytemuck::cast_slice(&[tint.to_linear()]));
+            self.applied_tint = tint;
+        }
+    }
+}
+
+// Move a child by `offset` pixels, if it has a position
+fn offset_child(child: &mut dyn Component, offset: [f32; 2]){
+    if let Some(AnimatedValue::Position([x, y])) = child.animated_value(AnimatedProperty::Position){
+        child.set_animated_value(AnimatedValue::Position([x + offset[0], y + offset[1]]));
+    }
+}
+
+impl Component for Panel{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.background.is_some(){
+            self.transform.bind(render_pass);
+            render_pass.set_bind_group(2, &self.tint_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+
+    fn children(&self) -> Option<&Layout>{
+        Some(&self.layout)
+    }
+
+    fn children_mut(&mut self) -> Option<&mut Layout>{
+        Some(&mut self.layout)
+    }
+
+    fn draw_bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        // Children can be outside the panel, so it's always drawn, and they're culled one by one
+        None
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(AccessInfo::new(Role::Group))
+    }
+
+    fn handle_event(&mut self, event: &GuiEvent, window: &winit::window::Window, state: &mut AppState) -> EventResult{
+        if !self.enabled{
+            return EventResult::Ignored;
+        }
+        let result = self.layout.dispatch_event(event, window, state);
+        // Presses on the background don't go through to whatever is behind the panel
+        let solid = self.background.is_some() && self.hovered && matches!(event,
+            GuiEvent::MouseDown{ .. } | GuiEvent::MouseUp{ .. } | GuiEvent::DoubleClick{ .. } | GuiEvent::TripleClick{ .. } | GuiEvent::Scroll{ .. }
+        );
+        if solid { EventResult::Consumed } else { result }
+    }
+
+    fn update(&mut self, delta_time: Duration){
+        for (_, child) in self.layout.components.iter_mut(){
+            child.update(delta_time);
+        }
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        // Fit the background's quad to the rect
+        let [left, top, width, height] = self.rect;
+        self.transform.set_position(cgmath::Vector3::new(left + width / 2.0, top + height / 2.0, 0.0));
+        self.transform.set_scale(cgmath::Vector3::new(width, height, 1.0));
+        self.transform.write_uniform();
+        self.apply_tint(context.queue);
+
+        for (_, child) in self.layout.components.iter_mut(){
+            child.prerender(context);
+        }
+    }
+
+    fn on_attach(&mut self, renderer: &Renderer){
+        // Children added after this are attached the next time the panel is
+        self.layout.attach(renderer);
+    }
+
+    fn on_detach(&mut self){
+        self.layout.detach();
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        self.layout.apply_theme(theme)
+    }
+
+    fn apply_locale(&mut self, locale: &Locale) -> bool{
+        self.layout.apply_locale(locale)
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        Some(ComponentState{
+            position: Some([self.rect[0], self.rect[1]]),
+            color: self.background,
+            visible: Some(self.visible),
+            enabled: Some(self.enabled),
+            children: Some(self.layout.save_state()),
+            ..ComponentState::default()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        // The children are restored first, so moving the panel afterwards moves them from where they were saved
+        if let Some(children) = &state.children{
+            self.layout.restore_state(children);
+        }
+        if let Some(position) = state.position{
+            self.set_pos(position);
+        }
+        if let Some(color) = state.color{
+            self.background = Some(color);
+        }
+        if let Some(visible) = state.visible{
+            self.set_visible(visible);
+        }
+        if let Some(enabled) = state.enabled{
+            self.set_enabled(enabled);
+        }
+    }
+
+    fn animated_value(&self, property: AnimatedProperty) -> Option<AnimatedValue>{
+        match property{
+            AnimatedProperty::Position => Some(AnimatedValue::Position([self.rect[0], self.rect[1]])),
+            AnimatedProperty::Opacity => Some(AnimatedValue::Opacity(self.opacity)),
+            AnimatedProperty::Color => self.background.map(AnimatedValue::Color),
+            AnimatedProperty::Scale => None,
+        }
+    }
+
+    fn set_animated_value(&mut self, value: AnimatedValue){
+        match value{
+            AnimatedValue::Position(pos) => self.set_pos(pos),
+            AnimatedValue::Opacity(opacity) => self.set_opacity(opacity),
+            AnimatedValue::Color(color) => self.background = Some(color),
+            AnimatedValue::Scale(_) => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        Panel::set_visible(self, visible);
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        Panel::set_enabled(self, enabled);
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        [self.rect[0], self.rect[1]]
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64); // Tint
+        self.layout.report_resources(report);
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        self.layout.hover_cursor()
+    }
+
+    fn take_close_request(&mut self) -> bool{
+        self.layout.take_close_request()
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        // Only a solid panel can be hovered, so the mouse goes through an empty one to whatever is behind it
+        self.background.map(|_| self.rect)
+    }
+
+    fn on_hover_enter(&mut self){
+        self.hovered = true;
+    }
+
+    fn on_hover_exit(&mut self){
+        self.hovered = false;
+    }
+}
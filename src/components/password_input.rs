@@ -0,0 +1,243 @@
+//! This module defines `PasswordInput`, a `TextInput` for passwords and other secrets.
+//!
+//! The text is drawn as bullets (or another mask character), and can't be copied, cut, or saved with the layout's state -
+//! pasting still works, for password managers. A "Show" toggle at the right end of the box reveals the text while it's
+//! needed, and hides it again when it's clicked a second time.
+
+use std::any::Any;
+
+use winit::window::{CursorIcon, Window};
+
+use crate::{accessibility::{AccessAction, AccessInfo}, event::{EventResult, GuiEvent, MouseButton}, persistence::ComponentState, rendering::{RenderContext, Renderer, ResourceReport, TextQueue}, state::AppState, theme::Theme};
+
+use super::{Component, TextInput};
+
+/// # PasswordInput
+///
+/// A single line text box that hides what's typed in it. It's created and laid out like a `TextInput` (see
+/// `borrow_input_mut` for the rest of its settings).
+pub struct PasswordInput{
+    input: TextInput,
+    mask: char,
+    revealed: bool,
+    reveal_toggle: bool,
+}
+
+impl PasswordInput{
+    /// The character drawn in place of each character of the text unless it's changed
+    pub const DEFAULT_MASK: char = '\u{2022}';
+
+    /// Create a new, empty password input
+    pub fn new(position: [f32; 2], size: (f32, f32), text_size: f32, renderer: &Renderer) -> Self{
+        let mut input = TextInput::new(position, size, text_size, renderer);
+        input.set_mask(Some(Self::DEFAULT_MASK), true);
+        Self{
+            input,
+            mask: Self::DEFAULT_MASK,
+            revealed: false,
+            reveal_toggle: true,
+        }
+    }
+
+    /// Get the password
+    pub fn get_text(&self) -> String{
+        self.input.get_text()
+    }
+
+    /// Replace the password, moving the caret to the end
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.input.set_text(text);
+    }
+
+    /// Change the character drawn in place of each character of the password
+    pub fn set_mask(&mut self, mask: char){
+        self.mask = mask;
+        self.update_mask();
+    }
+
+    /// Get the character drawn in place of each character of the password
+    pub fn get_mask(&self) -> char{
+        self.mask
+    }
+
+    /// Show the password as it is, or hide it behind the mask again. It still can't be copied while it's shown.
+    pub fn set_revealed(&mut self, revealed: bool){
+        self.revealed = revealed;
+        self.update_mask();
+    }
+
+    /// Returns true if the password is shown as it is
+    pub fn is_revealed(&self) -> bool{
+        self.revealed
+    }
+
+    /// Show the password if it's hidden, or hide it if it's shown
+    pub fn toggle_revealed(&mut self){
+        self.set_revealed(!self.revealed);
+    }
+
+    /// Choose whether the "Show" / "Hide" toggle is drawn at the right end of the box. Without it, the password can
+    /// still be revealed with `set_revealed`.
+    pub fn set_reveal_toggle(&mut self, reveal_toggle: bool){
+        self.reveal_toggle = reveal_toggle;
+    }
+
+    /// Borrow the text input underneath, eg, to change its colors
+    pub fn borrow_input_mut(&mut self) -> &mut TextInput{
+        &mut self.input
+    }
+
+    fn update_mask(&mut self){
+        let mask = if self.revealed { None } else { Some(self.mask) };
+        self.input.set_mask(mask, true);
+    }
+
+    // The label of the reveal toggle, for what clicking it will do
+    fn toggle_label(&self) -> &'static str{
+        if self.revealed { "Hide" } else { "Show" }
+    }
+
+    // The part of the box the reveal toggle takes up, as [left, top, width, height] in pixels from the top left of the window
+    fn toggle_rect(&self) -> [f32; 4]{
+        let [left, top, width, height] = self.input.rect();
+        // Wide enough for either label at the input's text size
+        let toggle_width = (self.input.text_size() * 2.5 + self.input.padding()).min(width);
+        [left + width - toggle_width, top, toggle_width, height]
+    }
+}
+
+impl Component for PasswordInput{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {
+        self.input.render(render_pass);
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        self.input.prerender(context);
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue) where 'a: 'b {
+        self.input.queue_text(queue);
+        if !self.reveal_toggle{
+            return;
+        }
+        let [left, top, width, height] = self.toggle_rect();
+        let scale = self.input.text_size() * 0.8;
+        queue.queue(wgpu_glyph::Section {
+            screen_position: (left + width - self.input.padding(), top + height / 2.0),
+            text: vec![wgpu_glyph::Text::new(self.toggle_label()).with_color(self.input.get_text_color().to_linear()).with_scale(scale)],
+            layout: wgpu_glyph::Layout::default_single_line()
+                .v_align(wgpu_glyph::VerticalAlign::Center)
+                .h_align(wgpu_glyph::HorizontalAlign::Right),
+            ..wgpu_glyph::Section::default()
+        });
+    }
+
+    fn handle_event(&mut self, event: &GuiEvent, window: &Window, state: &mut AppState) -> EventResult{
+        // A click on the toggle reveals (or hides) the password, keeping the input focused
+        if let GuiEvent::MouseDown{ button: MouseButton::Left, pos } = event{
+            let [left, top, width, height] = self.toggle_rect();
+            let (x, y) = (pos.0 as f32, pos.1 as f32);
+            let on_toggle = x >= left && x < left + width && y >= top && y < top + height;
+            // The layout's hit test tells the input if the press is on it
+            if self.reveal_toggle && on_toggle && self.input.is_enabled() && self.input.is_hovered(){
+                self.toggle_revealed();
+                self.input.set_focused(true);
+                return EventResult::Consumed;
+            }
+        }
+        self.input.handle_event(event, window, state)
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.input.is_enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        self.input.set_enabled(enabled);
+    }
+
+    fn is_visible(&self) -> bool{
+        Component::is_visible(&self.input)
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        self.input.set_visible(visible);
+    }
+
+    fn is_hit_testable(&self) -> bool{
+        self.input.is_hit_testable()
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.input.get_pos()
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        self.input.apply_theme(theme)
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        self.input.report_resources(report);
+    }
+
+    fn glyph_count(&self) -> usize{
+        let toggle = if self.reveal_toggle { self.toggle_label().len() } else { 0 };
+        self.input.glyph_count() + toggle
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        self.input.hover_cursor()
+    }
+
+    fn is_focusable(&self) -> bool{
+        self.input.is_focusable()
+    }
+
+    fn set_focus(&mut self, focused: bool){
+        self.input.set_focus(focused);
+    }
+
+    fn has_focus(&self) -> bool{
+        self.input.has_focus()
+    }
+
+    fn bounds(&self, screen: (u32, u32)) -> Option<[f32; 4]>{
+        self.input.bounds(screen)
+    }
+
+    fn on_hover_enter(&mut self){
+        self.input.on_hover_enter();
+    }
+
+    fn on_hover_exit(&mut self){
+        self.input.on_hover_exit();
+    }
+
+    fn accepts_text(&self) -> bool{
+        self.input.accepts_text()
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        self.input.save_state()
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        self.input.restore_state(state);
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        self.input.access_info()
+    }
+
+    fn access_action(&mut self, action: &AccessAction, window: &Window, state: &mut AppState) -> bool{
+        self.input.access_action(action, window, state)
+    }
+}
@@ -0,0 +1,602 @@
+//! This module defines `TextInput`, a single line text entry box.
+//!
+//! It handles typing, a caret that can be moved with the arrow keys, Home and End, and IME input for
+//! CJK and other composed text. While the input method composes, its text (the preedit, see `GuiEvent::ImePreedit`)
+//! is drawn inline at the caret with an underline, and the candidate window is kept next to the caret with
+//! `set_ime_position`. The composed text is inserted once it's committed (`GuiEvent::ImeCommit`).
+//!
+//! Ctrl+C, Ctrl+X and Ctrl+V (Cmd on macOS) copy, cut and paste through the renderer's clipboard. There's no selection yet,
+//! so copy and cut act on the whole text. A secret input (like a `PasswordInput`) can't be copied or cut, and may be
+//! drawn masked - every character replaced with the same one.
+
+use std::{any::Any, borrow::Cow, rc::Rc};
+
+use wgpu::ShaderStage;
+use winit::{dpi::PhysicalPosition, window::{CursorIcon, Window}};
+
+use crate::{accessibility::{AccessAction, AccessInfo, Role}, clipboard::Clipboard, color::Color, event::{EventResult, GuiEvent, ModifiersState, MouseButton, VirtualKeyCode}, persistence::ComponentState, rendering::{RenderContext, Renderer, ResourceReport, TextQueue, Tracked, TransformSlot, TransformUniform}, state::AppState, theme::Theme};
+
+use super::Component;
+
+/// # TextEdit
+///
+/// The text of a `TextInput`, the caret in it, and the keys that edit them - everything about the input but drawing it,
+/// so it can be used (and tested) without a renderer.
+pub struct TextEdit{
+    text: String,
+    caret: usize, // in characters, not bytes
+    // The character drawn in place of each character of the text, if it's masked
+    mask: Option<char>,
+    // Set for passwords and the like, which can't be copied, cut or saved
+    secret: bool,
+    clipboard: Clipboard,
+}
+
+impl TextEdit{
+    /// Create an empty edit, that cuts, copies and pastes through `clipboard`
+    pub fn new(clipboard: Clipboard) -> Self{
+        Self{
+            text: String::new(),
+            caret: 0,
+            mask: None,
+            secret: false,
+            clipboard,
+        }
+    }
+
+    /// Get the text
+    pub fn text(&self) -> &str{
+        &self.text
+    }
+
+    /// Replace the text, moving the caret to the end
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.text = text.into();
+        self.caret = self.char_count();
+    }
+
+    /// Insert text at the caret
+    pub fn insert_text(&mut self, text: &str){
+        let index = self.byte_index(self.caret);
+        self.text.insert_str(index, text);
+        self.caret += text.chars().count();
+    }
+
+    /// The caret position, in characters from the start of the text
+    pub fn caret(&self) -> usize{
+        self.caret
+    }
+
+    /// Move the caret, in characters from the start of the text. It's clamped to the length of the text.
+    pub fn set_caret(&mut self, caret: usize){
+        self.caret = caret.min(self.char_count());
+    }
+
+    /// Draw `mask` in place of each character (or the text itself with `None`), and stop the text being copied, cut or
+    /// saved if it's `secret` (a `PasswordInput` is masked and secret)
+    pub fn set_mask(&mut self, mask: Option<char>, secret: bool){
+        self.mask = mask;
+        self.secret = secret;
+    }
+
+    /// The character drawn in place of each character of the text, if it's masked
+    pub fn mask(&self) -> Option<char>{
+        self.mask
+    }
+
+    /// Returns true if the text can't be copied, cut or saved
+    pub fn is_secret(&self) -> bool{
+        self.secret
+    }
+
+    /// Use `clipboard` for cut, copy and paste
+    pub fn set_clipboard(&mut self, clipboard: Clipboard){
+        self.clipboard = clipboard;
+    }
+
+    /// The text as it's drawn - the mask character repeated for each character, if it's masked
+    pub fn shown(&self) -> Cow<'_, str>{
+        match self.mask{
+            Some(mask) => Cow::Owned(std::iter::repeat_n(mask, self.char_count()).collect()),
+            None => Cow::Borrowed(&self.text),
+        }
+    }
+
+    /// Handle a key press, returning true if it was used (so Enter and Escape still reach a dialog's keys)
+    pub fn handle_key(&mut self, key: VirtualKeyCode, modifiers: ModifiersState) -> bool{
+        // Cmd is the shortcut modifier on macOS, Ctrl everywhere else
+        let shortcut = if cfg!(target_os = "macos") { modifiers.logo() } else { modifiers.ctrl() };
+        if shortcut{
+            match key{
+                // Still used, so the shortcut doesn't go on to do something else
+                VirtualKeyCode::C | VirtualKeyCode::X if self.secret => {}
+                VirtualKeyCode::C => {
+                    let _ = self.clipboard.set_text(self.text.as_str());
+                }
+                VirtualKeyCode::X if self.clipboard.set_text(self.text.as_str()).is_ok() => self.set_text(""),
+                VirtualKeyCode::V => {
+                    if let Ok(text) = self.clipboard.get_text(){
+                        // This is a single line input, so drop newlines and other control characters
+                        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+                        self.insert_text(&text);
+                    }
+                }
+                _ => return false,
+            }
+            return true;
+        }
+
+        match key{
+            VirtualKeyCode::Back if self.caret > 0 => {
+                self.caret -= 1;
+                let index = self.byte_index(self.caret);
+                self.text.remove(index);
+            }
+            VirtualKeyCode::Delete if self.caret < self.char_count() => {
+                let index = self.byte_index(self.caret);
+                self.text.remove(index);
+            }
+            VirtualKeyCode::Left => self.caret = self.caret.saturating_sub(1),
+            VirtualKeyCode::Right => self.caret = (self.caret + 1).min(self.char_count()),
+            VirtualKeyCode::Home => self.caret = 0,
+            VirtualKeyCode::End => self.caret = self.char_count(),
+            // At either end of the text, but still handled
+            VirtualKeyCode::Back | VirtualKeyCode::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    // The byte index of a character index
+    fn byte_index(&self, char_index: usize) -> usize{
+        byte_index(&self.text, char_index)
+    }
+
+    fn char_count(&self) -> usize{
+        self.text.chars().count()
+    }
+}
+
+// The text and how the input is drawn
+struct TextInputState{
+    edit: TextEdit,
+    preedit: Option<String>,
+    focused: bool,
+    enabled: bool,
+    text_size: f32,
+    color: Color,
+    // Set every frame by the input, in pixels from the top left of the window
+    origin: [f32; 2],
+    caret_x: f32,
+}
+
+// The byte index of a character index in `text`
+fn byte_index(text: &str, char_index: usize) -> usize{
+    text.char_indices().nth(char_index).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/// # TextInput
+///
+/// A single line text box. Click it to focus it, then type.
+///
+/// The position is the center of the box in pixels from the top left of the window (like a button's transform),
+/// and the size is in pixels.
+///
+/// Disabled inputs are still drawn, with their text greyed out, but can't be focused or typed in.
+pub struct TextInput{
+    state: TextInputState,
+    position: [f32; 2],
+    size: (f32, f32),
+    padding: f32,
+    hovered: bool,
+    visible: bool,
+    hit_test: bool,
+    // Set when the theme changed the colors, which are written to the tint buffers in `prerender`
+    restyled: bool,
+
+    // The renderer's shared quad
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+    background_color: Color,
+    background: TransformSlot,
+    background_tint: (Tracked<wgpu::Buffer>, wgpu::BindGroup),
+    underline: TransformSlot,
+    underline_tint: (Tracked<wgpu::Buffer>, wgpu::BindGroup),
+}
+
+impl TextInput{
+    /// Create a new, empty text input
+    pub fn new(position: [f32; 2], size: (f32, f32), text_size: f32, renderer: &Renderer) -> Self{
+        let state = TextInputState{
+            edit: TextEdit::new(renderer.clipboard().clone()),
+            preedit: None,
+            focused: false,
+            enabled: true,
+            text_size,
+            color: renderer.theme().colors.text,
+            origin: [0.0, 0.0],
+            caret_x: 0.0,
+        };
+
+        let cache = renderer.bind_group_cache();
+        let create_tint = |color: Color, label| {
+            let (buffer, bind_group, _) = cache.create_uniform(ShaderStage::FRAGMENT, 0, &color.to_linear(), label);
+            (buffer, bind_group)
+        };
+        let background_color = renderer.theme().colors.input_background;
+
+        Self{
+            state,
+            position,
+            size,
+            visible: true,
+            hit_test: true,
+            restyled: false,
+            padding: 6.0,
+            hovered: false,
+
+            vertex_buffer: renderer.quad_buffer(),
+            background_color,
+            background: renderer.transform_pool().allocate(TransformUniform::new()),
+            background_tint: create_tint(background_color, "Text input background tint"),
+            underline: renderer.transform_pool().allocate(TransformUniform::new()),
+            underline_tint: create_tint(renderer.theme().colors.text, "Text input underline tint"),
+        }
+    }
+
+    /// Get the text in the input
+    pub fn get_text(&self) -> String{
+        self.state.edit.text.clone()
+    }
+
+    /// Replace the text in the input, moving the caret to the end
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.state.edit.set_text(text);
+    }
+
+    /// Insert text at the caret (this is also how committed IME text is added)
+    pub fn insert_text(&mut self, text: &str){
+        self.state.edit.insert_text(text);
+    }
+
+    /// The caret position, in characters from the start of the text
+    pub fn get_caret(&self) -> usize{
+        self.state.edit.caret
+    }
+
+    /// Move the caret, in characters from the start of the text. It's clamped to the length of the text.
+    pub fn set_caret(&mut self, caret: usize){
+        self.state.edit.set_caret(caret);
+    }
+
+    /// Use `clipboard` for cut, copy and paste instead of the renderer's
+    pub fn set_clipboard(&mut self, clipboard: Clipboard){
+        self.state.edit.set_clipboard(clipboard);
+    }
+
+    /// Set the text being composed with an input method (drawn underlined at the caret), or `None` when composition ends
+    pub fn set_preedit(&mut self, preedit: Option<String>){
+        self.state.preedit = preedit.filter(|v| !v.is_empty());
+    }
+
+    /// Get the text being composed with an input method
+    pub fn get_preedit(&self) -> Option<String>{
+        self.state.preedit.clone()
+    }
+
+    /// Returns true if the input has keyboard focus
+    pub fn is_focused(&self) -> bool{
+        self.state.focused
+    }
+
+    /// Give the input keyboard focus, or take it away
+    pub fn set_focused(&mut self, focused: bool){
+        self.state.focused = focused;
+    }
+
+    /// Get the color of the text
+    pub fn get_text_color(&self) -> Color{
+        self.state.color
+    }
+
+    /// Change the color of the text
+    pub fn set_text_color(&mut self, color: Color, renderer: &Renderer){
+        self.state.color = color;
+        renderer.queue.write_buffer(&self.underline_tint.0, 0, bytemuck::cast_slice(&[color.to_linear()]));
+    }
+
+    /// Change the background color of the box
+    pub fn set_background_color(&mut self, color: Color, renderer: &Renderer){
+        self.background_color = color;
+        renderer.queue.write_buffer(&self.background_tint.0, 0, bytemuck::cast_slice(&[color.to_linear()]));
+    }
+
+    pub fn enable(&mut self){
+        self.state.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        let state = &mut self.state;
+        state.enabled = false;
+        state.focused = false;
+    }
+
+    /// Show or hide the input. Hiding it takes its focus away.
+    pub fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+        if !visible{
+            self.state.focused = false;
+        }
+    }
+
+    /// Choose whether the input can be hovered and clicked. With `false`, the mouse goes through it to whatever is below.
+    pub fn set_hit_test(&mut self, hit_test: bool){
+        self.hit_test = hit_test;
+    }
+
+    // Draw `mask` in place of each character (or the text itself with `None`), and stop the text being copied, cut or
+    // saved if it's `secret`
+    pub(crate) fn set_mask(&mut self, mask: Option<char>, secret: bool){
+        self.state.edit.set_mask(mask, secret);
+    }
+
+    // The space between the edges of the box and the text, in pixels
+    pub(crate) fn padding(&self) -> f32{
+        self.padding
+    }
+
+    pub(crate) fn text_size(&self) -> f32{
+        self.state.text_size
+    }
+
+    pub(crate) fn is_hovered(&self) -> bool{
+        self.hovered
+    }
+
+    // The box as [left, top, width, height], in pixels from the top left of the window
+    pub(crate) fn rect(&self) -> [f32; 4]{
+        [
+            self.position[0] - self.size.0 / 2.0,
+            self.position[1] - self.size.1 / 2.0,
+            self.size.0,
+            self.size.1,
+        ]
+    }
+}
+
+impl Component for TextInput{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {
+        let state = &self.state;
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        self.background.bind(render_pass, 1);
+        render_pass.set_bind_group(2, &self.background_tint.1, &[]);
+        render_pass.draw(0..6, 0..1);
+
+        if state.focused && state.preedit.is_some(){
+            self.underline.bind(render_pass, 1);
+            render_pass.set_bind_group(2, &self.underline_tint.1, &[]);
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        if self.state.color == theme.colors.text && self.background_color == theme.colors.input_background{
+            return false;
+        }
+        self.state.color = theme.colors.text;
+        self.background_color = theme.colors.input_background;
+        self.restyled = true;
+        true
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        if std::mem::take(&mut self.restyled){
+            context.queue.write_buffer(&self.background_tint.0, 0, bytemuck::cast_slice(&[self.background_color.to_linear()]));
+            context.queue.write_buffer(&self.underline_tint.0, 0, bytemuck::cast_slice(&[self.state.color.to_linear()]));
+        }
+        let rect = self.rect();
+        let state = &mut self.state;
+        state.origin = [rect[0] + self.padding, rect[1] + rect[3] / 2.0];
+
+        // Work out where the caret (and any preedit text) is, so the IME window and underline line up with it
+        let shown = state.edit.shown();
+        let caret = byte_index(&shown, state.edit.caret);
+        let caret_x = state.origin[0] + context.measure_text(&shown[..caret], state.text_size).0;
+        state.caret_x = caret_x;
+        self.background.write(TransformUniform::from_pixel_rect(rect));
+
+        if let Some(preedit) = &state.preedit{
+            let width = context.measure_text(preedit, state.text_size).0;
+            let underline = [caret_x, state.origin[1] + state.text_size / 2.0 - 2.0, width, 2.0];
+            self.underline.write(TransformUniform::from_pixel_rect(underline));
+        }
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue) where 'a: 'b {
+        let state = &self.state;
+        let shown = state.edit.shown();
+        let caret = byte_index(&shown, state.edit.caret);
+        let (before, after) = shown.split_at(caret);
+        // Greyed out like a disabled button's tint
+        let color = if state.enabled{
+            state.color
+        }else{
+            state.color.mix(Color::GRAY, 0.75).with_alpha(state.color.a * 0.5)
+        }.to_linear();
+        let scale = wgpu_glyph::ab_glyph::PxScale::from(state.text_size);
+
+        let mut text = vec![wgpu_glyph::Text::new(before).with_color(color).with_scale(scale)];
+        if let Some(preedit) = &state.preedit{
+            text.push(wgpu_glyph::Text::new(preedit).with_color(color).with_scale(scale));
+        }
+        if state.focused{
+            text.push(wgpu_glyph::Text::new("|").with_color(color).with_scale(scale));
+        }
+        text.push(wgpu_glyph::Text::new(after).with_color(color).with_scale(scale));
+
+        queue.queue(wgpu_glyph::Section {
+            screen_position: (state.origin[0], state.origin[1]),
+            text,
+            layout: wgpu_glyph::Layout::default_single_line().v_align(wgpu_glyph::VerticalAlign::Center),
+            ..wgpu_glyph::Section::default()
+        });
+    }
+
+    fn handle_event(&mut self, event: &GuiEvent, window: &Window, _state: &mut AppState) -> EventResult{
+        if !self.state.enabled{
+            return EventResult::Ignored;
+        }
+
+        let consumed = match event{
+            // The layout's hit test tells us if the press is on the input
+            GuiEvent::MouseDown{ button: MouseButton::Left, .. } => {
+                let focused = self.hovered;
+                self.set_focused(focused);
+                focused
+            }
+            GuiEvent::TextInput(c) if self.is_focused() && !c.is_control() => {
+                self.insert_text(c.encode_utf8(&mut [0; 4]));
+                true
+            }
+            GuiEvent::ImePreedit(text) if self.is_focused() => {
+                self.set_preedit(Some(text.clone()));
+                true
+            }
+            GuiEvent::ImeCommit(text) if self.is_focused() => {
+                self.state.preedit = None;
+                self.insert_text(text);
+                true
+            }
+            GuiEvent::KeyDown{ key, modifiers, .. } if self.is_focused() => self.state.edit.handle_key(*key, *modifiers),
+            _ => return EventResult::Ignored,
+        };
+
+        // Keep the input method's window next to the caret
+        let state = &self.state;
+        if state.focused{
+            window.set_ime_position(PhysicalPosition::new(state.caret_x, state.origin[1] + state.text_size / 2.0));
+        }
+        EventResult::consumed_if(consumed)
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.state.enabled
+    }
+
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool){
+        TextInput::set_visible(self, visible);
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        if enabled { self.enable() } else { self.disable() }
+    }
+
+    fn is_hit_testable(&self) -> bool{
+        self.hit_test
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.position
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        for _ in 0..2{
+            report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64);
+        }
+    }
+
+    fn glyph_count(&self) -> usize{
+        let state = &self.state;
+        state.edit.text.chars().chain(state.preedit.iter().flat_map(|v| v.chars())).filter(|c| !c.is_whitespace()).count()
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        if self.hovered && self.is_enabled(){
+            Some(CursorIcon::Text)
+        }else{
+            None
+        }
+    }
+
+    fn is_focusable(&self) -> bool{
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool){
+        self.set_focused(focused);
+    }
+
+    fn has_focus(&self) -> bool{
+        self.is_focused()
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect())
+    }
+
+    fn on_hover_enter(&mut self){
+        self.hovered = true;
+    }
+
+    fn on_hover_exit(&mut self){
+        self.hovered = false;
+    }
+
+    fn accepts_text(&self) -> bool{
+        true
+    }
+
+    fn save_state(&self) -> Option<ComponentState>{
+        Some(ComponentState{
+            // Secrets aren't written to disk
+            text: if self.state.edit.secret { None } else { Some(self.state.edit.text.clone()) },
+            position: Some(self.position),
+            visible: Some(self.visible),
+            enabled: Some(self.state.enabled),
+            ..ComponentState::default()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ComponentState){
+        if let Some(text) = &state.text{
+            self.set_text(text.as_str());
+        }
+        if let Some(position) = state.position{
+            self.position = position;
+        }
+        if let Some(visible) = state.visible{
+            self.set_visible(visible);
+        }
+        if let Some(enabled) = state.enabled{
+            if enabled { self.enable() } else { self.disable() }
+        }
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        let mut info = AccessInfo::new(Role::TextInput);
+        // Screen readers read out what's drawn, so a masked input's text stays hidden
+        info.value = Some(self.state.edit.shown().into_owned());
+        Some(info)
+    }
+
+    fn access_action(&mut self, action: &AccessAction, _window: &Window, _state: &mut AppState) -> bool{
+        match action{
+            AccessAction::SetValue(text) if self.state.enabled => {
+                self.set_text(text.as_str());
+                true
+            }
+            _ => false,
+        }
+    }
+}
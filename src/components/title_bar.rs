@@ -0,0 +1,307 @@
+//! This module contains the pieces needed to build a custom title bar for windows built with
+//! `set_decorations(false)` - a `DragRegion` that moves the window when dragged, and `WindowControlButton`s
+//! that minimize, maximize and close it.
+//!
+//! The drag region hands dragging over to the OS (`Window::drag_window`), which snaps and tiles the window like its own title
+//! bar would. Where the OS can't take over, the region moves the window itself by following the cursor instead.
+//! That works on Windows, macOS and X11, but Wayland doesn't let apps position their windows.
+
+use std::{any::Any, time::Duration};
+
+use winit::{dpi::PhysicalPosition, window::{CursorIcon, Window}};
+
+use crate::{accessibility::{AccessAction, AccessInfo, Role}, event::{EventResult, GuiEvent, MouseButton}, locale::Locale, rendering::{RenderContext, Renderer, ResourceReport, TextQueue, Transform}, state::AppState, theme::Theme};
+
+use super::{Button, Component};
+
+/// The area of the window a `DragRegion` covers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragArea{
+    /// A strip across the top of the window, `height` pixels tall. It follows the window width as it's resized.
+    TopStrip{ height: f32 },
+    /// A rectangle, in pixels from the top left of the window
+    Rect{ x: f32, y: f32, width: f32, height: f32 },
+    /// The area of a component with this position (its center) and scale (use the same values as the component's transform)
+    Component{ position: [f32; 2], scale: [f32; 2] },
+}
+
+impl DragArea{
+    /// Returns true if `point` (in pixels from the top left of a window of `size` pixels) is in the area
+    pub fn contains(&self, point: (f64, f64), size: (u32, u32)) -> bool{
+        let (x, y) = (point.0 as f32, point.1 as f32);
+        match *self{
+            DragArea::TopStrip{ height } => x >= 0.0 && x < size.0 as f32 && y >= 0.0 && y < height,
+            DragArea::Rect{ x: left, y: top, width, height } => x >= left && x < left + width && y >= top && y < top + height,
+            DragArea::Component{ position, scale } => {
+                (x - position[0]).abs() < scale[0] / 2.0 && (y - position[1]).abs() < scale[1] / 2.0
+            }
+        }
+    }
+}
+
+/// # DragRegion
+///
+/// An invisible component that moves the window while the left mouse button is held down on it.
+/// Add it to the layout before the title bar buttons.
+pub struct DragRegion{
+    area: DragArea,
+    // Where the cursor was (in window coordinates) when the drag started
+    drag_start: Option<(f64, f64)>,
+    enabled: bool,
+}
+
+impl DragRegion{
+    /// Create a new drag region covering `area`
+    pub fn new(area: DragArea) -> Self{
+        Self{
+            area,
+            drag_start: None,
+            enabled: true,
+        }
+    }
+
+    /// Change the area the region covers
+    pub fn set_area(&mut self, area: DragArea){
+        self.area = area;
+    }
+
+    /// Returns true while the region is moving the window itself (a drag the OS took over isn't reported)
+    pub fn is_dragging(&self) -> bool{
+        self.drag_start.is_some()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.drag_start = None;
+    }
+}
+
+impl Component for DragRegion{
+    fn handle_event(&mut self, event: &GuiEvent, window: &Window, _state: &mut AppState) -> EventResult{
+        if !self.enabled{
+            return EventResult::Ignored;
+        }
+
+        match event{
+            GuiEvent::MouseMove{ pos } => {
+                if let (Some(start), Ok(outer)) = (self.drag_start, window.outer_position()){
+                    // The cursor position is relative to the window, so moving the window by how far the
+                    // cursor moved from where it was grabbed keeps the grab point under the cursor
+                    let offset = (pos.0 - start.0, pos.1 - start.1);
+                    window.set_outer_position(PhysicalPosition::new(outer.x + offset.0 as i32, outer.y + offset.1 as i32));
+                }
+            }
+            GuiEvent::MouseDown{ button: MouseButton::Left, pos } => {
+                let size = window.inner_size();
+                if self.area.contains(*pos, (size.width, size.height)){
+                    // The OS takes the drag from here (including the release), otherwise we follow the cursor ourselves
+                    if window.drag_window().is_err(){
+                        self.drag_start = Some(*pos);
+                    }
+                    return EventResult::Consumed;
+                }
+            }
+            // The release that ends a drag is ours too
+            GuiEvent::MouseUp{ button: MouseButton::Left, .. } if self.drag_start.take().is_some() => return EventResult::Consumed,
+            _ => {}
+        }
+        EventResult::Ignored
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        if enabled { self.enable() } else { self.disable() }
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        if self.is_dragging() { Some(CursorIcon::Grabbing) } else { None }
+    }
+}
+
+/// What a `WindowControlButton` does when clicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowControl{
+    Minimize,
+    /// Toggles between maximized and restored
+    Maximize,
+    /// Asks to close the window, the same as the close button of a decorated window
+    Close,
+}
+
+impl WindowControl{
+    /// What the control is called, for assistive technology
+    pub fn name(&self) -> &'static str{
+        match self{
+            WindowControl::Minimize => "Minimize",
+            WindowControl::Maximize => "Maximize",
+            WindowControl::Close => "Close",
+        }
+    }
+
+    /// The label drawn on the button
+    pub fn label(&self) -> &'static str{
+        match self{
+            WindowControl::Minimize => "_",
+            WindowControl::Maximize => "+",
+            WindowControl::Close => "X",
+        }
+    }
+}
+
+/// # WindowControlButton
+///
+/// A button that minimizes, maximizes or closes the window, for custom title bars.
+pub struct WindowControlButton{
+    button: Button,
+    control: WindowControl,
+    close_requested: bool,
+}
+
+impl WindowControlButton{
+    /// Create a new window control button, labelled with the control's symbol
+    pub fn new(control: WindowControl, transform: Transform, renderer: &Renderer, text_size: f32) -> Self{
+        Self{
+            button: Button::new(transform, None, renderer, Some(control.label()), text_size),
+            control,
+            close_requested: false,
+        }
+    }
+
+    /// The control this button performs
+    pub fn get_control(&self) -> WindowControl{
+        self.control
+    }
+
+    /// Borrow the button, eg, to change its tint
+    pub fn borrow_button_mut(&mut self) -> &mut Button{
+        &mut self.button
+    }
+
+    // Minimize, maximize or close the window
+    fn perform(&mut self, window: &Window){
+        match self.control{
+            WindowControl::Minimize => window.set_minimized(true),
+            WindowControl::Maximize => {
+                // Ask the window, so maximizing it some other way (eg, with the OS's keyboard shortcut) doesn't get us out of step
+                window.set_maximized(!window.is_maximized());
+            }
+            WindowControl::Close => self.close_requested = true,
+        }
+    }
+}
+
+impl Component for WindowControlButton{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {
+        self.button.render(render_pass);
+    }
+
+    fn queue_text<'a, 'b>(&'a self, queue: &'b mut TextQueue) where 'a: 'b {
+        self.button.queue_text(queue);
+    }
+
+    fn handle_event(&mut self, event: &GuiEvent, window: &Window, state: &mut AppState) -> EventResult{
+        let result = self.button.handle_event(event, window, state);
+
+        if let GuiEvent::MouseUp{ button: MouseButton::Left, .. } = event{
+            if self.button.is_enabled() && self.button.is_cursor_in_bounds(){
+                self.perform(window);
+            }
+        }
+        result
+    }
+
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(AccessInfo::named(Role::Button, self.control.name()))
+    }
+
+    fn access_action(&mut self, action: &AccessAction, window: &Window, _state: &mut AppState) -> bool{
+        if *action != AccessAction::Click || !self.button.is_enabled(){
+            return false;
+        }
+        self.perform(window);
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.button.is_enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool){
+        self.button.set_enabled(enabled);
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.button.get_pos()
+    }
+
+    fn update(&mut self, delta_time: Duration){
+        self.button.update(delta_time);
+    }
+
+    fn prerender(&mut self, context: &mut RenderContext){
+        self.button.prerender(context);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> bool{
+        self.button.apply_theme(theme)
+    }
+
+    fn apply_locale(&mut self, locale: &Locale) -> bool{
+        self.button.apply_locale(locale)
+    }
+
+    fn report_resources(&self, report: &mut ResourceReport){
+        self.button.report_resources(report);
+    }
+
+    fn glyph_count(&self) -> usize{
+        self.button.glyph_count()
+    }
+
+    fn get_text(&self) -> Option<&str>{
+        self.button.get_text()
+    }
+
+    fn hover_cursor(&self) -> Option<CursorIcon>{
+        self.button.hover_cursor()
+    }
+
+    fn bounds(&self, screen: (u32, u32)) -> Option<[f32; 4]>{
+        self.button.bounds(screen)
+    }
+
+    fn on_hover_enter(&mut self){
+        self.button.on_hover_enter();
+    }
+
+    fn on_hover_exit(&mut self){
+        self.button.on_hover_exit();
+    }
+
+    fn take_close_request(&mut self) -> bool{
+        std::mem::take(&mut self.close_requested)
+    }
+}
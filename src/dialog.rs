@@ -0,0 +1,220 @@
+//! This module contains message boxes - a title, some text and a row of buttons, waiting for the user to pick one
+//! (see `GUI::message_box`), for simple confirmations that don't need a whole layout.
+//!
+//! They're shown with the OS's own dialogs where there's a tool to show them (`osascript` on macOS, PowerShell on
+//! Windows, `zenity` or `kdialog` elsewhere). When there isn't, the GUI draws the message box in the main window itself.
+
+use std::{cell::Cell, process::{Command, Output}, rc::Rc};
+
+use crate::{components::{Button, Label}, layout::Layout, rendering::{Renderer, Transform}};
+
+/// The buttons a message box shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageButtons{
+    /// A single OK button, for messages that only need acknowledging (the default)
+    #[default]
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+}
+
+impl MessageButtons{
+    /// The choices the buttons stand for, in the order they're shown. The first one is the default.
+    pub fn choices(self) -> &'static [Choice]{
+        match self{
+            MessageButtons::Ok => &[Choice::Ok],
+            MessageButtons::OkCancel => &[Choice::Ok, Choice::Cancel],
+            MessageButtons::YesNo => &[Choice::Yes, Choice::No],
+            MessageButtons::YesNoCancel => &[Choice::Yes, Choice::No, Choice::Cancel],
+        }
+    }
+
+    /// The choice picked when the message box is closed without pressing a button - `Cancel` if there is one,
+    /// otherwise `No`, otherwise `Ok`
+    pub fn dismiss(self) -> Choice{
+        match self{
+            MessageButtons::Ok => Choice::Ok,
+            MessageButtons::YesNo => Choice::No,
+            MessageButtons::OkCancel | MessageButtons::YesNoCancel => Choice::Cancel,
+        }
+    }
+}
+
+/// The button picked in a message box
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice{
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+impl Choice{
+    /// The text on the choice's button
+    pub fn label(self) -> &'static str{
+        match self{
+            Choice::Ok => "OK",
+            Choice::Cancel => "Cancel",
+            Choice::Yes => "Yes",
+            Choice::No => "No",
+        }
+    }
+
+    // The choice with the button text `label`, among `buttons`
+    fn from_label(buttons: MessageButtons, label: &str) -> Option<Choice>{
+        buttons.choices().iter().copied().find(|choice| choice.label().eq_ignore_ascii_case(label))
+    }
+}
+
+/// Show a message box with the OS's own dialog, and wait for the user to pick a button. Returns `None` if there's no
+/// tool to show one (eg, `zenity` isn't installed), in which case `GUI::message_box` draws its own.
+pub fn native_message_box(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    show_native(title, text, buttons)
+}
+
+// Run a dialog tool with the environment variables `vars` set, returning its output (or `None` if it isn't there)
+fn run(program: &str, args: &[&str], vars: &[(&str, &str)]) -> Option<Output>{
+    Command::new(program).args(args).envs(vars.iter().copied()).output().ok()
+}
+
+#[cfg(target_os = "macos")]
+fn show_native(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let labels = buttons.choices().iter().map(|choice| format!("\"{}\"", choice.label())).collect::<Vec<_>>().join(", ");
+    let script = format!("display dialog \"{}\" with title \"{}\" buttons {{{}}} default button 1", escape(text), escape(title), labels);
+    let output = run("osascript", &["-e", &script], &[])?;
+    if !output.status.success(){
+        // A button called "Cancel" (or Escape) stops the script with error -128
+        return String::from_utf8_lossy(&output.stderr).contains("-128").then(|| buttons.dismiss());
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Choice::from_label(buttons, stdout.trim().strip_prefix("button returned:")?)
+}
+
+#[cfg(target_os = "windows")]
+fn show_native(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    let kind = match buttons{
+        MessageButtons::Ok => "OK",
+        MessageButtons::OkCancel => "OKCancel",
+        MessageButtons::YesNo => "YesNo",
+        MessageButtons::YesNoCancel => "YesNoCancel",
+    };
+    // The title and text are handed over in environment variables rather than quoted into the script, so nothing in them
+    // (like PowerShell's curly quotes, which close a string too) can break out of it
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.MessageBox]::Show($env:RUSTY_GUI_TEXT, $env:RUSTY_GUI_TITLE, '{}')",
+        kind
+    );
+    let output = run("powershell", &["-NoProfile", "-Command", &script], &[("RUSTY_GUI_TITLE", title), ("RUSTY_GUI_TEXT", text)])?;
+    if !output.status.success(){
+        return None;
+    }
+    Choice::from_label(buttons, String::from_utf8(output.stdout).ok()?.trim())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn show_native(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    // Without a display the tools can't show anything, but may still exit as if a button was pressed
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none(){
+        return None;
+    }
+    zenity(title, text, buttons).or_else(|| kdialog(title, text, buttons))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn zenity(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    let args = zenity_args(title, text, buttons);
+    let output = run("zenity", &args.iter().map(String::as_str).collect::<Vec<_>>(), &[])?;
+    zenity_choice(buttons, output.status.code(), &String::from_utf8_lossy(&output.stdout), &String::from_utf8_lossy(&output.stderr))
+}
+
+/// The arguments `zenity` is run with to show a message box (used on Linux and the BSDs)
+pub fn zenity_args(title: &str, text: &str, buttons: MessageButtons) -> Vec<String>{
+    let mut args = vec!["--title", title, "--text", text, "--no-markup"];
+    match buttons{
+        MessageButtons::Ok => args.push("--info"),
+        MessageButtons::OkCancel => args.extend(&["--question", "--ok-label", "OK", "--cancel-label", "Cancel"]),
+        MessageButtons::YesNo => args.extend(&["--question", "--ok-label", "Yes", "--cancel-label", "No"]),
+        MessageButtons::YesNoCancel => args.extend(&["--question", "--ok-label", "Yes", "--cancel-label", "No", "--extra-button", "Cancel"]),
+    }
+    args.into_iter().map(String::from).collect()
+}
+
+/// The choice a `zenity` message box run with `zenity_args` exited with, from its exit code and output. Returns `None`
+/// if it couldn't show the dialog.
+pub fn zenity_choice(buttons: MessageButtons, code: Option<i32>, stdout: &str, stderr: &str) -> Option<Choice>{
+    let choices = buttons.choices();
+    match code{
+        Some(0) => Some(choices[0]),
+        // The second button and closing the dialog both exit with 1, while extra buttons print their label. zenity also
+        // exits with 1 when GTK fails to start (eg, it can't open the display), with the reason on stderr.
+        Some(1) if stderr.trim().is_empty() => match stdout.trim(){
+            "" => Some(choices.get(1).copied().unwrap_or_else(|| buttons.dismiss())),
+            label => Choice::from_label(buttons, label),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn kdialog(title: &str, text: &str, buttons: MessageButtons) -> Option<Choice>{
+    let mut args = vec!["--title", title];
+    match buttons{
+        MessageButtons::Ok => args.extend(&["--msgbox", text]),
+        MessageButtons::OkCancel => args.extend(&["--yesno", text, "--yes-label", "OK", "--no-label", "Cancel"]),
+        MessageButtons::YesNo => args.extend(&["--yesno", text]),
+        MessageButtons::YesNoCancel => args.extend(&["--yesnocancel", text]),
+    }
+    let output = run("kdialog", &args, &[])?;
+    let choices = buttons.choices();
+    match output.status.code(){
+        // kdialog exits with the index of the button that was pressed
+        Some(code @ 0..=2) => choices.get(code as usize).copied(),
+        _ => None,
+    }
+}
+
+// The size of each button in the GUI's own message box, and the gap around them
+const BUTTON_SIZE: (f32, f32) = (96.0, 32.0);
+const MARGIN: f32 = 24.0;
+
+/// Build the layout the GUI draws in place of a native message box, filling the main window. Clicking a button
+/// stores its choice in `picked`. The default button starts with focus, so Enter picks it.
+pub(crate) fn message_box_layout(renderer: &Renderer, title: &str, text: &str, buttons: MessageButtons, picked: &Rc<Cell<Option<Choice>>>) -> Layout{
+    let mut layout = Layout::new();
+    let text_color = renderer.theme().colors.text;
+    let (width, height) = (renderer.size.width as f32, renderer.size.height as f32);
+
+    let mut title_label = Label::new(title, 24.0, [MARGIN, MARGIN]);
+    title_label.set_color(text_color);
+    layout.add_component(Box::new(title_label));
+    let mut text_label = Label::new(text, 16.0, [MARGIN, MARGIN * 3.0]);
+    text_label.set_color(text_color);
+    layout.add_component(Box::new(text_label));
+
+    // The buttons sit in a row along the bottom right, in order
+    let choices = buttons.choices();
+    let mut first = None;
+    for (i, choice) in choices.iter().copied().enumerate(){
+        let from_right = (choices.len() - 1 - i) as f32;
+        let center = (
+            width - MARGIN - BUTTON_SIZE.0 * 0.5 - from_right * (BUTTON_SIZE.0 + MARGIN * 0.5),
+            height - MARGIN - BUTTON_SIZE.1 * 0.5,
+        );
+        let transform = Transform::new(
+            cgmath::Vector3::new(center.0, center.1, 0.0),
+            cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            cgmath::Vector3::new(BUTTON_SIZE.0, BUTTON_SIZE.1, 1.0),
+            renderer.transform_pool(),
+        );
+        let mut button = Button::new(transform, None, renderer, Some(choice.label()), 16.0);
+        let picked = picked.clone();
+        button.on_click(move |_| picked.set(Some(choice)));
+        let id = layout.add_component(Box::new(button));
+        first.get_or_insert(id);
+    }
+    // The layout's components are all valid, so focusing one can't fail
+    let _ = layout.set_focus(first);
+    layout
+}
@@ -0,0 +1,203 @@
+//! This module contains easing curves - functions that take how far through an animation is (`t`, from 0 to 1) and
+//! return how far along its value should be. They're used by the `Animator` through `Easing`, and are plain functions
+//! so they can be used anywhere else too (eg, easing a value by hand in `Component::update`).
+//!
+//! Every curve starts at 0 and ends at 1. Elastic curves (and bezier curves with control points above 1 or below 0)
+//! overshoot in between, so eased values can go a little past their start and end.
+
+use std::f32::consts::PI;
+
+/// No easing - the same speed all the way through
+pub fn linear(t: f32) -> f32{
+    t
+}
+
+/// Start slow, and speed up (quadratic)
+pub fn quad_in(t: f32) -> f32{
+    t * t
+}
+
+/// Start fast, and slow down (quadratic)
+pub fn quad_out(t: f32) -> f32{
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+/// Start and end slow (quadratic)
+pub fn quad_in_out(t: f32) -> f32{
+    if t < 0.5{
+        2.0 * t * t
+    }else{
+        1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+    }
+}
+
+/// Start slow, and speed up (cubic)
+pub fn cubic_in(t: f32) -> f32{
+    t * t * t
+}
+
+/// Start fast, and slow down (cubic)
+pub fn cubic_out(t: f32) -> f32{
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Start and end slow (cubic)
+pub fn cubic_in_out(t: f32) -> f32{
+    if t < 0.5{
+        4.0 * t * t * t
+    }else{
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Wind up with a growing wobble, then snap to the end
+pub fn elastic_in(t: f32) -> f32{
+    if t <= 0.0 || t >= 1.0{
+        return t.clamp(0.0, 1.0);
+    }
+    -(2.0f32).powf(10.0 * t - 10.0) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+/// Overshoot the end, then wobble to rest on it, like a spring
+pub fn elastic_out(t: f32) -> f32{
+    if t <= 0.0 || t >= 1.0{
+        return t.clamp(0.0, 1.0);
+    }
+    (2.0f32).powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+}
+
+/// Wobble out of the start and into the end
+pub fn elastic_in_out(t: f32) -> f32{
+    if t <= 0.0 || t >= 1.0{
+        return t.clamp(0.0, 1.0);
+    }
+    let wobble = ((20.0 * t - 11.125) * (2.0 * PI / 4.5)).sin();
+    if t < 0.5{
+        -((2.0f32).powf(20.0 * t - 10.0) * wobble) / 2.0
+    }else{
+        (2.0f32).powf(-20.0 * t + 10.0) * wobble / 2.0 + 1.0
+    }
+}
+
+/// Bounce off the start a few times before leaving it
+pub fn bounce_in(t: f32) -> f32{
+    1.0 - bounce_out(1.0 - t)
+}
+
+/// Drop onto the end and bounce, like a ball
+pub fn bounce_out(t: f32) -> f32{
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D{
+        N * t * t
+    }else if t < 2.0 / D{
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    }else if t < 2.5 / D{
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    }else{
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Bounce out of the start and into the end
+pub fn bounce_in_out(t: f32) -> f32{
+    if t < 0.5{
+        (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+    }else{
+        (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+    }
+}
+
+/// A cubic bezier curve from (0, 0) to (1, 1), with control points (`x1`, `y1`) and (`x2`, `y2`) - the same as CSS's
+/// `cubic-bezier()`, so curves from design tools can be used as they are. `x1` and `x2` are kept between 0 and 1.
+pub fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, t: f32) -> f32{
+    if t <= 0.0 || t >= 1.0{
+        return t.clamp(0.0, 1.0);
+    }
+    let (x1, x2) = (x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0));
+    // One coordinate of the curve at `s`, and its slope
+    let curve = |a: f32, b: f32, s: f32| 3.0 * a * s * (1.0 - s) * (1.0 - s) + 3.0 * b * s * s * (1.0 - s) + s * s * s;
+    let slope = |a: f32, b: f32, s: f32| 3.0 * a * (1.0 - s) * (1.0 - s) + 6.0 * (b - a) * s * (1.0 - s) + 3.0 * (1.0 - b) * s * s;
+
+    // Find where the curve's x is `t` - Newton's method is quick where the curve is steep enough, bisection is the fallback
+    let mut s = t;
+    for _ in 0..8{
+        let error = curve(x1, x2, s) - t;
+        let d = slope(x1, x2, s);
+        if error.abs() < 1e-6{
+            return curve(y1, y2, s);
+        }
+        if d.abs() < 1e-6{
+            break;
+        }
+        s = (s - error / d).clamp(0.0, 1.0);
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    s = t;
+    for _ in 0..32{
+        let x = curve(x1, x2, s);
+        if (x - t).abs() < 1e-6{
+            break;
+        }
+        if x < t{
+            low = s;
+        }else{
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    curve(y1, y2, s)
+}
+
+/// # Easing
+///
+/// An easing curve, for an `Animation` (see `Animation::set_easing`). Any other curve can be drawn with `CubicBezier`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing{
+    /// The same speed all the way through (the default)
+    #[default]
+    Linear,
+    /// Start slow, and speed up (cubic)
+    EaseIn,
+    /// Start fast, and slow down (cubic)
+    EaseOut,
+    /// Start and end slow (cubic)
+    EaseInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    /// A cubic bezier curve with control points (x1, y1) and (x2, y2) (see `cubic_bezier`)
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing{
+    /// Ease `t` (the fraction of the duration that has passed, from 0 to 1)
+    pub fn apply(self, t: f32) -> f32{
+        let t = t.clamp(0.0, 1.0);
+        match self{
+            Easing::Linear => linear(t),
+            Easing::EaseIn => cubic_in(t),
+            Easing::EaseOut => cubic_out(t),
+            Easing::EaseInOut => cubic_in_out(t),
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => quad_out(t),
+            Easing::QuadInOut => quad_in_out(t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => elastic_in_out(t),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => bounce_in_out(t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! This module contains `GuiEvent`, the events components and user callbacks receive. winit's events are
+//! translated into them in one place (`EventTranslator`), so nothing else has to pattern match through
+//! winit's nested event types, or keep track of where the cursor is to find out where a click happened.
+//!
+//! Mouse buttons, key codes, scancodes and modifiers are winit's own types, re-exported here.
+//!
+//! Double and triple clicks are detected here too (`ClickCounter`), so every component agrees on what counts as one.
+
+use std::{any::Any, path::PathBuf, rc::Rc, time::{Duration, Instant}};
+
+use winit::event::{ElementState, Ime, KeyboardInput, MouseScrollDelta, Touch, WindowEvent};
+
+pub use winit::event::{ModifiersState, MouseButton, ScanCode, TouchPhase, VirtualKeyCode};
+
+use crate::{color_scheme::ColorScheme, gesture::{Gesture, GestureRecognizer}};
+
+/// How far the mouse wheel (or trackpad) scrolled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollDelta{
+    /// Scrolled by lines (most mouse wheels)
+    Lines{ x: f32, y: f32 },
+    /// Scrolled by pixels (most trackpads)
+    Pixels{ x: f64, y: f64 },
+}
+
+/// A navigation action, for moving around the UI without a pointer (eg, from a gamepad's D-pad and A and B buttons)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavAction{
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Press the focused component (eg, click a button)
+    Activate,
+    /// Go back, or cancel (eg, close a dialog)
+    Back,
+}
+
+/// # EventResult
+///
+/// What a component did with an event. A consumed event isn't passed on to the components under it, or to the
+/// event loop handler - so a click on a dialog doesn't also press the buttons behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventResult{
+    /// The event carries on to the next component
+    Ignored,
+    /// The event was handled, and stops here
+    Consumed,
+}
+
+impl EventResult{
+    /// `Consumed` if `handled` is true, otherwise `Ignored`
+    pub fn consumed_if(handled: bool) -> Self{
+        if handled { EventResult::Consumed } else { EventResult::Ignored }
+    }
+
+    /// Returns true if the event was consumed
+    pub fn is_consumed(self) -> bool{
+        self == EventResult::Consumed
+    }
+}
+
+/// # UserEvent
+///
+/// An event posted by the app itself, usually from another thread with an `EventSender`. It can hold any type -
+/// ask for it back with `get`. Clones share the same value.
+#[derive(Clone)]
+pub struct UserEvent{
+    value: Rc<dyn Any>,
+}
+
+impl UserEvent{
+    /// Wrap `value` in an event
+    pub fn new<T: Any>(value: T) -> Self{
+        Self{
+            value: Rc::new(value),
+        }
+    }
+
+    // Wrap a value that was sent from another thread
+    pub(crate) fn from_boxed(value: Box<dyn Any + Send>) -> Self{
+        let value: Box<dyn Any> = value;
+        Self{
+            value: Rc::from(value),
+        }
+    }
+
+    /// Borrow the value, if it's a `T`
+    pub fn get<T: Any>(&self) -> Option<&T>{
+        self.value.downcast_ref()
+    }
+
+    /// Returns true if the value is a `T`
+    pub fn is<T: Any>(&self) -> bool{
+        self.value.is::<T>()
+    }
+}
+
+impl std::fmt::Debug for UserEvent{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("UserEvent").finish_non_exhaustive()
+    }
+}
+
+// Events are only equal to their own clones, as the value can be any type
+impl PartialEq for UserEvent{
+    fn eq(&self, other: &Self) -> bool{
+        Rc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+/// # GuiEvent
+///
+/// Something that happened to a window. Positions are in physical pixels from the top left of the window.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuiEvent{
+    /// A mouse button was pressed at `pos`
+    MouseDown{ button: MouseButton, pos: (f64, f64) },
+    /// A mouse button was released at `pos`
+    MouseUp{ button: MouseButton, pos: (f64, f64) },
+    /// A mouse button was pressed twice in quick succession at `pos`. Sent after the second `MouseDown`.
+    DoubleClick{ button: MouseButton, pos: (f64, f64) },
+    /// A mouse button was pressed three times in quick succession at `pos` (eg, to select a line of text).
+    /// Sent after the third `MouseDown`.
+    TripleClick{ button: MouseButton, pos: (f64, f64) },
+    /// The cursor moved to `pos`
+    MouseMove{ pos: (f64, f64) },
+    /// The cursor entered the window
+    MouseEnter,
+    /// The cursor left the window
+    MouseLeave,
+    /// The mouse wheel (or trackpad) scrolled
+    Scroll{ delta: ScrollDelta },
+    /// A finger touched, moved on, or left the screen at `pos`. `id` tells fingers apart while they're down.
+    Touch{ id: u64, phase: TouchPhase, pos: (f64, f64) },
+    /// A gesture (tap, long press, swipe or pinch) was recognized from touches or the left mouse button
+    Gesture(Gesture),
+    /// A key was pressed (or repeated while held down). `key` is the logical key - what the keyboard layout makes
+    /// of it, for shortcuts and text editing. `scancode` is the physical key - where it sits on the keyboard whatever
+    /// the layout, for things like WASD movement. Synthesized presses (eg, from `test::key_press`) have a scancode of 0.
+    KeyDown{ key: VirtualKeyCode, scancode: ScanCode, modifiers: ModifiersState },
+    /// A key was released (see `KeyDown` for the logical and physical keys)
+    KeyUp{ key: VirtualKeyCode, scancode: ScanCode, modifiers: ModifiersState },
+    /// A navigation action (see the `gamepad` feature). The directions move focus, and the layout sends
+    /// `Activate` and `Back` to the focused component first, like a key press.
+    Navigate(NavAction),
+    /// A character was typed. Text composed with an input method arrives as `ImeCommit` instead.
+    TextInput(char),
+    /// An input method's composition (preedit) text changed, while it's being composed into the text to commit. Sent
+    /// empty when the composition is cleared. Input methods are only used while a component that takes text has focus.
+    ImePreedit(String),
+    /// An input method finished composing, and `String` is the text to insert
+    ImeCommit(String),
+    /// The modifier keys (Shift, Ctrl, Alt, Logo) changed
+    ModifiersChanged(ModifiersState),
+    /// The window was resized, in physical pixels
+    Resized{ width: u32, height: u32 },
+    /// The window moved to a monitor with a different scale factor (or the scale factor changed)
+    ScaleFactorChanged{ scale_factor: f64 },
+    /// The window gained (true) or lost (false) keyboard focus
+    Focused(bool),
+    /// The OS switched between light and dark mode (`ColorScheme::Light` or `Dark`). Windows, macOS and Wayland report this.
+    ColorSchemeChanged(ColorScheme),
+    /// A file was dropped on the window
+    FileDropped(PathBuf),
+    /// The window was asked to close (eg, by its close button)
+    CloseRequested,
+    /// An event the app posted (see `EventSender`)
+    User(UserEvent),
+}
+
+/// # EventTranslator
+///
+/// Turns a window's winit events into `GuiEvent`s. It remembers where the cursor is (so mouse buttons get a position)
+/// and which modifiers are held (so key presses carry them), so keep one per window.
+#[derive(Debug, Clone, Default)]
+pub struct EventTranslator{
+    cursor: Option<(f64, f64)>,
+    modifiers: ModifiersState,
+    clicks: ClickCounter,
+    gestures: GestureRecognizer,
+}
+
+impl EventTranslator{
+    /// Create a new translator, with the cursor outside the window and no modifiers held
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Where the cursor is, if it's over the window
+    pub fn cursor_position(&self) -> Option<(f64, f64)>{
+        self.cursor
+    }
+
+    /// The modifiers currently held down
+    pub fn modifiers(&self) -> ModifiersState{
+        self.modifiers
+    }
+
+    /// The double or triple click a translated `MouseDown` completes, if any. Dispatch it after the `MouseDown` itself.
+    pub fn click_event(&mut self, event: &GuiEvent) -> Option<GuiEvent>{
+        self.clicks.click_event(event)
+    }
+
+    /// The gestures a translated event completes, if any. Dispatch them after the event itself.
+    pub fn gesture_events(&mut self, event: &GuiEvent) -> Vec<GuiEvent>{
+        self.gestures.process(event, Instant::now()).into_iter().map(GuiEvent::Gesture).collect()
+    }
+
+    /// Check for gestures that are recognized by time rather than by an event (a long press), returning one if it's due
+    pub fn poll_gestures(&mut self) -> Option<GuiEvent>{
+        self.gestures.poll(Instant::now()).map(GuiEvent::Gesture)
+    }
+
+    /// When the next time based gesture could be recognized (see `poll_gestures`), so the event loop can wake up for it
+    pub fn next_gesture_deadline(&self) -> Option<Instant>{
+        self.gestures.next_deadline()
+    }
+
+    /// Translate a winit window event. Returns `None` for events with no `GuiEvent` equivalent, and for mouse
+    /// buttons pressed before the cursor position is known (they can't be placed).
+    pub fn translate(&mut self, event: &WindowEvent) -> Option<GuiEvent>{
+        match event{
+            WindowEvent::CursorMoved{ position, .. } => {
+                let pos = (position.x, position.y);
+                self.cursor = Some(pos);
+                Some(GuiEvent::MouseMove{ pos })
+            }
+            WindowEvent::CursorEntered{ .. } => Some(GuiEvent::MouseEnter),
+            WindowEvent::CursorLeft{ .. } => {
+                self.cursor = None;
+                Some(GuiEvent::MouseLeave)
+            }
+            WindowEvent::MouseInput{ state, button, .. } => {
+                let pos = self.cursor?;
+                match state{
+                    ElementState::Pressed => Some(GuiEvent::MouseDown{ button: *button, pos }),
+                    ElementState::Released => Some(GuiEvent::MouseUp{ button: *button, pos }),
+                }
+            }
+            WindowEvent::MouseWheel{ delta, .. } => {
+                let delta = match delta{
+                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines{ x: *x, y: *y },
+                    MouseScrollDelta::PixelDelta(v) => ScrollDelta::Pixels{ x: v.x, y: v.y },
+                };
+                Some(GuiEvent::Scroll{ delta })
+            }
+            WindowEvent::KeyboardInput{ input: KeyboardInput{ virtual_keycode: Some(key), scancode, state, .. }, .. } => {
+                let (key, scancode, modifiers) = (*key, *scancode, self.modifiers);
+                match state{
+                    ElementState::Pressed => Some(GuiEvent::KeyDown{ key, scancode, modifiers }),
+                    ElementState::Released => Some(GuiEvent::KeyUp{ key, scancode, modifiers }),
+                }
+            }
+            WindowEvent::Touch(Touch{ id, phase, location, .. }) => Some(GuiEvent::Touch{ id: *id, phase: *phase, pos: (location.x, location.y) }),
+            WindowEvent::ReceivedCharacter(c) => Some(GuiEvent::TextInput(*c)),
+            WindowEvent::Ime(Ime::Preedit(text, _)) => Some(GuiEvent::ImePreedit(text.clone())),
+            WindowEvent::Ime(Ime::Commit(text)) => Some(GuiEvent::ImeCommit(text.clone())),
+            // Turning the input method off drops whatever was being composed
+            WindowEvent::Ime(Ime::Disabled) => Some(GuiEvent::ImePreedit(String::new())),
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = *modifiers;
+                Some(GuiEvent::ModifiersChanged(*modifiers))
+            }
+            WindowEvent::Resized(size) => Some(GuiEvent::Resized{ width: size.width, height: size.height }),
+            WindowEvent::ScaleFactorChanged{ scale_factor, .. } => Some(GuiEvent::ScaleFactorChanged{ scale_factor: *scale_factor }),
+            WindowEvent::Focused(focused) => Some(GuiEvent::Focused(*focused)),
+            WindowEvent::ThemeChanged(theme) => Some(GuiEvent::ColorSchemeChanged(match theme{
+                winit::window::Theme::Dark => ColorScheme::Dark,
+                winit::window::Theme::Light => ColorScheme::Light,
+            })),
+            WindowEvent::DroppedFile(path) => Some(GuiEvent::FileDropped(path.clone())),
+            WindowEvent::CloseRequested => Some(GuiEvent::CloseRequested),
+            _ => None,
+        }
+    }
+}
+
+/// # ClickTracker
+///
+/// Finds completed clicks on something (eg, a button) - a left press that started on it, and a left release on it.
+/// Pressing, dragging off and releasing elsewhere isn't a click, and neither is pressing elsewhere and releasing on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClickTracker{
+    pressed: bool,
+}
+
+impl ClickTracker{
+    /// Create a new click tracker, with nothing pressed
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Feed it an event, and whether the cursor is over the thing being clicked. Returns true if the event completes a click.
+    pub fn update(&mut self, event: &GuiEvent, in_bounds: bool) -> bool{
+        match event{
+            GuiEvent::MouseDown{ button: MouseButton::Left, .. } => {
+                self.pressed = in_bounds;
+                false
+            }
+            GuiEvent::MouseUp{ button: MouseButton::Left, .. } => std::mem::take(&mut self.pressed) && in_bounds,
+            // We won't hear about the release if it happens in another window
+            GuiEvent::Focused(false) => {
+                self.pressed = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true while a press that started in bounds is held
+    pub fn is_pressed(&self) -> bool{
+        self.pressed
+    }
+
+    /// Forget the current press, so releasing it isn't a click (eg, when the button is disabled)
+    pub fn cancel(&mut self){
+        self.pressed = false;
+    }
+}
+
+/// # ClickCounter
+///
+/// Counts how many times in a row a mouse button was pressed - presses count as one run if they're close together,
+/// in time (`interval`) and space (`distance`, in pixels). After three clicks the count starts again, so a fourth
+/// quick click is a single click.
+#[derive(Debug, Clone)]
+pub struct ClickCounter{
+    interval: Duration,
+    distance: f64,
+    // The last press - its button, position, time, and how many clicks it made
+    last: Option<(MouseButton, (f64, f64), Instant, u32)>,
+}
+
+impl Default for ClickCounter{
+    fn default() -> ClickCounter{
+        Self::new()
+    }
+}
+
+impl ClickCounter{
+    /// The longest gap between two presses that still counts as a double click
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+    /// How far (in pixels) the cursor can move between two presses that still count as a double click
+    pub const DEFAULT_DISTANCE: f64 = 4.0;
+
+    /// Create a new click counter with the default limits
+    pub fn new() -> Self{
+        Self::with_limits(Self::DEFAULT_INTERVAL, Self::DEFAULT_DISTANCE)
+    }
+
+    /// Create a new click counter, with the longest gap between presses and the furthest the cursor can move between them
+    pub fn with_limits(interval: Duration, distance: f64) -> Self{
+        Self{
+            interval,
+            distance,
+            last: None,
+        }
+    }
+
+    /// Record a press of `button` at `pos` and `time`, returning how many clicks in a row it makes (1, 2 or 3)
+    pub fn register(&mut self, button: MouseButton, pos: (f64, f64), time: Instant) -> u32{
+        let count = match self.last{
+            Some((last_button, last_pos, last_time, count)) if last_button == button
+                && count < 3
+                && time.saturating_duration_since(last_time) <= self.interval
+                && (pos.0 - last_pos.0).hypot(pos.1 - last_pos.1) <= self.distance => count + 1,
+            _ => 1,
+        };
+        self.last = Some((button, pos, time, count));
+        count
+    }
+
+    /// Record a `MouseDown` event (ignoring anything else), returning the double or triple click it completes, if any
+    pub fn click_event(&mut self, event: &GuiEvent) -> Option<GuiEvent>{
+        let (button, pos) = match event{
+            GuiEvent::MouseDown{ button, pos } => (*button, *pos),
+            _ => return None,
+        };
+        match self.register(button, pos, Instant::now()){
+            2 => Some(GuiEvent::DoubleClick{ button, pos }),
+            3 => Some(GuiEvent::TripleClick{ button, pos }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! This module lets a gamepad (or a TV remote that shows up as one) drive the UI, for couch and embedded apps with no pointer.
+//! It's only built with the `gamepad` feature, and uses gilrs to read the controllers.
+//!
+//! The D-pad and left stick move focus (see `Layout::focus_direction`), A (the bottom face button) activates the
+//! focused component, and B (the right face button) goes back. Holding a direction repeats it, like a held key.
+//! Enable it with `GUI::enable_gamepad`.
+
+use std::time::{Duration, Instant};
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+use crate::event::NavAction;
+
+/// How far the stick has to be pushed to count as a direction
+const STICK_THRESHOLD: f32 = 0.5;
+/// How long a direction is held before it starts repeating
+const REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// How often a held direction repeats
+const REPEAT_INTERVAL: Duration = Duration::from_millis(150);
+
+/// # GamepadInput
+///
+/// Reads every connected controller, and turns their input into navigation actions.
+pub struct GamepadInput{
+    gilrs: Gilrs,
+    // The direction held on the D-pad, and on the stick
+    dpad: Option<NavAction>,
+    stick: Option<NavAction>,
+    stick_axes: (f32, f32),
+    // The direction being repeated, and when it next repeats
+    held: Option<(NavAction, Instant)>,
+}
+
+impl GamepadInput{
+    /// Connect to the system's controllers. Fails if the platform's gamepad API isn't available.
+    pub fn new() -> Result<Self, &'static str>{
+        let gilrs = match Gilrs::new(){
+            Ok(v) => v,
+            Err(_) => return Err("Error, failed to start gamepad input!"),
+        };
+        Ok(Self{
+            gilrs,
+            dpad: None,
+            stick: None,
+            stick_axes: (0.0, 0.0),
+            held: None,
+        })
+    }
+
+    /// Returns true if any controller is connected
+    pub fn is_connected(&self) -> bool{
+        self.gilrs.gamepads().next().is_some()
+    }
+
+    /// Read everything the controllers sent since the last call (at `now`), returning the navigation actions in order
+    pub fn poll(&mut self, now: Instant) -> Vec<NavAction>{
+        let mut actions = Vec::new();
+        while let Some(event) = self.gilrs.next_event(){
+            match event.event{
+                EventType::ButtonPressed(Button::South, _) => actions.push(NavAction::Activate),
+                EventType::ButtonPressed(Button::East, _) => actions.push(NavAction::Back),
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(direction) = dpad_direction(button){
+                        self.dpad = Some(direction);
+                    }
+                }
+                EventType::ButtonReleased(button, _) if dpad_direction(button).is_some() && dpad_direction(button) == self.dpad => {
+                    self.dpad = None;
+                }
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    self.stick_axes.0 = value;
+                    self.stick = stick_direction(self.stick_axes);
+                }
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => {
+                    self.stick_axes.1 = value;
+                    self.stick = stick_direction(self.stick_axes);
+                }
+                EventType::Disconnected => {
+                    self.dpad = None;
+                    self.stick = None;
+                    self.stick_axes = (0.0, 0.0);
+                }
+                _ => {}
+            }
+        }
+
+        // The D-pad wins if both are held. A new direction fires straight away, then repeats while it's held.
+        match (self.dpad.or(self.stick), self.held){
+            (Some(direction), Some((held, next))) if direction == held => {
+                if now >= next{
+                    actions.push(direction);
+                    self.held = Some((direction, now + REPEAT_INTERVAL));
+                }
+            }
+            (Some(direction), _) => {
+                actions.push(direction);
+                self.held = Some((direction, now + REPEAT_DELAY));
+            }
+            (None, _) => self.held = None,
+        }
+        actions
+    }
+
+    /// When a held direction next repeats, so the event loop can wake up for it
+    pub fn next_repeat(&self) -> Option<Instant>{
+        self.held.map(|(_, next)| next)
+    }
+}
+
+fn dpad_direction(button: Button) -> Option<NavAction>{
+    match button{
+        Button::DPadUp => Some(NavAction::Up),
+        Button::DPadDown => Some(NavAction::Down),
+        Button::DPadLeft => Some(NavAction::Left),
+        Button::DPadRight => Some(NavAction::Right),
+        _ => None,
+    }
+}
+
+// The stick's direction, if it's pushed far enough. Up is positive on the Y axis.
+fn stick_direction((x, y): (f32, f32)) -> Option<NavAction>{
+    if x.abs().max(y.abs()) < STICK_THRESHOLD{
+        return None;
+    }
+    Some(if x.abs() > y.abs(){
+        if x > 0.0 { NavAction::Right } else { NavAction::Left }
+    }else if y > 0.0{
+        NavAction::Up
+    }else{
+        NavAction::Down
+    })
+}
@@ -0,0 +1,239 @@
+//! This module contains gesture recognition - composing touches (and the left mouse button, which acts like a single finger)
+//! into taps, long presses, swipes and pinches. Each window's `EventTranslator` has a `GestureRecognizer`, and the main loop
+//! sends the gestures it recognizes to the components as `GuiEvent::Gesture`, after the event that completed them.
+//!
+//! Long presses are recognized by time, not by an event, so the main loop polls for them (and wakes up when one is due).
+
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+use crate::event::{GuiEvent, MouseButton, TouchPhase};
+
+/// The direction of a swipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection{
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// # Gesture
+///
+/// A gesture recognized from touches or the mouse. Positions are in physical pixels from the top left of the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture{
+    /// A quick press and release without moving
+    Tap{ pos: (f64, f64) },
+    /// A press held without moving. Sent while it's still held - releasing it afterwards isn't a tap.
+    LongPress{ pos: (f64, f64) },
+    /// A quick press, move and release, mostly in `direction`
+    Swipe{ direction: SwipeDirection, start: (f64, f64), end: (f64, f64) },
+    /// Two fingers moving together or apart. `scale` is the distance between them relative to when the pinch started,
+    /// and `delta` relative to the last pinch event (multiply a zoom level by it). Sent every time a finger moves.
+    Pinch{ center: (f64, f64), scale: f64, delta: f64 },
+}
+
+/// # GestureSettings
+///
+/// The limits the recognizer uses to tell gestures apart, in pixels and durations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings{
+    /// How far a press can move and still be a tap or long press
+    pub tap_slop: f64,
+    /// How long a press has to be held to be a long press
+    pub long_press: Duration,
+    /// How far a press has to move to be a swipe
+    pub swipe_distance: f64,
+    /// The longest a swipe can take
+    pub swipe_duration: Duration,
+}
+
+impl Default for GestureSettings{
+    fn default() -> GestureSettings{
+        Self{
+            tap_slop: 10.0,
+            long_press: Duration::from_millis(500),
+            swipe_distance: 50.0,
+            swipe_duration: Duration::from_millis(500),
+        }
+    }
+}
+
+// A finger (or the mouse) that's down
+#[derive(Debug, Clone, Copy)]
+struct Pointer{
+    start: (f64, f64),
+    pos: (f64, f64),
+    start_time: Instant,
+    // Moved further than the tap slop, so it can't be a tap or long press
+    moved: bool,
+    long_pressed: bool,
+}
+
+// The mouse is tracked like a finger with this ID
+const MOUSE_ID: u64 = u64::MAX;
+
+/// # GestureRecognizer
+///
+/// Tracks the fingers (and left mouse button) that are down, and recognizes gestures from them.
+/// Mouse input is ignored while any finger is down, as some platforms turn touches into mouse events as well.
+#[derive(Debug, Clone, Default)]
+pub struct GestureRecognizer{
+    settings: GestureSettings,
+    pointers: HashMap<u64, Pointer>,
+    // The distance between the two fingers when the pinch started, and at the last pinch event
+    pinch: Option<(f64, f64)>,
+    // Set once two fingers have been down together, until they're all lifted - the fingers can't make any other gesture
+    pinched: bool,
+}
+
+impl GestureRecognizer{
+    /// Create a new recognizer with the default settings
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Create a new recognizer with custom settings
+    pub fn with_settings(settings: GestureSettings) -> Self{
+        Self{
+            settings,
+            ..Self::default()
+        }
+    }
+
+    /// The settings used to recognize gestures
+    pub fn settings(&self) -> GestureSettings{
+        self.settings
+    }
+
+    /// Feed an event (at `time`) to the recognizer, returning the gestures it completes
+    pub fn process(&mut self, event: &GuiEvent, time: Instant) -> Vec<Gesture>{
+        let touching = self.pointers.keys().any(|id| *id != MOUSE_ID);
+        match *event{
+            GuiEvent::Touch{ id, phase: TouchPhase::Started, pos } => self.press(id, pos, time),
+            GuiEvent::Touch{ id, phase: TouchPhase::Moved, pos } => self.move_to(id, pos),
+            GuiEvent::Touch{ id, phase: TouchPhase::Ended, pos } => self.release(id, pos, time),
+            GuiEvent::Touch{ id, phase: TouchPhase::Cancelled, .. } => {
+                self.pointers.remove(&id);
+                self.end_pinch();
+                Vec::new()
+            }
+            GuiEvent::MouseDown{ button: MouseButton::Left, pos } if !touching => self.press(MOUSE_ID, pos, time),
+            GuiEvent::MouseMove{ pos } if !touching => self.move_to(MOUSE_ID, pos),
+            GuiEvent::MouseUp{ button: MouseButton::Left, pos } if !touching => self.release(MOUSE_ID, pos, time),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Recognize a long press if one is due at `time`
+    pub fn poll(&mut self, time: Instant) -> Option<Gesture>{
+        if self.pinched || self.pointers.len() != 1{
+            return None;
+        }
+        let long_press = self.settings.long_press;
+        let pointer = self.pointers.values_mut().next()?;
+        if pointer.moved || pointer.long_pressed || time.saturating_duration_since(pointer.start_time) < long_press{
+            return None;
+        }
+        pointer.long_pressed = true;
+        Some(Gesture::LongPress{ pos: pointer.pos })
+    }
+
+    /// When the next long press could be recognized, if a press is being held
+    pub fn next_deadline(&self) -> Option<Instant>{
+        if self.pinched || self.pointers.len() != 1{
+            return None;
+        }
+        self.pointers.values()
+            .find(|pointer| !pointer.moved && !pointer.long_pressed)
+            .map(|pointer| pointer.start_time + self.settings.long_press)
+    }
+
+    fn press(&mut self, id: u64, pos: (f64, f64), time: Instant) -> Vec<Gesture>{
+        self.pointers.insert(id, Pointer{ start: pos, pos, start_time: time, moved: false, long_pressed: false });
+        if self.pointers.len() == 2{
+            let distance = self.pinch_distance();
+            self.pinch = Some((distance, distance));
+            self.pinched = true;
+        }
+        Vec::new()
+    }
+
+    fn move_to(&mut self, id: u64, pos: (f64, f64)) -> Vec<Gesture>{
+        let slop = self.settings.tap_slop;
+        let pointer = match self.pointers.get_mut(&id){
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        pointer.pos = pos;
+        if distance(pointer.start, pos) > slop{
+            pointer.moved = true;
+        }
+
+        match self.pinch{
+            Some((start, last)) if self.pointers.len() == 2 && start > 0.0 && last > 0.0 => {
+                let current = self.pinch_distance();
+                self.pinch = Some((start, current));
+                vec![Gesture::Pinch{ center: self.pinch_center(), scale: current / start, delta: current / last }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn release(&mut self, id: u64, pos: (f64, f64), time: Instant) -> Vec<Gesture>{
+        let pointer = match self.pointers.remove(&id){
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        if self.pinched{
+            self.end_pinch();
+            return Vec::new();
+        }
+
+        let moved = distance(pointer.start, pos);
+        let duration = time.saturating_duration_since(pointer.start_time);
+        if !pointer.moved && moved <= self.settings.tap_slop && !pointer.long_pressed && duration < self.settings.long_press{
+            return vec![Gesture::Tap{ pos }];
+        }
+        if moved >= self.settings.swipe_distance && duration <= self.settings.swipe_duration{
+            let (dx, dy) = (pos.0 - pointer.start.0, pos.1 - pointer.start.1);
+            let direction = if dx.abs() >= dy.abs(){
+                if dx > 0.0 { SwipeDirection::Right } else { SwipeDirection::Left }
+            }else if dy > 0.0{
+                SwipeDirection::Down
+            }else{
+                SwipeDirection::Up
+            };
+            return vec![Gesture::Swipe{ direction, start: pointer.start, end: pos }];
+        }
+        Vec::new()
+    }
+
+    // Stop pinching, once all the fingers are lifted (a finger left behind can't start a new gesture)
+    fn end_pinch(&mut self){
+        self.pinch = None;
+        if self.pointers.is_empty(){
+            self.pinched = false;
+        }
+    }
+
+    fn pinch_distance(&self) -> f64{
+        let mut pointers = self.pointers.values();
+        match (pointers.next(), pointers.next()){
+            (Some(a), Some(b)) => distance(a.pos, b.pos),
+            _ => 0.0,
+        }
+    }
+
+    fn pinch_center(&self) -> (f64, f64){
+        let mut pointers = self.pointers.values();
+        match (pointers.next(), pointers.next()){
+            (Some(a), Some(b)) => ((a.pos.0 + b.pos.0) / 2.0, (a.pos.1 + b.pos.1) / 2.0),
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64{
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
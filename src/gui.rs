@@ -3,29 +3,126 @@
 //! of data around from the window to the renderer, without sacrificing much usability for
 //! the user.
 
-use std::time::{Duration, Instant};
+use std::{cell::Cell, path::{Path, PathBuf}, rc::Rc, time::{Duration, Instant}};
 
-use crate::{layout::Layout, rendering::{Window, WindowBuilder, Renderer}};
+use crate::{animation::Transition, color::Color, color_scheme::ColorScheme, dialog::{Choice, MessageButtons}, event::{EventTranslator, GuiEvent, UserEvent}, layout::Layout, locale::Locale, message::{Application, ApplicationRunner, Runner}, rendering::{EventCallback, Window, WindowBuilder, WindowBuildError, Renderer, Snapshot, SnapshotDiff}, sender::{EventLoopMessage, EventSender, Payload}, shortcut::{Shortcut, ShortcutCallback, ShortcutRegistry}, stylesheet::Stylesheet, theme::Theme, timer::{TimerCallback, TimerId, Timers}};
 use futures::executor::block_on;
 
-use winit::event_loop::ControlFlow;
-use winit::event::{WindowEvent, Event};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget};
+use winit::event::{Event, WindowEvent};
+
+/// The signature of the tick callback, which runs at a fixed rate in `ControlFlowMode::Poll`
+pub type TickCallback = Box<dyn FnMut(&mut winit::window::Window, &mut Renderer)>;
+
+/// The signature of the close requested callback, which runs when the window is asked to close (by the OS close button,
+/// or a component like a custom title bar's close button). Return false to keep the window open, eg, to ask about unsaved changes.
+pub type CloseRequestedCallback = Box<dyn FnMut(&mut winit::window::Window, &mut Renderer) -> bool>;
+
+/// # ControlFlowMode
+///
+/// Defines how the main loop waits for new events.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ControlFlowMode{
+    /// Sleep until an event arrives. This is the default, and uses the least power - the loop still wakes up for timers,
+    /// animations and components that need redrawing (see `Component::needs_redraw`), so it only sleeps while nothing changes.
+    #[default]
+    Wait,
+    /// Sleep until an event arrives, waking up at least once every `Duration` anyway. Useful for applications that
+    /// change something outside of the GUI's knowledge and rely on regular wakeups to show it.
+    WaitUntil(Duration),
+    /// Tick at a fixed rate, running the tick callback on every tick. Events are still handled as they arrive,
+    /// so the UI stays responsive between ticks. Useful for applications that need to poll hardware or the network.
+    Poll(Duration),
+    /// Never sleep - run the loop (and redraw) as fast as possible, limited by vsync. Best for smooth,
+    /// always running animations, at the cost of CPU and GPU time.
+    Continuous,
+}
+
+/// # RedrawPolicy
+///
+/// Defines when the main window gets redrawn. The OS can still ask for a redraw at any time (eg, when the window is uncovered).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedrawPolicy{
+    /// Redraw every time the loop wakes up (after events, ticks and timeouts)
+    EveryIteration,
+    /// Only redraw when something changed - the layout is dirty (see `Layout::is_dirty`), a bound value changed, an animation
+    /// is running, or something asked for it with `Renderer::request_redraw` (or the window is resized). This is the default.
+    #[default]
+    OnRequest,
+}
+
+/// # IdlePolicy
+///
+/// Defines what the main loop does while nothing is changing - a shorthand for the `ControlFlowMode` and `RedrawPolicy`
+/// pair (see `GUI::set_idle_policy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdlePolicy{
+    /// Do no work at all between OS events - no wakeups and no frames, unless a timer, animation or change in the layout
+    /// asks for one (`ControlFlowMode::Wait` with `RedrawPolicy::OnRequest`). This is the default.
+    #[default]
+    Sleep,
+    /// Draw a frame every vsync whether or not anything changed (`ControlFlowMode::Continuous` with
+    /// `RedrawPolicy::EveryIteration`), for apps animating things the GUI doesn't know about, like a custom pass.
+    Continuous,
+}
 
 pub struct GUI{
     pub window: Window,
     pub renderer: Renderer,
-    pub clear_color: wgpu::Color,
+    pub clear_color: Color,
+    pub control_flow: ControlFlowMode,
+    pub redraw_policy: RedrawPolicy,
+    tick_callback: Option<TickCallback>,
+    close_requested_callback: Option<CloseRequestedCallback>,
+    shortcuts: ShortcutRegistry,
+    // The shortcut that toggles the performance overlay, if one is set
+    perf_overlay_shortcut: Option<Shortcut>,
+    timers: Timers<TimerCallback>,
+    // The scheme picked with `set_color_scheme`, and the one the OS prefers (detected at startup)
+    color_scheme: ColorScheme,
+    system_color_scheme: ColorScheme,
+    // Set by `watch_stylesheet`, it's checked for changes by the main loop
+    stylesheet: Option<Stylesheet>,
+    // Set by `set_application`, it handles messages and rebuilds the layout
+    application: Option<Box<dyn ApplicationRunner>>,
+    // Kept between calls to `run_frame`
+    loop_state: Option<LoopState>,
+    #[cfg(feature = "automation")]
+    automation: Option<crate::automation::AutomationServer>,
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<crate::gamepad::GamepadInput>,
+    #[cfg(feature = "accesskit")]
+    accessibility: Option<crate::access_adapter::AccessAdapter>,
 }
 
 impl Default for GUI{
     fn default() -> GUI{
         let window = WindowBuilder::new().set_resolution((800, 600)).set_title("Rusty GUI app").build().expect("Error building window");
-        let renderer = block_on(Renderer::new(&window.window));
-        let clear_color = wgpu::Color::WHITE;
+        let mut renderer = block_on(Renderer::new(&window.window));
+        connect_tasks(&window, &mut renderer);
+        let clear_color = Color::WHITE;
         GUI{
-            window: window,
-            renderer: renderer,
-            clear_color: clear_color,
+            window,
+            renderer,
+            clear_color,
+            control_flow: ControlFlowMode::default(),
+            redraw_policy: RedrawPolicy::default(),
+            tick_callback: None,
+            close_requested_callback: None,
+            shortcuts: ShortcutRegistry::new(),
+            perf_overlay_shortcut: None,
+            timers: Timers::new(),
+            color_scheme: ColorScheme::Light,
+            system_color_scheme: ColorScheme::detect(),
+            stylesheet: None,
+            application: None,
+            loop_state: None,
+            #[cfg(feature = "automation")]
+            automation: None,
+            #[cfg(feature = "gamepad")]
+            gamepad: None,
+            #[cfg(feature = "accesskit")]
+            accessibility: None,
         }
     }
 }
@@ -35,15 +132,43 @@ impl GUI{
     /// This function takes the data required by a GUI struct and wraps it into itself
     /// 
     /// You can alternatively call default to generate a default renderer and window.
-    pub fn new(window: Window, renderer: Renderer, clear_color: wgpu::Color,) -> Self{
+    pub fn new(window: Window, mut renderer: Renderer, clear_color: Color,) -> Self{
+        renderer.set_transparent(window.is_transparent());
+        connect_tasks(&window, &mut renderer);
         Self{
             window,
             renderer,
-            clear_color
+            clear_color,
+            control_flow: ControlFlowMode::default(),
+            redraw_policy: RedrawPolicy::default(),
+            tick_callback: None,
+            close_requested_callback: None,
+            shortcuts: ShortcutRegistry::new(),
+            perf_overlay_shortcut: None,
+            timers: Timers::new(),
+            color_scheme: ColorScheme::Light,
+            system_color_scheme: ColorScheme::detect(),
+            stylesheet: None,
+            application: None,
+            loop_state: None,
+            #[cfg(feature = "automation")]
+            automation: None,
+            #[cfg(feature = "gamepad")]
+            gamepad: None,
+            #[cfg(feature = "accesskit")]
+            accessibility: None,
         }
     }
 }
 
+// Let the renderer's tasks send their results to the window's event loop
+fn connect_tasks(_window: &Window, _renderer: &mut Renderer){
+    #[cfg(feature = "tasks")]
+    if let Some(event_loop) = &_window.event_loop{
+        _renderer.tasks.set_sender(EventSender::new(event_loop.create_proxy()));
+    }
+}
+
 // This part just has some helpful functions to simplify adding components
 // and managing the GUI. Still needs a lot more functionality
 impl GUI{
@@ -55,15 +180,299 @@ impl GUI{
     ///
     /// We should also implement a basic check for buttons to check where the cursor is and automatically handle button callbacks if the
     /// user doesn't want to implement callbacks themselves.
+    ///
+    /// This consumes the GUI and never returns. To drive the GUI from your own loop, use `run_frame` or `run_until_exit` instead.
     pub fn main_loop(self){
         main_loop(self);
     }
 
     /// Sets the window event handler
-    pub fn set_event_handler(&mut self, event_handler: Box<dyn Fn(&winit::event::Event<()>, &mut winit::window::Window, &mut crate::rendering::Renderer) -> ()>){
+    pub fn set_event_handler(&mut self, event_handler: EventCallback){
         self.window.set_event_handler(event_handler)
     }
 
+    /// Sets how the main loop waits for events (see `ControlFlowMode`)
+    pub fn set_control_flow(&mut self, control_flow: ControlFlowMode){
+        self.control_flow = control_flow;
+    }
+
+    /// Sets when the main window is redrawn (see `RedrawPolicy`)
+    pub fn set_redraw_policy(&mut self, redraw_policy: RedrawPolicy){
+        self.redraw_policy = redraw_policy;
+    }
+
+    /// Sets what the main loop does while nothing is changing, setting both the control flow and the redraw policy
+    /// (see `IdlePolicy`)
+    pub fn set_idle_policy(&mut self, idle_policy: IdlePolicy){
+        let (control_flow, redraw_policy) = match idle_policy{
+            IdlePolicy::Sleep => (ControlFlowMode::Wait, RedrawPolicy::OnRequest),
+            IdlePolicy::Continuous => (ControlFlowMode::Continuous, RedrawPolicy::EveryIteration),
+        };
+        self.control_flow = control_flow;
+        self.redraw_policy = redraw_policy;
+    }
+
+    /// The idle policy matching the control flow and redraw policy, or `None` if they've been set to another combination
+    pub fn idle_policy(&self) -> Option<IdlePolicy>{
+        match (self.control_flow, self.redraw_policy){
+            (ControlFlowMode::Wait, RedrawPolicy::OnRequest) => Some(IdlePolicy::Sleep),
+            (ControlFlowMode::Continuous, RedrawPolicy::EveryIteration) => Some(IdlePolicy::Continuous),
+            _ => None,
+        }
+    }
+
+    /// Change the theme, restyling every component at once (see `Renderer::set_theme`). The clear color becomes the theme's background.
+    pub fn set_theme(&mut self, theme: Theme){
+        self.clear_color = theme.colors.background;
+        self.renderer.set_theme(theme);
+        self.renderer.request_redraw();
+    }
+
+    /// The current theme
+    pub fn theme(&self) -> &Theme{
+        self.renderer.theme()
+    }
+
+    /// Switch to another language - every label and button showing a text key looks it up in `locale` and is redrawn,
+    /// while the app runs (see `Renderer::set_locale`)
+    pub fn set_locale(&mut self, locale: Locale){
+        self.renderer.set_locale(locale);
+        self.renderer.request_redraw();
+    }
+
+    /// The current locale
+    pub fn locale(&self) -> &Locale{
+        self.renderer.locale()
+    }
+
+    /// Switch to the light or dark theme, or follow the OS with `ColorScheme::System` (restyling the GUI when the OS switches).
+    /// This replaces the theme, and any changes made to it.
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme){
+        self.color_scheme = scheme;
+        self.set_theme(scheme.theme(self.system_color_scheme));
+    }
+
+    /// The scheme picked with `set_color_scheme` (`Light` until then)
+    pub fn color_scheme(&self) -> ColorScheme{
+        self.color_scheme
+    }
+
+    /// The scheme the OS prefers, `Light` or `Dark`. It's detected when the GUI is created, and kept up to date
+    /// while following the OS.
+    pub fn system_color_scheme(&self) -> ColorScheme{
+        self.system_color_scheme
+    }
+
+    /// Load the theme from a stylesheet file (see `stylesheet`), and keep watching it - whenever the file changes, the
+    /// new theme is applied while the app runs. Fails if the file can't be loaded, leaving the theme as it was.
+    pub fn watch_stylesheet<P: Into<PathBuf>>(&mut self, path: P) -> Result<(), &'static str>{
+        let stylesheet = Stylesheet::load(path)?;
+        self.set_theme(stylesheet.theme().clone());
+        self.stylesheet = Some(stylesheet);
+        Ok(())
+    }
+
+    /// Stop watching the stylesheet. The theme stays as it is.
+    pub fn unwatch_stylesheet(&mut self){
+        self.stylesheet = None;
+    }
+
+    /// The stylesheet being watched, if any. Its `error` says why the last change to the file couldn't be loaded.
+    pub fn stylesheet(&self) -> Option<&Stylesheet>{
+        self.stylesheet.as_ref()
+    }
+
+    /// Ask for the main window to be redrawn. Only needed with `RedrawPolicy::OnRequest` - from inside a callback,
+    /// use `Renderer::request_redraw` instead.
+    pub fn request_redraw(&mut self){
+        self.renderer.request_redraw();
+    }
+
+    /// Sets the callback that runs on every tick in `ControlFlowMode::Poll`
+    pub fn set_tick_callback(&mut self, tick_callback: TickCallback){
+        self.tick_callback = Some(tick_callback);
+    }
+
+    /// Sets the callback that decides whether the window closes when asked to (see `CloseRequestedCallback`).
+    /// Without one, the main loop exits as soon as the window is asked to close.
+    pub fn set_close_requested_callback(&mut self, callback: CloseRequestedCallback){
+        self.close_requested_callback = Some(callback);
+    }
+
+    /// Run `callback` once, after `delay`. The main loop wakes up for it, whatever the control flow mode.
+    /// Returns the timer's ID, to clear it with `clear_timer` before it runs.
+    pub fn set_timeout(&mut self, delay: Duration, callback: TimerCallback) -> TimerId{
+        self.timers.set_timeout(delay, callback, Instant::now())
+    }
+
+    /// Run `callback` every `interval` (eg, to blink a caret), until it's cleared with `clear_timer`
+    pub fn set_interval(&mut self, interval: Duration, callback: TimerCallback) -> TimerId{
+        self.timers.set_interval(interval, callback, Instant::now())
+    }
+
+    /// Stop a timeout or interval. Returns false if it had already run, or been cleared.
+    pub fn clear_timer(&mut self, id: TimerId) -> bool{
+        self.timers.clear(id)
+    }
+
+    /// Register a keyboard shortcut (eg, `Shortcut::command(VirtualKeyCode::S)` for Ctrl+S). When it's pressed, the callback
+    /// runs instead of the key press going to the components and the event handler. Returns an error if the shortcut is
+    /// already registered, or uses the window's quit key.
+    pub fn register_shortcut(&mut self, shortcut: Shortcut, callback: ShortcutCallback) -> Result<(), &'static str>{
+        if Some(shortcut.key) == self.window.get_quit_key(){
+            return Err("Error, this shortcut uses the window's quit key!");
+        }
+        self.shortcuts.register(shortcut, callback)
+    }
+
+    /// Remove a keyboard shortcut. Returns false if it wasn't registered.
+    pub fn unregister_shortcut(&mut self, shortcut: Shortcut) -> bool{
+        self.shortcuts.unregister(shortcut)
+    }
+
+    /// Toggle the performance overlay (see `Renderer::set_perf_overlay`) with `shortcut` - eg,
+    /// `Shortcut::key(VirtualKeyCode::F3)` - replacing the shortcut set before, or stop
+    /// toggling it with `None`. Returns an error if the shortcut can't be registered (see `register_shortcut`).
+    pub fn set_perf_overlay_shortcut(&mut self, shortcut: Option<Shortcut>) -> Result<(), &'static str>{
+        if let Some(old) = self.perf_overlay_shortcut.take(){
+            self.shortcuts.unregister(old);
+        }
+        if let Some(shortcut) = shortcut{
+            self.register_shortcut(shortcut, Box::new(|_, renderer| renderer.toggle_perf_overlay()))?;
+            self.perf_overlay_shortcut = Some(shortcut);
+        }
+        Ok(())
+    }
+
+    /// The registered keyboard shortcuts
+    pub fn shortcuts(&self) -> &ShortcutRegistry{
+        &self.shortcuts
+    }
+
+    /// Set the app's own data, replacing any that was set before. Button callbacks are passed it, and the event, tick and
+    /// shortcut callbacks can reach it with `Renderer::app_state_mut` - so they can change it without `Rc<RefCell<...>>`.
+    pub fn set_app_state<T: 'static>(&mut self, state: T){
+        self.renderer.app_state_mut().set(state);
+    }
+
+    /// Borrow the app's own data, if it's a `T` (eg, to read it back once `run_until_exit` returns)
+    pub fn app_state<T: 'static>(&self) -> Option<&T>{
+        self.renderer.app_state().get()
+    }
+
+    /// Mutably borrow the app's own data, if it's a `T`
+    pub fn app_state_mut<T: 'static>(&mut self) -> Option<&mut T>{
+        self.renderer.app_state_mut().get_mut()
+    }
+
+    /// Drive the GUI with an `Application` (see the `message` module), replacing the render layout with its view.
+    /// From then on, the messages its components send are handled once per loop iteration, and the layout is rebuilt after them.
+    pub fn set_application<A: Application>(&mut self, app: A){
+        self.application = Some(Box::new(Runner::new(app, &mut self.renderer)));
+    }
+
+    /// Borrow the application, if it's an `A` (eg, to read its data once `run_until_exit` returns)
+    pub fn application<A: Application>(&self) -> Option<&A>{
+        self.application.as_ref()?.as_any().downcast_ref::<Runner<A>>().map(|runner| runner.app())
+    }
+
+    /// Mutably borrow the application, if it's an `A`. The layout isn't rebuilt until it next handles a message.
+    pub fn application_mut<A: Application>(&mut self) -> Option<&mut A>{
+        self.application.as_mut()?.as_any_mut().downcast_mut::<Runner<A>>().map(|runner| runner.app_mut())
+    }
+
+    /// Create a handle that other threads can post events into the GUI with (see `EventSender`). Posting an event wakes up
+    /// the main loop, which delivers it to the layouts and the event handler as `GuiEvent::User`.
+    ///
+    /// Fails while the event loop is running (create senders before starting it).
+    pub fn create_event_sender(&self) -> Result<EventSender, &'static str>{
+        match &self.window.event_loop{
+            Some(event_loop) => Ok(EventSender::new(event_loop.create_proxy())),
+            None => Err("Error, the event loop is already running!"),
+        }
+    }
+
+    /// Run `future` in the background (see the `task` module), so it doesn't block rendering. Its result arrives as a
+    /// `GuiEvent::User`. From inside a callback, use `Renderer::spawn` instead.
+    #[cfg(feature = "tasks")]
+    pub fn spawn<F>(&mut self, future: F) -> Result<(), &'static str>
+    where F: std::future::Future + Send + 'static, F::Output: Send + 'static{
+        self.renderer.spawn(future)
+    }
+
+    /// Run `future` in the background, then run `callback` with its result on the UI thread
+    #[cfg(feature = "tasks")]
+    pub fn spawn_with<F, C>(&mut self, future: F, callback: C) -> Result<crate::task::TaskId, &'static str>
+    where F: std::future::Future + Send + 'static, F::Output: Send + 'static, C: FnOnce(F::Output, &mut winit::window::Window, &mut Renderer) + 'static{
+        self.renderer.spawn_with(future, callback)
+    }
+
+    /// Ask the main loop to exit after the current event. From inside a callback, use `Renderer::request_exit` instead.
+    pub fn request_exit(&mut self){
+        self.renderer.request_exit();
+    }
+
+    /// Start the automation server on `address` (eg, "127.0.0.1:7878"), so external tools can inspect and drive the app.
+    /// See the `automation` module for the protocol. Returns the address the server is listening on, or an error if
+    /// `address` isn't a loopback address.
+    #[cfg(feature = "automation")]
+    pub fn enable_automation<A: std::net::ToSocketAddrs>(&mut self, address: A) -> std::io::Result<std::net::SocketAddr>{
+        let server = crate::automation::AutomationServer::start(address)?;
+        let address = server.address();
+        self.automation = Some(server);
+        Ok(address)
+    }
+
+    /// Start reading gamepads, so their D-pad and left stick move focus between components, and A and B send
+    /// `NavAction::Activate` and `NavAction::Back` (see the `gamepad` module). While a gamepad is connected, the main loop
+    /// wakes up every frame to read it, even in `ControlFlowMode::Wait`.
+    #[cfg(feature = "gamepad")]
+    pub fn enable_gamepad(&mut self) -> Result<(), &'static str>{
+        self.gamepad = Some(crate::gamepad::GamepadInput::new()?);
+        Ok(())
+    }
+
+    /// Hand the main window's accessibility tree (see `Layout::accessibility_tree`) to the OS's screen readers, and carry out
+    /// the actions they ask for (see the `access_adapter` module). The tree is sent again whenever it changes.
+    ///
+    /// On Windows and macOS, this has to be called before the window is first shown - build it with
+    /// `WindowBuilder::set_show_after_first_frame`. Fails on Windows if the window is already visible, and on platforms
+    /// AccessKit doesn't support.
+    #[cfg(feature = "accesskit")]
+    pub fn enable_accessibility(&mut self) -> Result<(), &'static str>{
+        self.accessibility = Some(crate::access_adapter::AccessAdapter::new(&self.window.window)?);
+        Ok(())
+    }
+
+    /// Set the cursor shown when the mouse isn't over a component with its own hover cursor (like a button).
+    /// It can be changed while the main loop is running with `Renderer::set_cursor_icon`.
+    pub fn set_cursor_icon(&mut self, cursor: winit::window::CursorIcon){
+        self.renderer.set_cursor_icon(cursor);
+    }
+
+    /// Open a secondary window (eg, a detachable inspector) that draws `layout`. It shares the renderer's device,
+    /// and gets its own events. Returns the window's ID, which its events are tagged with.
+    ///
+    /// Windows can also be opened while the main loop is running, with `Renderer::open_window`.
+    pub fn add_window(&mut self, builder: &WindowBuilder, layout: Layout, clear_color: Color) -> Result<winit::window::WindowId, WindowBuildError>{
+        let window = match &self.window.event_loop{
+            Some(event_loop) => builder.build_secondary(event_loop)?,
+            None => return Err(WindowBuildError::EventLoopStarted),
+        };
+        Ok(self.renderer.add_window(window, layout, clear_color))
+    }
+
+    /// The keys and mouse buttons held on the main window, and where the cursor is. The main loop keeps it up to date,
+    /// and event callbacks can reach it with `Renderer::input`.
+    pub fn input(&self) -> &crate::input::InputState{
+        self.renderer.input()
+    }
+
+    /// The system clipboard, for reading and writing text. Event callbacks can reach it with `Renderer::clipboard`.
+    pub fn clipboard(&self) -> &crate::clipboard::Clipboard{
+        self.renderer.clipboard()
+    }
+
     /// Gets a reference to the winit window. Used to make wgpu surfaces
     pub fn get_window_ref(&self) -> &winit::window::Window{
         &self.window.window
@@ -74,6 +483,12 @@ impl GUI{
         self.renderer.layout = layout;
     }
 
+    /// Sets the current components to render like `set_render_layout`, animating each of them in with `transition`
+    /// (see `Renderer::set_layout_with_transition`)
+    pub fn set_render_layout_with_transition(&mut self, layout: Layout, transition: Transition, duration: Duration){
+        self.renderer.set_layout_with_transition(layout, transition, duration);
+    }
+
     /// Returns a mutable reference to the currently active render layout
     pub fn borrow_render_layout(&mut self) -> &mut Layout{
         &mut self.renderer.layout
@@ -93,44 +508,375 @@ impl GUI{
     pub fn borrow_renderer(&self) -> &Renderer{
         &self.renderer
     }
+
+    /// Add a pack of components from another crate (see the `plugin` module), letting it register its pipelines, fonts,
+    /// textures and component factories with the renderer
+    pub fn add_plugin<P: crate::plugin::ComponentPlugin>(&mut self, plugin: P) -> Result<(), &'static str>{
+        self.renderer.add_plugin(plugin)
+    }
+
+    /// Show a message box with `title`, `text` and `buttons`, and wait for the user to pick one of the buttons.
+    ///
+    /// The OS's own dialog is used where there's one (see the `dialog` module). Otherwise, the message box is drawn in the
+    /// main window in place of the layout, running frames (like `run_frame`) until a button is clicked - closing the
+    /// window picks `MessageButtons::dismiss` rather than closing the app. Call it between frames, or before the main loop.
+    pub fn message_box(&mut self, title: &str, text: &str, buttons: MessageButtons) -> Choice{
+        match crate::dialog::native_message_box(title, text, buttons){
+            Some(choice) => choice,
+            None => self.modal_message_box(title, text, buttons),
+        }
+    }
+
+    // Draw a message box in place of the layout, and run frames until a button is picked
+    fn modal_message_box(&mut self, title: &str, text: &str, buttons: MessageButtons) -> Choice{
+        let picked = Rc::new(Cell::new(None));
+        let layout = crate::dialog::message_box_layout(&self.renderer, title, text, buttons, &picked);
+        let previous_layout = std::mem::replace(&mut self.renderer.layout, layout);
+
+        // Closing the window answers the message box, rather than closing the app
+        let dismissed = picked.clone();
+        let previous_callback = self.close_requested_callback.replace(Box::new(move |_, _| {
+            dismissed.set(Some(buttons.dismiss()));
+            false
+        }));
+
+        while picked.get().is_none() && self.run_frame(){}
+
+        self.close_requested_callback = previous_callback;
+        self.renderer.layout = previous_layout;
+        self.renderer.request_redraw();
+        picked.get().unwrap_or_else(|| buttons.dismiss())
+    }
+
+    /// Post a desktop notification, which shows even while the window is minimized (see `notification::notify`)
+    pub fn notify(&self, title: &str, body: &str, icon: Option<&Path>) -> Result<(), &'static str>{
+        crate::notification::notify(title, body, icon)
+    }
+
+    /// Feed `event` to the main window as if it came from the OS - it goes to the shortcuts, the components and the event handler,
+    /// and counts towards double clicks and gestures, just like a real event. Useful for integration tests and automation
+    /// scripts that drive a real layout. The window is redrawn on the next frame.
+    pub fn inject_event(&mut self, event: GuiEvent){
+        let state = self.loop_state.get_or_insert_with(LoopState::new);
+        let (window, renderer) = (&mut self.window.window, &mut self.renderer);
+        let (event_loop_handler, shortcuts, minimized) = (&mut self.window.event_callback_handler, &mut self.shortcuts, state.minimized);
+
+        dispatch_event(&event, window, renderer, event_loop_handler, shortcuts, minimized);
+        if let Some(click) = state.translator.click_event(&event){
+            dispatch_event(&click, window, renderer, event_loop_handler, shortcuts, minimized);
+        }
+        for gesture in state.translator.gesture_events(&event){
+            dispatch_event(&gesture, window, renderer, event_loop_handler, shortcuts, minimized);
+        }
+        renderer.request_redraw();
+    }
+
+    /// Click the left mouse button at (x, y), in pixels from the top left of the window - the cursor moves there first,
+    /// so whatever is under it is hovered (see `inject_event`)
+    pub fn click_at(&mut self, x: f64, y: f64){
+        for event in crate::test::click((x, y)){
+            self.inject_event(event);
+        }
+    }
+
+    /// Type `text` into the focused component, one character at a time (see `inject_event`)
+    pub fn type_text(&mut self, text: &str){
+        for event in crate::test::type_text(text){
+            self.inject_event(event);
+        }
+    }
+
+    /// Press and release `key` while `modifiers` are held (see `inject_event`)
+    pub fn press_key(&mut self, key: crate::event::VirtualKeyCode, modifiers: crate::event::ModifiersState){
+        for event in crate::test::key_press(key, modifiers){
+            self.inject_event(event);
+        }
+    }
+
+    /// Capture the current layout as a snapshot (development tool - this blocks on the GPU)
+    pub fn capture_snapshot(&mut self) -> Snapshot{
+        self.renderer.prepass();
+        self.renderer.capture_snapshot(self.clear_color)
+    }
+
+    /// Capture snapshots before and after `change` is applied to the renderer, and return them with a diff
+    /// of the pixels that changed. Use `SnapshotDiff::overlay` to visualise the changed region.
+    pub fn snapshot_diff<F: FnOnce(&mut Renderer)>(&mut self, change: F) -> (Snapshot, Snapshot, SnapshotDiff){
+        self.renderer.snapshot_diff(self.clear_color, change)
+    }
+}
+
+
+// What the main loop keeps track of between events
+struct LoopState{
+    minimized: bool,
+    next_tick: Instant,
+    // Set once the window is closed (or a component asks to exit)
+    exit: bool,
+    // When set, the loop hands control back once a frame has been drawn (used by `run_frame`)
+    single_frame: bool,
+    translator: EventTranslator,
+    // Whether the input method is on, which it is while a component that takes text has focus
+    ime_allowed: bool,
 }
 
+impl LoopState{
+    fn new() -> Self{
+        Self{
+            minimized: false,
+            next_tick: Instant::now(),
+            exit: false,
+            single_frame: false,
+            translator: EventTranslator::new(),
+            ime_allowed: false,
+        }
+    }
+}
 
 /// This function consumes a GUI struct and loops until application exit
 /// 
 /// This loop does NOT return once started
 /// 
 /// This workaround was also required as I had a lot of issues with references
-fn main_loop(gui: GUI){
-    let mut renderer = gui.renderer;
-    let mut window = gui.window.window;
-    let mut event_loop = gui.window.event_loop;
-    let clear_color = gui.clear_color;
-    let event_loop_handler = gui.window.event_callback_handler;
-    let mut minimized = false;
-
-    event_loop.take().unwrap().run(move |event, _, control_flow| {
-        // ControlFlow::WaitUntil pauses the event loop if no events are available to process.
-        // If no events are called, it will update every 10ms to make sure everything stays up to date
-        // This is ideal for non-game applications that only update in response to user
-        // input, and uses significantly less power/CPU time than ControlFlow::Poll.
-        *control_flow = ControlFlow::WaitUntil(Instant::now().checked_add(Duration::from_millis(250)).unwrap());
-
-        if !minimized{
-            // Run event components - things like buttons and so on
-            for event_comp in renderer.layout.event_components.iter_mut(){
-                event_comp.handle_event_callback(&event, &mut window);
+fn main_loop(mut gui: GUI){
+    let event_loop = gui.window.event_loop.take().unwrap();
+    gui.connect_automation(&event_loop);
+    gui.connect_accessibility(&event_loop);
+    let mut state = LoopState::new();
+
+    event_loop.run(move |event, target, control_flow| {
+        gui.handle_loop_event(event, target, control_flow, &mut state);
+    });
+}
+
+impl GUI{
+    /// Run the event loop until the window is closed, then hand control back to the caller. Unlike `main_loop`,
+    /// the GUI isn't consumed, so it can be inspected (or run again) afterwards.
+    ///
+    /// This uses winit's `run_return`, which isn't available on the web, iOS or Android.
+    pub fn run_until_exit(&mut self){
+        self.run_return(false);
+    }
+
+    /// Handle all the pending events and draw a single frame, then hand control back to the caller.
+    /// Call it from an existing application loop to drive the GUI alongside other work.
+    ///
+    /// Returns false once the window has been closed (or a component asked to exit).
+    pub fn run_frame(&mut self) -> bool{
+        self.run_return(true)
+    }
+
+    // Run the event loop with `run_return`, returning whether the app should keep running
+    fn run_return(&mut self, single_frame: bool) -> bool{
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        let mut event_loop = match self.window.event_loop.take(){
+            Some(v) => v,
+            None => return false,
+        };
+        self.connect_automation(&event_loop);
+        self.connect_accessibility(&event_loop);
+
+        let mut state = self.loop_state.take().unwrap_or_else(LoopState::new);
+        state.single_frame = single_frame;
+        event_loop.run_return(|event, target, control_flow| {
+            self.handle_loop_event(event, target, control_flow, &mut state);
+        });
+
+        let running = !state.exit;
+        self.loop_state = Some(state);
+        self.window.event_loop = Some(event_loop);
+        running
+    }
+
+    // Let the automation server wake the event loop when a request arrives
+    fn connect_automation(&self, _event_loop: &EventLoop<EventLoopMessage>){
+        #[cfg(feature = "automation")]
+        if let Some(server) = &self.automation{
+            server.set_event_loop_proxy(_event_loop.create_proxy());
+        }
+    }
+
+    // Let the accessibility adapter wake the event loop when a screen reader asks for something
+    fn connect_accessibility(&self, _event_loop: &EventLoop<EventLoopMessage>){
+        #[cfg(feature = "accesskit")]
+        if let Some(adapter) = &self.accessibility{
+            adapter.set_event_sender(EventSender::new(_event_loop.create_proxy()));
+        }
+    }
+
+    // Handle a single event from the event loop - this is the body of every way of running the GUI
+    fn handle_loop_event(&mut self, event: Event<EventLoopMessage>, target: &EventLoopWindowTarget<EventLoopMessage>, control_flow: &mut ControlFlow, state: &mut LoopState){
+        // Take the value out of an event posted from another thread - the rest of the loop only needs to know it woke up
+        let (event, payload) = match event{
+            Event::UserEvent(message) => (Event::UserEvent(EventLoopMessage::wake()), message.into_payload()),
+            event => (event, None),
+        };
+        let event = &event;
+        let quit_key = self.window.get_quit_key();
+        let show_after_first_frame = &mut self.window.show_after_first_frame;
+        let renderer = &mut self.renderer;
+        let window = &mut self.window.window;
+        let event_loop_handler = &mut self.window.event_callback_handler;
+        let shortcuts = &mut self.shortcuts;
+
+        // Build any windows that were opened since the last event
+        renderer.create_pending_windows(target);
+
+        match self.control_flow{
+            ControlFlowMode::Wait => control_flow.set_wait(),
+            ControlFlowMode::Continuous => control_flow.set_poll(),
+            ControlFlowMode::WaitUntil(timeout) => {
+                // ControlFlow::WaitUntil pauses the event loop if no events are available to process.
+                // If no events are called, it will update every `timeout` to make sure everything stays up to date
+                // This is ideal for non-game applications that only update in response to user
+                // input, and uses significantly less power/CPU time than ControlFlow::Poll.
+                // A timeout too long to represent (eg, Duration::MAX) never comes round, so just wait for events
+                match Instant::now().checked_add(timeout){
+                    Some(deadline) => control_flow.set_wait_until(deadline),
+                    None => control_flow.set_wait(),
+                }
+            }
+            ControlFlowMode::Poll(tick_rate) => {
+                // Run the tick if it's due. If we fell behind (eg, the window was being dragged), we skip
+                // the missed ticks rather than running them all at once
+                let now = Instant::now();
+                if now >= state.next_tick{
+                    if let Some(v) = &mut self.tick_callback{
+                        v(window, renderer);
+                    }
+                    state.next_tick += tick_rate;
+                    if state.next_tick < now{
+                        state.next_tick = now + tick_rate;
+                    }
+                }
+                // Events still wake us up early, so the UI latency is never worse than the event handling itself
+                control_flow.set_wait_until(state.next_tick);
+            }
+        }
+
+        // Run the timers that are due, then wake up in time for the next one
+        self.timers.poll(Instant::now(), |_, callback| callback(window, renderer));
+        if let Some(deadline) = self.timers.next_deadline(){
+            wake_by(control_flow, deadline);
+        }
+
+        // Reload the stylesheet if it has changed. Only the components the new theme restyles are drawn again.
+        if let Some(stylesheet) = &mut self.stylesheet{
+            if let Some(theme) = stylesheet.poll(Instant::now()){
+                self.clear_color = theme.colors.background;
+                renderer.set_theme(theme.clone());
+                renderer.request_redraw();
             }
+            wake_by(control_flow, stylesheet.next_check());
+        }
+
+        // Keep frames coming while anything is animating (or a component wants redrawing, or the glyph cache is being
+        // warmed), whatever the control flow mode. Otherwise nothing wakes the loop until the next OS event.
+        if !renderer.animator().is_empty() || (!state.minimized && renderer.needs_redraw()){
+            wake_by(control_flow, Instant::now() + Duration::from_millis(16));
+        }
+
+        // Wake up in time to recognize a long press that's being held
+        if let Some(deadline) = state.translator.next_gesture_deadline(){
+            wake_by(control_flow, deadline);
         }
 
-        match &event_loop_handler{
-            Some(v) => {
-                // We have a callback handler, so run it below (with our required parameters)
-                v(&event, &mut window, &mut renderer);
+        // Gamepads don't wake the event loop, so check on them every frame while one is connected
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepad) = self.gamepad.as_ref().filter(|gamepad| gamepad.is_connected()){
+            let wake = Instant::now() + Duration::from_millis(16);
+            wake_by(control_flow, gamepad.next_repeat().map_or(wake, |repeat| repeat.min(wake)));
+        }
+
+        let minimized = state.minimized;
+        // Events for secondary windows go to that window's layout instead of the main one
+        renderer.handle_window_event(event);
+        // The main window's events get translated, then go to its components and the user's event handler
+        let gui_event = match event{
+            Event::WindowEvent{ event, window_id } if *window_id == window.id() => state.translator.translate(event),
+            _ => None,
+        };
+        if let Some(gui_event) = &gui_event{
+            dispatch_event(gui_event, window, renderer, event_loop_handler, shortcuts, minimized);
+            // A press can complete a double (or triple) click, and any event can complete gestures, which follow the event itself
+            if let Some(click) = state.translator.click_event(gui_event){
+                dispatch_event(&click, window, renderer, event_loop_handler, shortcuts, minimized);
             }
-            None => {
-                // No callback handler set, so do nothing
+            for gesture in state.translator.gesture_events(gui_event){
+                dispatch_event(&gesture, window, renderer, event_loop_handler, shortcuts, minimized);
+            }
+        }
+        match payload{
+            // Events posted with an `EventSender` go to the secondary windows' layouts, then the main one and the user's event handler
+            Some(Payload::User(value)) => {
+                let user_event = GuiEvent::User(UserEvent::from_boxed(value));
+                renderer.dispatch_to_windows(&user_event);
+                dispatch_event(&user_event, window, renderer, event_loop_handler, shortcuts, minimized);
             }
+            // A finished task's result goes to its callback
+            Some(Payload::Task(_id, _result)) => {
+                #[cfg(feature = "tasks")]
+                if let Some(callback) = renderer.tasks.take_callback(_id){
+                    callback(_result, window, renderer);
+                }
+            }
+            None => {}
+        }
+
+        // Run any automation requests. Synthesized input goes through the same path as real events
+        #[cfg(feature = "automation")]
+        if let Some(server) = &self.automation{
+            server.process(window, renderer, |event, window, renderer| dispatch_event(event, window, renderer, event_loop_handler, shortcuts, minimized));
+        }
+
+        // Carry out what screen readers asked for, and tell them when the window gains or loses focus
+        #[cfg(feature = "accesskit")]
+        if let Some(adapter) = &mut self.accessibility{
+            if adapter.process(&mut renderer.layout, window, &mut renderer.app_state){
+                renderer.request_redraw();
+            }
+            if let Some(GuiEvent::Focused(focused)) = gui_event{
+                adapter.set_window_focused(focused);
+            }
+        }
+
+        // Only turn the input method on while a component takes text, so it doesn't catch key presses meant as shortcuts
+        let text_focused = renderer.layout.text_focused();
+        if text_focused != state.ime_allowed{
+            state.ime_allowed = text_focused;
+            window.set_ime_allowed(text_focused);
+        }
+
+        // Follow the OS between light and dark mode. Most platforms don't report it, so check again when the window gets focus.
+        let system_color_scheme = match &gui_event{
+            Some(GuiEvent::ColorSchemeChanged(scheme)) => Some(*scheme),
+            Some(GuiEvent::Focused(true)) if self.color_scheme == ColorScheme::System => Some(ColorScheme::detect()),
+            _ => None,
+        };
+        let previous = self.system_color_scheme;
+        if let Some(scheme) = system_color_scheme.filter(|scheme| *scheme != previous){
+            self.system_color_scheme = scheme;
+            if self.color_scheme == ColorScheme::System{
+                let theme = self.color_scheme.theme(scheme);
+                self.clear_color = theme.colors.background;
+                renderer.set_theme(theme);
+                renderer.request_redraw();
+            }
+        }
+
+        // Components like a custom title bar's close button can ask to close the window
+        let mut close_requested = renderer.layout.take_close_request();
+        match gui_event{
+            Some(GuiEvent::CloseRequested) => close_requested = true,
+            Some(GuiEvent::KeyDown{ key, .. }) if Some(key) == quit_key => close_requested = true,
+            _ => {}
+        }
+
+        // Once the events and redraws are handled, send screen readers the tree if the layout changed
+        #[cfg(feature = "accesskit")]
+        if let (Event::RedrawEventsCleared, Some(adapter)) = (event, &mut self.accessibility){
+            adapter.update(&renderer.layout, (renderer.size.width, renderer.size.height), window);
         }
 
         match event {
@@ -139,41 +885,72 @@ fn main_loop(gui: GUI){
             Event::WindowEvent {
                     ref event,
                     window_id,
-                } if window_id == window.id() =>  {
+                } if *window_id == window.id() =>  {
                     match event{
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                     WindowEvent::Resized(physical_size) => {
                         renderer.resize(*physical_size);
-                        if renderer.size.width == 0 && renderer.size.height == 0{
-                            minimized = true;
-                        }else{
-                            minimized = false;
-                        }
+                        state.minimized = renderer.size.width == 0 && renderer.size.height == 0;
                     }
                     WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                         // new_inner_size is &&mut so we have to dereference it twice
                         renderer.resize(**new_inner_size);
-                        if renderer.size.width == 0 && renderer.size.height == 0{
-                            minimized = true;
-                        }else{
-                            minimized = false;
-                        }
+                        state.minimized = renderer.size.width == 0 && renderer.size.height == 0;
                     },              
                     
                     _ => {}
                 }
             }
 
+            // Application update code.
             Event::MainEventsCleared => {
-                // Application update code.
-                if !minimized{
-                    // Queue a RedrawRequested event.
-                    //
-                    // You only need to call this if you've determined that you need to redraw, in
-                    // applications which do not always need to. Applications that redraw continuously
-                    // can just render here instead.
-                    window.request_redraw();
+                // Long presses are recognized by time, rather than by an event
+                if let Some(gesture) = state.translator.poll_gestures(){
+                    dispatch_event(&gesture, window, renderer, event_loop_handler, shortcuts, minimized);
+                }
+                renderer.poll_window_gestures();
+                #[cfg(feature = "gamepad")]
+                if let Some(gamepad) = &mut self.gamepad{
+                    for action in gamepad.poll(Instant::now()){
+                        dispatch_event(&GuiEvent::Navigate(action), window, renderer, event_loop_handler, shortcuts, minimized);
+                    }
+                }
+                // Handle the messages components sent while handling the events, then show the new view
+                if let Some(application) = &mut self.application{
+                    if application.process(renderer){
+                        renderer.request_redraw();
+                    }
                 }
+
+                // Queue a RedrawRequested event.
+                //
+                // You only need to call this if you've determined that you need to redraw, in
+                // applications which do not always need to. Applications that redraw continuously
+                // can just render here instead.
+                if *show_after_first_frame{
+                    // Draw the first frame here rather than waiting for a redraw, as some platforms
+                    // don't send redraws to hidden windows. Once it's drawn, the window can be shown.
+                    renderer.prepass();
+                    renderer.render(self.clear_color);
+                    window.set_visible(true);
+                    *show_after_first_frame = false;
+                }else if !state.minimized{
+                    let redraw = match self.redraw_policy{
+                        RedrawPolicy::EveryIteration => true,
+                        // Effects queued by signals changed in callbacks apply in the prepass, and bound components pick up
+                        // changed observables there, so they need a frame too. Animations need one every frame until they finish.
+                        RedrawPolicy::OnRequest => crate::binding::take_changes() || renderer.needs_redraw() || crate::signal::has_pending_effects()
+                            || !renderer.animator().is_empty(),
+                    };
+                    if redraw{
+                        window.request_redraw();
+                    }
+                }
+                // Secondary windows keep drawing even if the main window is minimized
+                renderer.request_window_redraws(self.redraw_policy == RedrawPolicy::OnRequest);
+            }
+            Event::RedrawRequested(window_id) if *window_id != window.id() => {
+                // One of the secondary windows needs a redraw
+                renderer.render_window(*window_id);
             }
             Event::RedrawRequested(_) => {
                 // Redraw the application.
@@ -182,9 +959,63 @@ fn main_loop(gui: GUI){
                 // this event rather than in MainEventsCleared, since rendering in here allows
                 // the program to gracefully handle redraws requested by the OS.
                 renderer.prepass(); // Update the layout and stuff
-                renderer.render(clear_color); // Render a single frame.
+                renderer.update_cursor(window); // Show the hovered component's cursor
+                // Drawing clears the layout's dirty flag, so tell the accessibility adapter the tree may have changed first
+                #[cfg(feature = "accesskit")]
+                if let (Some(adapter), true) = (&mut self.accessibility, renderer.layout.is_dirty()){
+                    adapter.mark_stale();
+                }
+                renderer.render(self.clear_color); // Render a single frame.
             }
+            // Every redraw for this iteration is done, so `run_frame` can hand control back
+            Event::RedrawEventsCleared if state.single_frame => control_flow.set_exit(),
             _ => {}
         }
-    });
-}
\ No newline at end of file
+
+        // Give the app a chance to keep the window open (eg, to ask about unsaved changes)
+        if close_requested{
+            state.exit |= match &mut self.close_requested_callback{
+                Some(callback) => callback(window, renderer),
+                None => true,
+            };
+        }
+        if renderer.is_exit_requested(){
+            state.exit = true;
+        }
+        if state.exit{
+            control_flow.set_exit();
+        }
+    }
+}
+
+// Make sure the event loop wakes up by `deadline`, without making it wake up any later than it would have
+fn wake_by(control_flow: &mut ControlFlow, deadline: Instant){
+    match *control_flow{
+        ControlFlow::Wait => control_flow.set_wait_until(deadline),
+        ControlFlow::WaitUntil(wake) if deadline < wake => control_flow.set_wait_until(deadline),
+        _ => {}
+    }
+}
+
+/// Pass an event for the main window to its components, then to the user's event handler (unless a component consumed it).
+/// Key presses that match a registered shortcut run its callback instead.
+fn dispatch_event(event: &GuiEvent, window: &mut winit::window::Window, renderer: &mut Renderer, event_loop_handler: &mut Option<EventCallback>, shortcuts: &mut ShortcutRegistry, minimized: bool){
+    renderer.track_input(event);
+    if shortcuts.trigger(event, renderer.layout.text_focused(), window, renderer){
+        return;
+    }
+    // Run the components - things like buttons and so on
+    if !minimized && renderer.layout.dispatch_event(event, window, &mut renderer.app_state).is_consumed(){
+        return;
+    }
+
+    match event_loop_handler{
+        Some(v) => {
+            // We have a callback handler, so run it below (with our required parameters)
+            v(event, window, renderer);
+        }
+        None => {
+            // No callback handler set, so do nothing
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! This module contains `InputState`, the keys and mouse buttons currently held down and where the cursor is.
+//! The main loop keeps it up to date from the main window's events, so callbacks can ask about the input
+//! (with `GUI::input` or `Renderer::input`) instead of piecing it together from the events themselves.
+
+use std::collections::HashSet;
+
+use crate::event::{GuiEvent, ModifiersState, MouseButton, ScanCode, VirtualKeyCode};
+
+/// # InputState
+///
+/// A snapshot of the input to a window, built from its events with `update`
+#[derive(Debug, Clone, Default)]
+pub struct InputState{
+    cursor: Option<(f64, f64)>,
+    keys: HashSet<VirtualKeyCode>,
+    scancodes: HashSet<ScanCode>,
+    buttons: HashSet<MouseButton>,
+    modifiers: ModifiersState,
+}
+
+impl InputState{
+    /// Create a new input state, with nothing held and the cursor outside the window
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Update the state from an event
+    pub fn update(&mut self, event: &GuiEvent){
+        match event{
+            GuiEvent::MouseMove{ pos } => self.cursor = Some(*pos),
+            GuiEvent::MouseLeave => self.cursor = None,
+            GuiEvent::MouseDown{ button, pos } => {
+                self.buttons.insert(*button);
+                self.cursor = Some(*pos);
+            }
+            GuiEvent::MouseUp{ button, .. } => {
+                self.buttons.remove(button);
+            }
+            GuiEvent::KeyDown{ key, scancode, modifiers } => {
+                self.keys.insert(*key);
+                self.scancodes.insert(*scancode);
+                self.modifiers = *modifiers;
+            }
+            GuiEvent::KeyUp{ key, scancode, modifiers } => {
+                self.keys.remove(key);
+                self.scancodes.remove(scancode);
+                self.modifiers = *modifiers;
+            }
+            GuiEvent::ModifiersChanged(modifiers) => self.modifiers = *modifiers,
+            // We won't hear about releases while another window has focus, so forget everything that's held
+            GuiEvent::Focused(false) => {
+                self.keys.clear();
+                self.scancodes.clear();
+                self.buttons.clear();
+                self.modifiers = ModifiersState::empty();
+            }
+            _ => {}
+        }
+    }
+
+    /// Where the cursor is, in physical pixels from the top left of the window, if it's over the window
+    pub fn cursor_position(&self) -> Option<(f64, f64)>{
+        self.cursor
+    }
+
+    /// Returns true if `key` is held down
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool{
+        self.keys.contains(&key)
+    }
+
+    /// Returns true if the physical key with this scancode is held down, whatever the keyboard layout maps it to
+    pub fn is_scancode_down(&self, scancode: ScanCode) -> bool{
+        self.scancodes.contains(&scancode)
+    }
+
+    /// Returns true if `button` is held down
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool{
+        self.buttons.contains(&button)
+    }
+
+    /// The modifier keys held down
+    pub fn modifiers(&self) -> ModifiersState{
+        self.modifiers
+    }
+
+    /// Every key held down, in no particular order
+    pub fn keys_down(&self) -> impl Iterator<Item = VirtualKeyCode> + '_{
+        self.keys.iter().copied()
+    }
+}
@@ -4,150 +4,922 @@
 //! with little to no delay.
 
 
-use crate::components::{EventGUIComponent, GUIComponent, TextGUIComponent};
+use std::collections::HashMap;
+
+use slab::Slab;
+
+use crate::{accessibility::{self, AccessAction, AccessNode}, components::Component, event::{EventResult, GuiEvent, MouseButton, NavAction, VirtualKeyCode}, locale::Locale, persistence::LayoutState, rendering::{Renderer, ResourceReport}, state::AppState, theme::Theme};
 
 /// # Layout
 ///
-/// Layout struct stores the data needed to render a layout
+/// Layout struct stores the data needed to render a layout - a list of components, drawn in the order
+/// they were added (so later ones are on top), with all of their text drawn on top of that.
+///
+/// Components are kept in a slab, and their ID is their key in it. IDs are stable - removing a component doesn't change
+/// the others' - and the ID of a removed component is given to the next one added. Components can also be given a name
+/// to look them up by (see `set_component_name`).
+///
+/// Components can be given a z value to change this (eg, to keep a popup or drag preview on top) - they're drawn from
+/// the lowest z to the highest, and in the order they were added where their z is the same (see `draw_order`). Every
+/// component starts at 0. Hit testing and event dispatch follow the same order, so whatever is drawn on top gets the mouse.
+///
+/// The layout hit tests the cursor against the components' bounds, to tell them when they're hovered,
+/// and when they're dragged (the dragged component captures the mouse until the button is released).
+/// A component can also be given the mouse directly with `set_mouse_capture`.
+///
+/// One focusable component at a time can hold keyboard focus. Tab and Shift+Tab move focus
+/// between them (in reading order, see `set_reading_order`), and the focused component gets keyboard events first.
 ///
-/// It stores one for regular image based GUI components,
-/// and one for rendering text based components like labels.
-/// It also stores event components, components which should check events.
+/// Events go to one component at a time, in the order given by `dispatch_order`, until one returns
+/// `EventResult::Consumed` - so a click on a dialog doesn't also press the buttons added before (under) it.
+///
+/// Components are attached (`Component::on_attach`) by the renderer before the first frame they're drawn in, and
+/// detached (`Component::on_detach`) when they're removed, or the layout is dropped or replaced.
+///
+/// The layout can describe its components for assistive technology (see `accessibility_tree`), and carry out the
+/// actions it asks for (see `access_action`). Components can be given a name and description for it to read out
+/// (see `set_accessible_name`), and it reads them in the same order Tab moves through them.
+///
+/// Components drawing entirely outside the window aren't drawn (see `visible_components`). A container that only shows
+/// part of its children (like a scrolling list) can give their layout a clip rect with `set_clip_rect` - components
+/// outside it aren't drawn either, and the cursor only hits components while it's inside it.
+///
+/// The layout is marked dirty whenever something it draws might have changed - components are added, removed, borrowed
+/// mutably, restyled or handed an event, or the hover, focus or drag moves - so the main loop only redraws when there's
+/// something new to show (see `is_dirty`). Anything changed another way (eg, straight through `components`) should call
+/// `mark_dirty`.
 pub struct Layout{
-    pub components: Vec<Box<dyn GUIComponent>>,
-    pub event_components: Vec<Box<dyn EventGUIComponent>>,
-    pub text_components: Vec<Box<dyn TextGUIComponent>>,
+    pub components: Slab<Box<dyn Component>>,
+    // Which components have been attached, by ID. Components inserted straight into `components` are caught up with in `attach`.
+    attached: Vec<bool>,
+    // The components' z values, by ID. Components past the end (eg, inserted straight into `components`) are at 0.
+    z: Vec<i32>,
+    // When each component was added, by ID, so they're drawn in that order even when IDs are reused
+    added: Vec<u64>,
+    next_added: u64,
+    // Components looked up by name, and each component's name, by ID
+    names: HashMap<String, usize>,
+    component_names: Vec<Option<String>>,
+    // The components read (and tabbed through) first, in order. The rest follow in the order they were added.
+    reading_order: Vec<usize>,
+    // The names and descriptions given to components for assistive technology, by ID
+    access_text: Vec<AccessText>,
+    focused: Option<usize>,
+    hovered: Option<usize>,
+    drag: Option<Drag>,
+    capture: Option<usize>,
+    clip_rect: Option<[f32; 4]>,
+    // Whether anything changed since the layout was last drawn
+    dirty: bool,
+}
+
+// A component's accessible name and description, replacing the ones it gives itself
+#[derive(Debug, Clone, Default)]
+struct AccessText{
+    name: Option<String>,
+    description: Option<String>,
 }
 
+// The component being dragged, the button dragging it, and where the cursor was last
+#[derive(Debug, Clone, Copy)]
+struct Drag{
+    id: usize,
+    button: MouseButton,
+    last: (f64, f64),
+}
+
+
+impl Default for Layout{
+    fn default() -> Layout{
+        Self::new()
+    }
+}
+
+impl Drop for Layout{
+    fn drop(&mut self){
+        self.detach();
+    }
+}
 
 // This part can definitely be improved but I'm not sure how
 impl Layout{
     /// Initialize a new layout
     pub fn new() -> Self{
         Self{
-            components: Vec::<Box<dyn GUIComponent>>::new(),
-            event_components: Vec::<Box<dyn EventGUIComponent>>::new(),
-            text_components: Vec::<Box<dyn TextGUIComponent>>::new(),
+            components: Slab::new(),
+            attached: Vec::new(),
+            z: Vec::new(),
+            added: Vec::new(),
+            next_added: 0,
+            names: HashMap::new(),
+            component_names: Vec::new(),
+            reading_order: Vec::new(),
+            access_text: Vec::new(),
+            focused: None,
+            hovered: None,
+            drag: None,
+            capture: None,
+            clip_rect: None,
+            dirty: true,
         }
     }
     
-    /// Adds a new component, Only accepts a GUIComponent type, and returns the ID (location in vec) of the component
-    pub fn add_component<T: GUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
-        self.components.push(comp);
+    /// Adds a new component, and returns the ID (key in the slab) of the component
+    pub fn add_component<T: Component + 'static>(&mut self, comp: Box<T>) -> usize{
+        let id = self.components.insert(comp);
+        if self.added.len() <= id{
+            self.added.resize(id + 1, 0);
+        }
+        self.added[id] = self.next_added;
+        self.next_added += 1;
+        self.dirty = true;
+        id
+    }
 
-        self.components.len() - 1
+    /// Remove a component using the ID of the component. The other components keep their IDs.
+    pub fn remove_component_by_id(&mut self, id: usize){
+        let mut comp = self.components.remove(id);
+        self.dirty = true;
+        if self.attached.get(id) == Some(&true){
+            comp.on_detach();
+            self.attached[id] = false;
+        }
+        // The next component given this ID starts afresh
+        if let Some(z) = self.z.get_mut(id){
+            *z = 0;
+        }
+        if let Some(access_text) = self.access_text.get_mut(id){
+            *access_text = AccessText::default();
+        }
+        if let Some(name) = self.component_names.get_mut(id).and_then(Option::take){
+            self.names.remove(&name);
+        }
+        self.reading_order.retain(|other| *other != id);
+        let clear = |index: Option<usize>| index.filter(|index| *index != id);
+        self.focused = clear(self.focused);
+        self.hovered = clear(self.hovered);
+        self.drag = self.drag.filter(|drag| drag.id != id);
+        self.capture = clear(self.capture);
     }
 
-    /// Adds a new component, Only accepts a TextGUIComponent type and return the ID (location in the vec) of the component
-    pub fn add_text_component<T: TextGUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
-        self.text_components.push(comp);
+    /// Every component ID, lowest first
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_{
+        self.components.iter().map(|(id, _)| id)
+    }
 
-        self.text_components.len() - 1
+    /// Returns true if there's a component with this ID
+    pub fn contains(&self, id: usize) -> bool{
+        self.components.contains(id)
     }
 
-    /// Adds a new event component, Only accepts a EventGUIComponent type, and returns the ID (location in vec) of the component
-    pub fn add_event_component<T: EventGUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
-        self.event_components.push(comp);
+    /// Give a component a name to look it up by with `find_component`, or take its name away with `None`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component doesn't exist, or another component already has the name.
+    pub fn set_component_name(&mut self, id: usize, name: Option<&str>) -> Result<(), &'static str>{
+        if !self.components.contains(id){
+            return Err("Error, there's no component with this ID!");
+        }
+        if let Some(name) = name{
+            if self.names.get(name).is_some_and(|other| *other != id){
+                return Err("Error, another component already has this name!");
+            }
+        }
+        if self.component_names.len() <= id{
+            self.component_names.resize(id + 1, None);
+        }
+        if let Some(old) = self.component_names[id].take(){
+            self.names.remove(&old);
+        }
+        if let Some(name) = name{
+            self.names.insert(name.to_string(), id);
+            self.component_names[id] = Some(name.to_string());
+        }
+        Ok(())
+    }
 
-        self.event_components.len() - 1
+    /// The name given to a component with `set_component_name`, if any
+    pub fn component_name(&self, id: usize) -> Option<&str>{
+        self.component_names.get(id)?.as_deref()
     }
 
-    /// Remove a component from the vec using the ID of the component
-    pub fn remove_component_by_id(&mut self, id: usize){
-        self.components.remove(id);
+    /// The ID of the component with this name (see `set_component_name`), if there is one
+    pub fn find_component(&self, name: &str) -> Option<usize>{
+        self.names.get(name).copied()
+    }
+
+    /// Set a component's z value. Components with a higher z are drawn on top of (and get the mouse before) ones with a lower z.
+    pub fn set_z(&mut self, id: usize, z: i32) -> Result<(), &'static str>{
+        if !self.components.contains(id){
+            return Err("Error, there's no component with this ID!");
+        }
+        if self.z.len() <= id{
+            self.z.resize(id + 1, 0);
+        }
+        self.z[id] = z;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// A component's z value (see `set_z`)
+    pub fn z(&self, id: usize) -> i32{
+        self.z.get(id).copied().unwrap_or(0)
+    }
+
+    /// Raise a component above every other one, giving it a z one higher than the highest. It's left alone if it's already on top.
+    pub fn bring_to_front(&mut self, id: usize) -> Result<(), &'static str>{
+        if !self.components.contains(id){
+            return Err("Error, there's no component with this ID!");
+        }
+        if self.draw_order().last() == Some(&id){
+            return Ok(());
+        }
+        let highest = self.ids().filter(|other| *other != id).map(|other| self.z(other)).max().unwrap_or(0);
+        self.set_z(id, highest.saturating_add(1))
+    }
+
+    /// Lower a component below every other one, giving it a z one lower than the lowest. It's left alone if it's already at the back.
+    pub fn send_to_back(&mut self, id: usize) -> Result<(), &'static str>{
+        if !self.components.contains(id){
+            return Err("Error, there's no component with this ID!");
+        }
+        if self.draw_order().first() == Some(&id){
+            return Ok(());
+        }
+        let lowest = self.ids().filter(|other| *other != id).map(|other| self.z(other)).min().unwrap_or(0);
+        self.set_z(id, lowest.saturating_sub(1))
+    }
+
+    /// The component IDs in the order they're drawn - by z, then in the order they were added. The last one is on top.
+    pub fn draw_order(&self) -> Vec<usize>{
+        let mut order: Vec<usize> = self.ids().collect();
+        order.sort_by_key(|id| (self.z(*id), self.added(*id)));
+        order
+    }
+
+    // When a component was added, relative to the others. Components inserted straight into `components` count as added first.
+    fn added(&self, id: usize) -> u64{
+        self.added.get(id).copied().unwrap_or(0)
+    }
+
+    /// Limit the components to `rect` ([left, top, width, height] in pixels from the top left of the window), or lift the
+    /// limit with `None`. Components entirely outside it aren't drawn, and the cursor doesn't hit anything outside it.
+    /// Components partly inside it are drawn whole.
+    pub fn set_clip_rect(&mut self, rect: Option<[f32; 4]>){
+        self.clip_rect = rect;
+        self.dirty = true;
+    }
+
+    /// The rect the components are limited to, if any (see `set_clip_rect`)
+    pub fn clip_rect(&self) -> Option<[f32; 4]>{
+        self.clip_rect
+    }
+
+    /// The IDs of the visible components that get drawn in a window of `screen` pixels, in draw order. Components whose
+    /// draw bounds (see `Component::draw_bounds`) are entirely outside the window, or the clip rect, are left out.
+    pub fn visible_components(&self, screen: (u32, u32)) -> Vec<usize>{
+        self.visible_in(screen, [0.0, 0.0, screen.0 as f32, screen.1 as f32])
+    }
+
+    // The visible components that draw inside `area` (and the clip rect), in draw order
+    pub(crate) fn visible_in(&self, screen: (u32, u32), area: [f32; 4]) -> Vec<usize>{
+        let area = self.clip_area(area);
+        self.draw_order().into_iter()
+            .filter(|id| {
+                let comp = &self.components[*id];
+                comp.is_visible() && match (comp.draw_bounds(screen), area){
+                    (Some(bounds), Some(area)) => intersect(bounds, area).is_some(),
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                }
+            })
+            .collect()
+    }
+
+    // `area` limited to the clip rect, or `None` if nothing of it is left
+    pub(crate) fn clip_area(&self, area: [f32; 4]) -> Option<[f32; 4]>{
+        match self.clip_rect{
+            Some(clip) => intersect(area, clip),
+            None => Some(area),
+        }
+    }
+
+    /// Set the order assistive technology reads the components in, and Tab moves focus through them in. The components in
+    /// `order` come first, and any left out follow in the order they were added - so a form can be read top to bottom
+    /// even if its fields were added in another order. An empty order goes back to the order they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a component doesn't exist, or is in `order` twice.
+    pub fn set_reading_order(&mut self, order: &[usize]) -> Result<(), &'static str>{
+        for (index, id) in order.iter().enumerate(){
+            if !self.components.contains(*id){
+                return Err("Error, there's no component with this ID!");
+            }
+            if order[..index].contains(id){
+                return Err("Error, a component can't be in the reading order twice!");
+            }
+        }
+        self.reading_order = order.to_vec();
+        Ok(())
+    }
+
+    /// Every component ID, in reading order (see `set_reading_order`)
+    pub fn reading_order(&self) -> Vec<usize>{
+        let mut order: Vec<usize> = self.reading_order.iter().copied().filter(|id| self.components.contains(*id)).collect();
+        let mut rest: Vec<usize> = self.ids().filter(|id| !self.reading_order.contains(id)).collect();
+        rest.sort_by_key(|id| self.added(*id));
+        order.extend(rest);
+        order
+    }
+
+    /// Give a component a name for assistive technology to read out (eg, "Email" for a text input with a label beside it),
+    /// replacing the one it gives itself (see `Component::access_info`). `None` goes back to its own.
+    pub fn set_accessible_name(&mut self, id: usize, name: Option<&str>) -> Result<(), &'static str>{
+        self.access_text_mut(id)?.name = name.map(str::to_string);
+        Ok(())
+    }
+
+    /// The name given to a component with `set_accessible_name`, if any
+    pub fn accessible_name(&self, id: usize) -> Option<&str>{
+        self.access_text.get(id)?.name.as_deref()
+    }
+
+    /// Give a component a description for assistive technology, read after its name (eg, "At least 8 characters"),
+    /// replacing the one it gives itself. `None` goes back to its own.
+    pub fn set_description(&mut self, id: usize, description: Option<&str>) -> Result<(), &'static str>{
+        self.access_text_mut(id)?.description = description.map(str::to_string);
+        Ok(())
+    }
+
+    /// The description given to a component with `set_description`, if any
+    pub fn description(&self, id: usize) -> Option<&str>{
+        self.access_text.get(id)?.description.as_deref()
+    }
+
+    // The accessible name and description of a component, growing the list to hold it
+    fn access_text_mut(&mut self, id: usize) -> Result<&mut AccessText, &'static str>{
+        if !self.components.contains(id){
+            return Err("Error, there's no component with this ID!");
+        }
+        if self.access_text.len() <= id{
+            self.access_text.resize(id + 1, AccessText::default());
+        }
+        Ok(&mut self.access_text[id])
+    }
+
+    /// Mark the layout as changed, so it's drawn again (see `is_dirty`)
+    pub fn mark_dirty(&mut self){
+        self.dirty = true;
+    }
+
+    /// Returns true if anything changed since the layout was last drawn - it was marked dirty, or one of its components
+    /// (or their children) needs a redraw (see `Component::needs_redraw`)
+    pub fn is_dirty(&self) -> bool{
+        self.dirty || self.components.iter().any(|(_, comp)| comp.needs_redraw() || comp.children().is_some_and(Layout::is_dirty))
+    }
+
+    /// Returns true if the layout is dirty (see `is_dirty`), clearing it and its components' children. The renderer does
+    /// this when it draws the layout - components that still need a redraw (see `Component::needs_redraw`) keep it dirty.
+    pub fn take_dirty(&mut self) -> bool{
+        // Every nested layout has to be cleared (no short circuiting)
+        let mut dirty = std::mem::take(&mut self.dirty);
+        for (_, comp) in self.components.iter_mut(){
+            dirty |= comp.needs_redraw();
+            if let Some(children) = comp.children_mut(){
+                dirty |= children.take_dirty();
+            }
+        }
+        dirty
+    }
+
+    /// Returns true if any components haven't been attached yet (see `attach`)
+    pub fn needs_attach(&self) -> bool{
+        self.ids().any(|id| self.attached.get(id) != Some(&true))
+    }
+
+    /// Attach every component that hasn't been attached yet, so it can create its GPU resources (see `Component::on_attach`).
+    /// The renderer does this for the layouts it draws before each frame.
+    pub fn attach(&mut self, renderer: &Renderer){
+        self.attached.resize(self.components.capacity().max(self.attached.len()), false);
+        for (id, comp) in self.components.iter_mut(){
+            if !self.attached[id]{
+                comp.on_attach(renderer);
+                self.attached[id] = true;
+            }
+        }
+    }
+
+    /// Detach every attached component (see `Component::on_detach`). They're attached again if the layout is drawn again.
+    pub fn detach(&mut self){
+        for (id, comp) in self.components.iter_mut(){
+            if self.attached.get(id) == Some(&true){
+                comp.on_detach();
+                self.attached[id] = false;
+            }
+        }
+    }
+
+    /// The number of components in the layout. IDs can go past this once components have been removed (see `ids`).
+    pub fn len(&self) -> usize{
+        self.components.len()
     }
 
-    /// Remove a text component from the vec using the ID of the text component
-    pub fn remove_text_component_by_id(&mut self, id: usize){
-        self.text_components.remove(id);
+    /// Returns true if the layout has no components
+    pub fn is_empty(&self) -> bool{
+        self.components.is_empty()
     }
 
-    /// Remove a event component from the vec using the ID of the component
-    pub fn remove_event_component_by_id(&mut self, id: usize){
-        self.event_components.remove(id);
+    // One past the highest ID in use
+    fn id_bound(&self) -> usize{
+        self.components.iter().next_back().map_or(0, |(id, _)| id + 1)
     }
 
     /// Borrow a component (non modifiable)
-    pub fn borrow_component(&self, id: usize) -> &Box<dyn GUIComponent>{
-        &self.components[id]
+    pub fn borrow_component(&self, id: usize) -> &dyn Component{
+        self.components[id].as_ref()
     }
 
     /// Borrow a component as a type (non modifiable)
-    pub fn borrow_component_as_type<T: GUIComponent + 'static>(&self, id: usize) -> Result<&T, &'static str>{
-        let comp = self.components.get(id).take().unwrap();
+    pub fn borrow_component_as_type<T: Component + 'static>(&self, id: usize) -> Result<&T, &'static str>{
+        let comp = self.components.get(id).unwrap();
         if let Some(downcast) = comp.as_any().downcast_ref::<T>(){
             return Ok(downcast);
         }
-        return Err("Error, failed to downcast!");
+        Err("Error, failed to downcast!")
     }
 
     /// Borrow a component mutably
-    pub fn borrow_component_mut(&mut self, id: usize) -> &mut Box<dyn GUIComponent>{
+    pub fn borrow_component_mut(&mut self, id: usize) -> &mut Box<dyn Component>{
+        self.dirty = true;
         &mut self.components[id]
     }
 
+    /// Borrow a component, or get `None` if there isn't one with that ID
+    pub fn get_component(&self, id: usize) -> Option<&dyn Component>{
+        self.components.get(id).map(|comp| comp.as_ref())
+    }
+
+    /// Borrow a component mutably, or get `None` if there isn't one with that ID
+    pub fn get_component_mut(&mut self, id: usize) -> Option<&mut Box<dyn Component>>{
+        self.dirty = true;
+        self.components.get_mut(id)
+    }
+
     /// Borrow a component as a type (modifiable)
-    pub fn borrow_component_as_type_mut<T: GUIComponent + 'static>(&mut self, id: usize) -> Result<&mut T, &'static str>{
-        let comp = self.components.get_mut(id).take().unwrap();
+    pub fn borrow_component_as_type_mut<T: Component + 'static>(&mut self, id: usize) -> Result<&mut T, &'static str>{
+        self.dirty = true;
+        let comp = self.components.get_mut(id).unwrap();
         if let Some(downcast) = comp.as_any_mut().downcast_mut::<T>(){
             return Ok(downcast);
         }
-        return Err("Error, failed to downcast!");
+        Err("Error, failed to downcast!")
     }
 
-    /// Borrow a text component (non modifiable)
-    pub fn borrow_text_component(&mut self, id: usize) -> &Box<dyn TextGUIComponent>{
-        &self.text_components[id]
+    /// Restyle every component with `theme` (see `Component::apply_theme`). Returns true if any of them changed.
+    pub fn apply_theme(&mut self, theme: &Theme) -> bool{
+        let mut changed = false;
+        for (_, comp) in self.components.iter_mut(){
+            changed |= comp.apply_theme(theme);
+        }
+        self.dirty |= changed;
+        changed
     }
 
-    /// Borrow a text component as a type (non modifiable)
-    pub fn borrow_text_component_as_type<T: TextGUIComponent + 'static>(&self, id: usize) -> Result<&T, &'static str>{
-        let comp = self.text_components.get(id).take().unwrap();
-        if let Some(downcast) = comp.as_any().downcast_ref::<T>(){
-            return Ok(downcast);
+    /// Tell every component the locale changed (see `Component::apply_locale`). Returns true if any of them look different.
+    pub fn apply_locale(&mut self, locale: &Locale) -> bool{
+        let mut changed = false;
+        for (_, comp) in self.components.iter_mut(){
+            changed |= comp.apply_locale(locale);
+        }
+        self.dirty |= changed;
+        changed
+    }
+
+    /// Save the state of every component (see `Component::save_state`), by ID, to put back later with `restore_state`
+    pub fn save_state(&self) -> LayoutState{
+        LayoutState{
+            components: (0..self.id_bound()).map(|id| self.components.get(id).and_then(|comp| comp.save_state())).collect(),
         }
-        return Err("Error, failed to downcast!");
     }
 
-    /// Borrow a text component mutably
-    pub fn borrow_text_component_mut(&mut self, id: usize) -> &mut Box<dyn TextGUIComponent>{
-        &mut self.text_components[id]
+    /// Put back a state saved with `save_state`, component by component. The layout should be built the same way as the
+    /// one that was saved - each state goes to the component with the same ID, and any without a component are ignored.
+    pub fn restore_state(&mut self, state: &LayoutState){
+        for (id, state) in state.components.iter().enumerate(){
+            if let (Some(comp), Some(state)) = (self.components.get_mut(id), state){
+                comp.restore_state(state);
+            }
+        }
+        self.dirty = true;
     }
 
-    /// Borrow a text component as a type (modifiable)
-    pub fn borrow_text_component_as_type_mut<T: TextGUIComponent + 'static>(&mut self, id: usize) -> Result<&mut T, &'static str>{
-        let comp = self.text_components.get_mut(id).take().unwrap();
-        if let Some(downcast) = comp.as_any_mut().downcast_mut::<T>(){
-            return Ok(downcast);
+    /// Count the components in this layout, and record the resources they hold, in a resource report
+    pub fn report_resources(&self, report: &mut ResourceReport){
+        report.components += self.components.len();
+
+        for (_, comp) in self.components.iter(){
+            if !comp.is_enabled(){
+                report.disabled_components += 1;
+            }
+            if !comp.is_visible(){
+                report.hidden_components += 1;
+            }
+            comp.report_resources(report);
         }
-        return Err("Error, failed to downcast!");
     }
 
-    /// Borrow a event component (non modifiable)
-    pub fn borrow_event_component(&mut self, id: usize) -> &Box<dyn EventGUIComponent>{
-        &self.event_components[id]
+    /// The cursor the hovered component wants, if any. Components drawn on top take priority.
+    pub fn hover_cursor(&self) -> Option<winit::window::CursorIcon>{
+        self.draw_order().into_iter().rev().find_map(|id| self.components[id].hover_cursor())
     }
 
-    /// Borrow a event component as a type (non modifiable)
-    pub fn borrow_event_component_as_type<T: EventGUIComponent + 'static>(&self, id: usize) -> Result<&T, &'static str>{
-        let comp = self.event_components.get(id).take().unwrap();
-        if let Some(downcast) = comp.as_any().downcast_ref::<T>(){
-            return Ok(downcast);
+    /// Returns true if any component asked for the window to close, clearing their requests
+    pub fn take_close_request(&mut self) -> bool{
+        // Every component has to be asked (no short circuiting), so none of them keep a stale request
+        let mut requested = false;
+        for (_, comp) in self.components.iter_mut(){
+            requested |= comp.take_close_request();
         }
-        return Err("Error, failed to downcast!");
+        requested
     }
 
-    /// Borrow a event component mutably
-    pub fn borrow_event_component_mut(&mut self, id: usize) -> &mut Box<dyn EventGUIComponent>{
-        &mut self.event_components[id]
+    /// The ID of the component with keyboard focus, if any
+    pub fn focused(&self) -> Option<usize>{
+        self.focused
     }
 
-    /// Borrow a event component as a type (modifiable)
-    pub fn borrow_event_component_as_type_mut<T: EventGUIComponent + 'static>(&mut self, id: usize) -> Result<&mut T, &'static str>{
-        let comp = self.event_components.get_mut(id).take().unwrap();
-        if let Some(downcast) = comp.as_any_mut().downcast_mut::<T>(){
-            return Ok(downcast);
+    /// Give keyboard focus to a component (taking it from the previous one), or clear it with `None`.
+    /// Returns an error if the component doesn't exist, or can't take focus.
+    pub fn set_focus(&mut self, id: Option<usize>) -> Result<(), &'static str>{
+        if let Some(id) = id{
+            match self.components.get(id){
+                Some(comp) if Self::can_focus(comp.as_ref()) => {}
+                Some(_) => return Err("Error, this component can't take focus!"),
+                None => return Err("Error, there's no component with this ID!"),
+            }
+        }
+        self.move_focus(id);
+        Ok(())
+    }
+
+    /// Move focus to the next focusable component in reading order (wrapping around), like pressing Tab. Returns the newly focused ID.
+    pub fn focus_next(&mut self) -> Option<usize>{
+        let order = self.reading_order();
+        let count = order.len();
+        // Start after the focused component, or from the first one if nothing is focused
+        let start = self.focused.and_then(|id| order.iter().position(|other| *other == id)).map_or(0, |index| index + 1);
+        let next = (0..count).map(|offset| order[(start + offset) % count]).find(|id| Self::can_focus(self.components[*id].as_ref()));
+        if next.is_some(){
+            self.move_focus(next);
+        }
+        self.focused
+    }
+
+    /// Move focus to the previous focusable component in reading order (wrapping around), like pressing Shift+Tab. Returns the newly focused ID.
+    pub fn focus_previous(&mut self) -> Option<usize>{
+        let order = self.reading_order();
+        let count = order.len();
+        // Start before the focused component, or from the last one if nothing is focused
+        let start = self.focused.and_then(|id| order.iter().position(|other| *other == id)).unwrap_or(count);
+        let previous = (1..=count).map(|offset| order[(start + count - offset) % count]).find(|id| Self::can_focus(self.components[*id].as_ref()));
+        if previous.is_some(){
+            self.move_focus(previous);
+        }
+        self.focused
+    }
+
+    /// Move focus to the nearest focusable component in `direction` (from the focused component's `focus_rect`), on a window
+    /// of `screen` pixels. If nothing is focused (or the focused component has no rect), this works like `focus_next` and
+    /// `focus_previous`. Returns the newly focused ID. `Activate` and `Back` don't move focus.
+    pub fn focus_direction(&mut self, direction: NavAction, screen: (u32, u32)) -> Option<usize>{
+        let forwards = match direction{
+            NavAction::Down | NavAction::Right => true,
+            NavAction::Up | NavAction::Left => false,
+            NavAction::Activate | NavAction::Back => return self.focused,
+        };
+        let center = |rect: [f32; 4]| (rect[0] + rect[2] / 2.0, rect[1] + rect[3] / 2.0);
+        let from = match self.focus_rect(screen){
+            Some(rect) => center(rect),
+            None => return if forwards { self.focus_next() } else { self.focus_previous() },
+        };
+
+        // Score the candidates by how far they are along the direction, with sideways distance counting double
+        let nearest = self.components.iter()
+            .filter(|(id, comp)| Some(*id) != self.focused && Self::can_focus(comp.as_ref()))
+            .filter_map(|(id, comp)| {
+                let to = center(comp.focus_rect(screen)?);
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let (along, sideways) = match direction{
+                    NavAction::Right => (dx, dy),
+                    NavAction::Left => (-dx, dy),
+                    NavAction::Down => (dy, dx),
+                    _ => (-dy, dx),
+                };
+                if along > 0.0 { Some((id, along + 2.0 * sideways.abs())) } else { None }
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id);
+        if nearest.is_some(){
+            self.move_focus(nearest);
+        }
+        self.focused
+    }
+
+    /// Returns true if the focused component takes typed text (see `Component::accepts_text`)
+    pub fn text_focused(&self) -> bool{
+        self.focused.is_some_and(|id| self.components[id].accepts_text())
+    }
+
+    /// The area to draw the focus ring around (see `Component::focus_rect`), if a component has focus
+    pub fn focus_rect(&self, screen: (u32, u32)) -> Option<[f32; 4]>{
+        self.components.get(self.focused?)?.focus_rect(screen)
+    }
+
+    /// Describe the visible components for assistive technology, on a window of `screen` pixels. Components that
+    /// describe themselves (see `Component::access_info`) are listed in reading order (see `set_reading_order`), with the
+    /// components inside them (see `Component::children`) as their children.
+    pub fn accessibility_tree(&self, screen: (u32, u32)) -> Vec<AccessNode>{
+        accessibility::build_tree(self, screen, &[])
+    }
+
+    /// Carry out an action assistive technology asked for on the component at `path` (see `AccessNode::path`).
+    /// `Focus` moves keyboard focus to it, and every other action is passed to the component (see `Component::access_action`).
+    /// Returns true if the action was carried out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no component at `path`, or it can't take focus when asked to.
+    pub fn access_action(&mut self, path: &[usize], action: &AccessAction, window: &winit::window::Window, state: &mut AppState) -> Result<bool, &'static str>{
+        let (id, rest) = match path.split_first(){
+            Some(v) => v,
+            None => return Err("Error, there's no component with this ID!"),
+        };
+        if !rest.is_empty(){
+            return match self.components.get_mut(*id).and_then(|comp| comp.children_mut()){
+                Some(children) => children.access_action(rest, action, window, state),
+                None => Err("Error, there's no component with this ID!"),
+            };
+        }
+
+        match action{
+            AccessAction::Focus => self.set_focus(Some(*id)).map(|_| true),
+            _ => match self.components.get_mut(*id){
+                Some(comp) => {
+                    let done = comp.access_action(action, window, state);
+                    self.dirty |= done;
+                    Ok(done)
+                }
+                None => Err("Error, there's no component with this ID!"),
+            },
+        }
+    }
+
+    /// The ID of the component the cursor is over, if any (see `Component::bounds`)
+    pub fn hovered(&self) -> Option<usize>{
+        self.hovered
+    }
+
+    /// The ID of the topmost visible, enabled and hit testable component whose bounds contain `pos` (in pixels from the top left
+    /// of a window of `screen` pixels). The one drawn on top wins (see `draw_order`).
+    pub fn component_at(&self, pos: (f64, f64), screen: (u32, u32)) -> Option<usize>{
+        let (x, y) = (pos.0 as f32, pos.1 as f32);
+        if let Some([left, top, width, height]) = self.clip_rect{
+            if x < left || x >= left + width || y < top || y >= top + height{
+                return None;
+            }
+        }
+        self.draw_order().into_iter().rev()
+            .filter(|id| {
+                let comp = &self.components[*id];
+                comp.is_visible() && comp.is_enabled() && comp.is_hit_testable()
+            })
+            .find(|id| match self.components[*id].bounds(screen){
+                Some([left, top, width, height]) => x >= left && x < left + width && y >= top && y < top + height,
+                None => false,
+            })
+    }
+
+    /// Hit test the cursor at `pos` (or `None` once it's left the window), telling components when it moves on or off them.
+    /// `dispatch_event` runs this for every mouse move, before the components get the event.
+    pub fn update_hover(&mut self, pos: Option<(f64, f64)>, screen: (u32, u32)){
+        let hovered = pos.and_then(|pos| self.component_at(pos, screen));
+        if hovered == self.hovered{
+            return;
+        }
+        if let Some(id) = self.hovered{
+            self.components[id].on_hover_exit();
+        }
+        if let Some(id) = hovered{
+            self.components[id].on_hover_enter();
+        }
+        self.hovered = hovered;
+        self.dirty = true;
+    }
+
+    /// The ID of the component being dragged, if any
+    pub fn dragging(&self) -> Option<usize>{
+        self.drag.map(|drag| drag.id)
+    }
+
+    /// Send every mouse event to one component (and none to the others) until the capture is released,
+    /// or clear it with `None`. The hovered component doesn't change while the mouse is captured.
+    ///
+    /// The OS keeps sending mouse events to the window while a button is held down, even outside of it,
+    /// so a captured component keeps getting them until the button is released.
+    pub fn set_mouse_capture(&mut self, id: Option<usize>) -> Result<(), &'static str>{
+        if let Some(id) = id{
+            if !self.components.contains(id){
+                return Err("Error, there's no component with this ID!");
+            }
+        }
+        self.capture = id;
+        Ok(())
+    }
+
+    /// Stop sending every mouse event to the component set with `set_mouse_capture`
+    pub fn release_mouse_capture(&mut self){
+        self.capture = None;
+    }
+
+    /// The ID of the component that has captured the mouse, if any - the one being dragged, or the one set with `set_mouse_capture`
+    pub fn mouse_capture(&self) -> Option<usize>{
+        self.dragging().or(self.capture)
+    }
+
+    /// Run the drag lifecycle for an event (see `Component::on_drag_start`), returning the component that has captured
+    /// the mouse if the event should only go to it. Presses start a drag on the hovered component, so run `update_hover` first.
+    /// `dispatch_event` runs this for every event.
+    pub fn update_drag(&mut self, event: &GuiEvent) -> Option<usize>{
+        match (event, self.drag){
+            (GuiEvent::MouseDown{ button, pos }, None) if self.capture.is_none() => {
+                if let Some(id) = self.hovered{
+                    if self.components[id].on_drag_start(*button, *pos){
+                        self.drag = Some(Drag{ id, button: *button, last: *pos });
+                        self.dirty = true;
+                    }
+                }
+                // The press that starts a drag is still dispatched as usual (eg, so a text input can take focus)
+                return None;
+            }
+            (GuiEvent::MouseMove{ pos }, Some(drag)) => {
+                let delta = (pos.0 - drag.last.0, pos.1 - drag.last.1);
+                self.drag = Some(Drag{ last: *pos, ..drag });
+                self.components[drag.id].on_drag_move(*pos, delta);
+                self.dirty = true;
+            }
+            (GuiEvent::MouseUp{ button, pos }, Some(drag)) if *button == drag.button => {
+                self.components[drag.id].on_drag_end(*pos);
+                self.drag = None;
+                self.dirty = true;
+                // The release belongs to the drag, so it only goes to the dragged component
+                return Some(drag.id);
+            }
+            (GuiEvent::Focused(false), Some(drag)) => {
+                // We won't hear about the release if it happens in another window
+                self.components[drag.id].on_drag_end(drag.last);
+                self.drag = None;
+                self.dirty = true;
+            }
+            _ => {}
         }
-        return Err("Error, failed to downcast!");
+
+        if Self::is_mouse_event(event){
+            self.mouse_capture()
+        }else{
+            None
+        }
+    }
+
+    /// The order components get `event` in, not counting a component that has captured the mouse (which gets
+    /// mouse events alone). Keyboard events go to the focused component first, and pointer presses, clicks, scrolls,
+    /// touches and gestures to the hovered one. The rest follow from the topmost (see `draw_order`) down.
+    ///
+    /// Hidden components don't get any events, and components that aren't hit testable don't get mouse events.
+    pub fn dispatch_order(&self, event: &GuiEvent) -> Vec<usize>{
+        let mouse = Self::is_mouse_event(event);
+        let first = match event{
+            GuiEvent::KeyDown{ .. } | GuiEvent::KeyUp{ .. } | GuiEvent::TextInput(_) | GuiEvent::ImePreedit(_) | GuiEvent::ImeCommit(_)
+                | GuiEvent::ModifiersChanged(_) | GuiEvent::Navigate(_) => self.focused,
+            GuiEvent::MouseDown{ .. } | GuiEvent::MouseUp{ .. } | GuiEvent::DoubleClick{ .. } | GuiEvent::TripleClick{ .. }
+                | GuiEvent::Scroll{ .. } | GuiEvent::Touch{ .. } | GuiEvent::Gesture(_) => self.hovered,
+            _ => None,
+        };
+        first.into_iter().chain(self.draw_order().into_iter().rev().filter(|id| Some(*id) != first))
+            .filter(|id| {
+                let comp = &self.components[*id];
+                comp.is_visible() && (!mouse || comp.is_hit_testable())
+            })
+            .collect()
+    }
+
+    // Mouse events are the ones a captured component gets alone, and hit test filtering applies to
+    fn is_mouse_event(event: &GuiEvent) -> bool{
+        matches!(event, GuiEvent::MouseDown{ .. } | GuiEvent::MouseUp{ .. } | GuiEvent::MouseMove{ .. } | GuiEvent::DoubleClick{ .. }
+            | GuiEvent::TripleClick{ .. } | GuiEvent::MouseEnter | GuiEvent::MouseLeave | GuiEvent::Scroll{ .. } | GuiEvent::Touch{ .. } | GuiEvent::Gesture(_))
     }
-}
\ No newline at end of file
+
+    /// Pass an event to the components, returning `Consumed` if one of them consumed it. Tab and Shift+Tab move focus
+    /// (if there's anything to focus), and the components get the event in `dispatch_order` until one consumes it.
+    /// Mouse moves update which component is hovered first, and while a component has captured the mouse (eg, it's being dragged),
+    /// it's the only one that gets mouse events. The components pass `state` on to their callbacks.
+    ///
+    /// The layout is marked dirty for every event except a mouse move nothing consumed (which only marks it if the hover
+    /// or a drag changed), as the components may have changed handling it.
+    pub fn dispatch_event(&mut self, event: &GuiEvent, window: &winit::window::Window, state: &mut AppState) -> EventResult{
+        let size = window.inner_size();
+        let screen = (size.width, size.height);
+        if !matches!(event, GuiEvent::MouseMove{ .. } | GuiEvent::MouseEnter | GuiEvent::MouseLeave){
+            self.dirty = true;
+        }
+        // The hovered component doesn't change while the mouse is captured
+        if self.mouse_capture().is_none(){
+            match event{
+                GuiEvent::MouseMove{ pos } => self.update_hover(Some(*pos), screen),
+                GuiEvent::MouseLeave => self.update_hover(None, screen),
+                _ => {}
+            }
+        }
+
+        if let Some(id) = self.update_drag(event){
+            let result = self.components[id].handle_event(event, window, state);
+            // Once the drag is over, whatever is under the cursor is hovered again
+            if let (GuiEvent::MouseUp{ pos, .. }, None) = (event, self.mouse_capture()){
+                self.update_hover(Some(*pos), screen);
+            }
+            self.sync_focus();
+            return result;
+        }
+
+        match event{
+            GuiEvent::KeyDown{ key: VirtualKeyCode::Tab, modifiers, .. } if self.components.iter().any(|(_, comp)| Self::can_focus(comp.as_ref())) => {
+                if modifiers.shift(){
+                    self.focus_previous();
+                }else{
+                    self.focus_next();
+                }
+                return EventResult::Consumed;
+            }
+            GuiEvent::Navigate(direction @ (NavAction::Up | NavAction::Down | NavAction::Left | NavAction::Right)) => {
+                self.focus_direction(*direction, screen);
+                return EventResult::Consumed;
+            }
+            // Pressing anywhere else takes focus away, even if something above the focused component consumes the press
+            GuiEvent::MouseDown{ .. } if self.focused.is_some() && self.focused != self.hovered => self.move_focus(None),
+            _ => {}
+        }
+
+        let mut result = EventResult::Ignored;
+        for id in self.dispatch_order(event){
+            result = self.components[id].handle_event(event, window, state);
+            if result.is_consumed(){
+                self.dirty = true;
+                break;
+            }
+        }
+        self.sync_focus();
+        result
+    }
+
+    // Follow components that took (or lost) focus themselves, eg, a text input that was clicked
+    fn sync_focus(&mut self){
+        if let Some(id) = self.focused{
+            if !self.components[id].has_focus(){
+                self.focused = None;
+                self.dirty = true;
+            }
+        }
+        let taken = self.components.iter().find(|(id, comp)| Some(*id) != self.focused && comp.has_focus()).map(|(id, _)| id);
+        if taken.is_some(){
+            self.move_focus(taken);
+        }
+    }
+
+    // Focus `id`, making sure no other component thinks it has focus
+    fn move_focus(&mut self, id: Option<usize>){
+        for (index, comp) in self.components.iter_mut(){
+            if Some(index) != id && comp.has_focus(){
+                comp.set_focus(false);
+            }
+        }
+        if let Some(id) = id{
+            self.components[id].set_focus(true);
+        }
+        self.focused = id;
+        self.dirty = true;
+    }
+
+    fn can_focus(comp: &dyn Component) -> bool{
+        comp.is_focusable() && comp.is_visible() && comp.is_enabled()
+    }
+}
+
+// The overlap of two rects ([left, top, width, height]), or `None` if they don't overlap
+fn intersect(a: [f32; 4], b: [f32; 4]) -> Option<[f32; 4]>{
+    let left = a[0].max(b[0]);
+    let top = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+    if right > left && bottom > top { Some([left, top, right - left, bottom - top]) } else { None }
+}
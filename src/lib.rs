@@ -1,4 +1,58 @@
 pub mod rendering;
 pub mod gui;
 pub mod components;
-pub mod layout;
\ No newline at end of file
+pub mod layout;
+pub mod color;
+pub mod theme;
+pub mod color_scheme;
+pub mod stylesheet;
+pub mod clipboard;
+pub mod event;
+pub mod gesture;
+pub mod input;
+pub mod message;
+pub mod shortcut;
+pub mod timer;
+pub mod sender;
+pub mod state;
+pub mod binding;
+pub mod signal;
+pub mod animation;
+pub mod accessibility;
+pub mod locale;
+pub mod persistence;
+pub mod dialog;
+pub mod notification;
+pub mod easing;
+pub mod plugin;
+pub mod test;
+#[cfg(feature = "automation")]
+pub mod automation;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+#[cfg(feature = "tasks")]
+pub mod task;
+#[cfg(feature = "accesskit")]
+pub mod access_adapter;
+
+pub use color::Color;
+pub use theme::Theme;
+pub use color_scheme::ColorScheme;
+pub use stylesheet::Stylesheet;
+pub use locale::{Locale, TextKey};
+pub use clipboard::Clipboard;
+pub use state::AppState;
+pub use binding::{Binding, Observable};
+pub use animation::{AnimatedValue, Animation, Animator, Easing, Transition};
+#[cfg(feature = "derive")]
+pub use rusty_gui_derive::{gui_component, GuiComponent};
+/// The egui version `EguiArea` hosts, so apps build their UIs with the same one
+#[cfg(feature = "egui")]
+pub use egui;
+
+// Used by the code `GuiComponent` generates, so crates using it don't need these as dependencies themselves
+#[doc(hidden)]
+pub mod __private{
+    pub use wgpu;
+    pub use winit;
+}
\ No newline at end of file
@@ -0,0 +1,134 @@
+//! This module contains `Locale`, the translated strings for one language, and `TextKey`, a reference to one of them.
+//! Labels and buttons can be given a text key instead of text (`Label::set_text_key`), which is looked up in the
+//! renderer's locale before they're drawn - so `GUI::set_locale` switches the whole GUI to another language while it runs.
+//!
+//! Locales can be built in code, or loaded from a list of `key = value` lines, with `#` starting a comment:
+//!
+//! ```text
+//! # French
+//! menu.file = Fichier
+//! menu.quit = Quitter
+//! greeting = Bonjour, {name} !
+//! ```
+//!
+//! `{name}` is replaced by the text key's argument called `name` (see `TextKey::with_args`). Keys that aren't in the
+//! locale are shown as they are, so missing strings are easy to spot.
+
+use std::{collections::HashMap, path::Path};
+
+/// # Locale
+///
+/// The strings for one language, by key. The default locale has no strings, so every key is shown as it is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Locale{
+    language: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale{
+    /// Create an empty locale for `language` (eg, "en" or "fr-CA")
+    pub fn new<S: Into<String>>(language: S) -> Self{
+        Self{
+            language: language.into(),
+            strings: HashMap::new(),
+        }
+    }
+
+    /// Parse a locale for `language` from `key = value` lines (see the module docs). Later lines replace earlier ones with the same key.
+    /// Values can use `\n` for a new line.
+    pub fn parse<S: Into<String>>(language: S, source: &str) -> Result<Self, &'static str>{
+        let mut locale = Self::new(language);
+        for line in source.lines(){
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#'){
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or("Error, locale line is missing an `=`!")?;
+            let key = key.trim();
+            if key.is_empty(){
+                return Err("Error, locale line is missing a key!");
+            }
+            locale.insert(key, value.trim().replace("\\n", "\n"));
+        }
+        Ok(locale)
+    }
+
+    /// Load a locale for `language` from a file of `key = value` lines (see `parse`)
+    pub fn load<S: Into<String>, P: AsRef<Path>>(language: S, path: P) -> Result<Self, &'static str>{
+        let source = std::fs::read_to_string(path).map_err(|_| "Error, failed to read the locale file!")?;
+        Self::parse(language, &source)
+    }
+
+    /// The language the locale is for
+    pub fn language(&self) -> &str{
+        &self.language
+    }
+
+    /// Add a string, replacing any with the same key
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self{
+        self.strings.insert(key.into(), value.into());
+        self
+    }
+
+    /// The string for `key`, if the locale has one
+    pub fn get(&self, key: &str) -> Option<&str>{
+        self.strings.get(key).map(String::as_str)
+    }
+
+    /// The number of strings in the locale
+    pub fn len(&self) -> usize{
+        self.strings.len()
+    }
+
+    /// Returns true if the locale has no strings
+    pub fn is_empty(&self) -> bool{
+        self.strings.is_empty()
+    }
+
+    /// Look up a text key, filling in its arguments. Keys the locale doesn't have are returned as they are.
+    pub fn resolve(&self, key: &TextKey) -> String{
+        let mut text = self.get(&key.key).unwrap_or(&key.key).to_string();
+        for (name, value) in key.args.iter(){
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+}
+
+/// # TextKey
+///
+/// The key of a string in a `Locale`, with the arguments to fill into it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextKey{
+    key: String,
+    args: Vec<(String, String)>,
+}
+
+impl TextKey{
+    /// A key with no arguments
+    pub fn new<S: Into<String>>(key: S) -> Self{
+        Self{
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// A key with arguments, as (name, value) pairs. Each `{name}` in the string is replaced by its value.
+    pub fn with_args<S: Into<String>>(key: S, args: &[(&str, &str)]) -> Self{
+        Self{
+            key: key.into(),
+            args: args.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    /// The key looked up in the locale
+    pub fn key(&self) -> &str{
+        &self.key
+    }
+}
+
+impl From<&str> for TextKey{
+    fn from(key: &str) -> Self{
+        Self::new(key)
+    }
+}
@@ -0,0 +1,147 @@
+//! This module contains the message pattern, an alternative to callbacks for larger apps (like Elm's architecture).
+//! Components send typed messages into a `Mailbox`, and the app handles them all in one place, with a single
+//! `Application::update` function. Once the messages are handled, `Application::view` rebuilds the layout from the app's data.
+//!
+//! Set the app with `GUI::set_application`. The main loop handles the queued messages once per iteration, after the events.
+
+use std::{any::Any, cell::RefCell, collections::VecDeque, rc::Rc};
+
+use winit::window::Window;
+
+use crate::{components::ButtonCallback, event::{GuiEvent, MouseButton}, layout::Layout, rendering::Renderer, state::AppState};
+
+/// # Mailbox
+///
+/// A queue of messages waiting to be handled. Clones are cheap, and all share the same queue, so each component
+/// that sends messages can keep its own handle.
+pub struct Mailbox<M>{
+    queue: Rc<RefCell<VecDeque<M>>>,
+}
+
+impl<M> Clone for Mailbox<M>{
+    fn clone(&self) -> Self{
+        Self{
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<M> Default for Mailbox<M>{
+    fn default() -> Self{
+        Self{
+            queue: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+}
+
+impl<M> Mailbox<M>{
+    /// Create a new, empty mailbox
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Queue a message
+    pub fn send(&self, message: M){
+        self.queue.borrow_mut().push_back(message);
+    }
+
+    /// The number of messages waiting
+    pub fn len(&self) -> usize{
+        self.queue.borrow().len()
+    }
+
+    /// Returns true if there are no messages waiting
+    pub fn is_empty(&self) -> bool{
+        self.queue.borrow().is_empty()
+    }
+
+    /// Take every waiting message, oldest first
+    pub fn take_all(&self) -> Vec<M>{
+        self.queue.borrow_mut().drain(..).collect()
+    }
+}
+
+impl<M: Clone + 'static> Mailbox<M>{
+    /// A button callback that sends `message` when the button is clicked (or activated from the keyboard)
+    pub fn on_click(&self, message: M) -> ButtonCallback{
+        let mailbox = self.clone();
+        Box::new(move |event: &GuiEvent, _window: &Window, cursor_in_bounds: &bool, _enabled: &mut bool, _state: &mut AppState|{
+            if let GuiEvent::MouseUp{ button: MouseButton::Left, .. } = event{
+                if *cursor_in_bounds{
+                    mailbox.send(message.clone());
+                }
+            }
+        })
+    }
+}
+
+/// # Application
+///
+/// An app built around messages. It holds all of the app's data (the model), changes it in `update`, and builds
+/// the layout that shows it in `view`.
+pub trait Application: 'static{
+    /// The messages the app's components send
+    type Message: 'static;
+
+    /// Handle a single message, changing the app's data
+    fn update(&mut self, message: Self::Message);
+
+    /// Build the layout from the app's data. Components send their messages into `mailbox` (see `Mailbox::on_click`).
+    /// It's called once when the app is set, then again after every batch of messages.
+    fn view(&self, mailbox: &Mailbox<Self::Message>, renderer: &mut Renderer) -> Layout;
+}
+
+// The GUI stores the app without knowing its message type
+pub(crate) trait ApplicationRunner{
+    // Handle the waiting messages, rebuilding the layout if there were any. Returns true if it was rebuilt.
+    fn process(&mut self, renderer: &mut Renderer) -> bool;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct Runner<A: Application>{
+    app: A,
+    mailbox: Mailbox<A::Message>,
+}
+
+impl<A: Application> Runner<A>{
+    // Start the app, showing its first view
+    pub(crate) fn new(app: A, renderer: &mut Renderer) -> Self{
+        let mailbox = Mailbox::new();
+        renderer.layout = app.view(&mailbox, renderer);
+        Self{
+            app,
+            mailbox,
+        }
+    }
+
+    pub(crate) fn app(&self) -> &A{
+        &self.app
+    }
+
+    pub(crate) fn app_mut(&mut self) -> &mut A{
+        &mut self.app
+    }
+}
+
+impl<A: Application> ApplicationRunner for Runner<A>{
+    fn process(&mut self, renderer: &mut Renderer) -> bool{
+        let messages = self.mailbox.take_all();
+        if messages.is_empty(){
+            return false;
+        }
+        for message in messages{
+            self.app.update(message);
+        }
+        renderer.layout = self.app.view(&self.mailbox, renderer);
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+}
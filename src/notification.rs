@@ -0,0 +1,67 @@
+//! This module posts desktop notifications (see `GUI::notify`), so long-running tools can tell the user something
+//! happened while their window is minimized or hidden.
+//!
+//! Notifications go through the OS's own tools, like the dialogs in the `dialog` module - `osascript` on macOS,
+//! PowerShell on Windows and `notify-send` elsewhere. The tool runs in the background, so posting doesn't block the GUI.
+
+use std::{path::Path, process::Command};
+
+/// Post a desktop notification with `title` and `body`, and an optional icon image (eg, a PNG).
+/// macOS always shows the app's icon, so `icon` is ignored there.
+///
+/// Fails if the OS's notification tool couldn't be started (eg, `notify-send` isn't installed).
+pub fn notify(title: &str, body: &str, icon: Option<&Path>) -> Result<(), &'static str>{
+    let mut command = notify_command(title, body, icon);
+    let mut child = command.spawn().map_err(|_| "Error, failed to post the notification!")?;
+    // Wait for the tool on another thread, so it doesn't linger once it's done
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// The command `notify` runs to post a notification, without running it
+#[cfg(target_os = "macos")]
+pub fn notify_command(title: &str, body: &str, _icon: Option<&Path>) -> Command{
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command.args(["-e", &format!("display notification \"{}\" with title \"{}\"", escape(body), escape(title))]);
+    command
+}
+
+/// The command `notify` runs to post a notification, without running it
+#[cfg(target_os = "windows")]
+pub fn notify_command(title: &str, body: &str, icon: Option<&Path>) -> Command{
+    // A toast, posted as PowerShell (toasts need an app ID, and scripts have no registered one of their own). The notification
+    // stays in the action center once the script exits. The title, body and icon are handed over in environment variables
+    // rather than quoted into the script, so nothing in them can break out of it.
+    const SCRIPT: &str = "$ErrorActionPreference = 'Stop'; \
+        $m = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]; \
+        $template = if ($env:RUSTY_GUI_ICON) { 'ToastImageAndText02' } else { 'ToastText02' }; \
+        $xml = $m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::$template); \
+        $text = $xml.GetElementsByTagName('text'); \
+        $text.Item(0).AppendChild($xml.CreateTextNode($env:RUSTY_GUI_TITLE)) | Out-Null; \
+        $text.Item(1).AppendChild($xml.CreateTextNode($env:RUSTY_GUI_BODY)) | Out-Null; \
+        if ($env:RUSTY_GUI_ICON) { $xml.GetElementsByTagName('image').Item(0).SetAttribute('src', $env:RUSTY_GUI_ICON) }; \
+        $toast = New-Object Windows.UI.Notifications.ToastNotification $xml; \
+        $m::CreateToastNotifier('{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe').Show($toast)";
+
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", SCRIPT]);
+    command.env("RUSTY_GUI_TITLE", title).env("RUSTY_GUI_BODY", body);
+    if let Some(icon) = icon{
+        // Toasts only load images from absolute paths
+        command.env("RUSTY_GUI_ICON", std::path::absolute(icon).unwrap_or_else(|_| icon.to_path_buf()));
+    }
+    command
+}
+
+/// The command `notify` runs to post a notification, without running it
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn notify_command(title: &str, body: &str, icon: Option<&Path>) -> Command{
+    let mut command = Command::new("notify-send");
+    if let Some(icon) = icon{
+        command.arg("--icon").arg(icon);
+    }
+    // Stop options in the text from being read as flags
+    command.args(["--", title, body]);
+    command
+}
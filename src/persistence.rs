@@ -0,0 +1,81 @@
+//! This module contains `ComponentState` and `LayoutState`, the parts of components worth keeping between sessions -
+//! text, form values, where things are and whether they're shown. Components save and restore their own
+//! (see `Component::save_state`), and `Layout::save_state` collects them all, so an app can save its window contents
+//! when it closes and put them back the next time it starts.
+//!
+//! With the `serde` feature, both implement `Serialize` and `Deserialize`, so they can be written in any format serde
+//! supports (JSON, TOML, bincode...).
+//!
+//! ```ignore
+//! let saved = serde_json::to_string(&gui.borrow_renderer().layout.save_state())?;
+//! // ...the next time the app starts, after building the same layout
+//! let state: LayoutState = serde_json::from_str(&saved)?;
+//! gui.borrow_renderer_mut().layout.restore_state(&state);
+//! ```
+
+use crate::{color::Color, rendering::Transform};
+
+/// # TransformState
+///
+/// The position, rotation and scale of a `Transform`, without its GPU buffer
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformState{
+    pub position: [f32; 3],
+    /// The rotation quaternion, as [s, x, y, z]
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl TransformState{
+    /// Save the state of `transform`
+    pub fn from_transform(transform: &Transform) -> Self{
+        let (position, rotation, scale) = (transform.position(), transform.rotation(), transform.scale());
+        Self{
+            position: position.into(),
+            rotation: [rotation.s, rotation.v.x, rotation.v.y, rotation.v.z],
+            scale: scale.into(),
+        }
+    }
+
+    /// Move, rotate and scale `transform` to match this state
+    pub fn apply(&self, transform: &mut Transform){
+        let [s, x, y, z] = self.rotation;
+        transform
+            .set_position(self.position.into())
+            .set_rotation(cgmath::Quaternion::new(s, x, y, z))
+            .set_scale(self.scale.into());
+    }
+}
+
+/// # ComponentState
+///
+/// The state of one component. Each component fills in the parts it has, and leaves the rest as `None` - restoring
+/// a state only changes the parts that are there.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComponentState{
+    /// The text shown or typed in (eg, a label's text, or a text input's value)
+    pub text: Option<String>,
+    /// The position, in pixels from the top left of the window
+    pub position: Option<[f32; 2]>,
+    /// The transform of components drawn with one (eg, a button)
+    pub transform: Option<TransformState>,
+    pub color: Option<Color>,
+    pub visible: Option<bool>,
+    pub enabled: Option<bool>,
+    /// Whether a toggle (eg, a checkbox) is on
+    pub checked: Option<bool>,
+    /// The state of the components inside this one (see `Component::children`)
+    pub children: Option<LayoutState>,
+}
+
+/// # LayoutState
+///
+/// The state of every component in a layout, by ID (see `Layout::save_state`). Components that don't save anything
+/// are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutState{
+    pub components: Vec<Option<ComponentState>>,
+}
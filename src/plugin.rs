@@ -0,0 +1,181 @@
+//! This module lets third-party crates extend rusty_gui with packs of components (a charts pack, a forms pack) without
+//! changing the core crate. A pack implements `ComponentPlugin`, and the app adds it with `GUI::add_plugin` (or
+//! `Renderer::add_plugin`). The plugin then registers what its components need against the renderer - pipelines,
+//! fonts and textures - along with factories that build its components by name.
+//!
+//! Everything is registered under a name, which plugins should prefix with their own (eg, "charts.line") so packs
+//! don't collide. Components find their resources again through `Renderer::plugins`.
+
+use std::{collections::HashMap, rc::Rc};
+
+use wgpu_glyph::{ab_glyph::FontArc, FontId};
+
+use crate::{components::Component, rendering::{Renderer, Texture}};
+
+/// The signature of a component factory, which builds a plugin's component with the renderer's device and theme
+pub type ComponentFactory = Box<dyn Fn(&Renderer) -> Box<dyn Component>>;
+
+/// # ComponentPlugin
+///
+/// A pack of components, added to a renderer with `Renderer::add_plugin`. `register` runs once, when it's added.
+pub trait ComponentPlugin{
+    /// The plugin's name, which is also the prefix it should register its resources under. A renderer only takes
+    /// one plugin with each name.
+    fn name(&self) -> &str;
+
+    /// Register the plugin's pipelines, fonts, textures and component factories. Returning an error stops the plugin
+    /// from being added, and undoes anything it registered.
+    fn register(&self, context: &mut PluginContext) -> Result<(), &'static str>;
+}
+
+/// # PluginContext
+///
+/// What a plugin registers its resources with. It gives access to the renderer, to create them with its device.
+pub struct PluginContext<'a>{
+    renderer: &'a mut Renderer,
+    // What this plugin registered, which joins the renderer's registry once it's been added
+    added: PluginRegistry,
+}
+
+impl<'a> PluginContext<'a>{
+    /// The renderer the plugin is being added to, eg, for its device, queue, format and theme
+    pub fn renderer(&self) -> &Renderer{
+        self.renderer
+    }
+
+    /// Register a render pipeline under `name`, for the plugin's components to draw with
+    pub fn add_pipeline(&mut self, name: &str, pipeline: wgpu::RenderPipeline) -> Result<Rc<wgpu::RenderPipeline>, &'static str>{
+        let pipeline = Rc::new(pipeline);
+        let taken = self.renderer.plugins.pipelines.contains_key(name);
+        insert_new(&mut self.added.pipelines, name, pipeline.clone(), taken)?;
+        Ok(pipeline)
+    }
+
+    /// Register a texture under `name` (see `Texture::from_image`)
+    pub fn add_texture(&mut self, name: &str, texture: Texture) -> Result<(), &'static str>{
+        let taken = self.renderer.plugins.textures.contains_key(name);
+        insert_new(&mut self.added.textures, name, texture, taken)
+    }
+
+    /// Register a font under `name`, returning the ID text sections use to pick it (eg, `wgpu_glyph::Text::with_font_id`).
+    /// The theme's font is always `FontId(0)`, and plugin fonts keep their IDs when the theme changes.
+    pub fn add_font(&mut self, name: &str, font: FontArc) -> Result<FontId, &'static str>{
+        if self.renderer.plugins.font(name).is_some() || self.added.font(name).is_some(){
+            return Err("Error, a plugin resource with this name is already registered!");
+        }
+        let id = self.renderer.add_glyph_font(font.clone());
+        self.added.fonts.push((name.to_string(), font, id));
+        Ok(id)
+    }
+
+    /// Register a factory that builds a component, so apps (or tools building layouts from data) can create it by `name`
+    /// with `Renderer::create_component`
+    pub fn add_factory<F: Fn(&Renderer) -> Box<dyn Component> + 'static>(&mut self, name: &str, factory: F) -> Result<(), &'static str>{
+        let taken = self.renderer.plugins.factories.contains_key(name);
+        insert_new(&mut self.added.factories, name, Box::new(factory) as ComponentFactory, taken)
+    }
+}
+
+// Insert `value` under `name`, unless something (in `map`, or another plugin if `taken`) already has that name
+fn insert_new<T>(map: &mut HashMap<String, T>, name: &str, value: T, taken: bool) -> Result<(), &'static str>{
+    if taken || map.contains_key(name){
+        return Err("Error, a plugin resource with this name is already registered!");
+    }
+    map.insert(name.to_string(), value);
+    Ok(())
+}
+
+/// # PluginRegistry
+///
+/// The plugins a renderer has, and everything they registered. Reach it with `Renderer::plugins`.
+#[derive(Default)]
+pub struct PluginRegistry{
+    plugins: Vec<String>,
+    pipelines: HashMap<String, Rc<wgpu::RenderPipeline>>,
+    textures: HashMap<String, Texture>,
+    // In the order they were added to the glyph brush, so they can be added again in the same order
+    fonts: Vec<(String, FontArc, FontId)>,
+    factories: HashMap<String, ComponentFactory>,
+}
+
+impl PluginRegistry{
+    /// The names of the plugins that were added, in order
+    pub fn plugins(&self) -> &[String]{
+        &self.plugins
+    }
+
+    /// Returns true if a plugin called `name` was added
+    pub fn has_plugin(&self, name: &str) -> bool{
+        self.plugins.iter().any(|plugin| plugin == name)
+    }
+
+    /// The pipeline registered under `name`
+    pub fn pipeline(&self, name: &str) -> Option<Rc<wgpu::RenderPipeline>>{
+        self.pipelines.get(name).cloned()
+    }
+
+    /// The texture registered under `name`
+    pub fn texture(&self, name: &str) -> Option<Texture>{
+        self.textures.get(name).cloned()
+    }
+
+    /// The ID of the font registered under `name`
+    pub fn font(&self, name: &str) -> Option<FontId>{
+        self.fonts.iter().find(|(font_name, _, _)| font_name == name).map(|(_, _, id)| *id)
+    }
+
+    /// The names components can be created with (see `Renderer::create_component`), in no particular order
+    pub fn factories(&self) -> impl Iterator<Item = &str>{
+        self.factories.keys().map(|name| name.as_str())
+    }
+
+    // The fonts plugins added, in the order they were added
+    pub(crate) fn fonts(&self) -> impl Iterator<Item = &FontArc>{
+        self.fonts.iter().map(|(_, font, _)| font)
+    }
+
+    pub(crate) fn factory(&self, name: &str) -> Option<&ComponentFactory>{
+        self.factories.get(name)
+    }
+}
+
+impl Renderer{
+    /// Add a plugin, letting it register its resources and component factories (see `ComponentPlugin`).
+    /// Fails if a plugin with the same name was already added, if it registers a name that's taken, or if its `register` fails -
+    /// in which case nothing it registered is kept.
+    pub fn add_plugin<P: ComponentPlugin>(&mut self, plugin: P) -> Result<(), &'static str>{
+        if self.plugins.has_plugin(plugin.name()){
+            return Err("Error, a plugin with this name was already added!");
+        }
+        tracing::debug!(plugin = plugin.name(), "Adding plugin");
+
+        let mut context = PluginContext{ renderer: self, added: PluginRegistry::default() };
+        let result = plugin.register(&mut context);
+        let added = context.added;
+        if result.is_err(){
+            // Take the plugin's fonts back out of the glyph brush
+            if !added.fonts.is_empty(){
+                self.rebuild_glyph_brush();
+            }
+            return result;
+        }
+
+        let registry = &mut self.plugins;
+        registry.plugins.push(plugin.name().to_string());
+        registry.pipelines.extend(added.pipelines);
+        registry.textures.extend(added.textures);
+        registry.fonts.extend(added.fonts);
+        registry.factories.extend(added.factories);
+        Ok(())
+    }
+
+    /// The plugins that were added, and the pipelines, fonts, textures and factories they registered
+    pub fn plugins(&self) -> &PluginRegistry{
+        &self.plugins
+    }
+
+    /// Build the component a plugin registered under `name` (see `PluginContext::add_factory`), or `None` if there's no such factory
+    pub fn create_component(&self, name: &str) -> Option<Box<dyn Component>>{
+        self.plugins.factory(name).map(|factory| factory(self))
+    }
+}
@@ -0,0 +1,76 @@
+//! This module contains the bind group cache - the uniform bind group layouts the renderer and its components share.
+//! Layouts only depend on their descriptor, so one is created for each descriptor and handed out again, rather than
+//! every button, panel and camera creating (and usually throwing away) its own.
+//!
+//! Bind groups are still created per buffer, as each one points at its own buffer. They're created once, when the
+//! buffer is, and reused for as long as it lives.
+
+use std::{cell::RefCell, collections::HashMap, num::NonZeroU64, rc::Rc, sync::Arc};
+
+use super::{Tracked, UniformUtils};
+
+// What a uniform bind group layout is created from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UniformLayoutKey{
+    binding: u32,
+    visibility: u32,
+    dynamic: bool,
+    min_binding_size: Option<NonZeroU64>,
+}
+
+/// # BindGroupCache
+///
+/// Shares uniform bind group layouts between everything created with the same renderer (see the module docs). The
+/// renderer owns one - get it with `Renderer::bind_group_cache`. Cloning it gives another handle to the same cache.
+#[derive(Clone)]
+pub struct BindGroupCache{
+    inner: Rc<RefCell<CacheInner>>,
+}
+
+struct CacheInner{
+    device: Arc<wgpu::Device>,
+    layouts: HashMap<UniformLayoutKey, Rc<wgpu::BindGroupLayout>>,
+}
+
+impl BindGroupCache{
+    /// Create an empty cache for `device`
+    pub fn new(device: Arc<wgpu::Device>) -> Self{
+        Self{
+            inner: Rc::new(RefCell::new(CacheInner{
+                device,
+                layouts: HashMap::new(),
+            })),
+        }
+    }
+
+    /// The layout of a single uniform buffer at `binding` (see `UniformUtils::create_bind_group_layout`), created the
+    /// first time it's asked for
+    pub fn uniform_layout(&self, binding: u32, visibility: wgpu::ShaderStage, dynamic: bool, min_binding_size: Option<NonZeroU64>) -> Rc<wgpu::BindGroupLayout>{
+        let key = UniformLayoutKey{ binding, visibility: visibility.bits(), dynamic, min_binding_size };
+        let mut inner = self.inner.borrow_mut();
+        let CacheInner{ device, layouts } = &mut *inner;
+        layouts.entry(key)
+            .or_insert_with(|| Rc::new(UniformUtils::create_bind_group_layout(device, binding, visibility, dynamic, min_binding_size, "Cached uniform layout")))
+            .clone()
+    }
+
+    /// Create a uniform buffer holding `uniform`, and a bind group for it, with a cached layout. This is `UniformUtils::create`
+    /// without the new layout.
+    pub fn create_uniform<T: bytemuck::Pod>(&self, visibility: wgpu::ShaderStage, binding: u32, uniform: &T, label: &str) -> (Tracked<wgpu::Buffer>, wgpu::BindGroup, Rc<wgpu::BindGroupLayout>){
+        let layout = self.uniform_layout(binding, visibility, false, None);
+        let device = self.inner.borrow().device.clone();
+        let buffer = UniformUtils::create_uniform_buffer(&device, uniform);
+        let bind_group = UniformUtils::create_bind_group(&device, &layout, binding, &buffer, label);
+        (buffer, bind_group, layout)
+    }
+
+    /// How many layouts have been created
+    pub fn len(&self) -> usize{
+        self.inner.borrow().layouts.len()
+    }
+
+    /// Returns true if no layouts have been created yet
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
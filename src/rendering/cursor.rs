@@ -0,0 +1,153 @@
+//! This module contains `CustomCursor`, which lets apps replace the system cursor with their own image
+//! (a brush outline for drawing tools, a crosshair and so on). winit can't set image cursors, so the renderer
+//! hides the system cursor and draws the image itself, on top of everything else, wherever the mouse is.
+
+use std::rc::Rc;
+
+use super::{MemoryKind, Tracked, TransformPool, TransformSlot, TransformUniform};
+
+/// # CustomCursor
+///
+/// An RGBA image to use as the cursor, and its hotspot - the pixel in the image that lines up with the
+/// mouse position (eg, the tip of an arrow, or the center of a crosshair).
+///
+/// Set it with `Renderer::set_custom_cursor`.
+#[derive(Debug, Clone)]
+pub struct CustomCursor{
+    image: image::RgbaImage,
+    hotspot: (u32, u32),
+}
+
+impl CustomCursor{
+    /// Create a cursor from RGBA8 pixels. Fails if the data doesn't match the dimensions, or the hotspot is outside the image.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>, hotspot: (u32, u32)) -> Result<Self, &'static str>{
+        match image::RgbaImage::from_raw(width, height, pixels){
+            Some(image) => Self::from_image(image, hotspot),
+            None => Err("Error, cursor pixel data doesn't match its dimensions!"),
+        }
+    }
+
+    /// Create a cursor from an `image` buffer
+    pub fn from_image(image: image::RgbaImage, hotspot: (u32, u32)) -> Result<Self, &'static str>{
+        if image.width() == 0 || image.height() == 0{
+            return Err("Error, cursor image is empty!");
+        }
+        if hotspot.0 >= image.width() || hotspot.1 >= image.height(){
+            return Err("Error, cursor hotspot is outside of the image!");
+        }
+        Ok(Self{
+            image,
+            hotspot,
+        })
+    }
+
+    /// Load a cursor from an image file (any format the `image` crate supports)
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P, hotspot: (u32, u32)) -> Result<Self, &'static str>{
+        match image::open(path){
+            Ok(image) => Self::from_image(image.to_rgba8(), hotspot),
+            Err(_) => Err("Error, failed to load cursor image!"),
+        }
+    }
+
+    /// The size of the cursor in pixels
+    pub fn size(&self) -> (u32, u32){
+        self.image.dimensions()
+    }
+
+    /// The hotspot of the cursor, in pixels from the top left of the image
+    pub fn hotspot(&self) -> (u32, u32){
+        self.hotspot
+    }
+
+    /// The top left corner of the cursor image when the mouse is at `position`
+    pub fn top_left(&self, position: (f64, f64)) -> (f64, f64){
+        (position.0 - self.hotspot.0 as f64, position.1 - self.hotspot.1 as f64)
+    }
+}
+
+// The GPU side of a custom cursor
+pub(crate) struct CursorSprite{
+    pub(crate) cursor: CustomCursor,
+    _texture: Tracked<wgpu::Texture>,
+    pub(crate) texture_bind_group: wgpu::BindGroup,
+    pub(crate) transform: TransformSlot,
+    // The renderer's shared quad
+    pub(crate) vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+}
+
+impl CursorSprite{
+    /// Upload the cursor image
+    pub(crate) fn new(cursor: CustomCursor, device: &wgpu::Device, queue: &wgpu::Queue, texture_bind_group_layout: &wgpu::BindGroupLayout, transform_pool: &TransformPool, quad_buffer: Rc<Tracked<wgpu::Buffer>>) -> Self{
+        let (width, height) = cursor.size();
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Cursor texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+        queue.write_texture(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            &cursor.image,
+            wgpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: 4 * width,
+                rows_per_image: height,
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Nearest filtering keeps pixel art cursors crisp
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("Cursor bind group"),
+        });
+
+        Self{
+            cursor,
+            _texture: Tracked::new(texture, MemoryKind::Texture, 4 * width as u64 * height as u64),
+            texture_bind_group,
+            transform: transform_pool.allocate(TransformUniform::new()),
+            vertex_buffer: quad_buffer,
+        }
+    }
+
+    /// Move the sprite so the hotspot sits at `position` (in window pixels)
+    pub(crate) fn update(&mut self, position: (f64, f64)){
+        let (width, height) = self.cursor.size();
+        let (left, top) = self.cursor.top_left(position);
+        let uniform = TransformUniform::from_pixel_rect([left as f32, top as f32, width as f32, height as f32]);
+        self.transform.write(uniform);
+    }
+}
@@ -0,0 +1,90 @@
+//! This module contains the focus ring - the outline the renderer draws around the component with keyboard focus,
+//! so people navigating with Tab can see where they are. It's four thin quads (one per edge) drawn with the
+//! component pipeline, on top of the rest of the frame (only the custom cursor goes over it).
+
+use std::rc::Rc;
+
+use wgpu::ShaderStage;
+
+use crate::color::Color;
+
+use super::{BindGroupCache, ResourceReport, Tracked, TransformPool, TransformSlot, TransformUniform};
+
+// The GPU side of the focus ring
+pub(crate) struct FocusRing{
+    color: Option<Color>,
+    width: f32,
+    tint: (Tracked<wgpu::Buffer>, wgpu::BindGroup),
+    pub(crate) edges: Vec<TransformSlot>,
+    // The rect and width the edges were last written for
+    drawn: Option<([f32; 4], f32)>,
+    // The renderer's shared quad
+    pub(crate) vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+}
+
+impl FocusRing{
+    /// The color the ring is drawn with unless it's changed
+    pub(crate) const DEFAULT_COLOR: Color = Color::new(0.0, 0.47, 0.84, 1.0);
+
+    pub(crate) fn new(cache: &BindGroupCache, transform_pool: &TransformPool, quad_buffer: Rc<Tracked<wgpu::Buffer>>) -> Self{
+        let (tint_buffer, tint_bind_group, _) = cache.create_uniform(ShaderStage::FRAGMENT, 0, &Self::DEFAULT_COLOR.to_linear(), "Focus ring tint");
+        let edges = (0..4).map(|_| transform_pool.allocate(TransformUniform::new())).collect();
+
+        Self{
+            color: Some(Self::DEFAULT_COLOR),
+            width: 2.0,
+            tint: (tint_buffer, tint_bind_group),
+            edges,
+            drawn: None,
+            vertex_buffer: quad_buffer,
+        }
+    }
+
+    /// The ring's color, or `None` if it's hidden
+    pub(crate) fn color(&self) -> Option<Color>{
+        self.color
+    }
+
+    pub(crate) fn set_color(&mut self, queue: &wgpu::Queue, color: Option<Color>){
+        if let Some(color) = color{
+            queue.write_buffer(&self.tint.0, 0, bytemuck::cast_slice(&[color.to_linear()]));
+        }
+        self.color = color;
+    }
+
+    pub(crate) fn width(&self) -> f32{
+        self.width
+    }
+
+    pub(crate) fn set_width(&mut self, width: f32){
+        self.width = width.max(0.0);
+    }
+
+    pub(crate) fn tint_bind_group(&self) -> &wgpu::BindGroup{
+        &self.tint.1
+    }
+
+    /// Move the edges so they surround `rect` ([left, top, width, height] in pixels)
+    pub(crate) fn update(&mut self, rect: [f32; 4]){
+        // The edges only need uploading again when they move
+        if self.drawn == Some((rect, self.width)){
+            return;
+        }
+        self.drawn = Some((rect, self.width));
+        let [left, top, width, height] = rect;
+        let w = self.width;
+        let edges = [
+            [left - w, top - w, width + 2.0 * w, w],
+            [left - w, top + height, width + 2.0 * w, w],
+            [left - w, top, w, height],
+            [left + width, top, w, height],
+        ];
+        for (edge, slot) in edges.iter().zip(self.edges.iter_mut()){
+            slot.write(TransformUniform::from_pixel_rect(*edge));
+        }
+    }
+
+    pub(crate) fn report_resources(&self, report: &mut ResourceReport){
+        report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64);
+    }
+}
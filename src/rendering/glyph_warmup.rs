@@ -0,0 +1,119 @@
+//! This module contains the glyph cache warm-up. Rasterizing and uploading the glyphs of a large font size the first
+//! time it's drawn (and growing the cache to fit them) can make that frame hitch, so apps can register the fonts, sizes
+//! and characters they'll use (`RendererBuilder::warm_glyphs`, `Renderer::warm_glyph_cache`). They're queued a few at a
+//! time with each frame's text, laid out offscreen so they're cached without being drawn, until they're all in.
+
+use std::collections::VecDeque;
+
+use wgpu_glyph::{ab_glyph::{point, Rect}, Extra, FontId, GlyphCruncher, Section, Text};
+
+// How many glyphs are queued each frame unless it's changed
+pub(crate) const DEFAULT_BUDGET: usize = 64;
+// Where warm-up text is laid out, far enough above the target that its glyphs are culled (a whole number of pixels, so
+// their sub-pixel offsets are the same as text laid out at the origin)
+const OFFSCREEN: f32 = -10000.0;
+
+// The text of a font at a size, to be cached
+#[derive(Debug, Clone)]
+struct WarmupRequest{
+    font: FontId,
+    size: f32,
+    text: String,
+}
+
+// The registered warm-ups, and the part of them that isn't cached yet
+#[derive(Debug)]
+pub(crate) struct GlyphWarmup{
+    registered: Vec<WarmupRequest>,
+    // The characters still to queue (whitespace has no glyph, so it's left out)
+    pending: VecDeque<WarmupRequest>,
+    budget: usize,
+}
+
+impl GlyphWarmup{
+    pub(crate) fn new() -> Self{
+        Self{
+            registered: Vec::new(),
+            pending: VecDeque::new(),
+            budget: DEFAULT_BUDGET,
+        }
+    }
+
+    // Warm `text` at each of `sizes` in `font`, and again each time `restart` is called
+    pub(crate) fn register(&mut self, font: FontId, sizes: &[f32], text: &str){
+        for &size in sizes{
+            let request = WarmupRequest{ font, size, text: text.to_string() };
+            self.push_pending(&request);
+            self.registered.push(request);
+        }
+    }
+
+    // Warm everything registered again (eg, after the glyph brush is rebuilt with an empty cache)
+    pub(crate) fn restart(&mut self){
+        self.pending.clear();
+        for request in self.registered.clone(){
+            self.push_pending(&request);
+        }
+    }
+
+    pub(crate) fn budget(&self) -> usize{
+        self.budget
+    }
+
+    // Queue up to `budget` glyphs each frame, or none with 0 (pausing the warm-up)
+    pub(crate) fn set_budget(&mut self, budget: usize){
+        self.budget = budget;
+    }
+
+    // The number of characters still waiting to be queued
+    pub(crate) fn pending(&self) -> usize{
+        self.pending.iter().map(|request| request.text.chars().count()).sum()
+    }
+
+    // Whether there's more to queue in the coming frames (it isn't done, or paused)
+    pub(crate) fn is_warming(&self) -> bool{
+        self.budget > 0 && !self.pending.is_empty()
+    }
+
+    // Queue up to `budget` of the pending glyphs into `brush`, to be cached with the next text it draws. Returns the
+    // number of glyphs queued.
+    pub(crate) fn queue_next(&mut self, brush: &mut wgpu_glyph::GlyphBrush<()>) -> usize{
+        if self.budget == 0{
+            return 0;
+        }
+        // Skip fonts that have gone since they were registered (eg, with the plugin that added them)
+        let fonts = brush.fonts().len();
+        while self.pending.front().is_some_and(|request| request.font.0 >= fonts){
+            self.pending.pop_front();
+        }
+        let request = match self.pending.front_mut(){
+            Some(v) => v,
+            None => return 0,
+        };
+        // Take the first `budget` characters, leaving the rest for the next frames
+        let split = request.text.char_indices().nth(self.budget).map_or(request.text.len(), |(i, _)| i);
+        let rest = request.text.split_off(split);
+        let text = std::mem::replace(&mut request.text, rest);
+        let (font, size) = (request.font, request.size);
+        if request.text.is_empty(){
+            self.pending.pop_front();
+        }
+
+        let section = Section::default()
+            .with_screen_position((0.0, OFFSCREEN))
+            .add_text(Text::new(&text).with_scale(size).with_font_id(font));
+        let glyphs: Vec<_> = brush.glyphs(&section).cloned().collect();
+        let count = glyphs.len();
+        // An empty rect culls every glyph when the vertices are built, after they've been cached
+        let bounds = Rect{ min: point(0.0, 0.0), max: point(0.0, 0.0) };
+        brush.queue_pre_positioned(glyphs, vec![Extra::default()], bounds);
+        count
+    }
+
+    fn push_pending(&mut self, request: &WarmupRequest){
+        let text: String = request.text.chars().filter(|c| !c.is_whitespace()).collect();
+        if !text.is_empty(){
+            self.pending.push_back(WarmupRequest{ text, ..request.clone() });
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! This module tracks the GPU memory rusty_gui allocates - vertex and uniform buffers, textures, render targets and the
+//! glyph cache. Each allocation is wrapped in a `Tracked`, which counts it when it's created and again when it's dropped,
+//! so `MemoryStats::current` can tell what's alive right now and how much has been created along the way.
+//!
+//! Where `ResourceReport` asks components what they're holding, these counters see every allocation - a buffer that
+//! keeps being recreated shows up as allocations and frees climbing every frame, even if the live total stays flat.
+
+use std::{ops::{Deref, DerefMut}, sync::atomic::{AtomicU64, AtomicUsize, Ordering}};
+
+/// # MemoryKind
+///
+/// What an allocation is used for, which decides the category it's counted in (see `MemoryStats`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryKind{
+    /// Vertex and index buffers, including the shared quad
+    VertexBuffer,
+    /// Uniform buffers, including the transform pool
+    UniformBuffer,
+    /// Images uploaded to the GPU, like texture atlases and the custom cursor
+    Texture,
+    /// Textures drawn into, like cached layouts and a headless renderer's target
+    RenderTarget,
+    /// The glyph cache text is drawn from
+    GlyphCache,
+}
+
+impl MemoryKind{
+    const COUNT: usize = 5;
+
+    fn counters(self) -> &'static Counters{
+        &COUNTERS[self as usize]
+    }
+}
+
+struct Counters{
+    allocations: AtomicUsize,
+    frees: AtomicUsize,
+    bytes: AtomicU64,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY: Counters = Counters{
+    allocations: AtomicUsize::new(0),
+    frees: AtomicUsize::new(0),
+    bytes: AtomicU64::new(0),
+};
+
+// Shared by every renderer in the process, as allocations are often made with only a device at hand
+static COUNTERS: [Counters; MemoryKind::COUNT] = [EMPTY; MemoryKind::COUNT];
+
+/// # MemoryUsage
+///
+/// The allocations in one category: how many were made and freed, and the size of the ones still alive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage{
+    /// Allocations made so far
+    pub allocations: usize,
+    /// Allocations dropped so far
+    pub frees: usize,
+    /// The total size of the live allocations, in bytes
+    pub bytes: u64,
+}
+
+impl MemoryUsage{
+    /// The number of allocations still alive
+    pub fn live(&self) -> usize{
+        self.allocations - self.frees
+    }
+
+    fn of(kind: MemoryKind) -> Self{
+        let counters = kind.counters();
+        Self{
+            allocations: counters.allocations.load(Ordering::Relaxed),
+            frees: counters.frees.load(Ordering::Relaxed),
+            bytes: counters.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// # MemoryStats
+///
+/// The GPU memory allocated by rusty_gui, by category. Get it with `MemoryStats::current()`. The counters are process wide -
+/// they add up every renderer (and secondary window) rather than belonging to one, which is why they're not asked for
+/// through a `Renderer`. Sizes are the sizes requested from wgpu - the driver may round them up, and the
+/// glyph cache may have grown past the size it was created with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats{
+    pub vertex_buffers: MemoryUsage,
+    pub uniform_buffers: MemoryUsage,
+    pub textures: MemoryUsage,
+    pub render_targets: MemoryUsage,
+    pub glyph_cache: MemoryUsage,
+}
+
+impl MemoryStats{
+    /// The counters as they are now, across every renderer in the process. Allocations that keep climbing from frame to
+    /// frame point at something being recreated instead of reused.
+    pub fn current() -> Self{
+        Self{
+            vertex_buffers: MemoryUsage::of(MemoryKind::VertexBuffer),
+            uniform_buffers: MemoryUsage::of(MemoryKind::UniformBuffer),
+            textures: MemoryUsage::of(MemoryKind::Texture),
+            render_targets: MemoryUsage::of(MemoryKind::RenderTarget),
+            glyph_cache: MemoryUsage::of(MemoryKind::GlyphCache),
+        }
+    }
+
+    /// The total size of every live allocation, in bytes
+    pub fn total_bytes(&self) -> u64{
+        self.categories().map(|usage| usage.bytes).sum()
+    }
+
+    /// The number of live allocations, across every category
+    pub fn total_live(&self) -> usize{
+        self.categories().map(|usage| usage.live()).sum()
+    }
+
+    fn categories(&self) -> impl Iterator<Item = &MemoryUsage>{
+        vec![&self.vertex_buffers, &self.uniform_buffers, &self.textures, &self.render_targets, &self.glyph_cache].into_iter()
+    }
+}
+
+/// # Tracked
+///
+/// A GPU object (a buffer, texture, or something holding one) counted in the memory stats for as long as it lives. It
+/// derefs to the object, so it can be used in its place.
+#[derive(Debug)]
+pub struct Tracked<T>{
+    value: T,
+    kind: MemoryKind,
+    bytes: u64,
+}
+
+impl<T> Tracked<T>{
+    /// Count `value`, which holds `bytes` of GPU memory used as `kind`, until it's dropped
+    pub fn new(value: T, kind: MemoryKind, bytes: u64) -> Self{
+        let counters = kind.counters();
+        counters.allocations.fetch_add(1, Ordering::Relaxed);
+        counters.bytes.fetch_add(bytes, Ordering::Relaxed);
+        Self{
+            value,
+            kind,
+            bytes,
+        }
+    }
+
+    /// What the allocation is used for
+    pub fn kind(&self) -> MemoryKind{
+        self.kind
+    }
+
+    /// The size of the allocation, in bytes
+    pub fn bytes(&self) -> u64{
+        self.bytes
+    }
+}
+
+impl<T> Deref for Tracked<T>{
+    type Target = T;
+
+    fn deref(&self) -> &T{
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Tracked<T>{
+    fn deref_mut(&mut self) -> &mut T{
+        &mut self.value
+    }
+}
+
+impl<T> Drop for Tracked<T>{
+    fn drop(&mut self){
+        let counters = self.kind.counters();
+        counters.frees.fetch_add(1, Ordering::Relaxed);
+        counters.bytes.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
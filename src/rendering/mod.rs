@@ -1,9 +1,35 @@
 mod window;
 mod render;
 mod transform;
+mod transform_pool;
+mod bind_group_cache;
+mod pipeline_cache;
 mod uniform;
+mod snapshot;
+mod stats;
+mod resources;
+mod secondary_window;
+mod cursor;
+mod focus_ring;
+mod glyph_warmup;
+mod perf_overlay;
+mod texture;
+mod text_queue;
+mod memory;
+mod surface_handle;
 
-pub use window::{Window, WindowBuilder, ScreenMode};
-pub use render::{Renderer, QUAD};
+pub use window::{Window, WindowBuilder, ScreenMode, EventCallback, MonitorInfo, VideoModeInfo, WindowBuildError};
+pub use render::{Renderer, RendererBuilder, GlyphCachePolicy, RenderContext, CustomPass, Viewport, Camera, QUAD, MAX_GLYPH_CACHE_SIZE};
 pub use transform::{Transform, TransformUniform};
-pub use uniform::UniformUtils;
\ No newline at end of file
+pub use transform_pool::{TransformPool, TransformSlot, TransformParts, TRANSFORM_SLOT_SIZE, PARALLEL_THRESHOLD};
+pub use uniform::UniformUtils;
+pub use bind_group_cache::BindGroupCache;
+pub use pipeline_cache::{PipelineCache, PipelineKey, Blend};
+pub use snapshot::{Snapshot, SnapshotDiff};
+pub use stats::FrameStats;
+pub use resources::{ResourceReport, ResourceUsage};
+pub use secondary_window::SecondaryWindow;
+pub use cursor::CustomCursor;
+pub use texture::{Texture, TextureRegion};
+pub use text_queue::TextQueue;
+pub use memory::{MemoryStats, MemoryUsage, MemoryKind, Tracked};
\ No newline at end of file
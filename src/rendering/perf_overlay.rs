@@ -0,0 +1,159 @@
+//! This module contains the performance overlay - a debug panel the renderer draws in the top left corner, over the rest
+//! of the frame (only the custom cursor goes over it). It shows the frame rate, a graph of recent frame times, the draw
+//! calls, how many components were drawn and culled, and how full the glyph cache is, all read from `FrameStats`.
+//! Show it with `Renderer::set_perf_overlay`, or bind it to a key with `GUI::set_perf_overlay_shortcut`.
+
+use std::{any::Any, collections::VecDeque, rc::Rc};
+
+use wgpu::ShaderStage;
+
+use crate::{color::Color, components::{Component, Label, Panel}, layout::Layout};
+
+use super::{FrameStats, RenderContext, Renderer, Tracked, TransformSlot, TransformUniform};
+
+// How many frames the graph shows, one bar each
+const GRAPH_FRAMES: usize = 120;
+// The frame time (in milliseconds) a full height bar stands for - two frames at 60 FPS
+const GRAPH_SCALE: f32 = 1000.0 / 30.0;
+const TEXT_SIZE: f32 = 14.0;
+const LINE_HEIGHT: f32 = 18.0;
+const PADDING: f32 = 8.0;
+const WIDTH: f32 = 320.0;
+const GRAPH_HEIGHT: f32 = 48.0;
+
+// The overlay's components, in a layout of their own so they're drawn over the app's
+pub(crate) struct PerfOverlay{
+    layout: Layout,
+    lines: Vec<usize>,
+    graph: usize,
+}
+
+impl PerfOverlay{
+    pub(crate) fn new(renderer: &Renderer) -> Self{
+        let mut layout = Layout::new();
+        let lines = 5;
+        let height = PADDING * 3.0 + LINE_HEIGHT * lines as f32 + GRAPH_HEIGHT;
+        let mut background = Panel::new([PADDING, PADDING, WIDTH, height], renderer);
+        background.set_background(Some(Color::new(0.0, 0.0, 0.0, 0.75)));
+        layout.add_component(Box::new(background));
+
+        let lines = (0..lines).map(|line| {
+            let mut label = Label::new("", TEXT_SIZE, [PADDING * 2.0, PADDING * 2.0 + LINE_HEIGHT * line as f32]);
+            label.set_color(Color::WHITE);
+            layout.add_component(Box::new(label))
+        }).collect::<Vec<_>>();
+        let top = PADDING * 2.0 + LINE_HEIGHT * lines.len() as f32;
+        let graph = layout.add_component(Box::new(FrameGraph::new([PADDING * 2.0, top, WIDTH - PADDING * 2.0, GRAPH_HEIGHT], renderer)));
+        Self{
+            layout,
+            lines,
+            graph,
+        }
+    }
+
+    // Show `stats`, the stats of the frame being drawn into `layout` (the app's layout)
+    pub(crate) fn update(&mut self, stats: &FrameStats, layout: &Layout){
+        let frame_ms = stats.frame_time.as_secs_f32() * 1000.0;
+        let (width, height) = stats.glyph_cache_size;
+        let glyph_cache = match stats.glyph_cache_occupancy(){
+            Some(occupancy) => format!("Glyph cache: {:.0}% of {}x{}", occupancy * 100.0, width, height),
+            None => format!("Glyph cache: {}x{}", width, height),
+        };
+        let text = [
+            format!("{:.0} FPS ({:.1} ms)", stats.fps(), frame_ms),
+            format!("CPU: {:.1} ms", stats.cpu_time.as_secs_f32() * 1000.0),
+            format!("Draw calls: {}", stats.draw_calls),
+            format!("Components: {} drawn, {} culled, {} total", stats.components_rendered, stats.components_culled, count_components(layout)),
+            format!("{} ({} glyphs queued)", glyph_cache, stats.glyphs_queued),
+        ];
+        for (id, text) in self.lines.iter().zip(text.iter()){
+            if let Ok(label) = self.layout.borrow_component_as_type_mut::<Label>(*id){
+                label.set_text(text.as_str());
+            }
+        }
+        if let Ok(graph) = self.layout.borrow_component_as_type_mut::<FrameGraph>(self.graph){
+            graph.push(frame_ms);
+        }
+    }
+
+    pub(crate) fn layout_mut(&mut self) -> &mut Layout{
+        &mut self.layout
+    }
+}
+
+// Every component in `layout`, including the ones nested in other components
+fn count_components(layout: &Layout) -> usize{
+    layout.components.iter()
+        .map(|(_, comp)| 1 + comp.children().map_or(0, count_components))
+        .sum()
+}
+
+// A bar for each of the last `GRAPH_FRAMES` frame times, newest on the right
+struct FrameGraph{
+    rect: [f32; 4],
+    times: VecDeque<f32>,
+    bars: Vec<TransformSlot>,
+    tint: (Tracked<wgpu::Buffer>, wgpu::BindGroup),
+    // The renderer's shared quad
+    vertex_buffer: Rc<Tracked<wgpu::Buffer>>,
+}
+
+impl FrameGraph{
+    fn new(rect: [f32; 4], renderer: &Renderer) -> Self{
+        let (tint_buffer, tint_bind_group, _) = renderer.bind_group_cache().create_uniform(ShaderStage::FRAGMENT, 0, &Color::GREEN.to_linear(), "Frame graph tint");
+        let bars = (0..GRAPH_FRAMES).map(|_| renderer.transform_pool().allocate(TransformUniform::new())).collect();
+        Self{
+            rect,
+            times: VecDeque::with_capacity(GRAPH_FRAMES),
+            bars,
+            tint: (tint_buffer, tint_bind_group),
+            vertex_buffer: renderer.quad_buffer(),
+        }
+    }
+
+    fn push(&mut self, frame_ms: f32){
+        if self.times.len() == GRAPH_FRAMES{
+            self.times.pop_front();
+        }
+        self.times.push_back(frame_ms);
+    }
+}
+
+impl Component for FrameGraph{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {
+        render_pass.set_bind_group(2, &self.tint.1, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        for bar in self.bars.iter().take(self.times.len()){
+            bar.bind(render_pass, 1);
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+
+    fn prerender(&mut self, _context: &mut RenderContext){
+        let [left, top, width, height] = self.rect;
+        let bar_width = width / GRAPH_FRAMES as f32;
+        // Right aligned, so the graph fills in from the right as the first frames come in
+        let start = GRAPH_FRAMES - self.times.len();
+        for (i, (bar, time)) in self.bars.iter_mut().zip(self.times.iter()).enumerate(){
+            let bar_height = (time / GRAPH_SCALE).clamp(0.0, 1.0) * height;
+            let x = left + (start + i) as f32 * bar_width;
+            bar.write(TransformUniform::from_pixel_rect([x, top + height - bar_height, bar_width.max(1.0), bar_height.max(1.0)]));
+        }
+    }
+
+    fn is_hit_testable(&self) -> bool{
+        false
+    }
+
+    fn draw_bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+}
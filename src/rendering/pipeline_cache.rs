@@ -0,0 +1,117 @@
+//! This module contains the pipeline cache - the render pipelines the renderer and its components share, keyed by what
+//! they're built from. Creating a pipeline compiles its shaders, so components with their own pipeline (like `EguiArea`)
+//! look theirs up here instead of each creating one when they're attached.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, rc::Rc};
+
+/// # Blend
+///
+/// How a pipeline blends what it draws with what's already in the target, for its color and alpha channels
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Blend{
+    pub color: wgpu::BlendDescriptor,
+    pub alpha: wgpu::BlendDescriptor,
+}
+
+impl Blend{
+    /// Blending colors that aren't premultiplied by their alpha, which the renderer's own pipelines use
+    pub const ALPHA: Blend = Blend{
+        color: wgpu::BlendDescriptor{
+            src_factor: wgpu::BlendFactor::SrcAlpha,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        },
+        alpha: wgpu::BlendDescriptor{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        },
+    };
+
+    /// Blending colors that are premultiplied by their alpha
+    pub const PREMULTIPLIED: Blend = Blend{
+        color: wgpu::BlendDescriptor{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        },
+        alpha: wgpu::BlendDescriptor{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        },
+    };
+
+    /// Overwriting the target, without blending
+    pub const REPLACE: Blend = Blend{
+        color: wgpu::BlendDescriptor::REPLACE,
+        alpha: wgpu::BlendDescriptor::REPLACE,
+    };
+}
+
+/// # PipelineKey
+///
+/// What a pipeline in the `PipelineCache` is built from. `shader` names the shaders (and bind group layouts) it uses -
+/// give each of your own a name that can't clash with another crate's, eg, "my_crate::outline".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineKey{
+    pub shader: Cow<'static, str>,
+    pub blend: Blend,
+    pub format: wgpu::TextureFormat,
+    pub sample_count: u32,
+}
+
+impl PipelineKey{
+    /// The key of a pipeline drawing with `shader` into single sampled textures of `format`
+    pub fn new(shader: impl Into<Cow<'static, str>>, blend: Blend, format: wgpu::TextureFormat) -> Self{
+        Self{
+            shader: shader.into(),
+            blend,
+            format,
+            sample_count: 1,
+        }
+    }
+}
+
+/// # PipelineCache
+///
+/// The render pipelines created with a renderer, by key (see the module docs). The renderer owns one - get it with
+/// `Renderer::pipeline_cache`. Cloning it gives another handle to the same cache.
+#[derive(Clone, Default)]
+pub struct PipelineCache{
+    pipelines: Rc<RefCell<HashMap<PipelineKey, Rc<wgpu::RenderPipeline>>>>,
+}
+
+impl PipelineCache{
+    /// Create an empty cache
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// The pipeline for `key`, created with `create` if there isn't one yet. `create` should build the pipeline the
+    /// key describes - its blend, format and sample count.
+    pub fn get_or_create<F: FnOnce(&PipelineKey) -> wgpu::RenderPipeline>(&self, key: PipelineKey, create: F) -> Rc<wgpu::RenderPipeline>{
+        if let Some(pipeline) = self.get(&key){
+            return pipeline;
+        }
+        tracing::debug!(shader = %key.shader, format = ?key.format, "Creating a render pipeline");
+        let pipeline = Rc::new(create(&key));
+        self.pipelines.borrow_mut().insert(key, pipeline.clone());
+        pipeline
+    }
+
+    /// The pipeline for `key`, if it has been created
+    pub fn get(&self, key: &PipelineKey) -> Option<Rc<wgpu::RenderPipeline>>{
+        self.pipelines.borrow().get(key).cloned()
+    }
+
+    /// How many pipelines have been created
+    pub fn len(&self) -> usize{
+        self.pipelines.borrow().len()
+    }
+
+    /// Returns true if no pipelines have been created yet
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
@@ -8,58 +8,277 @@
 
 
 
-use wgpu::{BindGroup, Device, ShaderStage, util::StagingBelt};
+use std::{collections::HashMap, rc::Rc, sync::Arc, time::{Duration, Instant}};
 
-use crate::{components::{Label}, layout::{Layout}};
+use futures::{executor::{block_on, LocalPool}, task::SpawnExt};
+use wgpu::{BindGroup, ShaderStage, util::StagingBelt};
 
-use super::{UniformUtils};
+use crate::{animation::{AnimationId, Animator, Transition}, clipboard::Clipboard, color::Color, event::GuiEvent, layout::{Layout}, input::InputState, locale::Locale, plugin::PluginRegistry, sender::EventLoopMessage, state::AppState, theme::Theme};
+
+use winit::{event_loop::EventLoopWindowTarget, window::{CursorIcon, WindowId}};
+
+use super::{cursor::CursorSprite, focus_ring::FocusRing, glyph_warmup::{self, GlyphWarmup}, perf_overlay::PerfOverlay, BindGroupCache, Blend, CustomCursor, FrameStats, PipelineCache, PipelineKey, ResourceReport, MemoryKind, TextQueue, Tracked, SecondaryWindow, Snapshot, SnapshotDiff, surface_handle::SurfaceHandle, TransformPool, TransformSlot, UniformUtils, WindowBuilder};
 
 /// # Renderer
 ///
 /// The renderer struct holds all the data we need to render, and
 /// provides a higher level abstraction over wgpu-rs to render our GUI
 pub struct Renderer{
-    pub surface: wgpu::Surface,
-    pub device: wgpu::Device,
-    pub queue: wgpu::Queue,
+    // `None` for a renderer built on someone else's device (see `Renderer::from_raw`)
+    instance: Option<wgpu::Instance>,
+    /// The window surface, or `None` for a headless or embedded renderer
+    pub surface: Option<wgpu::Surface>,
+    /// The device, which may be shared with the app (see `Renderer::from_raw`)
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
     pub sc_desc: wgpu::SwapChainDescriptor,
-    swap_chain: wgpu::SwapChain,
+    swap_chain: Option<wgpu::SwapChain>,
+    // What a headless renderer draws into, in place of the swapchain
+    offscreen: Option<(Tracked<wgpu::Texture>, wgpu::TextureView)>,
     pub size: winit::dpi::PhysicalSize<u32>,
 
-    render_pipeline: wgpu::RenderPipeline,
+    render_pipeline: Rc<wgpu::RenderPipeline>,
+    texture_pipeline: Rc<wgpu::RenderPipeline>,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    // Draws a texture with a tint multiplied over it, for skinned buttons
+    pub(crate) skin_pipeline: Rc<wgpu::RenderPipeline>,
+    pub(crate) skin_bind_group_layout: wgpu::BindGroupLayout,
     staging_belt: StagingBelt,
-
-    glyph_brush: wgpu_glyph::GlyphBrush<()>,
+    // Drives the staging belt's recall future, so its chunks get reused rather than reallocated
+    local_pool: LocalPool,
+
+    glyph_brush: Tracked<wgpu_glyph::GlyphBrush<()>>,
+    glyph_cache_size: (u32, u32),
+    // Plugins, and the pipelines, fonts, textures and component factories they registered
+    pub(crate) plugins: PluginRegistry,
+    // The default appearance of the components
+    theme: Theme,
+    // The strings text keys are looked up in
+    locale: Locale,
 
     pub layout: Layout,    
 
     camera: Camera,
+
+    // The quad most components draw, shared rather than each making their own (see `quad_buffer`)
+    quad_buffer: Rc<Tracked<wgpu::Buffer>>,
+    // The buffer every transform's matrix is in, bound at a dynamic offset (see `transform_pool`)
+    transform_pool: TransformPool,
+    // The uniform bind group layouts shared by everything the renderer creates (see `bind_group_cache`)
+    bind_group_cache: BindGroupCache,
+    // The pipelines created with the renderer, by what they're built from (see `pipeline_cache`)
+    pipeline_cache: PipelineCache,
+    // Components that don't set their own tint get drawn with this one
+    default_tint: (Tracked<wgpu::Buffer>, BindGroup),
+
+    // Frame timing
+    last_frame: Instant,
+    delta_time: Duration,
+    prepass_time: Duration,
+    frame_count: u64,
+    profiling: bool,
+    frame_stats: FrameStats,
+    // Whether the window is transparent, in which case the clear color gets premultiplied
+    transparent: bool,
+
+    // The cursor shown when no component asks for another one, and the one the window currently shows
+    cursor_icon: CursorIcon,
+    applied_cursor: CursorIcon,
+    // An image drawn in place of the system cursor
+    custom_cursor: Option<CursorSprite>,
+    // The app's own drawing, done between the components and the text
+    custom_pass: Option<Box<dyn CustomPass>>,
+    cursor_hidden: bool,
+    // The keys and buttons held on the main window, and where the mouse is (if it's over the window)
+    input: InputState,
+    // The outline drawn around the component with keyboard focus
+    focus_ring: FocusRing,
+    // The debug overlay drawn over everything but the cursor, created the first time it's shown
+    perf_overlay: Option<PerfOverlay>,
+    // The glyphs still to be cached ahead of being drawn
+    glyph_warmup: GlyphWarmup,
+
+    // Extra windows, which share our device and queue
+    windows: HashMap<WindowId, SecondaryWindow>,
+    pending_windows: Vec<(WindowBuilder, Layout, Color)>,
+
+    clipboard: Clipboard,
+    // The app's own data, which callbacks can change
+    pub(crate) app_state: AppState,
+    // Tweens the main layout's components, stepped in the prepass
+    animator: Animator,
+    // Async tasks running in the background
+    #[cfg(feature = "tasks")]
+    pub(crate) tasks: crate::task::Tasks,
+    // Set by `request_exit`, the main loop exits once it sees it
+    exit_requested: bool,
+    // Set by `request_redraw`, used by `RedrawPolicy::OnRequest`
+    redraw_requested: bool,
 }
 
 
+// The size of each chunk the staging belt allocates
+const STAGING_BELT_CHUNK_SIZE: wgpu::BufferAddress = 512;
+
+/// The largest glyph cache wgpu_glyph will create, in pixels per side
+pub const MAX_GLYPH_CACHE_SIZE: u32 = 2048;
+
+/// # GlyphCachePolicy
+///
+/// Decides how the glyph cache (the texture atlas text is drawn from) is allocated.
+///
+/// Whatever the policy, the cache grows when the queued glyphs don't fit, up to `MAX_GLYPH_CACHE_SIZE`.
+/// Growing is done mid-frame and throws away every cached glyph, so apps with large or many font sizes
+/// should start with a big enough cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlyphCachePolicy{
+    /// Start at the configured size, and grow when glyphs overflow it (the default)
+    #[default]
+    Grow,
+    /// Start at `MAX_GLYPH_CACHE_SIZE`, so the cache never has to grow. Uses 4MB of VRAM.
+    Preallocate,
+}
+
+/// # RendererBuilder
+///
+/// Builds a renderer with non default settings, in the same way the `WindowBuilder` builds a window.
+///
+/// ## Usage
+///
+/// `RendererBuilder::new().set_glyph_cache_size((1024, 1024)).build(&window.window)` - `build` is async,
+/// so wrap it in `block_on` like `Renderer::new`.
+#[derive(Debug, Clone)]
+pub struct RendererBuilder{
+    glyph_cache_size: (u32, u32),
+    glyph_cache_policy: GlyphCachePolicy,
+    // The sizes and text to warm the glyph cache with, in the theme's font
+    glyph_warmup: Vec<(Vec<f32>, String)>,
+    glyph_warmup_budget: usize,
+}
+
+impl Default for RendererBuilder{
+    fn default() -> RendererBuilder{
+        Self{
+            // The same as wgpu_glyph's default
+            glyph_cache_size: (256, 256),
+            glyph_cache_policy: GlyphCachePolicy::default(),
+            glyph_warmup: Vec::new(),
+            glyph_warmup_budget: glyph_warmup::DEFAULT_BUDGET,
+        }
+    }
+}
+
+impl RendererBuilder{
+    /// Create a new renderer builder with default values
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Set the initial size of the glyph cache in pixels. Clamped between 1 and `MAX_GLYPH_CACHE_SIZE`.
+    pub fn set_glyph_cache_size(&mut self, size: (u32, u32)) -> &mut Self{
+        self.glyph_cache_size = (size.0.clamp(1, MAX_GLYPH_CACHE_SIZE), size.1.clamp(1, MAX_GLYPH_CACHE_SIZE));
+        self
+    }
+
+    /// Set how the glyph cache is allocated (see `GlyphCachePolicy`)
+    pub fn set_glyph_cache_policy(&mut self, policy: GlyphCachePolicy) -> &mut Self{
+        self.glyph_cache_policy = policy;
+        self
+    }
+
+    /// Warm the glyph cache with the characters of `text` at each of `sizes` (in pixels), in the theme's font, so the first
+    /// frame drawing them doesn't hitch. They're cached a few per frame from the first frame on (see
+    /// `Renderer::warm_glyph_cache`).
+    pub fn warm_glyphs(&mut self, sizes: &[f32], text: &str) -> &mut Self{
+        self.glyph_warmup.push((sizes.to_vec(), text.to_string()));
+        self
+    }
+
+    /// Set how many glyphs are warmed each frame (see `Renderer::set_glyph_warmup_budget`)
+    pub fn set_glyph_warmup_budget(&mut self, budget: usize) -> &mut Self{
+        self.glyph_warmup_budget = budget;
+        self
+    }
+
+    /// The size the glyph cache will start at, taking the policy into account
+    pub fn get_glyph_cache_size(&self) -> (u32, u32){
+        match self.glyph_cache_policy{
+            GlyphCachePolicy::Grow => self.glyph_cache_size,
+            GlyphCachePolicy::Preallocate => (MAX_GLYPH_CACHE_SIZE, MAX_GLYPH_CACHE_SIZE),
+        }
+    }
+
+    /// Build the renderer for `window`
+    pub async fn build(&self, window: &winit::window::Window) -> Renderer{
+        Renderer::build_with(window, self).await
+    }
+
+    /// Build a headless renderer (see `Renderer::new_headless`)
+    pub async fn build_headless(&self, size: (u32, u32)) -> Result<Renderer, &'static str>{
+        let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+        Renderer::build_with_surface(instance, None, winit::dpi::PhysicalSize::new(size.0, size.1), self).await
+    }
+
+    /// Build an embedded renderer on an existing device (see `Renderer::from_raw`)
+    pub fn build_from_raw(&self, device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>, target_format: wgpu::TextureFormat) -> Renderer{
+        Renderer::build_with_device(None, None, device, queue, target_format, winit::dpi::PhysicalSize::new(1, 1), self)
+    }
+}
+
 impl Renderer{
-    /// Create a new renderer, initializing all values
+    /// Create a new renderer, initializing all values. Use a `RendererBuilder` to change the defaults.
     pub async fn new(window: &winit::window::Window) -> Self{
-        // Set our size to the window size
-        let size = window.inner_size();
+        Renderer::build_with(window, &RendererBuilder::default()).await
+    }
 
+    /// Create a headless renderer, which draws into an offscreen texture of `size` pixels instead of a window.
+    ///
+    /// There's no winit window or surface, so it works on machines without a display (like CI), as long as there's a
+    /// graphics adapter. Use it to exercise components in tests - draw with `render` or `capture_snapshot`, then
+    /// compare snapshots. Fails if no adapter is available.
+    pub async fn new_headless(size: (u32, u32)) -> Result<Self, &'static str>{
+        RendererBuilder::default().build_headless(size).await
+    }
 
+    /// Create a renderer that draws with a device and queue the app already owns (eg, a game's), into textures of
+    /// `target_format`.
+    ///
+    /// An embedded renderer has no window or swapchain of its own - don't call `render`. Instead, call `prepass`, then
+    /// `render_into` with the app's encoder and frame, and submit the encoder as usual. The app forwards its window
+    /// events to `handle_event`, as there's no `GUI` running the event loop.
+    pub fn from_raw(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>, target_format: wgpu::TextureFormat) -> Self{
+        RendererBuilder::default().build_from_raw(device, queue, target_format)
+    }
+
+    async fn build_with(window: &winit::window::Window, builder: &RendererBuilder) -> Self{
         // Create a new instance with the best api (VULKAN, DX12/DX11 or METAL)
         let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
 
         // Create a surface (like a link to the winit window)
-        
-            let surface = unsafe { instance.create_surface(window) };
+        let surface = unsafe { instance.create_surface(&SurfaceHandle(window)) };
 
+        // Set our size to the window size
+        Renderer::build_with_surface(instance, Some(surface), window.inner_size(), builder).await.unwrap()
+    }
+
+    async fn build_with_surface(instance: wgpu::Instance, surface: Option<wgpu::Surface>, size: winit::dpi::PhysicalSize<u32>, builder: &RendererBuilder) -> Result<Self, &'static str>{
         // Create our adapter. We can select things like the power preference
         // and define the surface to draw to.
         // We want low power as we're not drawing games and the like.
-        let adapter = instance.request_adapter(
+        let adapter = match instance.request_adapter(
             &wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::LowPower,
-                compatible_surface: Some(&surface),
+                compatible_surface: surface.as_ref(),
             },
-        ).await.unwrap();
+        ).await{
+            Some(v) => v,
+            None => {
+                tracing::error!("No suitable graphics adapter found");
+                return Err("Error, no suitable graphics adapter found!");
+            }
+        };
+        let info = adapter.get_info();
+        tracing::info!(adapter = %info.name, backend = ?info.backend, device_type = ?info.device_type, "Using graphics adapter");
 
         // Request the device and queue. This can be thought of as a link to the GPU,
         // and the queue is like a pipe to render down (eg, compute or graphics).
@@ -70,79 +289,231 @@ impl Renderer{
                 shader_validation: true,
             },
             None, // Trace path
-        ).await.unwrap();
+        ).await.map_err(|e| {
+            tracing::error!(error = %e, "Failed to create the graphics device");
+            "Error, failed to create the graphics device!"
+        })?;
+
+        Ok(Renderer::build_with_device(Some(instance), surface, Arc::new(device), Arc::new(queue), wgpu::TextureFormat::Bgra8UnormSrgb, size, builder))
+    }
 
+    fn build_with_device(instance: Option<wgpu::Instance>, surface: Option<wgpu::Surface>, device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>,
+        format: wgpu::TextureFormat, size: winit::dpi::PhysicalSize<u32>, builder: &RendererBuilder) -> Self{
+        let _span = tracing::info_span!("renderer_setup", width = size.width, height = size.height, ?format, headless = surface.is_none()).entered();
         // We define what a swapchain should be - eg, its usage, format (RGB, BGR)
         // size, width and present mode - vsync on or off for example.
         let sc_desc = wgpu::SwapChainDescriptor {
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            format,
             width: size.width,
             height: size.height,
             present_mode: wgpu::PresentMode::Fifo,
         };
 
         // create a swapchain using the swapchain description and link it to the surface
-        let swap_chain = device.create_swap_chain(&surface, &sc_desc);
-
-        let render_pipeline = Renderer::create_render_pipeline(&device);
+        // (headless renderers draw into a texture instead, and embedded ones into whatever the app gives them)
+        let swap_chain = surface.as_ref().map(|v| device.create_swap_chain(v, &sc_desc));
+        let offscreen = match (&surface, &instance){
+            (None, Some(_)) => Some(create_offscreen_target(&device, &sc_desc)),
+            _ => None,
+        };
 
-        let staging_belt = StagingBelt::new(512);
+        let pipeline_cache = PipelineCache::new();
+        let render_pipeline = pipeline_cache.get_or_create(PipelineKey::new("rusty_gui::tint", Blend::ALPHA, format), |key| Renderer::create_render_pipeline(&device, key.format));
+        let texture_bind_group_layout = Renderer::create_texture_bind_group_layout(&device);
+        let texture_pipeline = pipeline_cache.get_or_create(PipelineKey::new("rusty_gui::texture", Blend::ALPHA, format), |key| Renderer::create_texture_pipeline(&device, &texture_bind_group_layout, key.format));
+        let skin_bind_group_layout = Renderer::create_skin_bind_group_layout(&device);
+        let skin_pipeline = pipeline_cache.get_or_create(PipelineKey::new("rusty_gui::skin", Blend::ALPHA, format), |key| Renderer::create_skin_pipeline(&device, &skin_bind_group_layout, key.format));
 
-        
-        let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!("../../fonts/FingerPaint-Regular.ttf"))
-        .expect("Load font");
+        let staging_belt = StagingBelt::new(STAGING_BELT_CHUNK_SIZE);
+        let local_pool = LocalPool::new();
 
-        let glyph_brush = wgpu_glyph::GlyphBrushBuilder::using_font(font)
-            .build(&device, wgpu::TextureFormat::Bgra8UnormSrgb);
+        let theme = Theme::new();
+        let glyph_brush = create_glyph_brush(&device, &theme, &[], builder.get_glyph_cache_size(), format);
 
         let layout = Layout::new();
 
-        let camera = Camera::new(0.1, 750.0, &device, &sc_desc);
+        let bind_group_cache = BindGroupCache::new(device.clone());
+        let camera = Camera::new((sc_desc.width, sc_desc.height), &bind_group_cache);
+
+        let (tint_buffer, tint_bind_group, _) = bind_group_cache.create_uniform(ShaderStage::FRAGMENT, 0, &Color::RED.to_linear(), "Default tint");
+        let quad_buffer = Rc::new(crate::components::base_components::create_buffers(&device));
+        let transform_pool = TransformPool::new(device.clone(), queue.clone());
+        let focus_ring = FocusRing::new(&bind_group_cache, &transform_pool, quad_buffer.clone());
+        let mut glyph_warmup = GlyphWarmup::new();
+        glyph_warmup.set_budget(builder.glyph_warmup_budget);
+        for (sizes, text) in builder.glyph_warmup.iter(){
+            glyph_warmup.register(wgpu_glyph::FontId(0), sizes, text);
+        }
 
         Self{
+            instance,
             surface,
             device,
             queue,
             sc_desc,
             swap_chain,
+            offscreen,
             size,
 
             render_pipeline,
+            texture_pipeline,
+            texture_bind_group_layout,
+            skin_pipeline,
+            skin_bind_group_layout,
             staging_belt,
+            local_pool,
             glyph_brush,
+            glyph_cache_size: builder.get_glyph_cache_size(),
+            plugins: PluginRegistry::default(),
+            theme,
+            locale: Locale::default(),
             layout,
-            camera
+            camera,
+            default_tint: (tint_buffer, tint_bind_group),
+
+            last_frame: Instant::now(),
+            delta_time: Duration::from_secs(0),
+            prepass_time: Duration::from_secs(0),
+            frame_count: 0,
+            profiling: false,
+            frame_stats: FrameStats{
+                glyph_cache_size: builder.get_glyph_cache_size(),
+                ..FrameStats::default()
+            },
+            transparent: false,
+
+            cursor_icon: CursorIcon::Default,
+            applied_cursor: CursorIcon::Default,
+            custom_cursor: None,
+            custom_pass: None,
+            cursor_hidden: false,
+            input: InputState::new(),
+            quad_buffer,
+            transform_pool,
+            bind_group_cache,
+            pipeline_cache,
+            focus_ring,
+            perf_overlay: None,
+            glyph_warmup,
+
+            windows: HashMap::new(),
+            pending_windows: Vec::new(),
+
+            clipboard: Clipboard::new(),
+            app_state: AppState::new(),
+            animator: Animator::new(),
+            #[cfg(feature = "tasks")]
+            tasks: crate::task::Tasks::new(),
+            exit_requested: false,
+            redraw_requested: true,
         }
     }
 
-    /// Create a render pipeline from default values, taking in a reference to the device
-    pub fn create_render_pipeline(device: &wgpu::Device) -> wgpu::RenderPipeline{
+    /// Create a render pipeline from default values, taking in a reference to the device and the format of the
+    /// textures it draws into
+    pub fn create_render_pipeline(device: &wgpu::Device, format: wgpu::TextureFormat) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
+        let tint_layout = UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::FRAGMENT, false, None, "Tint bind group layout");
+
+        Renderer::create_pipeline_with_fragment(device, &fs_module, &tint_layout, format, "Render Pipeline")
+    }
+
+    /// Create a render pipeline that draws a texture onto a quad, instead of a flat tint
+    pub fn create_texture_pipeline(device: &wgpu::Device, texture_bind_group_layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/texture.frag.spv"));
+
+        Renderer::create_pipeline_with_fragment(device, &fs_module, texture_bind_group_layout, format, "Texture Pipeline")
+    }
+
+    /// Create a render pipeline that draws a texture onto a quad, with a tint multiplied over it
+    pub fn create_skin_pipeline(device: &wgpu::Device, skin_bind_group_layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/skin.frag.spv"));
+
+        Renderer::create_pipeline_with_fragment(device, &fs_module, skin_bind_group_layout, format, "Skin Pipeline")
+    }
+
+    /// Create the bind group layout used by the texture pipeline - a texture at binding 0, and its sampler at binding 1
+    pub fn create_texture_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout{
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &Self::texture_layout_entries(),
+            label: Some("Texture bind group layout"),
+        })
+    }
+
+    /// Create the bind group layout used by the skin pipeline - the same as the texture pipeline's, with a tint at binding 2
+    pub fn create_skin_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout{
+        let [texture, sampler] = Self::texture_layout_entries();
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                texture,
+                sampler,
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer {
+                        dynamic: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Skin bind group layout"),
+        })
+    }
+
+    // A texture at binding 0, and its sampler at binding 1
+    fn texture_layout_entries() -> [wgpu::BindGroupLayoutEntry; 2]{
+        [
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::SampledTexture {
+                    multisampled: false,
+                    dimension: wgpu::TextureViewDimension::D2,
+                    component_type: wgpu::TextureComponentType::Float,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::Sampler {
+                    comparison: false,
+                },
+                count: None,
+            },
+        ]
+    }
+
+    /// Shared pipeline setup. Every pipeline uses the same vertex shader, camera (set 0) and transform (set 1, a
+    /// slot of the `TransformPool` bound with a dynamic offset), and only differs in the fragment shader and what it reads from set 2.
+    fn create_pipeline_with_fragment(device: &wgpu::Device, fs_module: &wgpu::ShaderModule, fragment_layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat, label: &str) -> wgpu::RenderPipeline{
         // Define our pipeline layout. This is where we define bind_group_layouts
         let render_pipeline_layout =
        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
            label: Some("Render Pipeline Layout"),
            bind_group_layouts: &[
                &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, false, None, "Some bidn group layout"),
-               &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, false, None, "Some bidn group layout")
+               &TransformPool::create_bind_group_layout(device),
+               fragment_layout,
            ],
            push_constant_ranges: &[],
         });
 
         // Create our shader modules
         let vs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.vert.spv"));
-        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
 
         // Create the pipeline. We define it - we're rendering a GUI, so it doesn't matter much
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
+            label: Some(label),
             layout: Some(&render_pipeline_layout),
             vertex_stage: wgpu::ProgrammableStageDescriptor {
                 module: &vs_module,
                 entry_point: "main", // 1.
             },
             fragment_stage: Some(wgpu::ProgrammableStageDescriptor { // 2.
-                module: &fs_module,
+                module: fs_module,
                 entry_point: "main",
             }),
             rasterization_state: Some(
@@ -157,17 +528,9 @@ impl Renderer{
             ),
             color_states: &[
                 wgpu::ColorStateDescriptor {
-                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    color_blend: wgpu::BlendDescriptor {
-                        src_factor: wgpu::BlendFactor::SrcAlpha,
-                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                        operation: wgpu::BlendOperation::Add
-                    },
-                    alpha_blend: wgpu::BlendDescriptor {
-                        src_factor: wgpu::BlendFactor::One,
-                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                        operation: wgpu::BlendOperation::Add
-                    },
+                    format,
+                    color_blend: Blend::ALPHA.color,
+                    alpha_blend: Blend::ALPHA.alpha,
                     //color_blend: wgpu::BlendDescriptor::REPLACE,
                     //alpha_blend: wgpu::BlendDescriptor::REPLACE,
                     write_mask: wgpu::ColorWrite::ALL
@@ -190,106 +553,1315 @@ impl Renderer{
 
     /// This function gets called upon a resize, as we need to recreate the swapchain
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        let _span = tracing::debug_span!("resize", width = new_size.width, height = new_size.height).entered();
         self.size = new_size;
+        self.redraw_requested = true;
         if new_size.width > 0 && new_size.height > 0{
             self.sc_desc.width = new_size.width;
             self.sc_desc.height = new_size.height;
-            self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+            match (&self.surface, &self.instance){
+                (Some(surface), _) => self.swap_chain = Some(self.device.create_swap_chain(surface, &self.sc_desc)),
+                (None, Some(_)) => self.offscreen = Some(create_offscreen_target(&self.device, &self.sc_desc)),
+                // Embedded renderers draw into the app's textures, so there's nothing to recreate
+                (None, None) => {},
+            }
         }
     }
 
     /// This should run BEFORE we render. This lets us set up last minute values
     /// and update our layout before we render
     pub fn prepass(&mut self){
-        let mut text_child_components = Vec::<(usize, bool, [f32; 2])>::new();
-        let components = &self.layout.components;
-        for i in 0..components.len(){
-            let comp = &components[i];
-            if let Some(id) = comp.get_text_id(){
-                text_child_components.push((id, comp.is_enabled(), comp.get_pos()));
+        let _span = tracing::trace_span!("prepass", components = self.layout.components.len()).entered();
+        // Track how long it has been since the last frame, so components can animate at a consistent speed
+        let now = Instant::now();
+        self.delta_time = now - self.last_frame;
+        self.last_frame = now;
+        self.frame_stats.frame_time = self.delta_time;
+
+        // Attach any new components, with the layout taken out so they can borrow the renderer
+        if self.layout.needs_attach(){
+            let mut layout = std::mem::take(&mut self.layout);
+            layout.attach(self);
+            self.layout = layout;
+        }
+
+        // Effects update the values components are bound to, so they run before the components update
+        crate::signal::flush_effects();
+        self.animator.step(self.delta_time, &mut self.layout, &mut self.app_state);
+        update_layout(&mut self.layout, self.delta_time);
+        // Cached offscreen with this frame's text
+        self.glyph_warmup.queue_next(&mut self.glyph_brush);
+
+        self.prepass_time = now.elapsed();
+    }
+
+    /// Render a single frame 
+    pub fn render(&mut self, clear_color: Color){
+        let _span = tracing::trace_span!("render", frame = self.frame_count).entered();
+        let clear_color = if self.transparent { clear_color.premultiplied() } else { clear_color };
+        self.frame_stats.reset_counters();
+        let mut cpu_time = self.prepass_time;
+        // Anything changed from here on (eg, in `prerender`) is drawn next frame
+        self.redraw_requested = false;
+        self.layout.take_dirty();
+
+        if self.profiling{
+            self.render_profiled(clear_color);
+        }else{
+            let target = self.acquire_target();
+
+            let start = Instant::now();
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });   
+
+            let (width, height) = (self.sc_desc.width, self.sc_desc.height);
+            self.encode_frame(&mut encoder, target.view(), width, height, clear_color);
+            self.draw_focus_ring(&mut encoder, target.view(), width, height, None);
+            self.draw_perf_overlay(&mut encoder, target.view(), None);
+            self.draw_custom_cursor(&mut encoder, target.view());
+
+            self.staging_belt.finish();
+            // Upload the transforms components wrote in `prerender`
+            self.transform_pool.flush();
+            
+            // submit will accept anything that implements IntoIter
+            self.queue.submit(std::iter::once(encoder.finish()));
+            cpu_time += start.elapsed();
+            self.release_target(target);
+        }
+
+        self.recall_staging_belt();
+        self.frame_count += 1;
+        self.frame_stats.cpu_time = cpu_time;
+    }
+
+    /// Render a single frame into `view`, a texture of `size` pixels, instead of the window - eg, so another renderer
+    /// can composite the GUI as an overlay, or stream it elsewhere. The texture needs the renderer's format (see `sc_desc`).
+    ///
+    /// The layout is drawn as if the window was `size` pixels, clearing the texture with `clear_color` first, or drawing
+    /// over what's already there if it's `None`. The frame is submitted before this returns.
+    pub fn render_to_view(&mut self, view: &wgpu::TextureView, size: (u32, u32), clear_color: Option<Color>){
+        let _span = tracing::trace_span!("render_to_view", width = size.0, height = size.1).entered();
+        if size.0 == 0 || size.1 == 0{
+            return;
+        }
+        self.frame_stats.reset_counters();
+        let start = Instant::now();
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("View Encoder"),
+        });
+        self.camera.resize(size, &self.queue);
+        {
+            let (mut context, layout) = self.split_context(&mut encoder);
+            let camera = context.camera;
+            context.draw_layout_with(layout, view, camera, clear_color);
+        }
+        self.draw_focus_ring(&mut encoder, view, size.0, size.1, None);
+        self.draw_perf_overlay(&mut encoder, view, None);
+        self.staging_belt.finish();
+        self.transform_pool.flush();
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        self.recall_staging_belt();
+        self.frame_count += 1;
+        self.frame_stats.cpu_time = self.prepass_time + start.elapsed();
+    }
+
+    /// Tell the renderer the window has a transparent background, so the clear color gets premultiplied by its alpha.
+    /// `GUI::new` sets this from the window. The frames are still presented opaque (see `WindowBuilder::set_transparent`).
+    pub fn set_transparent(&mut self, transparent: bool){
+        self.transparent = transparent;
+    }
+
+    /// Returns true if the renderer is drawing to a transparent window
+    pub fn is_transparent(&self) -> bool{
+        self.transparent
+    }
+
+    /// Enable or disable GPU profiling. While enabled, every frame waits on the GPU after the component pass
+    /// and the text pass, and the timings are available through `frame_stats`.
+    ///
+    /// This slows rendering down, so only turn it on while you're looking for a bottleneck.
+    pub fn set_profiling(&mut self, enabled: bool){
+        self.profiling = enabled;
+        if !enabled{
+            self.frame_stats.gpu_component_time = None;
+            self.frame_stats.gpu_text_time = None;
+        }
+    }
+
+    /// Returns true if GPU profiling is enabled
+    pub fn is_profiling(&self) -> bool{
+        self.profiling
+    }
+
+    /// Statistics about the last rendered frame
+    pub fn frame_stats(&self) -> &FrameStats{
+        &self.frame_stats
+    }
+
+    /// Set the cursor shown when the mouse isn't over a component with its own hover cursor
+    pub fn set_cursor_icon(&mut self, cursor: CursorIcon){
+        self.cursor_icon = cursor;
+    }
+
+    /// Get the cursor shown when the mouse isn't over a component with its own hover cursor
+    pub fn get_cursor_icon(&self) -> CursorIcon{
+        self.cursor_icon
+    }
+
+    /// Draw with wgpu directly in every frame of the main window (or go back to only drawing the layout with `None`).
+    /// The pass is called after the components are drawn and before the text, in the same render pass, so the app can add
+    /// its own draws (eg, a 3D preview or a chart) without forking the renderer.
+    pub fn set_custom_pass(&mut self, pass: Option<Box<dyn CustomPass>>){
+        self.custom_pass = pass;
+    }
+
+    /// Replace the system cursor with an image (or go back to the system cursor with `None`).
+    /// The image is drawn by the renderer, on top of everything else, while the mouse is over the main window.
+    pub fn set_custom_cursor(&mut self, cursor: Option<CustomCursor>){
+        self.custom_cursor = cursor.map(|cursor| CursorSprite::new(cursor, &self.device, &self.queue, &self.texture_bind_group_layout, &self.transform_pool, self.quad_buffer.clone()));
+    }
+
+    /// The vertex buffer of a unit quad (see `QUAD`), shared by every component that draws one. Keep a clone rather
+    /// than creating a buffer per component.
+    pub fn quad_buffer(&self) -> Rc<Tracked<wgpu::Buffer>>{
+        self.quad_buffer.clone()
+    }
+
+    /// The pool every transform takes its slot from (see `TransformPool`), to create transforms with
+    pub fn transform_pool(&self) -> &TransformPool{
+        &self.transform_pool
+    }
+
+    /// The bind group layouts shared by the renderer and its components (see `BindGroupCache`), to create uniforms with
+    pub fn bind_group_cache(&self) -> &BindGroupCache{
+        &self.bind_group_cache
+    }
+
+    /// The render pipelines shared by the renderer and its components (see `PipelineCache`). Components with their own
+    /// pipeline should get it from here, so it's only created once however many of them there are.
+    pub fn pipeline_cache(&self) -> &PipelineCache{
+        &self.pipeline_cache
+    }
+
+    /// The current theme. Components created with the renderer start out using it.
+    pub fn theme(&self) -> &Theme{
+        &self.theme
+    }
+
+    /// Change the theme, restyling every component in the main layout and the secondary windows (see `Component::apply_theme`).
+    /// The focus ring takes the theme's color, and text is drawn with the theme's font from the next frame.
+    pub fn set_theme(&mut self, theme: Theme){
+        self.glyph_brush = create_glyph_brush(&self.device, &theme, &self.plugin_fonts(), self.glyph_cache_size, self.sc_desc.format);
+        self.glyph_warmup.restart();
+        self.focus_ring.set_color(&self.queue, Some(theme.colors.focus_ring));
+        self.layout.apply_theme(&theme);
+        for window in self.windows.values_mut(){
+            window.layout.apply_theme(&theme);
+        }
+        self.theme = theme;
+    }
+
+    // The fonts plugins registered, which follow the theme's font in the glyph brush
+    fn plugin_fonts(&self) -> Vec<wgpu_glyph::ab_glyph::FontArc>{
+        self.plugins.fonts().cloned().collect()
+    }
+
+    // Add a font to the glyph brush for a plugin (see `PluginContext::add_font`)
+    pub(crate) fn add_glyph_font(&mut self, font: wgpu_glyph::ab_glyph::FontArc) -> wgpu_glyph::FontId{
+        self.glyph_brush.add_font(font)
+    }
+
+    // Create the glyph brush again with only the theme's font and the registered plugin fonts
+    pub(crate) fn rebuild_glyph_brush(&mut self){
+        self.glyph_brush = create_glyph_brush(&self.device, &self.theme, &self.plugin_fonts(), self.glyph_cache_size, self.sc_desc.format);
+        self.glyph_warmup.restart();
+    }
+
+    /// Warm the glyph cache with the characters of `text` at each of `sizes` (in pixels) in `font` - the theme's font is
+    /// `FontId(0)`, and plugin fonts follow it. The glyphs are rasterized and uploaded a few per frame (see
+    /// `set_glyph_warmup_budget`), laid out offscreen with the frame's text, so a large font size doesn't make the first
+    /// frame using it hitch. They're warmed again whenever the glyph cache is rebuilt (eg, when the theme changes).
+    ///
+    /// Glyphs are cached at the sub-pixel offsets they land on when laid out, so text at other offsets may still need
+    /// a few glyphs rasterized - but far fewer, and the cache has already grown to fit them.
+    pub fn warm_glyph_cache(&mut self, font: wgpu_glyph::FontId, sizes: &[f32], text: &str){
+        self.glyph_warmup.register(font, sizes, text);
+        self.redraw_requested = true;
+    }
+
+    /// Set how many glyphs are warmed each frame - more finishes sooner, but makes each frame longer. 0 pauses the warm-up.
+    pub fn set_glyph_warmup_budget(&mut self, budget: usize){
+        self.glyph_warmup.set_budget(budget);
+    }
+
+    /// Get how many glyphs are warmed each frame
+    pub fn get_glyph_warmup_budget(&self) -> usize{
+        self.glyph_warmup.budget()
+    }
+
+    /// The number of characters still waiting to be warmed (see `warm_glyph_cache`)
+    pub fn pending_warm_glyphs(&self) -> usize{
+        self.glyph_warmup.pending()
+    }
+
+    /// The locale text keys are looked up in (see `locale`). It has no strings until one is set, so keys are shown as they are.
+    pub fn locale(&self) -> &Locale{
+        &self.locale
+    }
+
+    /// Change the locale, so every component showing a text key (in the main layout and the secondary windows) looks it up
+    /// again before it's next drawn (see `Component::apply_locale`)
+    pub fn set_locale(&mut self, locale: Locale){
+        self.layout.apply_locale(&locale);
+        for window in self.windows.values_mut(){
+            window.layout.apply_locale(&locale);
+        }
+        self.locale = locale;
+    }
+
+    /// Set the color of the ring drawn around the component with keyboard focus, or hide it with `None`
+    pub fn set_focus_ring(&mut self, color: Option<Color>){
+        self.focus_ring.set_color(&self.queue, color);
+    }
+
+    /// Get the color of the focus ring, or `None` if it's hidden
+    pub fn get_focus_ring(&self) -> Option<Color>{
+        self.focus_ring.color()
+    }
+
+    /// Set how thick the focus ring is, in pixels (it's drawn just outside the focused component)
+    pub fn set_focus_ring_width(&mut self, width: f32){
+        self.focus_ring.set_width(width);
+    }
+
+    /// Get how thick the focus ring is, in pixels
+    pub fn get_focus_ring_width(&self) -> f32{
+        self.focus_ring.width()
+    }
+
+    /// Show or hide the performance overlay - a panel in the top left corner with the frame rate, a graph of recent frame
+    /// times, the draw calls, component counts and how full the glyph cache is (see `FrameStats`). It's drawn over
+    /// everything but the custom cursor. While it's shown, the glyph cache space used is counted each frame.
+    pub fn set_perf_overlay(&mut self, visible: bool){
+        if visible && self.perf_overlay.is_none(){
+            self.perf_overlay = Some(PerfOverlay::new(self));
+        }else if !visible{
+            self.perf_overlay = None;
+        }
+        self.frame_stats.glyph_cache_used = if visible { Some(0) } else { None };
+        self.redraw_requested = true;
+    }
+
+    /// Whether the performance overlay is shown
+    pub fn perf_overlay_visible(&self) -> bool{
+        self.perf_overlay.is_some()
+    }
+
+    /// Show the performance overlay if it's hidden, or hide it if it's shown (see `set_perf_overlay`)
+    pub fn toggle_perf_overlay(&mut self){
+        self.set_perf_overlay(!self.perf_overlay_visible());
+    }
+
+    /// Get the custom cursor, if one is set
+    pub fn get_custom_cursor(&self) -> Option<&CustomCursor>{
+        self.custom_cursor.as_ref().map(|sprite| &sprite.cursor)
+    }
+
+    /// Keep track of the main window's input, so the custom cursor can follow the mouse and callbacks can ask what's held
+    pub(crate) fn track_input(&mut self, event: &GuiEvent){
+        self.input.update(event);
+    }
+
+    /// The keys and mouse buttons held on the main window, and where the cursor is (see `InputState`)
+    pub fn input(&self) -> &InputState{
+        &self.input
+    }
+
+    /// Show the cursor the hovered component asks for (or the default one) on the main window.
+    /// Run once per frame by the main loop - the window is only touched when the cursor changes.
+    pub fn update_cursor(&mut self, window: &winit::window::Window){
+        // The system cursor is hidden while we draw a custom one
+        let hidden = self.custom_cursor.is_some();
+        if hidden != self.cursor_hidden{
+            window.set_cursor_visible(!hidden);
+            self.cursor_hidden = hidden;
+        }
+
+        let cursor = self.layout.hover_cursor().unwrap_or(self.cursor_icon);
+        if cursor != self.applied_cursor{
+            window.set_cursor_icon(cursor);
+            self.applied_cursor = cursor;
+        }
+    }
+
+    /// Add an already built winit window as a secondary window, drawing `layout`. The window shares this renderer's device.
+    /// Returns the ID of the window.
+    pub fn add_window(&mut self, window: winit::window::Window, layout: Layout, clear_color: Color) -> WindowId{
+        let instance = self.instance.as_ref().expect("Error, embedded renderers can't open windows!");
+        let window = SecondaryWindow::new(instance, &self.device, &self.bind_group_cache, window, layout, clear_color);
+        let id = window.id();
+        self.windows.insert(id, window);
+        id
+    }
+
+    /// Open a new secondary window while the main loop is running (eg, from a button callback or the event handler).
+    /// Windows can only be created by the event loop, so the window is built at the start of the next event.
+    /// If building it fails, the error is logged (see the `tracing` crate) and the window isn't opened.
+    pub fn open_window(&mut self, builder: WindowBuilder, layout: Layout, clear_color: Color){
+        self.pending_windows.push((builder, layout, clear_color));
+    }
+
+    /// Close a secondary window, returning it. Dropping the returned window destroys it.
+    pub fn close_window(&mut self, id: WindowId) -> Option<SecondaryWindow>{
+        self.windows.remove(&id)
+    }
+
+    /// The IDs of every open secondary window
+    pub fn window_ids(&self) -> Vec<WindowId>{
+        self.windows.keys().copied().collect()
+    }
+
+    /// Borrow a secondary window
+    pub fn borrow_window(&self, id: WindowId) -> Option<&SecondaryWindow>{
+        self.windows.get(&id)
+    }
+
+    /// Borrow a secondary window mutably (eg, to change its layout)
+    pub fn borrow_window_mut(&mut self, id: WindowId) -> Option<&mut SecondaryWindow>{
+        self.windows.get_mut(&id)
+    }
+
+    /// Build any windows queued with `open_window`
+    pub(crate) fn create_pending_windows(&mut self, target: &EventLoopWindowTarget<EventLoopMessage>){
+        for (builder, layout, clear_color) in std::mem::take(&mut self.pending_windows){
+            // A window the platform refused (or a monitor that's gone) shouldn't take the whole app down, so the request is dropped
+            match builder.build_secondary(target){
+                Ok(window) => { self.add_window(window, layout, clear_color); }
+                Err(e) => tracing::error!(error = %e, "Failed to open a window"),
             }
         }
-        let components = &self.layout.event_components;
-        for i in 0..components.len() {
-            let comp = &components[i];
-            if let Some(id) = comp.get_text_id(){
-                text_child_components.push((id, comp.is_enabled(), comp.get_pos()));
+    }
+
+    /// Ask every secondary window to redraw, or only the ones whose layout is dirty if `only_dirty` is set
+    pub(crate) fn request_window_redraws(&self, only_dirty: bool){
+        for window in self.windows.values(){
+            if !window.is_minimized() && (!only_dirty || window.layout.is_dirty()){
+                window.window.request_redraw();
             }
         }
+    }
 
-        for (id, enabled, pos) in text_child_components.iter(){
-            let text = self.layout.borrow_text_component_as_type_mut::<Label>(*id).unwrap();
-            text.set_pos(*pos, (self.sc_desc.width, self.sc_desc.height));
-            if *enabled{
-                text.enable();
-            }else{
-                text.disable();
+    /// Send an event to every secondary window's layout (eg, one posted with an `EventSender`)
+    pub(crate) fn dispatch_to_windows(&mut self, event: &GuiEvent){
+        for window in self.windows.values_mut(){
+            if !window.is_minimized(){
+                window.layout.dispatch_event(event, &window.window, &mut self.app_state);
             }
         }
     }
 
-    /// Render a single frame 
-    pub fn render(&mut self, clear_color: wgpu::Color){
-        let frame = self.swap_chain.get_current_frame().unwrap().output;
+    /// Send any long presses that are due to the secondary windows
+    pub(crate) fn poll_window_gestures(&mut self){
+        for window in self.windows.values_mut(){
+            if let Some(gesture) = window.translator.poll_gestures(){
+                if !window.is_minimized(){
+                    window.layout.dispatch_event(&gesture, &window.window, &mut self.app_state);
+                }
+            }
+        }
+    }
 
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Render Encoder"),
-        });   
+    /// Handle an event meant for a secondary window - passing it to the window's components, and handling
+    /// resizing and closing. Returns false if the event isn't for a secondary window.
+    pub(crate) fn handle_window_event(&mut self, event: &winit::event::Event<EventLoopMessage>) -> bool{
+        let (id, window_event) = match event{
+            winit::event::Event::WindowEvent{ window_id, event } if self.windows.contains_key(window_id) => (*window_id, event),
+            _ => return false,
+        };
 
-        self.camera.update(&self.sc_desc);
+        let window = self.windows.get_mut(&id).unwrap();
+        if let Some(event) = window.translator.translate(window_event){
+            let click = window.translator.click_event(&event);
+            let gestures = window.translator.gesture_events(&event);
+            if !window.is_minimized(){
+                window.layout.dispatch_event(&event, &window.window, &mut self.app_state);
+                for event in click.iter().chain(gestures.iter()){
+                    window.layout.dispatch_event(event, &window.window, &mut self.app_state);
+                }
+            }
+        }
 
-        {
-            // Pre pass
-            // Main pass - Render all our shaders and objects to the screen
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[
-                    wgpu::RenderPassColorAttachmentDescriptor {
-                        attachment: &frame.view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(clear_color),
-                            store: true,
-                        }
-                    },
-                ],
-                depth_stencil_attachment: None,
+        match window_event{
+            winit::event::WindowEvent::CloseRequested => {
+                self.windows.remove(&id);
+            }
+            winit::event::WindowEvent::Resized(physical_size) => {
+                window.resize(&self.device, *physical_size);
+            }
+            winit::event::WindowEvent::ScaleFactorChanged{ new_inner_size, .. } => {
+                window.resize(&self.device, **new_inner_size);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Update and draw a secondary window's layout
+    pub fn render_window(&mut self, id: WindowId){
+        let _span = tracing::trace_span!("render_window", window = ?id).entered();
+        // Take the window out while drawing, so we can borrow the renderer and the window's layout at the same time
+        let mut window = match self.windows.remove(&id){
+            Some(v) => v,
+            None => return,
+        };
+
+        if !window.is_minimized(){
+            let now = Instant::now();
+            let delta_time = now - window.last_frame;
+            window.last_frame = now;
+            window.camera.resize(window.size(), &self.queue);
+            window.layout.attach(self);
+            update_layout(&mut window.layout, delta_time);
+            window.layout.take_dirty();
+
+            let cursor = window.layout.hover_cursor().unwrap_or(self.cursor_icon);
+            if cursor != window.applied_cursor{
+                window.window.set_cursor_icon(cursor);
+                window.applied_cursor = cursor;
+            }
+
+            let frame = window.swap_chain.get_current_frame().unwrap().output;
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Window Render Encoder"),
             });
+            {
+                let (mut context, _) = self.split_context(&mut encoder);
+                context.draw_layout(&mut window.layout, &frame.view, &window.camera, window.clear_color);
+            }
+            self.staging_belt.finish();
+            self.transform_pool.flush();
+            self.queue.submit(std::iter::once(encoder.finish()));
+            self.recall_staging_belt();
+        }
+
+        self.windows.insert(id, window);
+    }
+
+    /// Ask the main loop to exit after the current event. Unlike closing the window, this can't be vetoed by the close requested callback.
+    pub fn request_exit(&mut self){
+        self.exit_requested = true;
+    }
+
+    /// Ask for the main window to be redrawn on the next loop iteration. Changes made through the layout redraw it already
+    /// (see `Layout::is_dirty`), so this is only needed with `RedrawPolicy::OnRequest` when something else changed what's
+    /// drawn - for example, a component changed straight through `Layout::components`.
+    pub fn request_redraw(&mut self){
+        self.redraw_requested = true;
+    }
 
-            render_pass.set_pipeline(&self.render_pipeline);
+    /// Returns true if the main window has something new to draw - a redraw was requested (see `request_redraw`), or the
+    /// layout is dirty. Both are cleared when a frame is drawn. It's also true while the glyph cache is being warmed
+    /// (see `warm_glyph_cache`), which is done a few glyphs a frame.
+    pub fn needs_redraw(&self) -> bool{
+        self.redraw_requested || self.layout.is_dirty() || self.glyph_warmup.is_warming()
+    }
 
+    /// Returns true if `request_exit` has been called
+    pub fn is_exit_requested(&self) -> bool{
+        self.exit_requested
+    }
 
+    /// The system clipboard. Clone the handle to keep it (eg, in a component) - all clones share one connection.
+    pub fn clipboard(&self) -> &Clipboard{
+        &self.clipboard
+    }
 
-            {   
-                let components = &self.layout.components;
-                for i in 0..components.len(){
-                    let comp = &components[i];
-                    render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
-                    comp.render(&mut render_pass);
-                }
+    /// The app's own data (see `GUI::set_app_state`)
+    pub fn app_state(&self) -> &AppState{
+        &self.app_state
+    }
+
+    /// Mutably borrow the app's own data, eg, to change it from the event handler
+    pub fn app_state_mut(&mut self) -> &mut AppState{
+        &mut self.app_state
+    }
+
+    /// The animations running on the main layout's components
+    pub fn animator(&self) -> &Animator{
+        &self.animator
+    }
+
+    /// Mutably borrow the animator, to start or cancel animations (see `Animator::start`)
+    pub fn animator_mut(&mut self) -> &mut Animator{
+        &mut self.animator
+    }
+
+    /// Show a component in the main layout, animating it in with `transition` (see `Animator::show`)
+    pub fn show_component(&mut self, id: usize, transition: Transition, duration: Duration) -> AnimationId{
+        self.animator.show(&mut self.layout, id, transition, duration)
+    }
+
+    /// Animate a component in the main layout out with `transition`, hiding it at the end (see `Animator::hide`)
+    pub fn hide_component(&mut self, id: usize, transition: Transition, duration: Duration) -> AnimationId{
+        self.animator.hide(&mut self.layout, id, transition, duration)
+    }
+
+    /// Replace the main layout, showing each of the new layout's visible components with `transition`.
+    /// The animations running on the old layout are stopped.
+    pub fn set_layout_with_transition(&mut self, layout: Layout, transition: Transition, duration: Duration){
+        self.layout = layout;
+        self.animator.clear();
+        let ids: Vec<usize> = self.layout.ids().collect();
+        for id in ids{
+            if self.layout.borrow_component(id).is_visible(){
+                self.animator.show(&mut self.layout, id, transition, duration);
             }
-            {
-                let components = &self.layout.event_components;
-                for i in 0..components.len() {
-                    let comp = &components[i];
-                    render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
-                    comp.render(&mut render_pass);
-                }
+        }
+    }
+
+    /// Run `future` in the background (see the `task` module). Its result arrives as a `GuiEvent::User`.
+    /// Fails if the renderer isn't driven by a `GUI`.
+    #[cfg(feature = "tasks")]
+    pub fn spawn<F>(&mut self, future: F) -> Result<(), &'static str>
+    where F: std::future::Future + Send + 'static, F::Output: Send + 'static{
+        self.tasks.spawn(future)
+    }
+
+    /// Run `future` in the background, then run `callback` with its result on the UI thread (eg, to show a loaded file)
+    #[cfg(feature = "tasks")]
+    pub fn spawn_with<F, C>(&mut self, future: F, callback: C) -> Result<crate::task::TaskId, &'static str>
+    where F: std::future::Future + Send + 'static, F::Output: Send + 'static, C: FnOnce(F::Output, &mut winit::window::Window, &mut Renderer) + 'static{
+        self.tasks.spawn_with(future, callback)
+    }
+
+    /// Summarize the components in the active layout and the GPU memory held by the renderer and its components.
+    /// Useful for finding components or GPU objects that are never released.
+    pub fn resource_report(&self) -> ResourceReport{
+        let mut report = ResourceReport::new();
+        self.layout.report_resources(&mut report);
+        for window in self.windows.values(){
+            window.layout.report_resources(&mut report);
+            window.camera.report_resources(&mut report);
+        }
+        if let Some(sprite) = &self.custom_cursor{
+            let (width, height) = sprite.cursor.size();
+            report.add_texture(width, height, 4);
+        }
+        self.focus_ring.report_resources(&mut report);
+        report.add_buffer(std::mem::size_of_val(QUAD) as u64);
+        report.add_buffer(self.transform_pool.buffer_size());
+
+        // Every cached pipeline (the renderer's own, and any components'), and the glyph brush's own pipeline
+        report.pipelines += self.pipeline_cache.len() + 1;
+        self.camera.report_resources(&mut report);
+        report.add_buffer(std::mem::size_of::<[f32; 4]>() as u64); // Default tint
+        // The glyph cache is a single channel texture
+        report.glyph_atlas.add(self.glyph_cache_size.0 as u64 * self.glyph_cache_size.1 as u64);
+        report.upload_pools.add(STAGING_BELT_CHUNK_SIZE);
+
+        report
+    }
+
+    /// Measure the size (in pixels) a string of text takes up when drawn at `size`
+    pub fn measure_text(&mut self, text: &str, size: f32) -> (f32, f32){
+        measure_text(&mut self.glyph_brush, text, size)
+    }
+
+    /// The time between the start of the last frame and the start of the current one
+    pub fn delta_time(&self) -> Duration{
+        self.delta_time
+    }
+
+    /// The number of frames that have been rendered so far
+    pub fn frame_count(&self) -> u64{
+        self.frame_count
+    }
+
+    /// Once the frame has been submitted, the staging belt can reclaim the chunks it used.
+    /// Without this the belt keeps allocating new chunks every frame.
+    fn recall_staging_belt(&mut self){
+        self.local_pool.spawner().spawn(self.staging_belt.recall()).expect("Failed to recall the staging belt");
+        self.local_pool.run_until_stalled();
+    }
+
+    /// Render the current layout into an offscreen texture and read the pixels back.
+    ///
+    /// This is a development utility - it blocks until the GPU has finished drawing the frame,
+    /// so don't call it every frame.
+    pub fn capture_snapshot(&mut self, clear_color: Color) -> Snapshot{
+        let (width, height) = (self.sc_desc.width.max(1), self.sc_desc.height.max(1));
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Snapshot Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.sc_desc.format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows copied out of a texture have to be padded to a multiple of 256 bytes
+        let unpadded_bytes_per_row = 4 * width;
+        let padding = (wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - unpadded_bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row + padding;
+
+        let output_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Snapshot Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Snapshot Encoder"),
+        });
+
+        self.encode_frame(&mut encoder, &view, width, height, clear_color);
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::BufferCopyView {
+                buffer: &output_buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: padded_bytes_per_row,
+                    rows_per_image: height,
+                },
+            },
+            size,
+        );
+
+        self.staging_belt.finish();
+        self.transform_pool.flush();
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.recall_staging_belt();
+
+        // Wait for the GPU to finish, then copy the rows out (dropping the padding)
+        let buffer_slice = output_buffer.slice(..);
+        let mapping = buffer_slice.map_async(wgpu::MapMode::Read);
+        self.device.poll(wgpu::Maintain::Wait);
+        block_on(mapping).expect("Failed to map the snapshot buffer");
+
+        let mut pixels = Vec::<u8>::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let data = buffer_slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize){
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
             }
+        }
+        output_buffer.unmap();
+
+        // The swapchain is BGRA, but snapshots are always stored as RGBA
+        if let wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb = self.sc_desc.format{
+            for pixel in pixels.chunks_mut(4){
+                pixel.swap(0, 2);
+            }
+        }
+
+        Snapshot::from_rgba(width, height, pixels)
+    }
+
+    /// Capture a snapshot before and after running `change` on the renderer, and compare the two.
+    ///
+    /// Useful to check that a state change only touches the pixels you expect it to.
+    pub fn snapshot_diff<F: FnOnce(&mut Renderer)>(&mut self, clear_color: Color, change: F) -> (Snapshot, Snapshot, SnapshotDiff){
+        self.prepass();
+        let before = self.capture_snapshot(clear_color);
+
+        change(self);
+
+        self.prepass();
+        let after = self.capture_snapshot(clear_color);
+
+        let diff = before.diff(&after, 0);
+        (before, after, diff)
+    }
+
+    /// Record all the draw commands for the current layout into `encoder`, targeting `view`
+    fn encode_frame(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, width: u32, height: u32, clear_color: Color){
+        self.camera.resize((width, height), &self.queue);
+
+        let (mut context, layout) = self.split_context(encoder);
+        let camera = context.camera;
+        context.draw_layout_with(layout, view, camera, Some(clear_color));
+    }
+
+    // Draw the focus ring around the focused component (if there is one, and the ring isn't hidden)
+    fn draw_focus_ring(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, width: u32, height: u32, viewport: Option<Viewport>){
+        if self.focus_ring.color().is_none(){
+            return;
+        }
+        if let Some(rect) = self.layout.focus_rect((width, height)){
+            self.focus_ring.update(rect);
+            let ring = &self.focus_ring;
+            let mut context = RenderContext{
+                device: &self.device,
+                queue: &self.queue,
+                bind_group_cache: &self.bind_group_cache,
+                pipeline_cache: &self.pipeline_cache,
+                encoder,
+                render_pipeline: &self.render_pipeline,
+                texture_pipeline: &self.texture_pipeline,
+                texture_bind_group_layout: &self.texture_bind_group_layout,
+                size: (width, height),
+                camera: &self.camera,
+                format: self.sc_desc.format,
+                viewport,
+                custom_pass: None,
+                locale: &self.locale,
+                default_tint: &self.default_tint.1,
+                glyph_brush: &mut self.glyph_brush,
+                staging_belt: &mut self.staging_belt,
+                stats: &mut self.frame_stats,
+            };
+            context.draw_focus_ring(view, ring);
+        }
+    }
+
+    // Draw the performance overlay (if it's shown) on top of the frame, with the stats of the frame so far
+    fn draw_perf_overlay(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, viewport: Option<Viewport>){
+        // Take the overlay out while drawing, as the context borrows the rest of the renderer
+        let mut overlay = match self.perf_overlay.take(){
+            Some(v) => v,
+            None => return,
+        };
+        // Drawing the overlay shouldn't show up in the stats it's showing
+        let stats = self.frame_stats.clone();
+        overlay.update(&stats, &self.layout);
+        {
+            let (mut context, _) = self.split_context(encoder);
+            context.viewport = viewport;
+            context.custom_pass = None;
+            let camera = context.camera;
+            context.draw_layout_with(overlay.layout_mut(), view, camera, None);
+        }
+        self.frame_stats = stats;
+        self.perf_overlay = Some(overlay);
+    }
+
+    // Draw the custom cursor image (if there is one) on top of the frame
+    fn draw_custom_cursor(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView){
+        let position = match self.input.cursor_position(){
+            Some(v) => v,
+            None => return,
+        };
+        // Take the sprite out while drawing, as the context borrows the rest of the renderer
+        if let Some(mut sprite) = self.custom_cursor.take(){
+            sprite.update(position);
             {
-                for text_comp in self.layout.text_components.iter(){
-                    text_comp.render_text(&mut self.glyph_brush);
-                }
+                let (mut context, _) = self.split_context(encoder);
+                context.draw_sprite(view, &sprite.transform, &sprite.texture_bind_group, &sprite.vertex_buffer);
             }
+            self.custom_cursor = Some(sprite);
         }
+    }
+
+    /// Render a single frame, submitting the component pass and the text pass separately and waiting on the GPU
+    /// after each one, so we can time them. This is a lot slower than `render`, so it's only used when profiling.
+    fn render_profiled(&mut self, clear_color: Color){
+        let target = self.acquire_target();
+        let (width, height) = (self.sc_desc.width, self.sc_desc.height);
+        self.camera.resize((self.sc_desc.width, self.sc_desc.height), &self.queue);
+
+        // Make sure nothing from the previous frame is still running
+        self.device.poll(wgpu::Maintain::Wait);
 
+        let start = Instant::now();
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Component Encoder"),
+        });
         {
-            self.glyph_brush.draw_queued(&self.device, &mut self.staging_belt, &mut encoder, &frame.view, self.sc_desc.width, self.sc_desc.height).unwrap();
+            let (mut context, layout) = self.split_context(&mut encoder);
+            context.draw_components(layout, target.view(), clear_color);
         }
+        self.transform_pool.flush();
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.device.poll(wgpu::Maintain::Wait);
+        self.frame_stats.gpu_component_time = Some(start.elapsed());
 
+        let start = Instant::now();
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Text Encoder"),
+        });
+        {
+            let (mut context, layout) = self.split_context(&mut encoder);
+            context.draw_text(layout, target.view(), width, height);
+        }
+        self.draw_focus_ring(&mut encoder, target.view(), width, height, None);
+        self.draw_perf_overlay(&mut encoder, target.view(), None);
+        self.draw_custom_cursor(&mut encoder, target.view());
         self.staging_belt.finish();
-        
-        // submit will accept anything that implements IntoIter
+        self.transform_pool.flush();
         self.queue.submit(std::iter::once(encoder.finish()));
+        self.device.poll(wgpu::Maintain::Wait);
+        self.frame_stats.gpu_text_time = Some(start.elapsed());
+        self.release_target(target);
+    }
+
+    /// Returns true if the renderer draws into an offscreen texture rather than a window
+    pub fn is_headless(&self) -> bool{
+        self.surface.is_none() && self.instance.is_some()
+    }
+
+    /// Returns true if the renderer was built on the app's device, and draws with `render_into` (see `Renderer::from_raw`)
+    pub fn is_embedded(&self) -> bool{
+        self.instance.is_none()
+    }
+
+    /// Draw the layout into `view`, one of the app's textures, on top of what's already there. The layout is drawn
+    /// inside the viewport, and sees it as its window - so (0, 0) is the viewport's top left.
+    ///
+    /// This only records commands into the app's `encoder`, so the GUI ends up wherever the app submits it (eg, over its
+    /// 3D scene). Call `prepass` first, and submit the encoder before calling this again. Meant for embedded renderers
+    /// (see `Renderer::from_raw`), but any renderer can draw into a texture with the same format as its own.
+    pub fn render_into(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, viewport: Viewport){
+        let _span = tracing::trace_span!("render_into", viewport = ?viewport.rect).entered();
+        let [_, _, width, height] = viewport.rect;
+        if width == 0 || height == 0{
+            return;
+        }
+        // The app has submitted the last call's encoder by now, so the staging belt's chunks can be reused
+        self.recall_staging_belt();
+        self.frame_stats.reset_counters();
+        let start = Instant::now();
+
+        // The viewport is the layout's window, so components and input see its size
+        if (width, height) != (self.sc_desc.width, self.sc_desc.height){
+            self.resize(winit::dpi::PhysicalSize::new(width, height));
+        }
+        self.camera.resize((width, height), &self.queue);
+        {
+            let (mut context, layout) = self.split_context(encoder);
+            context.viewport = Some(viewport);
+            let camera = context.camera;
+            context.draw_layout_with(layout, view, camera, None);
+        }
+        self.draw_focus_ring(encoder, view, width, height, Some(viewport));
+        self.draw_perf_overlay(encoder, view, Some(viewport));
+        self.staging_belt.finish();
+
+        self.frame_count += 1;
+        self.frame_stats.cpu_time = self.prepass_time + start.elapsed();
+    }
+
+    // Get the texture to draw the next frame into - the next swapchain image, or the offscreen texture when headless
+    fn acquire_target(&mut self) -> RenderTarget{
+        match (&mut self.swap_chain, self.offscreen.take()){
+            (Some(swap_chain), _) => RenderTarget::Frame(swap_chain.get_current_frame().unwrap().output),
+            (None, Some((texture, view))) => RenderTarget::Offscreen(texture, view),
+            (None, None) => panic!("Error, embedded renderers draw with `render_into`, not `render`!"),
+        }
+    }
+
+    // Present the frame, or give the offscreen texture back
+    fn release_target(&mut self, target: RenderTarget){
+        if let RenderTarget::Offscreen(texture, view) = target{
+            self.offscreen = Some((texture, view));
+        }
+    }
+
+    // Borrow everything a RenderContext needs, along with the layout to draw
+    fn split_context<'a>(&'a mut self, encoder: &'a mut wgpu::CommandEncoder) -> (RenderContext<'a>, &'a mut Layout){
+        let context = RenderContext{
+            device: &self.device,
+            queue: &self.queue,
+            bind_group_cache: &self.bind_group_cache,
+            pipeline_cache: &self.pipeline_cache,
+            encoder,
+            render_pipeline: &self.render_pipeline,
+            texture_pipeline: &self.texture_pipeline,
+            texture_bind_group_layout: &self.texture_bind_group_layout,
+            size: (self.sc_desc.width, self.sc_desc.height),
+            camera: &self.camera,
+            format: self.sc_desc.format,
+            viewport: None,
+            custom_pass: self.custom_pass.as_deref(),
+            locale: &self.locale,
+            default_tint: &self.default_tint.1,
+            glyph_brush: &mut self.glyph_brush,
+            staging_belt: &mut self.staging_belt,
+            stats: &mut self.frame_stats,
+        };
+        (context, &mut self.layout)
+    }
+}
+
+
+/// # CustomPass
+///
+/// The app's own wgpu drawing, done in the GUI's render pass after the components and before the text
+/// (see `Renderer::set_custom_pass`). Like `Component::render`, the pass can only use resources that outlive it, so
+/// anything it binds (pipelines, buffers...) should be owned by the custom pass.
+///
+/// Closures taking `(&mut wgpu::RenderPass, &wgpu::Device)` are custom passes too, for draws that don't bind their own resources.
+pub trait CustomPass{
+    /// Record the draws into `render_pass`. The pipeline and bind groups are left as the last component set them,
+    /// so set your own before drawing.
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, device: &wgpu::Device);
+}
+
+impl<F: for<'a> Fn(&mut wgpu::RenderPass<'a>, &wgpu::Device)> CustomPass for F{
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, device: &wgpu::Device){
+        self(render_pass, device)
+    }
+}
+
+/// # Viewport
+///
+/// The area of one of the app's textures an embedded renderer draws into (see `Renderer::render_into`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport{
+    /// The area to draw in, as [left, top, width, height] in pixels from the top left of the texture
+    pub rect: [u32; 4],
+    /// The size of the whole texture, in pixels
+    pub target_size: (u32, u32),
+}
+
+impl Viewport{
+    /// The area `rect` ([left, top, width, height] in pixels) of a texture of `target_size` pixels
+    pub fn new(rect: [u32; 4], target_size: (u32, u32)) -> Self{
+        Self{
+            rect,
+            target_size,
+        }
+    }
+
+    /// The whole of a texture of `size` pixels
+    pub fn full(size: (u32, u32)) -> Self{
+        Self::new([0, 0, size.0, size.1], size)
+    }
+
+    // Limit a render pass to the viewport
+    fn apply(&self, render_pass: &mut wgpu::RenderPass){
+        let [x, y, width, height] = self.rect;
+        render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(x, y, width, height);
+    }
+
+    // Maps pixels from the top left of the viewport onto the whole texture, for the glyph brush
+    fn text_transform(&self) -> [f32; 16]{
+        let [x, y, _, _] = self.rect;
+        let mut transform = wgpu_glyph::orthographic_projection(self.target_size.0, self.target_size.1);
+        transform[12] += 2.0 * x as f32 / self.target_size.0 as f32;
+        transform[13] -= 2.0 * y as f32 / self.target_size.1 as f32;
+        transform
+    }
+}
+
+// The texture a frame is drawn into. Swapchain frames get presented when they're dropped.
+
+enum RenderTarget{
+    Frame(wgpu::SwapChainTexture),
+    Offscreen(Tracked<wgpu::Texture>, wgpu::TextureView),
+}
+
+impl RenderTarget{
+    fn view(&self) -> &wgpu::TextureView{
+        match self{
+            RenderTarget::Frame(frame) => &frame.view,
+            RenderTarget::Offscreen(_, view) => view,
+        }
+    }
+}
+
+// Create the texture a headless renderer draws into, matching the swapchain description
+fn create_offscreen_target(device: &wgpu::Device, sc_desc: &wgpu::SwapChainDescriptor) -> (Tracked<wgpu::Texture>, wgpu::TextureView){
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Texture"),
+        size: wgpu::Extent3d {
+            width: sc_desc.width.max(1),
+            height: sc_desc.height.max(1),
+            depth: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: sc_desc.format,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let bytes = 4 * sc_desc.width.max(1) as u64 * sc_desc.height.max(1) as u64;
+    (Tracked::new(texture, MemoryKind::RenderTarget, bytes), view)
+}
+
+// Measure the size of `text` at `size` pixels, using the glyph brush's font
+fn measure_text(glyph_brush: &mut wgpu_glyph::GlyphBrush<()>, text: &str, size: f32) -> (f32, f32){
+    use wgpu_glyph::GlyphCruncher;
+
+    let section = wgpu_glyph::Section {
+        text: vec![wgpu_glyph::Text::new(text).with_scale(wgpu_glyph::ab_glyph::PxScale::from(size))],
+        ..wgpu_glyph::Section::default()
+    };
+    match glyph_brush.glyph_bounds(section){
+        Some(bounds) => (bounds.width(), bounds.height()),
+        None => (0.0, 0.0),
+    }
+}
+
+// Create the glyph brush text is drawn with, using the theme's font (or the built-in one)
+// The theme's font is `FontId(0)`, and the fonts plugins registered follow it in order
+fn create_glyph_brush(device: &wgpu::Device, theme: &Theme, plugin_fonts: &[wgpu_glyph::ab_glyph::FontArc], cache_size: (u32, u32), format: wgpu::TextureFormat) -> Tracked<wgpu_glyph::GlyphBrush<()>>{
+    let font = match &theme.font{
+        Some(font) => font.clone(),
+        None => wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!("../../fonts/FingerPaint-Regular.ttf"))
+            .expect("Load font"),
+    };
+    let fonts = std::iter::once(font).chain(plugin_fonts.iter().cloned()).collect();
+    let brush = wgpu_glyph::GlyphBrushBuilder::using_fonts(fonts)
+        .initial_cache_size(cache_size)
+        .build(device, format);
+    // The cache is a single channel texture
+    Tracked::new(brush, MemoryKind::GlyphCache, cache_size.0 as u64 * cache_size.1 as u64)
+}
+
+// Draw the visible components of `layout` that draw inside `area` (in pixels of a target of `screen` pixels) in order,
+// each followed by its children (see `Component::children`). Components may switch pipelines (eg, to draw a texture), so
+// the default pipeline and bind groups are reset for each one.
+fn render_components<'a>(render_pass: &mut wgpu::RenderPass<'a>, layout: &'a Layout, defaults: (&'a wgpu::RenderPipeline, &'a BindGroup, &'a BindGroup), screen: (u32, u32), area: [f32; 4], stats: &mut FrameStats){
+    let (pipeline, camera_bind_group, default_tint) = defaults;
+    let ids = layout.visible_in(screen, area);
+    stats.components_culled += layout.components.iter().filter(|(_, comp)| comp.is_visible()).count() - ids.len();
+    for comp in ids.into_iter().map(|id| &layout.components[id]){
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_bind_group(2, default_tint, &[]);
+        comp.render(render_pass);
+        stats.components_rendered += 1;
+        stats.draw_calls += 1;
+        if let (Some(children), Some(area)) = (comp.children(), layout.clip_area(area)){
+            render_components(render_pass, children, defaults, screen, area, stats);
+        }
+    }
+}
+
+// Queue the text of the components of `layout` that draw inside `area`, and their children's, like `render_components`.
+// While a layout with a clip rect is queued, its text is clipped to it (and to the clip rects around it).
+fn queue_layout_text(queue: &mut TextQueue, layout: &Layout, screen: (u32, u32), area: [f32; 4], stats: &mut FrameStats){
+    let outer = queue.clip_rect();
+    if layout.clip_rect().is_some(){
+        match layout.clip_area(area){
+            Some(clip) => queue.set_clip_rect(Some(clip)),
+            None => return,
+        }
+    }
+    for comp in layout.visible_in(screen, area).into_iter().map(|id| &layout.components[id]){
+        comp.queue_text(queue);
+        stats.glyphs_queued += comp.glyph_count();
+        if let (Some(children), Some(area)) = (comp.children(), layout.clip_area(area)){
+            queue_layout_text(queue, children, screen, area, stats);
+        }
+    }
+    queue.set_clip_rect(outer);
+}
+
+// Update the components in a layout
+fn update_layout(layout: &mut Layout, delta_time: Duration){
+    for (_, comp) in layout.components.iter_mut(){
+        comp.update(delta_time);
+    }
+}
+
+/// # RenderContext
+///
+/// Everything needed to record draw commands for a layout. It gets passed to `prerender` on every component,
+/// so components can draw things offscreen (like a cached copy of themselves) before the main pass starts.
+pub struct RenderContext<'a>{
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// The renderer's shared bind group layouts
+    pub bind_group_cache: &'a BindGroupCache,
+    /// The renderer's shared pipelines
+    pub pipeline_cache: &'a PipelineCache,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    render_pipeline: &'a wgpu::RenderPipeline,
+    pub texture_pipeline: &'a Rc<wgpu::RenderPipeline>,
+    pub texture_bind_group_layout: &'a wgpu::BindGroupLayout,
+    /// The size (in pixels) of the target currently being drawn to
+    pub size: (u32, u32),
+    /// The format of the textures the renderer's pipelines draw into
+    pub format: wgpu::TextureFormat,
+    // The area of the app's texture an embedded renderer draws into, or `None` for the whole target
+    viewport: Option<Viewport>,
+    // Drawn after the components, for the main window only (see `Renderer::set_custom_pass`)
+    custom_pass: Option<&'a dyn CustomPass>,
+    camera: &'a Camera,
+    /// The locale text keys are looked up in
+    pub locale: &'a Locale,
+    default_tint: &'a BindGroup,
+    glyph_brush: &'a mut wgpu_glyph::GlyphBrush<()>,
+    staging_belt: &'a mut StagingBelt,
+    stats: &'a mut FrameStats,
+}
+
+impl<'a> RenderContext<'a>{
+    /// The part of the target components are drawn in, as [left, top, width, height] in pixels of the target - the
+    /// viewport of an embedded renderer (see `Renderer::render_into`), otherwise the whole target. Components that set
+    /// their own scissor rect should keep it inside this, and put it back when they're done.
+    pub fn clip_rect(&self) -> [u32; 4]{
+        match &self.viewport{
+            Some(viewport) => viewport.rect,
+            None => [0, 0, self.size.0, self.size.1],
+        }
+    }
+
+    /// Draw a whole layout (components, then text) into `view`, clearing it first. `camera` is for the target `view`
+    /// belongs to, which sets the size it's drawn at.
+    pub fn draw_layout(&mut self, layout: &mut Layout, view: &wgpu::TextureView, camera: &Camera, clear_color: Color){
+        // Nested layouts (like a cached one) fill their own target, whatever viewport the outer layout is drawn in,
+        // and don't get the app's custom pass
+        let (viewport, custom_pass) = (self.viewport.take(), self.custom_pass.take());
+        self.draw_layout_with(layout, view, camera, Some(clear_color));
+        self.viewport = viewport;
+        self.custom_pass = custom_pass;
+    }
+
+    // Draw a layout into `view`, clearing it first unless `clear_color` is `None`
+    fn draw_layout_with(&mut self, layout: &mut Layout, view: &wgpu::TextureView, camera: &Camera, clear_color: Option<Color>){
+        // Nested layouts (like a cached one) draw to a different size, so put the old size back afterwards
+        let size = self.size;
+        self.size = camera.size();
+        self.draw_components_with_camera(layout, view, camera, clear_color);
+        self.draw_text(layout, view, self.size.0, self.size.1);
+        self.size = size;
+    }
+
+    /// Measure the size of `text` at `size` pixels, like `Renderer::measure_text`
+    pub fn measure_text(&mut self, text: &str, size: f32) -> (f32, f32){
+        measure_text(self.glyph_brush, text, size)
+    }
+
+    /// Draw the (non-text) components of a layout into the window's `view`, clearing it first
+    pub fn draw_components(&mut self, layout: &mut Layout, view: &wgpu::TextureView, clear_color: Color){
+        let camera = self.camera;
+        self.draw_components_with_camera(layout, view, camera, Some(clear_color));
+    }
+
+    fn draw_components_with_camera(&mut self, layout: &mut Layout, view: &wgpu::TextureView, camera: &Camera, clear_color: Option<Color>){
+        // Components get a chance to draw offscreen before we start the pass
+        for (_, comp) in layout.components.iter_mut(){
+            comp.prerender(self);
+        }
+
+        // Main pass - Render all our shaders and objects to the screen
+        let mut render_pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[
+                wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: match clear_color{
+                            Some(color) => wgpu::LoadOp::Clear(color.into()),
+                            None => wgpu::LoadOp::Load,
+                        },
+                        store: true,
+                    }
+                },
+            ],
+            depth_stencil_attachment: None,
+        });
+        if let Some(viewport) = &self.viewport{
+            viewport.apply(&mut render_pass);
+        }
+
+        let (width, height) = self.size;
+        render_components(&mut render_pass, layout, (self.render_pipeline, &camera.bind_group, self.default_tint), self.size, [0.0, 0.0, width as f32, height as f32], self.stats);
+        if let Some(pass) = self.custom_pass{
+            pass.draw(&mut render_pass, self.device);
+            self.stats.draw_calls += 1;
+        }
+    }
+
+    /// Draw a textured quad on top of whatever is already in `view` (used for overlays like the custom cursor)
+    pub fn draw_sprite(&mut self, view: &wgpu::TextureView, transform: &TransformSlot, texture_bind_group: &BindGroup, vertex_buffer: &wgpu::Buffer){
+        let mut render_pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[
+                wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    }
+                },
+            ],
+            depth_stencil_attachment: None,
+        });
+        if let Some(viewport) = &self.viewport{
+            viewport.apply(&mut render_pass);
+        }
+        render_pass.set_pipeline(self.texture_pipeline);
+        render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
+        transform.bind(&mut render_pass, 1);
+        render_pass.set_bind_group(2, texture_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.draw(0..6, 0..1);
+        self.stats.draw_calls += 1;
+    }
+
+    /// Draw a focus ring on top of whatever is already in `view`
+    pub(crate) fn draw_focus_ring(&mut self, view: &wgpu::TextureView, ring: &FocusRing){
+        let mut render_pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[
+                wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    }
+                },
+            ],
+            depth_stencil_attachment: None,
+        });
+        if let Some(viewport) = &self.viewport{
+            viewport.apply(&mut render_pass);
+        }
+        render_pass.set_pipeline(self.render_pipeline);
+        render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
+        render_pass.set_bind_group(2, ring.tint_bind_group(), &[]);
+        render_pass.set_vertex_buffer(0, ring.vertex_buffer.slice(..));
+        for edge in ring.edges.iter(){
+            edge.bind(&mut render_pass, 1);
+            render_pass.draw(0..6, 0..1);
+        }
+        self.stats.draw_calls += 1;
+    }
+
+    /// Draw the text of a layout's components (and their children) on top of whatever is already in `view`. It's all drawn
+    /// at once, with the text of clipped layouts cut off at their clip rects (see `TextQueue`).
+    pub fn draw_text(&mut self, layout: &mut Layout, view: &wgpu::TextureView, width: u32, height: u32){
+        let mut queue = TextQueue::new(self.glyph_brush);
+        if self.stats.glyph_cache_used.is_some(){
+            queue.track_glyphs();
+        }
+        queue_layout_text(&mut queue, layout, (width, height), [0.0, 0.0, width as f32, height as f32], self.stats);
+        if let (Some(used), Some(pixels)) = (&mut self.stats.glyph_cache_used, queue.glyph_pixels()){
+            *used += pixels;
+        }
+        self.stats.draw_calls += 1;
+        match &self.viewport{
+            Some(viewport) => {
+                let [x, y, width, height] = viewport.rect;
+                let region = wgpu_glyph::Region{ x, y, width, height };
+                self.glyph_brush.draw_queued_with_transform_and_scissoring(self.device, self.staging_belt, self.encoder, view, viewport.text_transform(), region).unwrap();
+            },
+            None => self.glyph_brush.draw_queued(self.device, self.staging_belt, self.encoder, view, width, height).unwrap(),
+        }
     }
 }
 
@@ -333,30 +1905,30 @@ impl Vertex {
 /// This is a helpful quad type to help you render sprites to the screen
 pub const QUAD: &[Vertex] = &[
     // Changed
-    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
-    Vertex { position: [-1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
-    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
+    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [-1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
+    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
     
-    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
-    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
-    Vertex { position: [1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
+    Vertex { position: [1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
     
 ]; 
 
 
 use cgmath::{Matrix4, SquareMatrix};
+
+/// # Camera
+///
+/// Maps pixels from the top left of a render target onto the screen. Every component is positioned in pixels (see
+/// `Transform`), and the vertex shader converts them with the camera of the target being drawn - the window's, or
+/// one of a nested layout's own (eg, a `CachedLayout`'s texture).
 #[derive(Debug)]
 pub struct Camera {
-    pub near: f32,
-    pub far: f32,
-
-    pub width: u32,
-    pub height: u32,
-
+    size: (u32, u32),
     camera_uniform: CameraUniform,
-    buffer: wgpu::Buffer,
-
-    bind_group: BindGroup,
+    buffer: Tracked<wgpu::Buffer>,
+    pub bind_group: BindGroup,
 }
 
 #[rustfmt::skip]
@@ -369,29 +1941,41 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
 
 
 impl Camera {
-    pub fn new(near: f32, far: f32, device: &Device, sc_desc: &wgpu::SwapChainDescriptor) -> Self{
+    /// Create a camera for a target of `size` pixels, with its bind group layout from `cache` (see `Renderer::bind_group_cache`)
+    pub fn new(size: (u32, u32), cache: &BindGroupCache) -> Self{
         let mut camera_uniform = CameraUniform::new();
-        let proj = cgmath::ortho(0.0, sc_desc.width as f32, sc_desc.height as f32, 0.0, 0.0, 1000.0);
-        camera_uniform.update_view_proj(proj);
-        let buffer = UniformUtils::create_uniform_buffer(device, &camera_uniform);
-        let layout = UniformUtils::create_bind_group_layout(device, 0, ShaderStage::VERTEX, false, None, "Camera layout");
-        let bind_group = UniformUtils::create_bind_group(device, &layout, 0, &buffer, "Camera bind group");
+        camera_uniform.update_view_proj(Self::build_view_projection_matrix(size));
+        let (buffer, bind_group, _) = cache.create_uniform(ShaderStage::VERTEX, 0, &camera_uniform, "Camera bind group");
         Self{
-            near,
-            far,
-            width: 0,
-            height: 0,
+            size,
             camera_uniform,
             buffer,
             bind_group,
         }
     }
-    pub fn build_view_projection_matrix(&mut self, sc_desc: &wgpu::SwapChainDescriptor) -> cgmath::Matrix4<f32>{
-        self.width = sc_desc.width;
-        self.height = sc_desc.height;
-        // 1.
-        // 2.
-        let proj = cgmath::ortho(0.0, self.width as f32, self.height as f32, 0.0, 0.0, 1000.0);
+
+    /// The size (in pixels) of the target the camera is for
+    pub fn size(&self) -> (u32, u32){
+        self.size
+    }
+
+    /// Point the camera at a target of `size` pixels, writing the new projection if the size changed
+    pub fn resize(&mut self, size: (u32, u32), queue: &wgpu::Queue){
+        if size != self.size{
+            self.size = size;
+            self.camera_uniform.update_view_proj(Self::build_view_projection_matrix(size));
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+        }
+    }
+
+    /// Add the camera's uniform buffer to a resource report
+    pub fn report_resources(&self, report: &mut ResourceReport){
+        report.add_buffer(std::mem::size_of::<CameraUniform>() as u64);
+    }
+
+    /// The matrix mapping pixels from the top left of a target of `size` pixels (with y going down) to clip space
+    pub fn build_view_projection_matrix(size: (u32, u32)) -> cgmath::Matrix4<f32>{
+        let proj = cgmath::ortho(0.0, size.0.max(1) as f32, size.1.max(1) as f32, 0.0, 0.0, 1000.0);
 
         let view = cgmath::Matrix4::<f32>::look_at_rh(
             cgmath::Point3::<f32>::new(0.0, 0.0, 5.0), 
@@ -399,13 +1983,7 @@ impl Camera {
             cgmath::Vector3::<f32>::new(0.0, 1.0, 0.0)
         );
         
-        // 3.
-        return OPENGL_TO_WGPU_MATRIX * (proj * view);
-    }
-
-    pub fn update(&mut self, sc_desc: &wgpu::SwapChainDescriptor){
-        let value = self.build_view_projection_matrix(sc_desc);
-        self.camera_uniform.update_view_proj(value);
+        OPENGL_TO_WGPU_MATRIX * (proj * view)
     }
 }
 
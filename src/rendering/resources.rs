@@ -0,0 +1,75 @@
+//! This module contains `ResourceReport`, a summary of the resources a GUI is holding on to -
+//! how many components each layout has, and roughly how much GPU memory is allocated, split by category.
+//! Taking a report every so often (or before and after opening and closing a screen) makes it easy to spot
+//! components or GPU objects that are never released.
+
+/// # ResourceUsage
+///
+/// The number of allocations in a category, and their total size in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage{
+    pub count: usize,
+    pub bytes: u64,
+}
+
+impl ResourceUsage{
+    /// Record an allocation of `bytes`
+    pub fn add(&mut self, bytes: u64){
+        self.count += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// # ResourceReport
+///
+/// A snapshot of the resources used by the renderer and its active layout. Get one with `Renderer::resource_report()`.
+///
+/// Component counts include the layouts nested inside other components (like `CachedLayout`). GPU sizes are the sizes
+/// requested from wgpu - the driver may round them up, and the glyph cache may have grown past the reported size.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceReport{
+    /// Number of components
+    pub components: usize,
+    /// Number of components that are currently disabled, but still hold their resources
+    pub disabled_components: usize,
+    /// Number of components that are currently hidden, but still hold their resources
+    pub hidden_components: usize,
+
+    /// Vertex and uniform buffers
+    pub buffers: ResourceUsage,
+    /// Textures, including render targets for cached layouts (but not the swapchain)
+    pub textures: ResourceUsage,
+    /// The glyph cache texture that text is drawn from
+    pub glyph_atlas: ResourceUsage,
+    /// Chunks of memory used to upload data to the GPU (the staging belt)
+    pub upload_pools: ResourceUsage,
+    /// Render pipelines
+    pub pipelines: usize,
+}
+
+impl ResourceReport{
+    /// Create an empty report
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Record a buffer of `bytes`
+    pub fn add_buffer(&mut self, bytes: u64){
+        self.buffers.add(bytes);
+    }
+
+    /// Record a texture of `width` by `height` pixels, with `bytes_per_pixel` bytes per pixel
+    pub fn add_texture(&mut self, width: u32, height: u32, bytes_per_pixel: u32){
+        self.textures.add(width as u64 * height as u64 * bytes_per_pixel as u64);
+    }
+
+    /// Total number of components, including the ones in nested layouts
+    pub fn total_components(&self) -> usize{
+        self.components
+    }
+
+    /// Total GPU memory, in bytes, across every category
+    pub fn total_gpu_bytes(&self) -> u64{
+        self.buffers.bytes + self.textures.bytes + self.glyph_atlas.bytes + self.upload_pools.bytes
+    }
+}
@@ -0,0 +1,86 @@
+//! This module contains `SecondaryWindow`, an extra window (like a detachable inspector or tool palette)
+//! that shares the renderer's device and queue with the main window, but has its own surface, swapchain and layout.
+
+use std::time::Instant;
+
+use winit::window::WindowId;
+
+use crate::{color::Color, event::EventTranslator, layout::Layout};
+
+use super::{surface_handle::SurfaceHandle, BindGroupCache, Camera};
+
+/// # SecondaryWindow
+///
+/// A window opened alongside the main one. The main loop sends each window the events meant for it,
+/// and draws its layout whenever it needs a redraw. Closing it (from the title bar, or with `Renderer::close_window`)
+/// drops the window and everything in its layout.
+///
+/// Secondary windows are created with `GUI::add_window` before the main loop starts, or `Renderer::open_window` while it runs.
+pub struct SecondaryWindow{
+    // The swapchain and surface have to be dropped before the window they draw to
+    pub(crate) swap_chain: wgpu::SwapChain,
+    pub(crate) surface: wgpu::Surface,
+    pub(crate) sc_desc: wgpu::SwapChainDescriptor,
+    pub(crate) last_frame: Instant,
+    pub(crate) applied_cursor: winit::window::CursorIcon,
+    pub(crate) translator: EventTranslator,
+    pub(crate) camera: Camera,
+
+    pub window: winit::window::Window,
+    pub layout: Layout,
+    pub clear_color: Color,
+}
+
+impl SecondaryWindow{
+    /// Create the surface and swapchain for `window`, using the renderer's device and bind group cache
+    pub(crate) fn new(instance: &wgpu::Instance, device: &wgpu::Device, cache: &BindGroupCache, window: winit::window::Window, layout: Layout, clear_color: Color) -> Self{
+        let size = window.inner_size();
+        let surface = unsafe { instance.create_surface(&SurfaceHandle(&window)) };
+        let sc_desc = wgpu::SwapChainDescriptor {
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+        let swap_chain = device.create_swap_chain(&surface, &sc_desc);
+
+        Self{
+            swap_chain,
+            surface,
+            sc_desc,
+            last_frame: Instant::now(),
+            applied_cursor: winit::window::CursorIcon::Default,
+            translator: EventTranslator::new(),
+            camera: Camera::new((size.width, size.height), cache),
+            window,
+            layout,
+            clear_color,
+        }
+    }
+
+    /// The ID of the window, which events for it are tagged with
+    pub fn id(&self) -> WindowId{
+        self.window.id()
+    }
+
+    /// The size of the window's surface in pixels
+    pub fn size(&self) -> (u32, u32){
+        (self.sc_desc.width, self.sc_desc.height)
+    }
+
+    /// Returns true if the window is minimized (and so shouldn't be drawn)
+    pub fn is_minimized(&self) -> bool{
+        self.sc_desc.width == 0 || self.sc_desc.height == 0
+    }
+
+    /// Recreate the swapchain after the window was resized
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, new_size: winit::dpi::PhysicalSize<u32>){
+        self.sc_desc.width = new_size.width;
+        self.sc_desc.height = new_size.height;
+        self.layout.mark_dirty();
+        if !self.is_minimized(){
+            self.swap_chain = device.create_swap_chain(&self.surface, &self.sc_desc);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! This module contains a small development tool to capture what the renderer drew
+//! and compare two captures. Diffing a snapshot taken before a state change with one taken
+//! after it shows exactly which pixels changed, which makes it easy to check that redraw
+//! logic only touches what it should.
+
+/// # Snapshot
+///
+/// A CPU-side copy of a rendered frame, stored as tightly packed RGBA8 pixels.
+///
+/// Snapshots are usually captured with `Renderer::capture_snapshot`, but can also be built
+/// from raw pixel data (for example, a reference image loaded from disk).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot{
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Snapshot{
+    /// Create a snapshot from RGBA8 pixel data. Panics if the data doesn't match the dimensions.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Self{
+        assert_eq!(pixels.len(), (width * height * 4) as usize, "Snapshot pixel data doesn't match its dimensions");
+        Self{
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// The width of the snapshot in pixels
+    pub fn width(&self) -> u32{
+        self.width
+    }
+
+    /// The height of the snapshot in pixels
+    pub fn height(&self) -> u32{
+        self.height
+    }
+
+    /// The raw RGBA8 pixels of the snapshot
+    pub fn pixels(&self) -> &[u8]{
+        &self.pixels
+    }
+
+    /// Get a single pixel as RGBA
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4]{
+        let i = ((y * self.width + x) * 4) as usize;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// Convert the snapshot into an `image` buffer, so it can be saved or inspected
+    pub fn to_image(&self) -> image::RgbaImage{
+        image::RgbaImage::from_raw(self.width, self.height, self.pixels.clone()).expect("Snapshot has an invalid size")
+    }
+
+    /// Save the snapshot to disk. The format is picked from the file extension.
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> image::ImageResult<()>{
+        self.to_image().save(path)
+    }
+
+    /// Load a snapshot from an image on disk (eg, a reference image saved with `save`)
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> image::ImageResult<Self>{
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+        Ok(Self::from_rgba(width, height, image.into_raw()))
+    }
+
+    /// Compare this snapshot with another one.
+    ///
+    /// A pixel counts as changed if any of its channels differs by more than `tolerance`.
+    /// Snapshots of different sizes are compared over the area they share, and everything outside
+    /// of it counts as changed.
+    pub fn diff(&self, other: &Snapshot, tolerance: u8) -> SnapshotDiff{
+        let width = self.width.max(other.width);
+        let height = self.height.max(other.height);
+        let mut changed = vec![false; (width * height) as usize];
+        let mut changed_pixels = 0;
+        let mut bounds: Option<([u32; 2], [u32; 2])> = None;
+
+        for y in 0..height{
+            for x in 0..width{
+                let is_changed = if x < self.width && y < self.height && x < other.width && y < other.height{
+                    let a = self.get_pixel(x, y);
+                    let b = other.get_pixel(x, y);
+                    a.iter().zip(b.iter()).any(|(a, b)| (*a as i16 - *b as i16).abs() > tolerance as i16)
+                }else{
+                    true
+                };
+
+                if is_changed{
+                    changed[(y * width + x) as usize] = true;
+                    changed_pixels += 1;
+                    bounds = Some(match bounds{
+                        Some((min, max)) => ([min[0].min(x), min[1].min(y)], [max[0].max(x), max[1].max(y)]),
+                        None => ([x, y], [x, y]),
+                    });
+                }
+            }
+        }
+
+        SnapshotDiff{
+            width,
+            height,
+            changed,
+            changed_pixels,
+            bounds,
+        }
+    }
+}
+
+/// # SnapshotDiff
+///
+/// The result of comparing two snapshots. It stores a mask of the pixels that changed,
+/// and the smallest rectangle containing all of them (the region that actually needed a redraw).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff{
+    width: u32,
+    height: u32,
+    changed: Vec<bool>,
+    changed_pixels: usize,
+    bounds: Option<([u32; 2], [u32; 2])>,
+}
+
+impl SnapshotDiff{
+    /// Returns true if no pixels changed
+    pub fn is_empty(&self) -> bool{
+        self.changed_pixels == 0
+    }
+
+    /// The number of pixels that changed
+    pub fn changed_pixels(&self) -> usize{
+        self.changed_pixels
+    }
+
+    /// The bounding box of all changed pixels, as (top left, bottom right) inclusive pixel coordinates
+    pub fn bounds(&self) -> Option<([u32; 2], [u32; 2])>{
+        self.bounds
+    }
+
+    /// Returns true if the pixel at (x, y) changed
+    pub fn is_changed(&self, x: u32, y: u32) -> bool{
+        x < self.width && y < self.height && self.changed[(y * self.width + x) as usize]
+    }
+
+    /// Build a visual overlay of the diff on top of `base` - unchanged pixels are dimmed,
+    /// and changed pixels are painted with `highlight`.
+    pub fn overlay(&self, base: &Snapshot, highlight: [u8; 4]) -> Snapshot{
+        let mut pixels = Vec::<u8>::with_capacity((self.width * self.height * 4) as usize);
+        for y in 0..self.height{
+            for x in 0..self.width{
+                if self.is_changed(x, y){
+                    pixels.extend_from_slice(&highlight);
+                }else if x < base.width() && y < base.height(){
+                    let pixel = base.get_pixel(x, y);
+                    pixels.extend_from_slice(&[pixel[0] / 3, pixel[1] / 3, pixel[2] / 3, pixel[3]]);
+                }else{
+                    pixels.extend_from_slice(&[0, 0, 0, 255]);
+                }
+            }
+        }
+
+        Snapshot::from_rgba(self.width, self.height, pixels)
+    }
+}
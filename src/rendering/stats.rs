@@ -0,0 +1,62 @@
+//! This module contains `FrameStats`, which the renderer fills in as it draws
+//! so applications can see where their frame time goes.
+
+use std::time::Duration;
+
+/// # FrameStats
+///
+/// Statistics about the last frame the renderer drew. Read them with `Renderer::frame_stats()`,
+/// for example to show a debug HUD or to log performance regressions.
+///
+/// The GPU timings are only filled in while profiling is enabled (`Renderer::set_profiling`).
+/// wgpu doesn't expose timestamp queries yet, so they're measured by waiting on the GPU after
+/// each pass - they include the time spent recording and submitting the pass, so treat them as an
+/// upper bound, and compare them against each other rather than against other tools.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStats{
+    /// Time between the start of the previous frame and the start of this one
+    pub frame_time: Duration,
+    /// Time the CPU spent on the prepass and on recording and submitting the frame (not counting waiting for vsync)
+    pub cpu_time: Duration,
+    /// Number of enabled components that were drawn, including ones drawn into caches
+    pub components_rendered: usize,
+    /// Number of visible components that weren't drawn, as they were entirely outside the window or their layout's clip rect
+    pub components_culled: usize,
+    /// Number of draw calls, counting one per drawn component and one per text flush
+    pub draw_calls: usize,
+    /// Number of glyphs queued for drawing
+    pub glyphs_queued: usize,
+    /// How long the component (quad) pass took
+    pub gpu_component_time: Option<Duration>,
+    /// How long the text (glyph) pass took
+    pub gpu_text_time: Option<Duration>,
+    /// The size of the glyph cache, in pixels
+    pub glyph_cache_size: (u32, u32),
+    /// Roughly how many pixels of the glyph cache the text drawn this frame takes up. Only counted while the performance
+    /// overlay is shown (`Renderer::set_perf_overlay`).
+    pub glyph_cache_used: Option<u64>,
+}
+
+impl FrameStats{
+    /// Frames per second, based on the last frame time
+    pub fn fps(&self) -> f32{
+        let seconds = self.frame_time.as_secs_f32();
+        if seconds > 0.0 { 1.0 / seconds } else { 0.0 }
+    }
+
+    /// How full the glyph cache is, from 0 to 1, if it's being counted (see `glyph_cache_used`)
+    pub fn glyph_cache_occupancy(&self) -> Option<f32>{
+        let (width, height) = self.glyph_cache_size;
+        let area = (width as u64 * height as u64).max(1);
+        self.glyph_cache_used.map(|used| used as f32 / area as f32)
+    }
+
+    // Clear the per-frame counters before a new frame is drawn
+    pub(crate) fn reset_counters(&mut self){
+        self.components_rendered = 0;
+        self.components_culled = 0;
+        self.draw_calls = 0;
+        self.glyphs_queued = 0;
+        self.glyph_cache_used = self.glyph_cache_used.map(|_| 0);
+    }
+}
@@ -0,0 +1,66 @@
+//! This file lets wgpu draw into winit's windows. wgpu 0.6 takes windows through raw-window-handle 0.3, but winit 0.28
+//! hands them out through raw-window-handle 0.5, so `SurfaceHandle` converts one into the other.
+
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use rwh_05::{HasRawDisplayHandle, HasRawWindowHandle as HasRawWindowHandle05, RawDisplayHandle, RawWindowHandle as RawWindowHandle05};
+
+/// # SurfaceHandle
+///
+/// A winit window, as the raw-window-handle 0.3 handle `wgpu::Instance::create_surface` takes
+pub(crate) struct SurfaceHandle<'a>(pub &'a winit::window::Window);
+
+// Safety: the handle is built from the window's own handles, which stay valid for as long as the window is borrowed
+unsafe impl HasRawWindowHandle for SurfaceHandle<'_>{
+    fn raw_window_handle(&self) -> RawWindowHandle{
+        convert(self.0.raw_window_handle(), self.0.raw_display_handle())
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+fn convert(window: RawWindowHandle05, display: RawDisplayHandle) -> RawWindowHandle{
+    use raw_window_handle::unix::{WaylandHandle, XcbHandle, XlibHandle};
+    match (window, display){
+        (RawWindowHandle05::Xlib(window), RawDisplayHandle::Xlib(display)) => RawWindowHandle::Xlib(XlibHandle{
+            window: window.window,
+            display: display.display,
+            ..XlibHandle::empty()
+        }),
+        (RawWindowHandle05::Xcb(window), RawDisplayHandle::Xcb(display)) => RawWindowHandle::Xcb(XcbHandle{
+            window: window.window,
+            connection: display.connection,
+            ..XcbHandle::empty()
+        }),
+        (RawWindowHandle05::Wayland(window), RawDisplayHandle::Wayland(display)) => RawWindowHandle::Wayland(WaylandHandle{
+            surface: window.surface,
+            display: display.display,
+            ..WaylandHandle::empty()
+        }),
+        _ => unreachable!("winit only creates X11 and Wayland windows here"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn convert(window: RawWindowHandle05, _display: RawDisplayHandle) -> RawWindowHandle{
+    use raw_window_handle::windows::WindowsHandle;
+    match window{
+        RawWindowHandle05::Win32(window) => RawWindowHandle::Windows(WindowsHandle{
+            hwnd: window.hwnd,
+            hinstance: window.hinstance,
+            ..WindowsHandle::empty()
+        }),
+        _ => unreachable!("winit only creates Win32 windows here"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn convert(window: RawWindowHandle05, _display: RawDisplayHandle) -> RawWindowHandle{
+    use raw_window_handle::macos::MacOSHandle;
+    match window{
+        RawWindowHandle05::AppKit(window) => RawWindowHandle::MacOS(MacOSHandle{
+            ns_window: window.ns_window,
+            ns_view: window.ns_view,
+            ..MacOSHandle::empty()
+        }),
+        _ => unreachable!("winit only creates AppKit windows here"),
+    }
+}
@@ -0,0 +1,96 @@
+//! This module contains the `TextQueue`, which components queue their text into (see `Component::queue_text`). Every
+//! section in a window is drawn with a single glyph brush draw, so instead of a draw per clip rect, each section
+//! carries its own - text inside a clipped layout (like a scrolling list) is cut off at the clip rect's edges.
+
+use std::{borrow::Cow, collections::HashSet};
+
+use wgpu_glyph::{ab_glyph::{point, Font, Rect, ScaleFont}, GlyphCruncher, GlyphPositioner, Section, SectionGeometry, SectionGlyph};
+
+/// # TextQueue
+///
+/// The text drawn in the current frame. `queue` sections into it, and the renderer draws them all on top of the
+/// components. While a layout with a clip rect is queued, sections are limited to it (see `clip_rect`).
+pub struct TextQueue<'a>{
+    brush: &'a mut wgpu_glyph::GlyphBrush<()>,
+    clip: Option<[f32; 4]>,
+    // The distinct glyphs queued (by font, glyph and scale), while they're being counted for the performance overlay
+    glyphs: Option<HashSet<(usize, u16, u32, u32)>>,
+    glyph_pixels: u64,
+}
+
+impl<'a> TextQueue<'a>{
+    /// Queue text with `brush`, without a clip rect
+    pub fn new(brush: &'a mut wgpu_glyph::GlyphBrush<()>) -> Self{
+        Self{
+            brush,
+            clip: None,
+            glyphs: None,
+            glyph_pixels: 0,
+        }
+    }
+
+    // Start counting the glyph cache space the queued text takes up (see `glyph_pixels`)
+    pub(crate) fn track_glyphs(&mut self){
+        self.glyphs.get_or_insert_with(HashSet::new);
+    }
+
+    // Roughly how many pixels of the glyph cache the distinct glyphs queued so far take up, if they're being counted
+    pub(crate) fn glyph_pixels(&self) -> Option<u64>{
+        self.glyphs.as_ref().map(|_| self.glyph_pixels)
+    }
+
+    /// Queue a section, to be drawn at the end of the frame. It's cut off at the clip rect, if there is one, as well as
+    /// at its own bounds.
+    pub fn queue<'s, S: Into<Cow<'s, Section<'s>>>>(&mut self, section: S){
+        let section = section.into();
+        if self.glyphs.is_some(){
+            self.count_glyphs(&section);
+        }
+        let clip = match self.clip{
+            Some(v) => v,
+            None => return self.brush.queue(section),
+        };
+        let bounds = section.layout.bounds_rect(&SectionGeometry::from(&*section));
+        let bounds = Rect{
+            min: point(bounds.min.x.max(clip[0]), bounds.min.y.max(clip[1])),
+            max: point(bounds.max.x.min(clip[0] + clip[2]), bounds.max.y.min(clip[1] + clip[3])),
+        };
+        if bounds.max.x <= bounds.min.x || bounds.max.y <= bounds.min.y{
+            return;
+        }
+        // Positioned glyphs can be given any bounds, which the brush clips them to when it builds their quads
+        let glyphs = self.brush.glyphs(&*section).cloned().collect();
+        let extra = section.text.iter().map(|text| text.extra).collect();
+        self.brush.queue_pre_positioned(glyphs, extra, bounds);
+    }
+
+    // Add the glyphs of `section` that haven't been seen yet to the pixel count. Each one is counted as its advance by
+    // the line height, as outlining them all would cost more than the overlay is worth.
+    fn count_glyphs(&mut self, section: &Section){
+        let glyphs: Vec<SectionGlyph> = self.brush.glyphs(section).cloned().collect();
+        let fonts = self.brush.fonts();
+        let seen = match &mut self.glyphs{
+            Some(v) => v,
+            None => return,
+        };
+        for SectionGlyph{ glyph, font_id, .. } in glyphs{
+            if seen.insert((font_id.0, glyph.id.0, glyph.scale.x.to_bits(), glyph.scale.y.to_bits())){
+                let font = fonts[font_id.0].as_scaled(glyph.scale);
+                self.glyph_pixels += (font.h_advance(glyph.id) * font.height()).ceil() as u64;
+            }
+        }
+    }
+
+    /// The rect (as [left, top, width, height] in pixels from the top left of the window) sections are being cut off
+    /// at, if any
+    pub fn clip_rect(&self) -> Option<[f32; 4]>{
+        self.clip
+    }
+
+    /// Cut the sections queued from now on off at `rect`, or stop clipping them with `None`. The renderer sets this to
+    /// each layout's clip rect while its components queue their text. Components clipping their own text should keep
+    /// inside the current rect, and put it back when they're done.
+    pub fn set_clip_rect(&mut self, rect: Option<[f32; 4]>){
+        self.clip = rect;
+    }
+}
@@ -1,102 +1,162 @@
-//! This module controls the various functions to load and store
-//! textures for use in the GUI app. It works by storing textures
-//! and the various buffers/bind groups in a hashmap as a pool,
-//! to avoid reloading textures over and over.
+//! This module loads textures for use in the GUI app - images uploaded to the GPU once, that components
+//! (like a skinned `Button`) can then draw. Textures are reference counted, so the same image can be shared
+//! between components (or between the states of a button) without uploading it again.
+//!
+//! Parts of a texture can be drawn on their own with a `TextureRegion`, so lots of small images can be packed into one atlas.
 
-use std::{collections::HashMap, ops::Deref};
-use super::{Renderer, UniformUtils, render};
-use image::GenericImageView;
-use wgpu::BindGroup;
+use std::rc::Rc;
 
+use super::{MemoryKind, Renderer, Tracked};
 
-pub struct TexturePool{
-    pub pool: HashMap<&'static str, wgpu::BindGroup>
+/// # Texture
+///
+/// An RGBA image on the GPU. Clones are cheap, and share the same texture.
+#[derive(Clone)]
+pub struct Texture{
+    inner: Rc<TextureInner>,
 }
 
-impl TexturePool{
-    pub fn new() -> Self{
-        Self{
-            pool: HashMap::<&'static str, wgpu::BindGroup>::new()
-        }
-    }
-
-    pub fn add_texture(&mut self, name: &'static str, texture: BindGroup){
-        self.pool.insert(name, texture);
-    }
+struct TextureInner{
+    _texture: Tracked<wgpu::Texture>,
+    view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    size: (u32, u32),
+}
 
-    pub fn get_texture(&self, name: &'static str) -> Option<&BindGroup>{
-        self.pool.get(name)
+impl std::fmt::Debug for Texture{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("Texture").field("size", &self.inner.size).finish()
     }
 }
 
-pub struct Texture{
-    texture: wgpu::Texture,
-    view: wgpu::TextureView,
-    sampler: wgpu::Sampler,
-
-    bind_group: wgpu::BindGroup,
-    bind_group_layout: wgpu::BindGroupLayout
+impl PartialEq for Texture{
+    fn eq(&self, other: &Self) -> bool{
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
 }
 
 impl Texture{
-    pub fn from_path(path: &'static str, renderer: &Renderer) -> Self{
-
-        let loaded_image = image::open(path).expect("image failed to load");
-        let rgba = loaded_image.as_rgba8().expect("Image failed to load/convert as RGBA8!");
-        let dimensions = loaded_image.dimensions();
-
-        let queue = renderer.queue;
-        let device = renderer.device;
-
+    /// Upload an `image` buffer. Fails if the image is empty.
+    pub fn from_image(image: &image::RgbaImage, renderer: &Renderer) -> Result<Self, &'static str>{
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0{
+            return Err("Error, texture image is empty!");
+        }
         let size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
+            width,
+            height,
             depth: 1,
         };
 
-        let texture = device.create_texture(
-            &wgpu::TextureDescriptor {
-                label: Some("Image"),
-                size,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
-            }
-        );
-
-        queue.write_texture(
+        let texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+        renderer.queue.write_texture(
             wgpu::TextureCopyView {
                 texture: &texture,
                 mip_level: 0,
                 origin: wgpu::Origin3d::ZERO,
             },
-            &rgba,
+            image,
             wgpu::TextureDataLayout {
                 offset: 0,
-                bytes_per_row: 4 * dimensions.0,
-                rows_per_image: dimensions.1,
+                bytes_per_row: 4 * width,
+                rows_per_image: height,
             },
             size,
         );
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = device.create_sampler(
-            &wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Nearest,
-                mipmap_filter: wgpu::FilterMode::Nearest,
-                ..Default::default()
-            }
-        );
+        let sampler = renderer.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Ok(Self{
+            inner: Rc::new(TextureInner{
+                _texture: Tracked::new(texture, MemoryKind::Texture, 4 * width as u64 * height as u64),
+                view,
+                sampler,
+                size: (width, height),
+            }),
+        })
+    }
 
-        todo!()
+    /// Load a texture from an image file (any format the `image` crate supports)
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P, renderer: &Renderer) -> Result<Self, &'static str>{
+        match image::open(path){
+            Ok(image) => Self::from_image(&image.to_rgba8(), renderer),
+            Err(_) => Err("Error, failed to load texture image!"),
+        }
+    }
+
+    /// The size of the texture in pixels
+    pub fn size(&self) -> (u32, u32){
+        self.inner.size
+    }
+
+    /// A part of the texture, as [x, y, width, height] in pixels from the top left (eg, one image in an atlas)
+    pub fn region(&self, rect: [u32; 4]) -> TextureRegion{
+        let (width, height) = (self.inner.size.0 as f32, self.inner.size.1 as f32);
+        TextureRegion{
+            texture: self.clone(),
+            uv: [rect[0] as f32 / width, rect[1] as f32 / height, rect[2] as f32 / width, rect[3] as f32 / height],
+        }
     }
+
+    pub(crate) fn view(&self) -> &wgpu::TextureView{
+        &self.inner.view
+    }
+
+    pub(crate) fn sampler(&self) -> &wgpu::Sampler{
+        &self.inner.sampler
+    }
+}
+
+/// # TextureRegion
+///
+/// A part of a texture to draw. A whole texture converts into a region covering all of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureRegion{
+    texture: Texture,
+    // [left, top, width, height], as fractions of the texture size
+    uv: [f32; 4],
 }
 
-/// This struct holds useful utilities to create textures.
-pub struct TextureUtils;
\ No newline at end of file
+impl TextureRegion{
+    /// The texture this region is part of
+    pub fn texture(&self) -> &Texture{
+        &self.texture
+    }
+
+    /// The region as [left, top, width, height] texture coordinates, from 0.0 to 1.0
+    pub fn uv(&self) -> [f32; 4]{
+        self.uv
+    }
+
+    /// The region's size in pixels
+    pub fn size(&self) -> (u32, u32){
+        let size = self.texture.size();
+        ((self.uv[2] * size.0 as f32).round() as u32, (self.uv[3] * size.1 as f32).round() as u32)
+    }
+}
+
+impl From<Texture> for TextureRegion{
+    fn from(texture: Texture) -> Self{
+        Self{
+            texture,
+            uv: [0.0, 0.0, 1.0, 1.0],
+        }
+    }
+}
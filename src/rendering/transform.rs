@@ -1,67 +1,240 @@
 //! This module contains the `Transform` struct, which defines a transformation when rendering (and in general)
 //! This can be used to translate, scale and rotate GUI components.
 
-use wgpu::{BindGroup, Device, ShaderStage};
+use std::{cell::RefCell, rc::Rc};
 
 use cgmath::SquareMatrix;
 
-use super::UniformUtils;
+use super::{TransformParts, TransformPool, TransformSlot};
 
 
-#[rustfmt::skip]
-pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
-    1.0, 0.0, 0.0, 0.0,
-    0.0, 1.0, 0.0, 0.0,
-    0.0, 0.0, 0.5, 0.0,
-    0.0, 0.0, 0.5, 1.0,
-);
-
+/// # Transform
+///
+/// A position, rotation and scale, and its slot in the renderer's `TransformPool` the shaders read them from. Change it
+/// with the setters, which mark it dirty - the slot is rewritten the next time it's drawn.
+///
+/// Like everything else (labels, hit testing, the cursor), it's in pixels from the top left of the window: the
+/// position is the center of the component's quad, and the scale is its width and height. It rotates around its center.
+///
+/// A transform can have a parent (see `set_parent`), which makes it relative to the parent: its position is in pixels
+/// from the parent's position, it turns with the parent, and it's scaled by the parent's content scale. Moving, turning
+/// or scaling a parent moves all of its children (and their children) with it - their slots are rewritten the next time
+/// they're drawn. The parent's own scale only sizes its own quad, it doesn't stretch its children.
 pub struct Transform{
-    pub position: cgmath::Vector3::<f32>,
-    pub rotation: cgmath::Quaternion::<f32>,
-    pub scale: cgmath::Vector3::<f32>,
+    position: cgmath::Vector3::<f32>,
+    rotation: cgmath::Quaternion::<f32>,
+    scale: cgmath::Vector3::<f32>,
+    content_scale: f32,
     value: cgmath::Matrix4::<f32>,
     uniform: TransformUniform,
-    buffer: wgpu::Buffer,
-    pub bind_group: BindGroup,
+    // Set when the matrix has changed since the slot was last written
+    dirty: bool,
+    // The frame children are placed in, shared with them
+    frame: Rc<RefCell<Frame>>,
+    // The parent's world frame when the slot was last written, to notice when an ancestor changes
+    parent_world: cgmath::Matrix4::<f32>,
+    slot: TransformSlot,
+}
+
+// A transform's position, rotation and content scale as a matrix, and the frame of its parent (if it has one)
+struct Frame{
+    local: cgmath::Matrix4::<f32>,
+    parent: Option<Rc<RefCell<Frame>>>,
+}
+
+impl Frame{
+    // The frame in window pixels, with every ancestor applied
+    fn world(&self) -> cgmath::Matrix4::<f32>{
+        match &self.parent{
+            Some(parent) => parent.borrow().world() * self.local,
+            None => self.local,
+        }
+    }
 }
 impl Transform{
-    /// Create a new transform. Takes in the position, rotation and scale values.
-    pub fn new(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, scale: cgmath::Vector3::<f32>, device: &Device) -> Self{
-        let value: cgmath::Matrix4<f32> = cgmath::Matrix4::from_translation(position) * cgmath::Matrix4::from(rotation) * cgmath::Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+    /// Create a new transform. Takes in the position, rotation and scale values, and the pool it takes a slot from
+    /// (see `Renderer::transform_pool`).
+    pub fn new(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, scale: cgmath::Vector3::<f32>, pool: &TransformPool) -> Self{
+        let value = Self::matrix(position, rotation, scale);
         let mut uniform = TransformUniform::new();
         uniform.update(value);
 
-        let (buffer, bind_group, _) = UniformUtils::create(device, ShaderStage::VERTEX, 0, &uniform, "Transform");
+        let frame = Frame{
+            local: Self::frame_matrix(position, rotation, 1.0),
+            parent: None,
+        };
         
         Self{
             position,
             rotation,
             scale,
+            content_scale: 1.0,
             value,
             uniform,
-            buffer,
-            bind_group
+            dirty: false,
+            frame: Rc::new(RefCell::new(frame)),
+            parent_world: cgmath::Matrix4::identity(),
+            slot: pool.allocate(uniform),
         }
     }
 
+    /// Make the transform relative to `parent`, or to the window again with `None`. Its position, rotation and scale
+    /// aren't changed, so it usually moves. The slot is updated the next time it's drawn.
+    ///
+    /// The transform keeps a handle to the parent's frame, so if the parent is dropped first, the child stays where
+    /// the parent last put it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transform is `parent`, or one of its ancestors.
+    pub fn set_parent(&mut self, parent: Option<&Transform>) -> Result<&mut Self, &'static str>{
+        if let Some(parent) = parent{
+            let mut ancestor = Some(parent.frame.clone());
+            while let Some(frame) = ancestor{
+                if Rc::ptr_eq(&frame, &self.frame){
+                    return Err("Error, a transform can't be its own ancestor!");
+                }
+                ancestor = frame.borrow().parent.clone();
+            }
+        }
+        self.frame.borrow_mut().parent = parent.map(|parent| parent.frame.clone());
+        self.dirty = true;
+        Ok(self)
+    }
+
+    /// Returns true if the transform is relative to a parent
+    pub fn has_parent(&self) -> bool{
+        self.frame.borrow().parent.is_some()
+    }
+
+    /// How much the transform's children are scaled by (1 by default). This doesn't change the transform's own quad.
+    pub fn content_scale(&self) -> f32{
+        self.content_scale
+    }
+
+    /// Scale the transform's children (and their positions) by `scale`, to zoom a whole group at once
+    pub fn set_content_scale(&mut self, scale: f32) -> &mut Self{
+        self.content_scale = scale;
+        self.update_frame();
+        self
+    }
+
+    /// The position
+    pub fn position(&self) -> cgmath::Vector3::<f32>{
+        self.position
+    }
+
+    /// Move the transform. The slot is updated the next time it's drawn.
+    pub fn set_position(&mut self, position: cgmath::Vector3::<f32>) -> &mut Self{
+        self.position = position;
+        self.dirty = true;
+        self.update_frame();
+        self
+    }
+
+    /// The rotation
+    pub fn rotation(&self) -> cgmath::Quaternion::<f32>{
+        self.rotation
+    }
+
+    /// Rotate the transform. The slot is updated the next time it's drawn.
+    pub fn set_rotation(&mut self, rotation: cgmath::Quaternion::<f32>) -> &mut Self{
+        self.rotation = rotation;
+        self.dirty = true;
+        self.update_frame();
+        self
+    }
+
+    /// The scale
+    pub fn scale(&self) -> cgmath::Vector3::<f32>{
+        self.scale
+    }
+
+    /// Scale the transform. The slot is updated the next time it's drawn.
+    pub fn set_scale(&mut self, scale: cgmath::Vector3::<f32>) -> &mut Self{
+        self.scale = scale;
+        self.dirty = true;
+        self
+    }
+
+    /// Returns true if the transform (or one of its ancestors) has changed since its slot was last written
+    pub fn is_dirty(&self) -> bool{
+        self.dirty || (self.has_parent() && self.parent_frame() != self.parent_world)
+    }
+
     /// Force an update to the transformation matrix. This is implicitly called when rendering, so is only necessary if you need to use it before a frame is drawn.
     pub fn update(&mut self){
-        self.value = cgmath::Matrix4::from_translation(self.position) * cgmath::Matrix4::from(self.rotation) * cgmath::Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z);
+        self.parent_world = self.parent_frame();
+        self.value = self.parent_world * Self::matrix(self.position, self.rotation, self.scale);
         self.uniform.update(self.value);
     }
 
-    pub fn get_buffer(&mut self, device: &Device) -> &wgpu::Buffer{
-        let value: [[f32; 4]; 4] = self.value.into();
+    /// The matrix placing the quad in pixels from the top left of the window, with every ancestor applied
+    pub fn world_matrix(&self) -> cgmath::Matrix4::<f32>{
+        self.parent_frame() * Self::matrix(self.position, self.rotation, self.scale)
+    }
+
+    /// The quad's center, in pixels from the top left of the window (with every ancestor applied)
+    pub fn world_position(&self) -> cgmath::Vector3::<f32>{
+        (self.parent_frame() * self.position.extend(1.0)).truncate()
+    }
+
+    /// The area the quad covers, as [left, top, width, height] in pixels from the top left of the window. With a
+    /// parent, or a rotation, this is the box around the quad as it's drawn.
+    pub fn rect(&self) -> [f32; 4]{
+        let matrix = self.world_matrix();
+        let corners = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]]
+            .iter()
+            .map(|[x, y]| matrix * cgmath::Vector4::new(*x, *y, 0.0, 1.0))
+            .collect::<Vec<_>>();
+        let left = corners.iter().map(|corner| corner.x).fold(f32::INFINITY, f32::min);
+        let top = corners.iter().map(|corner| corner.y).fold(f32::INFINITY, f32::min);
+        let right = corners.iter().map(|corner| corner.x).fold(f32::NEG_INFINITY, f32::max);
+        let bottom = corners.iter().map(|corner| corner.y).fold(f32::NEG_INFINITY, f32::max);
+        [left, top, right - left, bottom - top]
+    }
+
+    // The parent's frame in window pixels, or the identity without a parent
+    fn parent_frame(&self) -> cgmath::Matrix4::<f32>{
+        match &self.frame.borrow().parent{
+            Some(parent) => parent.borrow().world(),
+            None => cgmath::Matrix4::identity(),
+        }
+    }
+
+    // Write the frame children are placed in, so they see the change the next time they're drawn
+    fn update_frame(&mut self){
+        self.frame.borrow_mut().local = Self::frame_matrix(self.position, self.rotation, self.content_scale);
+    }
+
+    // Children are placed from the transform's position, turned with it, and scaled by its content scale
+    fn frame_matrix(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, content_scale: f32) -> cgmath::Matrix4::<f32>{
+        cgmath::Matrix4::from_translation(position) * cgmath::Matrix4::from(rotation) * cgmath::Matrix4::from_scale(content_scale)
+    }
 
-        if  value != self.uniform.transform{
-            self.update();
-            let (buffer, bind_group, _) = UniformUtils::create(device, ShaderStage::VERTEX, 0, &self.uniform, "Transform");
-            self.buffer = buffer;
-            self.bind_group = bind_group;
+    // The quad spans -1 to 1 with y going up, so it's halved and flipped to fit `scale` pixels going down
+    pub(crate) fn matrix(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, scale: cgmath::Vector3::<f32>) -> cgmath::Matrix4::<f32>{
+        cgmath::Matrix4::from_translation(position) * cgmath::Matrix4::from(rotation) * cgmath::Matrix4::from_nonuniform_scale(scale.x / 2.0, -scale.y / 2.0, scale.z)
+    }
+
+    /// Write the transform into its slot if it has changed. Components call this in `prerender`, and the pool builds the
+    /// matrix (with every other changed transform's) and uploads it before the frame is submitted.
+    pub fn write_uniform(&mut self){
+        if self.is_dirty(){
+            self.parent_world = self.parent_frame();
+            self.slot.write_parts(TransformParts{
+                parent: self.parent_world,
+                position: self.position,
+                rotation: self.rotation,
+                scale: self.scale,
+            });
+            self.dirty = false;
         }
+    }
 
-        &self.buffer
+    /// Bind the transform's slot as set 1, where the renderer's pipelines read it
+    pub fn bind<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>){
+        self.slot.bind(render_pass, 1);
     }
 }
 
@@ -76,6 +249,13 @@ impl Transform{
 pub struct TransformUniform{
     transform: [[f32; 4]; 4] // Store our rotation as a 4x4 matrix
 }
+
+impl Default for TransformUniform{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
 impl TransformUniform{
     pub fn new() -> Self{
         Self{
@@ -83,9 +263,16 @@ impl TransformUniform{
         }
     }
 
+    /// Create a uniform that maps the quad onto a rectangle, given as [left, top, width, height] in pixels from the top left
+    pub fn from_pixel_rect(rect: [f32; 4]) -> Self{
+        let center = cgmath::Vector3::new(rect[0] + rect[2] / 2.0, rect[1] + rect[3] / 2.0, 0.0);
+        let mut uniform = Self::new();
+        uniform.update(Transform::matrix(center, cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0), cgmath::Vector3::new(rect[2], rect[3], 1.0)));
+        uniform
+    }
+
+    /// Set the matrix, which places the quad in pixels from the top left (the camera maps that to the screen)
     pub fn update(&mut self, value: cgmath::Matrix4::<f32>){
-        let value = value * OPENGL_TO_WGPU_MATRIX;
         self.transform = value.into();
-        
     }
 }
\ No newline at end of file
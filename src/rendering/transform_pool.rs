@@ -0,0 +1,399 @@
+//! This module contains the transform pool - one large uniform buffer holding the matrix of every transform the renderer
+//! draws. Each transform gets a slot in it, which is bound with a dynamic offset, so there's one bind group for all of
+//! them instead of a buffer and bind group each. Changed slots are uploaded together, with a single `write_buffer`
+//! before each frame is submitted.
+//!
+//! The pool grows (doubling) when it runs out of slots. Slots are freed when they're dropped, for the next transform.
+//!
+//! Transforms don't build their matrices themselves - they hand the pool the parts (see `TransformParts`), and the
+//! matrices are built together in `flush`. With the `parallel` feature, that's spread across threads once enough
+//! transforms have changed (see `PARALLEL_THRESHOLD`).
+
+use std::{cell::RefCell, num::NonZeroU64, rc::Rc, sync::Arc};
+
+use wgpu::util::DeviceExt;
+
+use super::{MemoryKind, Tracked, Transform, TransformUniform, UniformUtils};
+
+/// How far apart slots are in the buffer, as dynamic offsets have to be aligned to this
+pub const TRANSFORM_SLOT_SIZE: wgpu::BufferAddress = wgpu::BIND_BUFFER_ALIGNMENT;
+
+// How many slots the pool starts with
+const INITIAL_SLOTS: u32 = 64;
+
+/// How many changed slots it takes for `flush` to build their matrices across threads, with the `parallel` feature.
+/// Below this, handing the work out costs more than it saves.
+pub const PARALLEL_THRESHOLD: usize = 512;
+
+/// # TransformParts
+///
+/// What a transform's matrix is built from - its parent's frame (with every ancestor applied), and its position, rotation
+/// and scale. It's plain data, so matrices can be built on any thread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformParts{
+    pub parent: cgmath::Matrix4::<f32>,
+    pub position: cgmath::Vector3::<f32>,
+    pub rotation: cgmath::Quaternion::<f32>,
+    pub scale: cgmath::Vector3::<f32>,
+}
+
+impl TransformParts{
+    /// The matrix placing the quad in pixels from the top left of the window
+    pub fn matrix(&self) -> cgmath::Matrix4::<f32>{
+        self.parent * Transform::matrix(self.position, self.rotation, self.scale)
+    }
+}
+
+/// # SlotAllocator
+///
+/// The bookkeeping of a `TransformPool`'s slots - which are in use, which are free for the next transform, how many the
+/// buffer has room for, and which have changed since it was last written. It only deals in slot indices, so it doesn't
+/// need a device.
+#[derive(Debug, Clone)]
+pub(crate) struct SlotAllocator{
+    capacity: u32,
+    // How many slots have ever been handed out, including the freed ones
+    allocated: u32,
+    free: Vec<u32>,
+    // The slots changed since the buffer was last written, as an inclusive range
+    dirty: Option<(u32, u32)>,
+}
+
+impl SlotAllocator{
+    /// Create an allocator with room for `capacity` slots before it has to grow
+    pub fn new(capacity: u32) -> Self{
+        Self{
+            capacity: capacity.max(1),
+            allocated: 0,
+            free: Vec::new(),
+            dirty: None,
+        }
+    }
+
+    /// Take a slot, reusing the last one freed if there is one. Returns its index, and whether the capacity grew
+    /// (doubling) to fit it - in which case the whole buffer has to be created again, so the slot isn't marked changed.
+    pub fn allocate(&mut self) -> (u32, bool){
+        let index = match self.free.pop(){
+            Some(index) => index,
+            None => {
+                self.allocated += 1;
+                self.allocated - 1
+            }
+        };
+        if index < self.capacity{
+            self.mark_dirty(index);
+            return (index, false);
+        }
+        while self.capacity <= index{
+            self.capacity *= 2;
+        }
+        (index, true)
+    }
+
+    /// Give a slot back, for the next `allocate`
+    pub fn free(&mut self, index: u32){
+        self.free.push(index);
+    }
+
+    /// How many slots are in use
+    pub fn len(&self) -> usize{
+        (self.allocated as usize) - self.free.len()
+    }
+
+    /// How many slots there's room for before it has to grow
+    pub fn capacity(&self) -> u32{
+        self.capacity
+    }
+
+    /// The offset of slot `index` in the buffer, which it's bound at
+    pub fn offset(index: u32) -> u32{
+        index * TRANSFORM_SLOT_SIZE as u32
+    }
+
+    /// Note that slot `index` changed, so it's written with the next `take_dirty`
+    pub fn mark_dirty(&mut self, index: u32){
+        self.dirty = Some(match self.dirty{
+            Some((first, last)) => (first.min(index), last.max(index)),
+            None => (index, index),
+        });
+    }
+
+    /// The range of slots (inclusive) changed since this was last called, if any were
+    pub fn take_dirty(&mut self) -> Option<(u32, u32)>{
+        self.dirty.take()
+    }
+}
+
+/// # TransformPool
+///
+/// The buffer every transform's matrix lives in (see the module docs). The renderer owns one - get it with
+/// `Renderer::transform_pool` to create transforms. Cloning it gives another handle to the same pool.
+#[derive(Clone)]
+pub struct TransformPool{
+    inner: Rc<RefCell<PoolInner>>,
+}
+
+struct PoolInner{
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    layout: wgpu::BindGroupLayout,
+    buffer: Tracked<wgpu::Buffer>,
+    bind_group: Rc<wgpu::BindGroup>,
+    slots: SlotAllocator,
+    // What each slot holds, so the buffer can be filled again when it grows
+    uniforms: Vec<TransformUniform>,
+    // The parts of the slots whose matrices haven't been built yet, by slot
+    parts: Vec<Option<TransformParts>>,
+    // Bumped whenever the buffer (and so the bind group) is replaced
+    generation: u32,
+}
+
+impl TransformPool{
+    /// Create an empty pool
+    pub fn new(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>) -> Self{
+        let layout = Self::create_bind_group_layout(&device);
+        let (buffer, bind_group) = create_buffer(&device, &layout, INITIAL_SLOTS, &[]);
+        Self{
+            inner: Rc::new(RefCell::new(PoolInner{
+                device,
+                queue,
+                layout,
+                buffer,
+                bind_group: Rc::new(bind_group),
+                slots: SlotAllocator::new(INITIAL_SLOTS),
+                uniforms: Vec::new(),
+                parts: Vec::new(),
+                generation: 0,
+            })),
+        }
+    }
+
+    /// Create the bind group layout transforms are bound with (set 1 of the renderer's pipelines) - a single
+    /// `TransformUniform` at binding 0, with a dynamic offset. Pipelines that draw pooled transforms need it.
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout{
+        let size = NonZeroU64::new(std::mem::size_of::<TransformUniform>() as u64);
+        UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, true, size, "Transform pool layout")
+    }
+
+    /// Take a free slot, holding `uniform`
+    pub fn allocate(&self, uniform: TransformUniform) -> TransformSlot{
+        let mut inner = self.inner.borrow_mut();
+        let (index, grew) = inner.slots.allocate();
+        if index as usize == inner.uniforms.len(){
+            inner.uniforms.push(uniform);
+            inner.parts.push(None);
+        }else{
+            inner.uniforms[index as usize] = uniform;
+            inner.parts[index as usize] = None;
+        }
+        if grew{
+            inner.grow();
+        }
+
+        TransformSlot{
+            pool: self.inner.clone(),
+            index,
+            bind_group: inner.bind_group.clone(),
+            generation: inner.generation,
+        }
+    }
+
+    /// How many slots are in use
+    pub fn len(&self) -> usize{
+        self.inner.borrow().slots.len()
+    }
+
+    /// Returns true if no slots are in use
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    /// How many slots the buffer has room for before it has to grow
+    pub fn capacity(&self) -> u32{
+        self.inner.borrow().slots.capacity()
+    }
+
+    /// The size of the buffer, in bytes
+    pub fn buffer_size(&self) -> u64{
+        self.capacity() as u64 * TRANSFORM_SLOT_SIZE
+    }
+
+    /// Build the matrices of the slots written with `TransformSlot::write_parts`, then write the slots that changed into
+    /// the buffer. The renderer does this before it submits a frame.
+    pub fn flush(&self){
+        self.inner.borrow_mut().flush();
+    }
+}
+
+impl PoolInner{
+    fn flush(&mut self){
+        if let Some((first, last)) = self.slots.take_dirty(){
+            let range = first as usize..=last as usize;
+            build_matrices(&mut self.uniforms[range.clone()], &mut self.parts[range]);
+            let bytes = padded(&self.uniforms[first as usize..=last as usize]);
+            self.queue.write_buffer(&self.buffer, first as u64 * TRANSFORM_SLOT_SIZE, &bytes);
+        }
+    }
+
+    // Move to a buffer of the slots' new capacity. Slots still holding the old bind group keep drawing from the old buffer
+    // until they're next written, so anything changed is written there first.
+    fn grow(&mut self){
+        self.flush();
+        let capacity = self.slots.capacity();
+        tracing::debug!(capacity, "Growing the transform pool");
+
+        let (buffer, bind_group) = create_buffer(&self.device, &self.layout, capacity, &self.uniforms);
+        self.buffer = buffer;
+        self.bind_group = Rc::new(bind_group);
+        self.generation += 1;
+    }
+}
+
+// Build the matrix of every slot that has parts waiting, spread across threads if there are enough of them
+#[cfg(feature = "parallel")]
+fn build_matrices(uniforms: &mut [TransformUniform], parts: &mut [Option<TransformParts>]){
+    use rayon::prelude::*;
+
+    if parts.len() < PARALLEL_THRESHOLD{
+        return build_matrices_serial(uniforms, parts);
+    }
+    uniforms.par_iter_mut().zip(parts.par_iter_mut()).for_each(|(uniform, parts)| {
+        if let Some(parts) = parts.take(){
+            uniform.update(parts.matrix());
+        }
+    });
+}
+
+#[cfg(not(feature = "parallel"))]
+fn build_matrices(uniforms: &mut [TransformUniform], parts: &mut [Option<TransformParts>]){
+    build_matrices_serial(uniforms, parts);
+}
+
+fn build_matrices_serial(uniforms: &mut [TransformUniform], parts: &mut [Option<TransformParts>]){
+    for (uniform, parts) in uniforms.iter_mut().zip(parts.iter_mut()){
+        if let Some(parts) = parts.take(){
+            uniform.update(parts.matrix());
+        }
+    }
+}
+
+// Create a buffer of `capacity` slots, starting with `uniforms`, and a bind group reading one slot of it at a time
+fn create_buffer(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, capacity: u32, uniforms: &[TransformUniform]) -> (Tracked<wgpu::Buffer>, wgpu::BindGroup){
+    let mut contents = padded(uniforms);
+    contents.resize((capacity as u64 * TRANSFORM_SLOT_SIZE) as usize, 0);
+    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+        label: Some("Transform pool"),
+        contents: &contents,
+        usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+    });
+    let buffer = Tracked::new(buffer, MemoryKind::UniformBuffer, contents.len() as u64);
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry{
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(buffer.slice(..std::mem::size_of::<TransformUniform>() as u64)),
+            }
+        ],
+        label: Some("Transform pool bind group"),
+    });
+    (buffer, bind_group)
+}
+
+// The uniforms laid out a slot apart, as they are in the buffer
+fn padded(uniforms: &[TransformUniform]) -> Vec<u8>{
+    let mut bytes = vec![0; uniforms.len() * TRANSFORM_SLOT_SIZE as usize];
+    for (slot, uniform) in bytes.chunks_mut(TRANSFORM_SLOT_SIZE as usize).zip(uniforms){
+        let uniform = bytemuck::bytes_of(uniform);
+        slot[..uniform.len()].copy_from_slice(uniform);
+    }
+    bytes
+}
+
+/// # TransformSlot
+///
+/// A transform's place in the `TransformPool`. Write the matrix with `write` (or the parts it's built from with
+/// `write_parts`), and bind it with `bind`. The slot is freed when it's dropped.
+pub struct TransformSlot{
+    pool: Rc<RefCell<PoolInner>>,
+    index: u32,
+    bind_group: Rc<wgpu::BindGroup>,
+    generation: u32,
+}
+
+impl TransformSlot{
+    /// The slot's offset in the pool's buffer, which it's bound at
+    pub fn offset(&self) -> u32{
+        SlotAllocator::offset(self.index)
+    }
+
+    /// Set the matrix the slot holds. It's uploaded with the rest of the pool before the frame is submitted.
+    pub fn write(&mut self, uniform: TransformUniform){
+        let mut pool = self.pool.borrow_mut();
+        pool.uniforms[self.index as usize] = uniform;
+        pool.parts[self.index as usize] = None;
+        pool.slots.mark_dirty(self.index);
+        if self.generation != pool.generation{
+            self.bind_group = pool.bind_group.clone();
+            self.generation = pool.generation;
+        }
+    }
+
+    /// Set the parts the slot's matrix is built from. The matrix is built with the rest of the pool's (see `TransformPool::flush`).
+    pub fn write_parts(&mut self, parts: TransformParts){
+        let mut pool = self.pool.borrow_mut();
+        pool.parts[self.index as usize] = Some(parts);
+        pool.slots.mark_dirty(self.index);
+        if self.generation != pool.generation{
+            self.bind_group = pool.bind_group.clone();
+            self.generation = pool.generation;
+        }
+    }
+
+    /// Bind the slot as bind group `index` (1 for the renderer's pipelines)
+    pub fn bind<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, index: u32){
+        render_pass.set_bind_group(index, &self.bind_group, &[self.offset()]);
+    }
+}
+
+impl Drop for TransformSlot{
+    fn drop(&mut self){
+        let mut pool = self.pool.borrow_mut();
+        pool.parts[self.index as usize] = None;
+        pool.slots.free(self.index);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that transform pool slots are handed out in order, reused once they're freed, and that the pool doubles when
+    /// it's full, with the changed slots tracked as one range
+    #[test]
+    fn slot_allocator_test(){
+        let mut slots = SlotAllocator::new(2);
+        assert_eq!(slots.len(), 0);
+        assert_eq!(slots.allocate(), (0, false));
+        assert_eq!(slots.allocate(), (1, false));
+        assert_eq!(slots.take_dirty(), Some((0, 1)));
+        assert_eq!(slots.take_dirty(), None);
+
+        // The third slot doesn't fit, so the buffer has to be made again
+        assert_eq!(slots.allocate(), (2, true));
+        assert_eq!(slots.capacity(), 4);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots.take_dirty(), None);
+
+        // Freed slots are taken again before new ones
+        slots.free(0);
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots.allocate(), (0, false));
+        assert_eq!(slots.allocate(), (3, false));
+        assert_eq!(slots.take_dirty(), Some((0, 3)));
+        assert_eq!(slots.capacity(), 4);
+
+        assert_eq!(SlotAllocator::offset(0), 0);
+        assert_eq!(SlotAllocator::offset(3) as u64, 3 * TRANSFORM_SLOT_SIZE);
+    }
+}
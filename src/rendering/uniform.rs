@@ -3,12 +3,15 @@
 use std::num::NonZeroU64;
 use wgpu::util::DeviceExt;
 
+use super::{MemoryKind, Tracked};
+
 /// UniformUtils is a tool meant to simplify uniform buffer creation
 pub struct UniformUtils;
 
 impl UniformUtils{
     /// All in one creation tool with some default values. Returns the bind group, layout and buffer.
-    pub fn create<T: bytemuck::Pod>(device: &wgpu::Device, visibility: wgpu::ShaderStage, binding: u32, uniform: &T, label: &str) -> (wgpu::Buffer, wgpu::BindGroup, wgpu::BindGroupLayout){
+    /// This creates a new layout every time - `BindGroupCache::create_uniform` shares one instead.
+    pub fn create<T: bytemuck::Pod>(device: &wgpu::Device, visibility: wgpu::ShaderStage, binding: u32, uniform: &T, label: &str) -> (Tracked<wgpu::Buffer>, wgpu::BindGroup, wgpu::BindGroupLayout){
         let layout = UniformUtils::create_bind_group_layout(device, binding, visibility, false, None, label);
         let buffer = UniformUtils::create_uniform_buffer(device, uniform);
         let bind_group = UniformUtils::create_bind_group(device, &layout, binding, &buffer, label);
@@ -16,15 +19,16 @@ impl UniformUtils{
         (buffer, bind_group, layout)
     }
 
-    /// Create a buffer from uniform that derives from `Pod`
-    pub fn create_uniform_buffer<T: bytemuck::Pod>(device: &wgpu::Device, uniform: &T) -> wgpu::Buffer{
-        device.create_buffer_init(
+    /// Create a buffer from uniform that derives from `Pod` (counted in the memory stats, see `Tracked`)
+    pub fn create_uniform_buffer<T: bytemuck::Pod>(device: &wgpu::Device, uniform: &T) -> Tracked<wgpu::Buffer>{
+        let buffer = device.create_buffer_init(
             &wgpu::util::BufferInitDescriptor {
                 label: Some("Uniform Buffer"),
                 contents: bytemuck::cast_slice(&[*uniform]),
                 usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
             }
-        )
+        );
+        Tracked::new(buffer, MemoryKind::UniformBuffer, std::mem::size_of::<T>() as u64)
     }
 
     /// Create a new bind group layout based on various parameters
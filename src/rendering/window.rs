@@ -2,19 +2,18 @@
 //! it should make it easier to access input, modify the window and access window variables
 //! for the user of the library and the developer
 
-use winit::{dpi, event_loop::{self, EventLoop}, monitor, window};
+use winit::{dpi, event_loop::{self, EventLoop, EventLoopBuilder, EventLoopWindowTarget}, monitor::{MonitorHandle, VideoMode}, window};
 
-#[cfg(target_os = "linux")]
-use winit::platform::unix::EventLoopExtUnix;
+pub use winit::window::Theme;
 
-#[cfg(target_os = "macos")]
-use winit::platform::unix::EventLoopExtUnix;
 
-#[cfg(target_os = "windows")]
-use winit::platform::windows::EventLoopExtWindows;
+use winit::event::VirtualKeyCode;
 
+use crate::{event::GuiEvent, sender::EventLoopMessage};
 
-use winit::event::Event;
+/// The signature of the user defined event callback handler. It gets every event for the main window that a component didn't consume,
+/// and can change the app's state through `Renderer::app_state_mut`.
+pub type EventCallback = Box<dyn FnMut(&GuiEvent, &mut window::Window, &mut crate::rendering::Renderer)>;
 
 /// # Window
 ///
@@ -30,25 +29,252 @@ use winit::event::Event;
 /// Once the window is build, set the event handler using `set_event_handler`
 pub struct Window{
     pub window: window::Window,
-    pub event_loop: Option<event_loop::EventLoop<()>>,
-    pub event_callback_handler: Option<Box<dyn Fn(&Event<()>, &mut window::Window, &mut crate::rendering::Renderer) -> ()>>,
+    pub event_loop: Option<event_loop::EventLoop<EventLoopMessage>>,
+    pub event_callback_handler: Option<EventCallback>,
+    transparent: bool,
+    quit_key: Option<VirtualKeyCode>,
+    // Set while the window is hidden, waiting for its first frame to be drawn
+    pub(crate) show_after_first_frame: bool,
 }
 
 
 impl Window{
-    /// The default event callback handler.
+    /// The default event callback handler. It logs each event at the trace level (see the `tracing` crate).
     ///
     /// You can define your own to handle events
     ///
     /// Button presses will still be automatically handled.
-    pub fn default_event_callback(event: &Event<()>, _window: &mut window::Window, _renderer: &mut crate::rendering::Renderer){
-        println!("Event: {:?}", event);
+    pub fn default_event_callback(event: &GuiEvent, _window: &mut window::Window, _renderer: &mut crate::rendering::Renderer){
+        tracing::trace!(?event, "Unhandled event");
     }
 
     /// Sets the event callback handler. This cannot be changed once the GUI is running.
-    pub fn set_event_handler(&mut self, event_handler: Box<dyn Fn(&Event<()>, &mut window::Window, &mut crate::rendering::Renderer) -> ()>){
+    pub fn set_event_handler(&mut self, event_handler: EventCallback){
         self.event_callback_handler = Some(event_handler);
     }
+
+    /// Returns true if the window was built with a transparent background (see `WindowBuilder::set_transparent`)
+    pub fn is_transparent(&self) -> bool{
+        self.transparent
+    }
+
+    /// The key that closes the window, if there is one (see `WindowBuilder::set_quit_key`)
+    pub fn get_quit_key(&self) -> Option<VirtualKeyCode>{
+        self.quit_key
+    }
+
+    /// Change the key that closes the window, or pass `None` so no key does
+    pub fn set_quit_key(&mut self, quit_key: Option<VirtualKeyCode>){
+        self.quit_key = quit_key;
+    }
+
+    /// Move the window, setting the position of its top left corner in physical pixels on the desktop
+    pub fn set_position(&self, position: (i32, i32)){
+        self.window.set_outer_position(dpi::PhysicalPosition::new(position.0, position.1));
+    }
+
+    /// Move the window to the center of the monitor it's currently on
+    pub fn center_on_current_monitor(&self) -> Result<(), &'static str>{
+        let monitor = match self.window.current_monitor(){
+            Some(v) => v,
+            None => return Err("Error, the window isn't on a monitor!"),
+        };
+        let (monitor_pos, monitor_size) = (monitor.position(), monitor.size());
+        let window_size = self.window.outer_size();
+
+        self.set_position((
+            monitor_pos.x + (monitor_size.width as i32 - window_size.width as i32) / 2,
+            monitor_pos.y + (monitor_size.height as i32 - window_size.height as i32) / 2,
+        ));
+        Ok(())
+    }
+
+    /// Show the window, if it was built hidden
+    pub fn show(&self){
+        self.window.set_visible(true);
+    }
+
+    /// Hide the window. It keeps running, and can be shown again with `show`.
+    pub fn hide(&self){
+        self.window.set_visible(false);
+    }
+
+    /// Maximize the window
+    pub fn maximize(&self){
+        self.window.set_maximized(true);
+    }
+
+    /// Minimize the window (to the taskbar or dock)
+    pub fn minimize(&self){
+        self.window.set_minimized(true);
+    }
+
+    /// Restore the window to its normal size, if it's minimized or maximized
+    pub fn restore(&self){
+        self.window.set_minimized(false);
+        self.window.set_maximized(false);
+    }
+
+    /// Returns true if the window is maximized, however it got that way (including from its title bar)
+    pub fn is_maximized(&self) -> bool{
+        self.window.is_maximized()
+    }
+
+    /// Keep the window above other applications' windows, or let it be covered again
+    pub fn set_always_on_top(&self, always_on_top: bool){
+        self.window.set_window_level(window_level(always_on_top));
+    }
+
+    /// The theme (light or dark) the window's title bar and decorations are drawn in, if the platform reports it
+    pub fn theme(&self) -> Option<Theme>{
+        self.window.theme()
+    }
+
+    /// Draw the window's title bar and decorations in a light or dark theme, or follow the system's (`None`).
+    /// Only Windows, macOS and Wayland support this.
+    pub fn set_theme(&self, theme: Option<Theme>){
+        self.window.set_theme(theme);
+    }
+
+    /// The scale factor of the monitor the window is on (eg, 2.0 on a high DPI display). Each monitor can have its own,
+    /// and `GuiEvent::ScaleFactorChanged` is sent when the window moves to one with a different factor.
+    pub fn scale_factor(&self) -> f64{
+        self.window.scale_factor()
+    }
+
+    /// The monitor the window is on, if the platform can tell
+    pub fn current_monitor(&self) -> Option<MonitorInfo>{
+        self.window.current_monitor().map(|v| MonitorInfo::from_handle(&v))
+    }
+
+    /// List the monitors connected to the system. The index of a monitor in this list is what `WindowBuilder::set_monitor` takes.
+    pub fn available_monitors(&self) -> Vec<MonitorInfo>{
+        self.window.available_monitors().map(|v| MonitorInfo::from_handle(&v)).collect()
+    }
+
+    /// Show or hide the system cursor while it's over the window.
+    /// Note that a custom cursor (see `Renderer::set_custom_cursor`) hides the system cursor by itself.
+    pub fn set_cursor_visible(&self, visible: bool){
+        self.window.set_cursor_visible(visible);
+    }
+
+    /// Confine the cursor to the window (or release it). Not every platform supports this, in which case an error is returned.
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), &'static str>{
+        // Confined is what Windows supports, Locked is what macOS supports - try one, then the other
+        let mode = match grab{
+            true => window::CursorGrabMode::Confined,
+            false => window::CursorGrabMode::None,
+        };
+        self.window.set_cursor_grab(mode)
+            .or_else(|_| match grab{
+                true => self.window.set_cursor_grab(window::CursorGrabMode::Locked),
+                false => Ok(()),
+            })
+            .map_err(|_| "Error, failed to grab the cursor!")
+    }
+}
+
+/// # WindowBuildError
+///
+/// Why building a window failed
+#[derive(Debug)]
+pub enum WindowBuildError{
+    /// There's no monitor at the index passed to `WindowBuilder::set_monitor`
+    MonitorNotFound(usize),
+    /// The monitor doesn't support the resolution and refresh rate passed to `WindowBuilder::set_video_mode`
+    UnsupportedVideoMode{ size: (u32, u32), refresh_rate: u16 },
+    /// The window was added with `GUI::add_window` after the event loop started (use `Renderer::open_window` instead)
+    EventLoopStarted,
+    /// The platform failed to create the window
+    Os(winit::error::OsError),
+}
+
+impl std::fmt::Display for WindowBuildError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        match self{
+            WindowBuildError::MonitorNotFound(index) => write!(f, "Error, there's no monitor with index {}!", index),
+            WindowBuildError::UnsupportedVideoMode{ size, refresh_rate } => write!(f, "Error, the monitor doesn't support {}x{} at {}Hz!", size.0, size.1, refresh_rate),
+            WindowBuildError::EventLoopStarted => write!(f, "Error, the event loop has already started!"),
+            WindowBuildError::Os(e) => write!(f, "Error, failed to build window: {}!", e),
+        }
+    }
+}
+
+impl std::error::Error for WindowBuildError{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>{
+        match self{
+            WindowBuildError::Os(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for WindowBuildError{
+    fn from(e: winit::error::OsError) -> Self{
+        WindowBuildError::Os(e)
+    }
+}
+
+/// # VideoModeInfo
+///
+/// A resolution, bit depth and refresh rate a monitor can run at in exclusive fullscreen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoModeInfo{
+    pub size: (u32, u32),
+    pub bit_depth: u16,
+    pub refresh_rate: u16,
+}
+
+impl VideoModeInfo{
+    fn from_video_mode(mode: &VideoMode) -> Self{
+        Self{
+            size: (mode.size().width, mode.size().height),
+            bit_depth: mode.bit_depth(),
+            // winit reports millihertz, round to the nearest hertz
+            refresh_rate: ((mode.refresh_rate_millihertz() + 500) / 1000) as u16,
+        }
+    }
+}
+
+/// # MonitorInfo
+///
+/// A description of a monitor - its name, where it sits on the desktop, and the video modes it supports
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo{
+    /// The name of the monitor, if the platform gives one
+    pub name: Option<String>,
+    /// The position of the top left corner on the desktop, in physical pixels
+    pub position: (i32, i32),
+    /// The current resolution in physical pixels
+    pub size: (u32, u32),
+    pub scale_factor: f64,
+    pub video_modes: Vec<VideoModeInfo>,
+}
+
+impl MonitorInfo{
+    fn from_handle(monitor: &MonitorHandle) -> Self{
+        Self{
+            name: monitor.name(),
+            position: (monitor.position().x, monitor.position().y),
+            size: (monitor.size().width, monitor.size().height),
+            scale_factor: monitor.scale_factor(),
+            video_modes: monitor.video_modes().map(|v| VideoModeInfo::from_video_mode(&v)).collect(),
+        }
+    }
+
+    /// The video mode fullscreen windows use by default - the monitor's current resolution at the highest refresh rate
+    /// (and bit depth) it supports, or the largest mode if the current resolution isn't listed
+    pub fn best_video_mode(&self) -> Option<VideoModeInfo>{
+        let key = |v: &&VideoModeInfo| (v.refresh_rate, v.bit_depth);
+        self.video_modes.iter().filter(|v| v.size == self.size).max_by_key(key)
+            .or_else(|| self.video_modes.iter().max_by_key(|v| (v.size.0 as u64 * v.size.1 as u64, v.refresh_rate, v.bit_depth)))
+            .copied()
+    }
+
+    /// Find a supported video mode with this resolution and refresh rate (picking the highest bit depth)
+    pub fn find_video_mode(&self, size: (u32, u32), refresh_rate: u16) -> Option<VideoModeInfo>{
+        self.video_modes.iter().filter(|v| v.size == size && v.refresh_rate == refresh_rate).max_by_key(|v| v.bit_depth).copied()
+    }
 }
 
 /// # WindowBuilder
@@ -56,7 +282,7 @@ impl Window{
 /// This builds a window struct, based either on default values or
 /// user defined values. Meant to simplify and abstract winit's WindowBuilder,
 /// for ease of use when making GUI applications.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WindowBuilder{
     resolution: (u32, u32),
     title: String,
@@ -64,6 +290,16 @@ pub struct WindowBuilder{
     screen_mode: ScreenMode,
     resizeable: bool,
     decorations: bool,
+    transparent: bool,
+    always_on_top: bool,
+    theme: Option<Theme>,
+    position: Option<(i32, i32)>,
+    monitor: Option<usize>,
+    video_mode: Option<((u32, u32), u16)>,
+    quit_key: Option<VirtualKeyCode>,
+    start_maximized: bool,
+    visible: bool,
+    show_after_first_frame: bool,
 }
 
 /// Default init for WindowBuilder
@@ -76,7 +312,16 @@ impl Default for WindowBuilder{
             screen_mode: ScreenMode::Windowed,
             resizeable: true,
             decorations: true,
-            
+            transparent: false,
+            always_on_top: false,
+            theme: None,
+            position: None,
+            monitor: None,
+            video_mode: None,
+            quit_key: None,
+            start_maximized: false,
+            visible: true,
+            show_after_first_frame: false,
         }
     }
 }
@@ -118,79 +363,141 @@ impl WindowBuilder{
         self
     }
 
+    /// Ask the platform for a window with a transparent background, and have the renderer premultiply the clear color.
+    ///
+    /// This doesn't make the window see-through yet. wgpu 0.6 can't choose how a swapchain's alpha is composited, so frames
+    /// are presented opaque and the clear color's alpha has no effect on what's behind the window.
+    pub fn set_transparent(&mut self, transparent: bool) -> &mut Self{
+        self.transparent = transparent;
+        self
+    }
+
+    /// Keep the window above other applications' windows (useful for overlays and small utilities)
+    pub fn set_always_on_top(&mut self, always_on_top: bool) -> &mut Self{
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Draw the window's title bar and decorations in a light or dark theme. By default (`None`) it follows the system's.
+    /// Only Windows, macOS and Wayland support this.
+    pub fn set_theme(&mut self, theme: Option<Theme>) -> &mut Self{
+        self.theme = theme;
+        self
+    }
+
+    /// Set where the window appears, as the position of its top left corner in physical pixels on the desktop.
+    /// By default the platform decides.
+    pub fn set_position(&mut self, position: (i32, i32)) -> &mut Self{
+        self.position = Some(position);
+        self
+    }
+
+    /// Choose the monitor fullscreen and borderless windows cover, by its index in `Window::available_monitors()`.
+    /// By default the primary monitor is used.
+    pub fn set_monitor(&mut self, index: usize) -> &mut Self{
+        self.monitor = Some(index);
+        self
+    }
+
+    /// Choose the resolution and refresh rate for `ScreenMode::Fullscreen`. Building fails if the monitor doesn't support it.
+    /// By default the monitor's current resolution is used, at its highest refresh rate (see `MonitorInfo::best_video_mode`).
+    pub fn set_video_mode(&mut self, size: (u32, u32), refresh_rate: u16) -> &mut Self{
+        self.video_mode = Some((size, refresh_rate));
+        self
+    }
+
+    /// Set a key that closes the window when pressed (like the OS close button, so the close requested callback can veto it).
+    /// There's no quit key by default, so keys like Escape reach the components and event handler as normal.
+    pub fn set_quit_key(&mut self, quit_key: Option<VirtualKeyCode>) -> &mut Self{
+        self.quit_key = quit_key;
+        self
+    }
+
+    /// Set whether the window is visible when it's built. A hidden window can be shown later with `Window::show`.
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self{
+        self.visible = visible;
+        self
+    }
+
+    /// Keep the window hidden until the main loop has drawn its first frame, then show it. This avoids the blank
+    /// (or garbage) frame some platforms show while the app starts up. Overrides `set_visible`.
+    pub fn set_show_after_first_frame(&mut self, show_after_first_frame: bool) -> &mut Self{
+        self.show_after_first_frame = show_after_first_frame;
+        self
+    }
+
+    /// Open the window maximized
+    pub fn set_start_maximized(&mut self, start_maximized: bool) -> &mut Self{
+        self.start_maximized = start_maximized;
+        self
+    }
+
     /// Enable or disable resizing
     pub fn set_resizeable(&mut self, resizable: bool) -> &mut Self{
         self.resizeable = resizable;
         self
     }
 
-    /// Build the window and return a Window
-    pub fn build(&mut self) -> Result<Window, &'static str>{
-        // Create our winit WindowBuilder
-        let winit_builder = window::WindowBuilder::new();
-
-                
+    /// Build the window and return a Window.
+    ///
+    /// If fullscreen or borderless was asked for but there's no monitor (or the monitor lists no video modes),
+    /// the window falls back to windowed mode rather than failing.
+    pub fn build(&self) -> Result<Window, WindowBuildError>{
         // Create an event loop
-        let mut event_loop = event_loop::EventLoop::new();
-        
-  
-        // Gather information about the monitor and video modes for fullscreen and stuff
-        let mut x = 0;
-        let mut monitor: Vec<monitor::MonitorHandle> = event_loop.available_monitors().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
-        let monitor = monitor.swap_remove(0);
-        
-        let mut x = 0;
-        let mut video_modes: Vec<monitor::VideoMode> = monitor.video_modes().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
-        let video_modes = video_modes.swap_remove(0);
+        let event_loop = EventLoopBuilder::with_user_event().build();
+        let window = self.build_winit_window(&event_loop)?;
 
-        // Vsync mode - refresh rate
-        let _vsync_mode = match self.vsync{
-            true => {
-                wgpu::PresentMode::Fifo
-            }
-            false => {
-                wgpu::PresentMode::Mailbox
-            }
-        };
+        Ok(Window{
+            window,
+            event_loop: Some(event_loop),
+            event_callback_handler: Some(Box::new(Window::default_event_callback)),
+            transparent: self.transparent,
+            quit_key: self.quit_key,
+            show_after_first_frame: self.show_after_first_frame,
+        })
+    }
 
-        // Check if we're running fullscreen and/or set resolutions
-        let winit_builder = match self.screen_mode{
-            ScreenMode::Fullscreen => {
-                winit_builder.with_fullscreen(Some(window::Fullscreen::Exclusive(video_modes)))
-            }
-            ScreenMode::Windowed => {
-                winit_builder.with_inner_size(dpi::Size::from(dpi::LogicalSize{ width: self.resolution.0, height: self.resolution.1}))
-            }
-            ScreenMode::Borderless => {
-                winit_builder.with_fullscreen(Some(window::Fullscreen::Borderless(Some(monitor))))
-            }
-        };
+    /// Build the window on an event loop that can be created outside of the main thread (used by the tests)
+    ///
+    /// # Safety
+    ///
+    /// winit expects the event loop to live on the main thread on most platforms, so only use this
+    /// where that isn't possible (like the test harness).
+    pub unsafe fn build_unsafe(&self) -> Result<Window, WindowBuildError>{
+        let event_loop: EventLoop<EventLoopMessage> = build_unsafe_event_loop(); // Build a new event loop that can run on other threads (ie, multithreading support)
+        let window = self.build_winit_window(&event_loop)?;
 
-        
-        // Build the window
         Ok(Window{
-            window: winit_builder.with_resizable(self.resizeable).with_decorations(self.decorations).with_title(&self.title).build(&mut event_loop).expect("Failed to build window!"),
+            window,
             event_loop: Some(event_loop),
             event_callback_handler: Some(Box::new(Window::default_event_callback)),
+            transparent: self.transparent,
+            quit_key: self.quit_key,
+            show_after_first_frame: self.show_after_first_frame,
         })
-        
     }
 
-    pub unsafe fn build_unsafe(&mut self) -> Result<Window, &'static str>{
+    /// Build an extra window on an existing event loop, for apps with more than one window.
+    ///
+    /// Use `GUI::add_window` (before the main loop starts) or `Renderer::open_window` (while it's running)
+    /// rather than calling this directly, as the window also needs a surface to render to.
+    pub fn build_secondary(&self, target: &EventLoopWindowTarget<EventLoopMessage>) -> Result<window::Window, WindowBuildError>{
+        self.build_winit_window(target)
+    }
+
+    // Create the winit window itself, using the settings in the builder
+    fn build_winit_window(&self, target: &EventLoopWindowTarget<EventLoopMessage>) -> Result<window::Window, WindowBuildError>{
         // Create our winit WindowBuilder
         let winit_builder = window::WindowBuilder::new();
 
-        let mut event_loop: EventLoop<()> = build_unsafe_event_loop(); // Build a new event loop that can run on other threads (ie, multithreading support)
-        
-  
         // Gather information about the monitor and video modes for fullscreen and stuff
-        let mut x = 0;
-        let mut monitor: Vec<monitor::MonitorHandle> = event_loop.available_monitors().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
-        let monitor = monitor.swap_remove(0);
-        
-        let mut x = 0;
-        let mut video_modes: Vec<monitor::VideoMode> = monitor.video_modes().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
-        let video_modes = video_modes.swap_remove(0);
+        let monitor = match self.monitor{
+            Some(index) => match target.available_monitors().nth(index){
+                Some(v) => Some(v),
+                None => return Err(WindowBuildError::MonitorNotFound(index)),
+            },
+            None => target.primary_monitor().or_else(|| target.available_monitors().next()),
+        };
 
         // Vsync mode - refresh rate
         let _vsync_mode = match self.vsync{
@@ -203,45 +510,78 @@ impl WindowBuilder{
         };
 
         // Check if we're running fullscreen and/or set resolutions
-        let winit_builder = match self.screen_mode{
-            ScreenMode::Fullscreen => {
-                winit_builder.with_fullscreen(Some(window::Fullscreen::Exclusive(video_modes)))
-            }
-            ScreenMode::Windowed => {
-                winit_builder.with_inner_size(dpi::Size::from(dpi::LogicalSize{ width: self.resolution.0, height: self.resolution.1}))
-            }
-            ScreenMode::Borderless => {
-                winit_builder.with_fullscreen(Some(window::Fullscreen::Borderless(Some(monitor))))
-            }
+        // Without a monitor (or video modes), fullscreen modes fall back to a normal window
+        let fullscreen = match (self.screen_mode, monitor){
+            (ScreenMode::Fullscreen, Some(monitor)) => self.find_video_mode(&monitor)?.map(window::Fullscreen::Exclusive),
+            (ScreenMode::Borderless, Some(monitor)) => Some(window::Fullscreen::Borderless(Some(monitor))),
+            _ => None,
         };
+        let winit_builder = match fullscreen{
+            Some(fullscreen) => winit_builder.with_fullscreen(Some(fullscreen)),
+            None => winit_builder.with_inner_size(dpi::Size::from(dpi::LogicalSize{ width: self.resolution.0, height: self.resolution.1})),
+        };
+
+        // winit can't place a window as it's created, so if we have a position, create it hidden
+        // and only show it once it's been moved (otherwise it flashes up in the default spot)
+        let visible = self.visible && !self.show_after_first_frame;
+        let winit_builder = winit_builder.with_visible(visible && self.position.is_none());
 
-        
         // Build the window
-        Ok(Window{
-            window: winit_builder.with_resizable(self.resizeable).with_decorations(self.decorations).with_title(&self.title).build(&mut event_loop).expect("Failed to build window!"),
-            event_loop: Some(event_loop),
-            event_callback_handler: Some(Box::new(Window::default_event_callback)),
-        })
-        
+        let window = winit_builder.with_resizable(self.resizeable).with_decorations(self.decorations).with_transparent(self.transparent).with_window_level(window_level(self.always_on_top)).with_theme(self.theme).with_maximized(self.start_maximized).with_title(&self.title).build(target)?;
+
+        if let Some(position) = self.position{
+            window.set_outer_position(dpi::PhysicalPosition::new(position.0, position.1));
+            window.set_visible(visible);
+        }
+
+        Ok(window)
+    }
+
+    // Pick the video mode for exclusive fullscreen - the one asked for, or the monitor's best. Ok(None) means the monitor lists no modes.
+    fn find_video_mode(&self, monitor: &MonitorHandle) -> Result<Option<VideoMode>, WindowBuildError>{
+        let info = MonitorInfo::from_handle(monitor);
+        let wanted = match self.video_mode{
+            Some((size, refresh_rate)) => match info.find_video_mode(size, refresh_rate){
+                Some(v) => v,
+                None => return Err(WindowBuildError::UnsupportedVideoMode{ size, refresh_rate }),
+            },
+            None => match info.best_video_mode(){
+                Some(v) => v,
+                None => return Ok(None),
+            },
+        };
+        Ok(monitor.video_modes().find(|v| VideoModeInfo::from_video_mode(v) == wanted))
     }
 }
 
-#[cfg(target_os = "linux")]
-unsafe fn build_unsafe_event_loop() -> EventLoop<()>{
-    EventLoopExtUnix::new_any_thread()
+// The window level for a window that's (or isn't) kept above other windows
+fn window_level(always_on_top: bool) -> window::WindowLevel{
+    match always_on_top{
+        true => window::WindowLevel::AlwaysOnTop,
+        false => window::WindowLevel::Normal,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+unsafe fn build_unsafe_event_loop() -> EventLoop<EventLoopMessage>{
+    use winit::platform::x11::EventLoopBuilderExtX11;
+    // The X11 and Wayland options set the same flag, so this covers both
+    EventLoopBuilder::with_user_event().with_any_thread(true).build()
 }
 
+// macOS can only run the event loop on the main thread, so there's nothing to opt out of
 #[cfg(target_os = "macos")]
-unsafe fn build_unsafe_event_loop() -> EventLoop<()>{
-    EventLoopExtUnix::new_any_thread()
+unsafe fn build_unsafe_event_loop() -> EventLoop<EventLoopMessage>{
+    EventLoopBuilder::with_user_event().build()
 }
 
 #[cfg(target_os = "windows")]
-unsafe fn build_unsafe_event_loop() -> EventLoop<()>{
-    EventLoopExtWindows::new_any_thread()
+unsafe fn build_unsafe_event_loop() -> EventLoop<EventLoopMessage>{
+    use winit::platform::windows::EventLoopBuilderExtWindows;
+    EventLoopBuilder::with_user_event().with_any_thread(true).build()
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScreenMode{
     Fullscreen,
     Borderless,
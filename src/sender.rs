@@ -0,0 +1,92 @@
+//! This module contains `EventSender`, a handle other threads use to post events into the GUI.
+//! Background workers (eg, a download, or a file being loaded) send their results with it, which wakes up the
+//! main loop and delivers them to the layouts and the event handler as `GuiEvent::User`.
+
+use std::any::Any;
+
+use winit::event_loop::EventLoopProxy;
+
+/// # EventLoopMessage
+///
+/// What the event loop carries between threads - a user event, a finished task's result, or just a request to wake up
+/// (eg, from the automation server). Only `EventSender` creates them.
+pub struct EventLoopMessage{
+    payload: Option<Payload>,
+}
+
+// A value sent to the main loop
+pub(crate) enum Payload{
+    // Delivered as `GuiEvent::User`
+    User(Box<dyn Any + Send>),
+    // Handed to the callback of the task with this ID
+    Task(u64, Box<dyn Any + Send>),
+}
+
+impl EventLoopMessage{
+    // A message that only wakes the loop up
+    pub(crate) fn wake() -> Self{
+        Self{
+            payload: None,
+        }
+    }
+
+    // The value being carried, if any
+    pub(crate) fn into_payload(self) -> Option<Payload>{
+        self.payload
+    }
+}
+
+impl std::fmt::Debug for EventLoopMessage{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        let kind = match self.payload{
+            Some(Payload::User(_)) => "user event",
+            Some(Payload::Task(..)) => "task result",
+            None => "wake",
+        };
+        f.debug_struct("EventLoopMessage").field("kind", &kind).finish()
+    }
+}
+
+/// # EventSender
+///
+/// Posts events into the GUI from any thread. Clones are cheap, so give each worker its own.
+/// Create one with `GUI::create_event_sender`.
+#[derive(Clone)]
+pub struct EventSender{
+    proxy: EventLoopProxy<EventLoopMessage>,
+}
+
+impl std::fmt::Debug for EventSender{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("EventSender").finish()
+    }
+}
+
+impl EventSender{
+    pub(crate) fn new(proxy: EventLoopProxy<EventLoopMessage>) -> Self{
+        Self{
+            proxy,
+        }
+    }
+
+    /// Post `event` to the GUI, waking up the main loop. It arrives as `GuiEvent::User` - get it back with
+    /// `UserEvent::get::<T>()`. Fails once the event loop has exited.
+    pub fn send<T: Any + Send>(&self, event: T) -> Result<(), &'static str>{
+        self.post(EventLoopMessage{ payload: Some(Payload::User(Box::new(event))) })
+    }
+
+    /// Wake up the main loop without sending an event (eg, after changing data the next frame reads)
+    pub fn wake(&self) -> Result<(), &'static str>{
+        self.post(EventLoopMessage::wake())
+    }
+
+    // Hand a finished task's result back to the main loop
+    #[cfg_attr(not(feature = "tasks"), allow(dead_code))]
+    pub(crate) fn send_task_result(&self, id: u64, result: Box<dyn Any + Send>) -> Result<(), &'static str>{
+        self.post(EventLoopMessage{ payload: Some(Payload::Task(id, result)) })
+    }
+
+    fn post(&self, message: EventLoopMessage) -> Result<(), &'static str>{
+        self.proxy.send_event(message).map_err(|_| "Error, the event loop has exited!")
+    }
+}
@@ -0,0 +1,144 @@
+//! This module contains keyboard shortcuts (accelerators like Ctrl+S). Register them with `GUI::register_shortcut`,
+//! and the main loop runs their callbacks when they're pressed, instead of passing the key press on to the components
+//! and the event handler.
+//!
+//! While a text field has keyboard focus, shortcuts that would get in the way of typing (keys without Ctrl, Alt or
+//! Logo held, and the text editing shortcuts like Ctrl+C) are suppressed, so the text field gets them instead.
+
+use std::fmt;
+
+use crate::{event::{GuiEvent, ModifiersState, VirtualKeyCode}, rendering::Renderer};
+
+/// The signature of a shortcut callback. It takes the main window and the renderer, like the tick callback.
+pub type ShortcutCallback = Box<dyn FnMut(&mut winit::window::Window, &mut Renderer)>;
+
+/// # Shortcut
+///
+/// A key, and the exact modifiers that have to be held with it. Ctrl+S doesn't match Ctrl+Shift+S.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shortcut{
+    pub modifiers: ModifiersState,
+    pub key: VirtualKeyCode,
+}
+
+impl Shortcut{
+    /// Create a new shortcut
+    pub fn new(modifiers: ModifiersState, key: VirtualKeyCode) -> Self{
+        Self{
+            modifiers,
+            key,
+        }
+    }
+
+    /// A shortcut for a key on its own, with no modifiers
+    pub fn key(key: VirtualKeyCode) -> Self{
+        Self::new(ModifiersState::empty(), key)
+    }
+
+    /// A shortcut using the platform's command modifier - Cmd on macOS, Ctrl everywhere else
+    pub fn command(key: VirtualKeyCode) -> Self{
+        let modifiers = if cfg!(target_os = "macos") { ModifiersState::LOGO } else { ModifiersState::CTRL };
+        Self::new(modifiers, key)
+    }
+
+    /// Add Shift to the modifiers
+    pub fn with_shift(mut self) -> Self{
+        self.modifiers |= ModifiersState::SHIFT;
+        self
+    }
+
+    /// Returns true if `event` is a press of this shortcut
+    pub fn matches(&self, event: &GuiEvent) -> bool{
+        match event{
+            GuiEvent::KeyDown{ key, modifiers, .. } => *key == self.key && *modifiers == self.modifiers,
+            _ => false,
+        }
+    }
+
+    /// Returns true if a focused text field should get this key press instead - it's typing (no Ctrl, Alt or Logo held),
+    /// or a text editing shortcut (select all, copy, cut, paste, undo and redo)
+    pub fn is_text_editing(&self) -> bool{
+        if !(self.modifiers.ctrl() || self.modifiers.alt() || self.modifiers.logo()){
+            return true;
+        }
+        let command = if cfg!(target_os = "macos") { self.modifiers.logo() } else { self.modifiers.ctrl() };
+        command && matches!(self.key, VirtualKeyCode::A | VirtualKeyCode::C | VirtualKeyCode::V | VirtualKeyCode::X | VirtualKeyCode::Z | VirtualKeyCode::Y)
+    }
+}
+
+impl fmt::Display for Shortcut{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        if self.modifiers.ctrl(){
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt(){
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift(){
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.logo(){
+            write!(f, "{}+", if cfg!(target_os = "macos") { "Cmd" } else { "Logo" })?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// # ShortcutRegistry
+///
+/// The registered shortcuts and their callbacks. Each shortcut can only be registered once.
+#[derive(Default)]
+pub struct ShortcutRegistry{
+    shortcuts: Vec<(Shortcut, ShortcutCallback)>,
+}
+
+impl ShortcutRegistry{
+    /// Create a new, empty registry
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Register a shortcut. Returns an error if it's already registered.
+    pub fn register(&mut self, shortcut: Shortcut, callback: ShortcutCallback) -> Result<(), &'static str>{
+        if self.is_registered(shortcut){
+            return Err("Error, this shortcut is already registered!");
+        }
+        self.shortcuts.push((shortcut, callback));
+        Ok(())
+    }
+
+    /// Remove a shortcut. Returns false if it wasn't registered.
+    pub fn unregister(&mut self, shortcut: Shortcut) -> bool{
+        let count = self.shortcuts.len();
+        self.shortcuts.retain(|(registered, _)| *registered != shortcut);
+        self.shortcuts.len() != count
+    }
+
+    /// Returns true if the shortcut is registered
+    pub fn is_registered(&self, shortcut: Shortcut) -> bool{
+        self.shortcuts.iter().any(|(registered, _)| *registered == shortcut)
+    }
+
+    /// The registered shortcuts, in the order they were registered
+    pub fn shortcuts(&self) -> impl Iterator<Item = Shortcut> + '_{
+        self.shortcuts.iter().map(|(shortcut, _)| *shortcut)
+    }
+
+    /// The registered shortcut `event` presses, if any. Pass true for `text_focused` while a text field has focus,
+    /// to suppress the shortcuts it needs for typing.
+    pub fn matching(&self, event: &GuiEvent, text_focused: bool) -> Option<Shortcut>{
+        self.shortcuts().find(|shortcut| shortcut.matches(event) && !(text_focused && shortcut.is_text_editing()))
+    }
+
+    /// Run the callback of the shortcut `event` presses (see `matching`). Returns true if one ran.
+    pub fn trigger(&mut self, event: &GuiEvent, text_focused: bool, window: &mut winit::window::Window, renderer: &mut Renderer) -> bool{
+        let shortcut = match self.matching(event, text_focused){
+            Some(v) => v,
+            None => return false,
+        };
+        if let Some((_, callback)) = self.shortcuts.iter_mut().find(|(registered, _)| *registered == shortcut){
+            callback(window, renderer);
+        }
+        true
+    }
+}
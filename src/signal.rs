@@ -0,0 +1,248 @@
+//! This module contains signals and effects, a reactive layer on top of data binding for UIs where lots of values
+//! depend on each other. A `Signal` holds a value, and an effect (`create_effect`) is a closure that runs again whenever
+//! a signal it read changes. Memos (`create_memo`) are derived values, that only notify when the result actually changes.
+//!
+//! Effects don't run as soon as a signal changes - they're queued, and the renderer runs them in its prepass, so a
+//! burst of changes only reruns each effect once. Effects usually update the `Observable`s components are bound to,
+//! so only the components whose inputs changed pick anything up:
+//!
+//! ```ignore
+//! let count = create_signal(0);
+//! let text = Observable::new(String::new());
+//! let label_text = text.clone();
+//! let _effect = create_effect(move || label_text.set(format!("Clicked {} times", count.get())));
+//! label.bind_text(&text);
+//! ```
+//!
+//! Signals belong to the thread they were created on (the UI thread).
+
+use std::{cell::{Cell, RefCell}, rc::{Rc, Weak}};
+
+// How many times effects can set off other effects in one flush, before the rest wait for the next one.
+// This stops an effect that changes its own inputs from hanging the app.
+const MAX_FLUSH_ROUNDS: usize = 64;
+
+thread_local!{
+    static RUNTIME: Runtime = Runtime::default();
+}
+
+#[derive(Default)]
+struct Runtime{
+    // The effect that's running, which the signals read are recorded against
+    observer: RefCell<Option<Weak<EffectInner>>>,
+    // Effects waiting to run again, because a signal they read changed
+    pending: RefCell<Vec<Weak<EffectInner>>>,
+}
+
+struct EffectInner{
+    run: RefCell<Box<dyn FnMut()>>,
+    // Set while the effect is in the pending queue, so it's only queued once
+    queued: Cell<bool>,
+}
+
+struct SignalInner<T>{
+    value: RefCell<T>,
+    // The effects that read the signal
+    subscribers: RefCell<Vec<Weak<EffectInner>>>,
+}
+
+/// # Signal
+///
+/// A value that effects and memos track. Reading it with `get` or `with` inside an effect subscribes the effect,
+/// and changing it queues every subscribed effect to run again. Clones are cheap, and share the same value.
+pub struct Signal<T>{
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> Clone for Signal<T>{
+    fn clone(&self) -> Self{
+        Self{
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Signal<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("Signal").field("value", &self.inner.value.borrow()).finish()
+    }
+}
+
+/// Create a new signal holding `value`
+pub fn create_signal<T>(value: T) -> Signal<T>{
+    Signal{
+        inner: Rc::new(SignalInner{
+            value: RefCell::new(value),
+            subscribers: RefCell::new(Vec::new()),
+        }),
+    }
+}
+
+impl<T> Signal<T>{
+    /// Read the value without cloning it, subscribing the running effect (if any)
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, read: F) -> R{
+        self.track();
+        read(&self.inner.value.borrow())
+    }
+
+    /// Read the value without subscribing the running effect
+    pub fn with_untracked<R, F: FnOnce(&T) -> R>(&self, read: F) -> R{
+        read(&self.inner.value.borrow())
+    }
+
+    /// Replace the value, queueing the effects that read it
+    pub fn set(&self, value: T){
+        *self.inner.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    /// Change the value in place, queueing the effects that read it
+    pub fn update<F: FnOnce(&mut T)>(&self, change: F){
+        change(&mut self.inner.value.borrow_mut());
+        self.notify();
+    }
+
+    // Subscribe the running effect
+    fn track(&self){
+        let observer = RUNTIME.with(|runtime| runtime.observer.borrow().clone());
+        if let Some(observer) = observer{
+            let mut subscribers = self.inner.subscribers.borrow_mut();
+            if !subscribers.iter().any(|effect| effect.ptr_eq(&observer)){
+                subscribers.push(observer);
+            }
+        }
+    }
+
+    // Queue the subscribed effects, forgetting the ones that have been dropped
+    fn notify(&self){
+        self.inner.subscribers.borrow_mut().retain(|effect| match effect.upgrade(){
+            Some(effect) => {
+                if !effect.queued.replace(true){
+                    RUNTIME.with(|runtime| runtime.pending.borrow_mut().push(Rc::downgrade(&effect)));
+                }
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl<T: Clone> Signal<T>{
+    /// A copy of the value, subscribing the running effect (if any)
+    pub fn get(&self) -> T{
+        self.with(T::clone)
+    }
+
+    /// A copy of the value, without subscribing the running effect
+    pub fn get_untracked(&self) -> T{
+        self.with_untracked(T::clone)
+    }
+}
+
+impl<T: PartialEq> Signal<T>{
+    /// Replace the value, only queueing the effects that read it if it's different
+    pub fn set_if_changed(&self, value: T){
+        if *self.inner.value.borrow() != value{
+            self.set(value);
+        }
+    }
+}
+
+/// # Effect
+///
+/// A closure that runs again whenever a signal it read changes. The effect stops when this handle is dropped,
+/// so keep it for as long as the effect should run.
+pub struct Effect{
+    _inner: Rc<EffectInner>,
+}
+
+impl std::fmt::Debug for Effect{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("Effect").finish()
+    }
+}
+
+/// Run `effect` now, and again (during the next `flush_effects`) every time a signal it read changes
+pub fn create_effect<F: FnMut() + 'static>(effect: F) -> Effect{
+    let inner = Rc::new(EffectInner{
+        run: RefCell::new(Box::new(effect)),
+        queued: Cell::new(false),
+    });
+    run_effect(&inner);
+    Effect{
+        _inner: inner,
+    }
+}
+
+/// # Memo
+///
+/// A value derived from signals (see `create_memo`). It can be read like a signal, and effects that read it only run
+/// again when the derived value changes, not every time one of its inputs does.
+pub struct Memo<T>{
+    signal: Signal<T>,
+    _effect: Effect,
+}
+
+impl<T> Memo<T>{
+    /// Read the value without cloning it, subscribing the running effect (if any)
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, read: F) -> R{
+        self.signal.with(read)
+    }
+}
+
+impl<T: Clone> Memo<T>{
+    /// A copy of the value, subscribing the running effect (if any)
+    pub fn get(&self) -> T{
+        self.signal.get()
+    }
+}
+
+/// Derive a value from signals with `compute`, which runs again when they change
+pub fn create_memo<T: PartialEq + 'static, F: FnMut() -> T + 'static>(mut compute: F) -> Memo<T>{
+    // The signal can only be created once the first value has been computed, inside the effect
+    let cell: Rc<RefCell<Option<Signal<T>>>> = Rc::new(RefCell::new(None));
+    let memo = cell.clone();
+    let effect = create_effect(move || {
+        let value = compute();
+        let signal = memo.borrow().clone();
+        match signal{
+            Some(signal) => signal.set_if_changed(value),
+            None => *memo.borrow_mut() = Some(create_signal(value)),
+        }
+    });
+    let signal = cell.borrow().clone().unwrap();
+    Memo{
+        signal,
+        _effect: effect,
+    }
+}
+
+/// Run the effects whose signals have changed, including any that those effects set off. The renderer calls this in
+/// its prepass, so it only needs calling by hand when the main loop isn't running. Returns the number of effects that ran.
+pub fn flush_effects() -> usize{
+    let mut count = 0;
+    for _ in 0..MAX_FLUSH_ROUNDS{
+        let pending = RUNTIME.with(|runtime| std::mem::take(&mut *runtime.pending.borrow_mut()));
+        if pending.is_empty(){
+            break;
+        }
+        for effect in pending.iter().filter_map(Weak::upgrade){
+            effect.queued.set(false);
+            run_effect(&effect);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Returns true if there are effects waiting to run
+pub fn has_pending_effects() -> bool{
+    RUNTIME.with(|runtime| !runtime.pending.borrow().is_empty())
+}
+
+// Run an effect, recording the signals it reads against it
+fn run_effect(effect: &Rc<EffectInner>){
+    let previous = RUNTIME.with(|runtime| runtime.observer.replace(Some(Rc::downgrade(effect))));
+    (effect.run.borrow_mut())();
+    RUNTIME.with(|runtime| *runtime.observer.borrow_mut() = previous);
+}
@@ -0,0 +1,58 @@
+//! This module contains `AppState`, the application's own data. The renderer owns it, and every callback can reach it -
+//! button callbacks are passed it directly, and the event, tick and shortcut callbacks through `Renderer::app_state_mut`.
+//! That lets handlers change the app's data without sharing it through `Rc<RefCell<...>>`.
+
+use std::any::Any;
+
+/// # AppState
+///
+/// Holds a single value of any type, set with `GUI::set_app_state`. Callbacks get it back by asking for that type,
+/// which returns `None` if no state has been set, or it's a different type.
+#[derive(Default)]
+pub struct AppState{
+    value: Option<Box<dyn Any>>,
+}
+
+impl std::fmt::Debug for AppState{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("AppState").field("set", &self.value.is_some()).finish()
+    }
+}
+
+impl AppState{
+    /// Create an empty state
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Replace the state with `state`
+    pub fn set<T: 'static>(&mut self, state: T){
+        self.value = Some(Box::new(state));
+    }
+
+    /// Returns true if a state has been set
+    pub fn is_set(&self) -> bool{
+        self.value.is_some()
+    }
+
+    /// Borrow the state, if it's a `T`
+    pub fn get<T: 'static>(&self) -> Option<&T>{
+        self.value.as_ref()?.downcast_ref()
+    }
+
+    /// Mutably borrow the state, if it's a `T`
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T>{
+        self.value.as_mut()?.downcast_mut()
+    }
+
+    /// Take the state out, if it's a `T`, leaving it empty. A state of another type is left where it is.
+    pub fn take<T: 'static>(&mut self) -> Option<T>{
+        match self.value.take()?.downcast(){
+            Ok(value) => Some(*value),
+            Err(value) => {
+                self.value = Some(value);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! This module loads themes from stylesheet files, and watches them for changes so a running app can be restyled
+//! without restarting it (`GUI::watch_stylesheet`). The main loop checks the file's modification time a couple of
+//! times a second, and when it changes the new theme is applied - only the components it actually restyles are
+//! redrawn (see `Component::apply_theme`).
+//!
+//! A stylesheet is a list of `property = value` lines, with `#` starting a comment:
+//!
+//! ```text
+//! base = dark              # start from the light (default) or dark theme
+//! accent = #3366cc         # colors are hex, like `Color::from_hex`
+//! background = #202020
+//! text = #f0f0f0
+//! input_background = #303030
+//! focus_ring = #66aaff
+//! corner_radius = 4
+//! spacing = 8
+//! text_size.small = 14
+//! text_size.body = 20
+//! text_size.heading = 28
+//! font = fonts/Inter.ttf   # relative to the stylesheet
+//! ```
+
+use std::{path::{Path, PathBuf}, time::{Duration, Instant, SystemTime}};
+
+use crate::{color::Color, theme::Theme};
+
+/// How often a watched stylesheet is checked for changes, by default
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Parse a stylesheet into a theme. Font paths are relative to `dir`.
+pub fn parse_stylesheet(source: &str, dir: &Path) -> Result<Theme, &'static str>{
+    let mut lines = Vec::new();
+    for line in source.lines(){
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#'){
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or("Error, stylesheet line is missing an `=`!")?;
+        lines.push((key.trim(), strip_comment(value.trim())));
+    }
+
+    // The base has to be picked before anything is changed on top of it
+    let mut theme = match lines.iter().find(|(key, _)| *key == "base").map(|(_, value)| *value){
+        Some("dark") => Theme::dark(),
+        Some("light") | None => Theme::light(),
+        Some(_) => return Err("Error, stylesheet base must be `light` or `dark`!"),
+    };
+
+    let number = |value: &str| value.parse::<f32>().map_err(|_| "Error, stylesheet property needs a number!");
+    for (key, value) in lines{
+        match key{
+            "base" => {}
+            "background" => theme.colors.background = Color::from_hex(value)?,
+            "text" => theme.colors.text = Color::from_hex(value)?,
+            "accent" => theme.colors.accent = Color::from_hex(value)?,
+            "input_background" => theme.colors.input_background = Color::from_hex(value)?,
+            "focus_ring" => theme.colors.focus_ring = Color::from_hex(value)?,
+            "corner_radius" => theme.corner_radius = number(value)?,
+            "spacing" => theme.spacing = number(value)?,
+            "text_size.small" => theme.text_sizes.small = number(value)?,
+            "text_size.body" => theme.text_sizes.body = number(value)?,
+            "text_size.heading" => theme.text_sizes.heading = number(value)?,
+            "font" => {
+                let bytes = std::fs::read(dir.join(value)).map_err(|_| "Error, failed to read the stylesheet's font!")?;
+                theme.font = Some(Theme::load_font(bytes)?);
+            }
+            _ => return Err("Error, unknown stylesheet property!"),
+        }
+    }
+    Ok(theme)
+}
+
+// Cut a `#` comment off the end of a value. Hex colors start with `#` too, so a comment has to follow some whitespace.
+fn strip_comment(value: &str) -> &str{
+    let mut previous = 'x';
+    for (i, c) in value.char_indices(){
+        if c == '#' && previous.is_whitespace(){
+            return value[..i].trim_end();
+        }
+        previous = c;
+    }
+    value
+}
+
+/// # Stylesheet
+///
+/// A stylesheet file, and the theme loaded from it. `poll` reloads it when the file changes.
+#[derive(Debug)]
+pub struct Stylesheet{
+    path: PathBuf,
+    theme: Theme,
+    modified: Option<SystemTime>,
+    interval: Duration,
+    next_check: Instant,
+    error: Option<&'static str>,
+}
+
+impl Stylesheet{
+    /// Load a stylesheet. Fails if the file can't be read, or isn't a valid stylesheet.
+    pub fn load<P: Into<PathBuf>>(path: P) -> Result<Self, &'static str>{
+        let path = path.into();
+        let modified = modified_time(&path);
+        let theme = read_stylesheet(&path)?;
+        Ok(Self{
+            path,
+            theme,
+            modified,
+            interval: DEFAULT_CHECK_INTERVAL,
+            next_check: Instant::now() + DEFAULT_CHECK_INTERVAL,
+            error: None,
+        })
+    }
+
+    /// The path of the stylesheet file
+    pub fn path(&self) -> &Path{
+        &self.path
+    }
+
+    /// The theme, as it was last loaded
+    pub fn theme(&self) -> &Theme{
+        &self.theme
+    }
+
+    /// Change how often `poll` checks the file for changes
+    pub fn set_check_interval(&mut self, interval: Duration) -> &mut Self{
+        self.next_check = self.next_check - self.interval + interval;
+        self.interval = interval;
+        self
+    }
+
+    /// When the file is next due to be checked, so the event loop can wake up for it
+    pub fn next_check(&self) -> Instant{
+        self.next_check
+    }
+
+    /// Why the last reload failed, if it did. The stylesheet keeps the last theme that loaded.
+    pub fn error(&self) -> Option<&'static str>{
+        self.error
+    }
+
+    /// If the check is due at `now`, reload the stylesheet when the file has changed. Returns the new theme if it reloaded.
+    /// A file that fails to load is skipped until it changes again (see `error`).
+    pub fn poll(&mut self, now: Instant) -> Option<&Theme>{
+        if now < self.next_check{
+            return None;
+        }
+        self.next_check = now + self.interval;
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified{
+            return None;
+        }
+        self.modified = modified;
+        match read_stylesheet(&self.path){
+            Ok(theme) => {
+                self.theme = theme;
+                self.error = None;
+                Some(&self.theme)
+            }
+            Err(error) => {
+                tracing::warn!(path = %self.path.display(), error, "Failed to reload the stylesheet");
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime>{
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn read_stylesheet(path: &Path) -> Result<Theme, &'static str>{
+    let source = std::fs::read_to_string(path).map_err(|_| "Error, failed to read the stylesheet!")?;
+    parse_stylesheet(&source, path.parent().unwrap_or_else(|| Path::new("")))
+}
@@ -0,0 +1,94 @@
+//! This module runs async tasks (file loads, network requests...) in the background, so they don't block rendering.
+//! It's only built with the `tasks` feature, and uses the `futures` thread pool.
+//!
+//! A task's result comes back on the UI thread - either to the callback given to `Renderer::spawn_with`, or as a
+//! `GuiEvent::User` for tasks started with `Renderer::spawn`. Both are also on `GUI`, for tasks started before the main loop.
+
+use std::{any::Any, collections::HashMap, future::Future};
+
+use futures::executor::ThreadPool;
+
+use crate::{rendering::Renderer, sender::EventSender};
+
+// A task's completion callback, taking the result before it's been downcast back to its type
+type CompletionCallback = Box<dyn FnOnce(Box<dyn Any + Send>, &mut winit::window::Window, &mut Renderer)>;
+
+/// Identifies a task that has a completion callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+/// # Tasks
+///
+/// Spawns futures on a thread pool, and keeps the completion callbacks of the ones still running.
+/// The renderer owns one, which the GUI connects to its event loop.
+#[derive(Default)]
+pub struct Tasks{
+    // Started the first time a task is spawned
+    pool: Option<ThreadPool>,
+    sender: Option<EventSender>,
+    callbacks: HashMap<u64, CompletionCallback>,
+    next_id: u64,
+}
+
+impl Tasks{
+    /// Create a new set of tasks. Nothing can be spawned until it's connected to an event loop.
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    // Connect to the event loop the results are sent to
+    pub(crate) fn set_sender(&mut self, sender: EventSender){
+        self.sender = Some(sender);
+    }
+
+    /// The number of tasks still running that have a completion callback
+    pub fn pending(&self) -> usize{
+        self.callbacks.len()
+    }
+
+    /// Run `future` in the background, sending its result to the main loop as a `GuiEvent::User`
+    pub fn spawn<F>(&mut self, future: F) -> Result<(), &'static str>
+    where F: Future + Send + 'static, F::Output: Send + 'static{
+        let sender = self.sender.clone().ok_or("Error, tasks can't be spawned without an event loop!")?;
+        self.pool()?.spawn_ok(async move {
+            // The loop has exited if this fails, so there's nobody left to tell
+            let _ = sender.send(future.await);
+        });
+        Ok(())
+    }
+
+    /// Run `future` in the background, then run `callback` with its result on the UI thread
+    pub fn spawn_with<F, C>(&mut self, future: F, callback: C) -> Result<TaskId, &'static str>
+    where F: Future + Send + 'static, F::Output: Send + 'static, C: FnOnce(F::Output, &mut winit::window::Window, &mut Renderer) + 'static{
+        let sender = self.sender.clone().ok_or("Error, tasks can't be spawned without an event loop!")?;
+        let pool = self.pool()?;
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.callbacks.insert(id, Box::new(move |result, window, renderer| {
+            // Only this task's future sends results with its ID, so the type always matches
+            if let Ok(result) = result.downcast::<F::Output>(){
+                callback(*result, window, renderer);
+            }
+        }));
+        pool.spawn_ok(async move {
+            let _ = sender.send_task_result(id, Box::new(future.await));
+        });
+        Ok(TaskId(id))
+    }
+
+    // Take the callback of a finished task
+    pub(crate) fn take_callback(&mut self, id: u64) -> Option<CompletionCallback>{
+        self.callbacks.remove(&id)
+    }
+
+    // The thread pool, starting it if it hasn't been. Clones share the same threads.
+    fn pool(&mut self) -> Result<ThreadPool, &'static str>{
+        if let Some(pool) = &self.pool{
+            return Ok(pool.clone());
+        }
+        let pool = ThreadPool::new().map_err(|_| "Error, failed to start the task thread pool!")?;
+        self.pool = Some(pool.clone());
+        Ok(pool)
+    }
+}
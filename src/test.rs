@@ -0,0 +1,222 @@
+//! This module is a harness for testing layouts without a window. `TestHarness` draws a layout with a headless renderer
+//! at a fixed size and reads the pixels back, and `compare_snapshot` checks them against a reference PNG stored
+//! with the tests. The functions at the bottom build the event sequences a user's input would produce (a click, a drag,
+//! typing), to send to the layout.
+//!
+//! Components get the window along with their events (see `Component::handle_event`), so sending events needs one. Hover
+//! doesn't, so `TestHarness::hover` works without a window too. To drive a running app's window, use `GUI::inject_event`.
+
+use std::path::{Path, PathBuf};
+
+use futures::executor::block_on;
+use winit::window::Window;
+
+use crate::{color::Color, event::{EventResult, GuiEvent, ModifiersState, MouseButton, ScrollDelta, VirtualKeyCode}, layout::Layout, rendering::{Renderer, Snapshot}};
+
+/// Set this environment variable to have `compare_snapshot` overwrite the reference images instead of comparing with them
+pub const UPDATE_SNAPSHOTS_VAR: &str = "RUSTY_GUI_UPDATE_SNAPSHOTS";
+
+/// # TestHarness
+///
+/// A headless renderer at a fixed size, for drawing a layout in tests. Add components to `layout_mut`, then `render`
+/// (or `assert_snapshot`) to see what they draw.
+///
+/// Creating one fails on machines without a graphics adapter, so tests should skip themselves when it does.
+pub struct TestHarness{
+    renderer: Renderer,
+    clear_color: Color,
+}
+
+impl TestHarness{
+    /// Create a harness that draws at `size` pixels, clearing to white
+    pub fn new(size: (u32, u32)) -> Result<Self, &'static str>{
+        Ok(Self{
+            renderer: block_on(Renderer::new_headless(size))?,
+            clear_color: Color::WHITE,
+        })
+    }
+
+    /// Set the color the frame is cleared to before the layout is drawn
+    pub fn set_clear_color(&mut self, color: Color) -> &mut Self{
+        self.clear_color = color;
+        self
+    }
+
+    /// The size frames are drawn at, in pixels
+    pub fn size(&self) -> (u32, u32){
+        (self.renderer.sc_desc.width, self.renderer.sc_desc.height)
+    }
+
+    /// The renderer the harness draws with
+    pub fn renderer(&self) -> &Renderer{
+        &self.renderer
+    }
+
+    /// The renderer the harness draws with, eg, to change its theme or swap the layout
+    pub fn renderer_mut(&mut self) -> &mut Renderer{
+        &mut self.renderer
+    }
+
+    /// The layout that gets drawn
+    pub fn layout(&self) -> &Layout{
+        &self.renderer.layout
+    }
+
+    /// The layout that gets drawn, to add components to
+    pub fn layout_mut(&mut self) -> &mut Layout{
+        &mut self.renderer.layout
+    }
+
+    /// Draw the layout and read the frame back
+    pub fn render(&mut self) -> Snapshot{
+        self.renderer.prepass();
+        self.renderer.capture_snapshot(self.clear_color)
+    }
+
+    /// Draw the layout and compare the frame with the reference image at `reference` (see `compare_snapshot`).
+    /// Panics with the reason if they don't match.
+    pub fn assert_snapshot<P: AsRef<Path>>(&mut self, reference: P, tolerance: u8){
+        let snapshot = self.render();
+        if let Err(e) = compare_snapshot(&snapshot, reference, tolerance){
+            panic!("{}", e);
+        }
+    }
+
+    /// Move the (pretend) cursor to `pos`, or off the window with `None`, updating which component is hovered.
+    /// Unlike `send`, this doesn't need a window.
+    pub fn hover(&mut self, pos: Option<(f64, f64)>){
+        let size = self.size();
+        self.renderer.layout.update_hover(pos, size);
+    }
+
+    /// Send `events` to the layout one after another (see `Layout::dispatch_event`), returning `Consumed` if any of them
+    /// was consumed. Mouse moves update the hovered component as usual.
+    pub fn send(&mut self, events: &[GuiEvent], window: &Window) -> EventResult{
+        let renderer = &mut self.renderer;
+        let mut result = EventResult::Ignored;
+        for event in events{
+            if renderer.layout.dispatch_event(event, window, &mut renderer.app_state).is_consumed(){
+                result = EventResult::Consumed;
+            }
+        }
+        result
+    }
+}
+
+/// # SnapshotError
+///
+/// Why a snapshot didn't match its reference image
+#[derive(Debug)]
+pub enum SnapshotError{
+    /// The reference image exists, but couldn't be read (or isn't an image)
+    UnreadableReference(PathBuf),
+    /// The reference image (or the images saved on a mismatch) couldn't be written
+    Write(PathBuf),
+    /// The frame differs from the reference. The frame and an overlay of the changed pixels were saved next to the reference.
+    Mismatch{
+        /// The number of pixels that differ by more than the tolerance (see `Snapshot::diff`)
+        changed_pixels: usize,
+        /// The bounding box of the changed pixels, as (top left, bottom right) inclusive pixel coordinates
+        bounds: Option<([u32; 2], [u32; 2])>,
+        /// Where the frame was saved
+        actual: PathBuf,
+        /// Where the overlay of the changed pixels was saved
+        diff: PathBuf,
+    },
+}
+
+impl std::fmt::Display for SnapshotError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        match self{
+            SnapshotError::UnreadableReference(path) => write!(f, "Error, failed to read the reference image {}!", path.display()),
+            SnapshotError::Write(path) => write!(f, "Error, failed to write {}!", path.display()),
+            SnapshotError::Mismatch{ changed_pixels, bounds, actual, diff } => {
+                write!(f, "Error, {} pixels differ from the reference", changed_pixels)?;
+                if let Some((min, max)) = bounds{
+                    write!(f, " between ({}, {}) and ({}, {})", min[0], min[1], max[0], max[1])?;
+                }
+                write!(f, "! The frame was saved to {} and the changes to {}", actual.display(), diff.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError{}
+
+/// Compare `snapshot` with the PNG at `reference`, allowing each channel of each pixel to differ by up to `tolerance`
+/// (a few units absorbs the rounding differences between graphics drivers).
+///
+/// When there's no reference yet, or the `RUSTY_GUI_UPDATE_SNAPSHOTS` environment variable is set, the snapshot is saved
+/// as the reference instead. On a mismatch, the snapshot is saved next to the reference as `<name>.actual.png`, along
+/// with `<name>.diff.png` highlighting the changed pixels in magenta.
+pub fn compare_snapshot<P: AsRef<Path>>(snapshot: &Snapshot, reference: P, tolerance: u8) -> Result<(), SnapshotError>{
+    let reference = reference.as_ref();
+    if !reference.exists() || std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some(){
+        if let Some(parent) = reference.parent(){
+            std::fs::create_dir_all(parent).map_err(|_| SnapshotError::Write(reference.to_path_buf()))?;
+        }
+        return snapshot.save(reference).map_err(|_| SnapshotError::Write(reference.to_path_buf()));
+    }
+
+    let expected = Snapshot::load(reference).map_err(|_| SnapshotError::UnreadableReference(reference.to_path_buf()))?;
+    let diff = expected.diff(snapshot, tolerance);
+    if diff.is_empty(){
+        return Ok(());
+    }
+
+    let actual = reference.with_extension("actual.png");
+    let diff_path = reference.with_extension("diff.png");
+    snapshot.save(&actual).map_err(|_| SnapshotError::Write(actual.clone()))?;
+    diff.overlay(snapshot, [255, 0, 255, 255]).save(&diff_path).map_err(|_| SnapshotError::Write(diff_path.clone()))?;
+    Err(SnapshotError::Mismatch{
+        changed_pixels: diff.changed_pixels(),
+        bounds: diff.bounds(),
+        actual,
+        diff: diff_path,
+    })
+}
+
+/// The events of a left click at `pos` - moving there, pressing and releasing
+pub fn click(pos: (f64, f64)) -> Vec<GuiEvent>{
+    vec![
+        GuiEvent::MouseMove{ pos },
+        GuiEvent::MouseDown{ button: MouseButton::Left, pos },
+        GuiEvent::MouseUp{ button: MouseButton::Left, pos },
+    ]
+}
+
+/// The events of dragging with the left button from `from` to `to`, moving in `steps` even steps (at least one)
+pub fn drag(from: (f64, f64), to: (f64, f64), steps: u32) -> Vec<GuiEvent>{
+    let steps = steps.max(1);
+    let mut events = vec![
+        GuiEvent::MouseMove{ pos: from },
+        GuiEvent::MouseDown{ button: MouseButton::Left, pos: from },
+    ];
+    for step in 1..=steps{
+        let t = step as f64 / steps as f64;
+        events.push(GuiEvent::MouseMove{ pos: (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t) });
+    }
+    events.push(GuiEvent::MouseUp{ button: MouseButton::Left, pos: to });
+    events
+}
+
+/// The events of scrolling the mouse wheel by `lines` (positive is up) with the cursor at `pos`
+pub fn scroll(pos: (f64, f64), lines: f32) -> Vec<GuiEvent>{
+    vec![
+        GuiEvent::MouseMove{ pos },
+        GuiEvent::Scroll{ delta: ScrollDelta::Lines{ x: 0.0, y: lines } },
+    ]
+}
+
+/// The events of pressing and releasing `key` while `modifiers` are held (with no physical key, so a scancode of 0)
+pub fn key_press(key: VirtualKeyCode, modifiers: ModifiersState) -> Vec<GuiEvent>{
+    vec![
+        GuiEvent::KeyDown{ key, scancode: 0, modifiers },
+        GuiEvent::KeyUp{ key, scancode: 0, modifiers },
+    ]
+}
+
+/// The events of typing `text`, one character at a time
+pub fn type_text(text: &str) -> Vec<GuiEvent>{
+    text.chars().map(GuiEvent::TextInput).collect()
+}
@@ -0,0 +1,128 @@
+//! This module contains `Theme`, the default appearance of the built-in components - their colors, the font, and
+//! the spacing and text sizes they're laid out with. The renderer holds the current theme (`Renderer::theme`),
+//! components created with the renderer start out using it, and `GUI::set_theme` restyles every component at once.
+
+use crate::color::Color;
+
+/// # ThemeColors
+///
+/// The colors of a theme
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeColors{
+    /// The window's clear color
+    pub background: Color,
+    /// Text, and the underline of text being composed in a text input
+    pub text: Color,
+    /// Buttons (their hovered, pressed and disabled tints are derived from it, see `ButtonStyle::from_tint`)
+    pub accent: Color,
+    /// The box behind the text in a text input
+    pub input_background: Color,
+    /// The ring drawn around the component with keyboard focus
+    pub focus_ring: Color,
+}
+
+impl Default for ThemeColors{
+    fn default() -> Self{
+        Self{
+            background: Color::WHITE,
+            text: Color::BLACK,
+            accent: Color::RED,
+            input_background: Color::from_rgba8(240, 240, 240, 255),
+            focus_ring: Color::new(0.0, 0.47, 0.84, 1.0),
+        }
+    }
+}
+
+/// # TextSizes
+///
+/// The text sizes of a theme, in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextSizes{
+    /// Secondary text, like captions
+    pub small: f32,
+    /// Most text, including button labels
+    pub body: f32,
+    /// Titles and headings
+    pub heading: f32,
+}
+
+impl Default for TextSizes{
+    fn default() -> Self{
+        Self{
+            small: 16.0,
+            body: 24.0,
+            heading: 32.0,
+        }
+    }
+}
+
+/// # Theme
+///
+/// The default appearance of the built-in components. Changing the theme with `GUI::set_theme` replaces the colors
+/// of every component, including ones that were set by hand - text sizes are only used when a component is created.
+#[derive(Clone)]
+pub struct Theme{
+    pub colors: ThemeColors,
+    /// The font all text is drawn with, or `None` for the built-in font
+    pub font: Option<wgpu_glyph::ab_glyph::FontArc>,
+    /// The corner radius (in pixels) of components that draw rounded corners
+    pub corner_radius: f32,
+    /// The space (in pixels) between components laid out together, like the buttons of a `ButtonBar`, and around their contents
+    pub spacing: f32,
+    pub text_sizes: TextSizes,
+}
+
+impl std::fmt::Debug for Theme{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        f.debug_struct("Theme")
+            .field("colors", &self.colors)
+            .field("font", &self.font.is_some())
+            .field("corner_radius", &self.corner_radius)
+            .field("spacing", &self.spacing)
+            .field("text_sizes", &self.text_sizes)
+            .finish()
+    }
+}
+
+impl Default for Theme{
+    fn default() -> Self{
+        Self{
+            colors: ThemeColors::default(),
+            font: None,
+            corner_radius: 0.0,
+            spacing: 8.0,
+            text_sizes: TextSizes::default(),
+        }
+    }
+}
+
+impl Theme{
+    /// Create the default theme - black text on white, with red buttons
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// The light theme (the default)
+    pub fn light() -> Self{
+        Self::default()
+    }
+
+    /// The dark theme - light text on a dark grey background
+    pub fn dark() -> Self{
+        Self{
+            colors: ThemeColors{
+                background: Color::from_rgba8(30, 30, 30, 255),
+                text: Color::from_rgba8(235, 235, 235, 255),
+                accent: Color::from_rgba8(200, 50, 50, 255),
+                input_background: Color::from_rgba8(50, 50, 50, 255),
+                focus_ring: Color::new(0.3, 0.6, 1.0, 1.0),
+            },
+            ..Self::default()
+        }
+    }
+
+    /// Load the font for a theme from a TrueType or OpenType file's bytes
+    pub fn load_font(bytes: Vec<u8>) -> Result<wgpu_glyph::ab_glyph::FontArc, &'static str>{
+        wgpu_glyph::ab_glyph::FontArc::try_from_vec(bytes).map_err(|_| "Error, failed to load the theme's font!")
+    }
+}
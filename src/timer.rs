@@ -0,0 +1,108 @@
+//! This module contains timers - callbacks that run once after a delay (`GUI::set_timeout`), or repeatedly
+//! (`GUI::set_interval`). The main loop runs them when they're due, and sleeps until the next one rather than
+//! polling, so blinking carets and auto-dismissing toasts don't need their own timing thread.
+
+use std::time::{Duration, Instant};
+
+use crate::rendering::Renderer;
+
+/// The signature of a timer callback. It takes the main window and the renderer, like the tick callback.
+pub type TimerCallback = Box<dyn FnMut(&mut winit::window::Window, &mut Renderer)>;
+
+/// Identifies a timer, so it can be cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+// A scheduled callback
+struct Timer<C>{
+    id: TimerId,
+    due: Instant,
+    // Set for intervals, which are rescheduled after they run
+    interval: Option<Duration>,
+    callback: C,
+}
+
+/// # Timers
+///
+/// The scheduled timers, and their callbacks. The GUI keeps one with `TimerCallback`s, but any callback type works.
+pub struct Timers<C>{
+    timers: Vec<Timer<C>>,
+    next_id: u64,
+}
+
+impl<C> Default for Timers<C>{
+    fn default() -> Self{
+        Self{
+            timers: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<C> Timers<C>{
+    /// Create an empty set of timers
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Run `callback` once, `delay` after `now`
+    pub fn set_timeout(&mut self, delay: Duration, callback: C, now: Instant) -> TimerId{
+        self.add(now + delay, None, callback)
+    }
+
+    /// Run `callback` every `interval`, starting `interval` after `now`
+    pub fn set_interval(&mut self, interval: Duration, callback: C, now: Instant) -> TimerId{
+        self.add(now + interval, Some(interval), callback)
+    }
+
+    /// Stop a timer. Returns false if it had already run (for a timeout), or been cleared.
+    pub fn clear(&mut self, id: TimerId) -> bool{
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != count
+    }
+
+    /// The number of timers waiting to run
+    pub fn len(&self) -> usize{
+        self.timers.len()
+    }
+
+    /// Returns true if there aren't any timers
+    pub fn is_empty(&self) -> bool{
+        self.timers.is_empty()
+    }
+
+    /// When the next timer is due, so the event loop can wake up for it
+    pub fn next_deadline(&self) -> Option<Instant>{
+        self.timers.iter().map(|timer| timer.due).min()
+    }
+
+    /// Run every timer that's due at `now` with `run`, earliest first. Timeouts are removed once they've run, and intervals
+    /// are rescheduled - if one fell behind (eg, the loop was busy), it runs once, rather than once for every interval it missed.
+    pub fn poll<F: FnMut(TimerId, &mut C)>(&mut self, now: Instant, mut run: F){
+        self.timers.sort_by_key(|timer| timer.due);
+        let due = self.timers.iter().take_while(|timer| timer.due <= now).count();
+        for timer in self.timers.iter_mut().take(due){
+            run(timer.id, &mut timer.callback);
+            if let Some(interval) = timer.interval{
+                timer.due += interval;
+                if timer.due <= now{
+                    timer.due = now + interval;
+                }
+            }
+        }
+
+        let mut index = 0;
+        self.timers.retain(|timer| {
+            index += 1;
+            index > due || timer.interval.is_some()
+        });
+    }
+
+    fn add(&mut self, due: Instant, interval: Option<Duration>, callback: C) -> TimerId{
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer{ id, due, interval, callback });
+        id
+    }
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "accesskit")]
+
+use accesskit::{Action, ActionData, ActionRequest};
+use rusty_gui::{accessibility::{AccessAction, AccessNode, Role}, access_adapter::{access_action, tree_update, NodeIds, ROOT_ID}};
+
+
+// A visible, enabled component at `path`
+fn node(path: &[usize], role: Role, name: &str) -> AccessNode{
+    AccessNode{
+        path: path.to_vec(),
+        role,
+        name: Some(name.to_string()),
+        value: None,
+        description: None,
+        bounds: Some([10.0, 20.0, 100.0, 30.0]),
+        enabled: true,
+        focusable: role != Role::Label,
+        focused: false,
+        children: Vec::new(),
+    }
+}
+
+/// Test that the accessibility tree is handed to AccessKit under a window node, with IDs that last between updates, and that
+/// AccessKit's actions are routed back to the right component
+#[test]
+fn access_adapter_test(){
+    let mut panel = node(&[0], Role::Group, "Form");
+    let mut input = node(&[0, 2], Role::TextInput, "Name");
+    input.value = Some("Ada".to_string());
+    input.focused = true;
+    panel.children.push(input);
+    let mut save = node(&[1], Role::Button, "Save");
+    save.enabled = false;
+    let tree = vec![panel, save, node(&[3], Role::Label, "Status")];
+
+    let mut ids = NodeIds::new();
+    let update = tree_update(&tree, &mut ids);
+    let find = |path: &[usize]| {
+        let id = ids.get(path);
+        update.nodes.iter().find(|(node_id, _)| Some(*node_id) == id).map(|(_, node)| node.clone()).unwrap()
+    };
+    assert_eq!(update.tree.as_ref().unwrap().root, ROOT_ID);
+    let root = update.nodes.iter().find(|(id, _)| *id == ROOT_ID).unwrap();
+    assert_eq!(root.1.role(), accesskit::Role::Window);
+    assert_eq!(root.1.children().len(), 3);
+    assert_eq!(update.nodes.len(), 5);
+
+    let input = find(&[0, 2]);
+    assert_eq!((input.role(), input.label(), input.value()), (accesskit::Role::TextInput, Some("Name"), Some("Ada")));
+    assert!(input.supports_action(Action::Focus) && input.supports_action(Action::SetValue));
+    assert_eq!(find(&[0]).children(), [ids.get(&[0, 2]).unwrap()]);
+    assert_eq!(update.focus, ids.get(&[0, 2]).unwrap());
+    let bounds = input.bounds().unwrap();
+    assert_eq!((bounds.x0, bounds.y0, bounds.x1, bounds.y1), (10.0, 20.0, 110.0, 50.0));
+    // Disabled components can't be acted on
+    let save = find(&[1]);
+    assert!(save.is_disabled() && !save.supports_action(Action::Click));
+
+    // Components keep their IDs while they're in the tree, and components that left it are forgotten
+    let input_id = ids.get(&[0, 2]).unwrap();
+    let save_id = ids.get(&[1]).unwrap();
+    let update = tree_update(&tree[..1], &mut ids);
+    assert_eq!(ids.get(&[0, 2]), Some(input_id));
+    assert_eq!(ids.path(save_id), None);
+    assert_eq!(update.focus, input_id);
+
+    let request = |action, target, data| ActionRequest{ action, target, data };
+    assert_eq!(
+        access_action(&request(Action::SetValue, input_id, Some(ActionData::Value("Grace".into()))), &ids),
+        Some((vec![0, 2], AccessAction::SetValue("Grace".to_string())))
+    );
+    assert_eq!(access_action(&request(Action::Focus, input_id, None), &ids), Some((vec![0, 2], AccessAction::Focus)));
+    // The window itself, components that left the tree, and actions the layout doesn't know aren't routed anywhere
+    assert_eq!(access_action(&request(Action::Focus, ROOT_ID, None), &ids), None);
+    assert_eq!(access_action(&request(Action::Click, save_id, None), &ids), None);
+    assert_eq!(access_action(&request(Action::ScrollDown, input_id, None), &ids), None);
+}
@@ -0,0 +1,107 @@
+use std::any::Any;
+
+use rusty_gui::{accessibility::{AccessInfo, Role}, components::Component, layout::Layout};
+
+// A component that describes itself as `info`, and can take focus
+struct Item{
+    info: Option<AccessInfo>,
+    visible: bool,
+    focused: bool,
+}
+
+impl Item{
+    fn new(info: Option<AccessInfo>) -> Box<Self>{
+        Box::new(Self{ info, visible: true, focused: false })
+    }
+}
+
+impl Component for Item{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+    fn set_visible(&mut self, visible: bool){
+        self.visible = visible;
+    }
+    fn is_focusable(&self) -> bool{
+        true
+    }
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some([10.0, 20.0, 30.0, 40.0])
+    }
+    fn access_info(&self) -> Option<AccessInfo>{
+        self.info.clone()
+    }
+}
+
+// A component holding other components
+struct Container{
+    info: Option<AccessInfo>,
+    layout: Layout,
+}
+
+impl Component for Container{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn children(&self) -> Option<&Layout>{
+        Some(&self.layout)
+    }
+    fn access_info(&self) -> Option<AccessInfo>{
+        self.info.clone()
+    }
+}
+
+/// Test that the accessibility tree lists the visible components that describe themselves, with their state and children
+#[test]
+fn accessibility_test(){
+    let mut layout = Layout::new();
+    let ok = layout.add_component(Item::new(Some(AccessInfo::named(Role::Button, "OK"))));
+    let hidden = layout.add_component(Item::new(Some(AccessInfo::named(Role::Label, "Hidden"))));
+    layout.add_component(Item::new(None));
+
+    // A group keeps its children, and a container that doesn't describe itself passes them up
+    let mut group = Layout::new();
+    group.add_component(Item::new(Some(AccessInfo::named(Role::Label, "Name"))));
+    layout.add_component(Box::new(Container{ info: Some(AccessInfo::new(Role::Group)), layout: group }));
+    let mut plain = Layout::new();
+    plain.add_component(Item::new(Some(AccessInfo::new(Role::TextInput))));
+    layout.add_component(Box::new(Container{ info: None, layout: plain }));
+
+    layout.borrow_component_mut(hidden).set_visible(false);
+    layout.set_focus(Some(ok)).unwrap();
+
+    let tree = layout.accessibility_tree((800, 600));
+    assert_eq!(tree.len(), 3);
+
+    assert_eq!(tree[0].path, vec![0]);
+    assert_eq!(tree[0].role, Role::Button);
+    assert_eq!(tree[0].name.as_deref(), Some("OK"));
+    assert_eq!(tree[0].bounds, Some([10.0, 20.0, 30.0, 40.0]));
+    assert!(tree[0].enabled && tree[0].focusable && tree[0].focused);
+
+    assert_eq!(tree[1].role, Role::Group);
+    assert_eq!(tree[1].children.len(), 1);
+    assert_eq!(tree[1].children[0].path, vec![3, 0]);
+    assert_eq!(tree[1].children[0].name.as_deref(), Some("Name"));
+    assert!(!tree[1].children[0].focused);
+
+    assert_eq!(tree[2].path, vec![4, 0]);
+    assert_eq!(tree[2].role, Role::TextInput);
+    assert_eq!(tree[0].find(&[0]).map(|node| node.role), Some(Role::Button));
+    assert_eq!(tree[1].find(&[3, 0]).map(|node| node.role), Some(Role::Label));
+}
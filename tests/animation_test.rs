@@ -0,0 +1,50 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use rusty_gui::{AnimatedValue, Animation, Animator, AppState, Color, Easing, components::Label, layout::Layout};
+
+
+/// Test that animations tween a component's properties with the frame's delta time, from its current value or a set one,
+/// waiting out their delay and running their start and complete callbacks once each
+#[test]
+fn animation_test(){
+    let mut layout = Layout::new();
+    let label = layout.add_component(Box::new(Label::new("Hi", 16.0, [0.0, 0.0])));
+    let mut state = AppState::new();
+    let mut animator = Animator::new();
+
+    let started = Rc::new(Cell::new(0));
+    let completed = Rc::new(Cell::new(0));
+    let (on_start, on_complete) = (started.clone(), completed.clone());
+    let mut animation = Animation::new(label, AnimatedValue::Position([100.0, 50.0]), Duration::from_millis(100));
+    animation.set_delay(Duration::from_millis(50))
+        .on_start(move |_| on_start.set(on_start.get() + 1))
+        .on_complete(move |_| on_complete.set(on_complete.get() + 1));
+    let id = animator.start(animation);
+
+    let frame = Duration::from_millis(50);
+    let pos = |layout: &Layout| layout.borrow_component_as_type::<Label>(label).unwrap().anchor();
+    // The first step is time zero, then the delay
+    animator.step(frame, &mut layout, &mut state);
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(started.get(), 1);
+    assert_eq!(pos(&layout), [0.0, 0.0]);
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(pos(&layout), [50.0, 25.0]);
+    assert!(animator.is_running(id));
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(pos(&layout), [100.0, 50.0]);
+    assert_eq!((started.get(), completed.get()), (1, 1));
+    assert!(animator.is_empty());
+
+    // Eased colors, and cancelling without completing
+    let mut animation = Animation::new(label, AnimatedValue::Color(Color::WHITE), Duration::from_millis(100));
+    animation.set_from(AnimatedValue::Color(Color::BLACK)).set_easing(Easing::EaseIn);
+    let id = animator.start(animation);
+    animator.step(frame, &mut layout, &mut state);
+    animator.step(frame, &mut layout, &mut state);
+    let color = layout.borrow_component_as_type::<Label>(label).unwrap().get_color();
+    assert!((color.r - 0.125).abs() < 0.001);
+    assert!(animator.cancel(id));
+    assert!(!animator.cancel(id));
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+}
@@ -0,0 +1,24 @@
+use rusty_gui::AppState;
+
+struct Counter{
+    count: u32,
+}
+
+/// Test that the app state hands back the value it holds, but only as its own type
+#[test]
+fn app_state_test(){
+    let mut state = AppState::new();
+    assert!(!state.is_set());
+    assert!(state.get::<Counter>().is_none());
+
+    state.set(Counter{ count: 1 });
+    assert!(state.is_set());
+    state.get_mut::<Counter>().unwrap().count += 1;
+    assert_eq!(state.get::<Counter>().map(|v| v.count), Some(2));
+
+    // Asking for the wrong type gets nothing, and leaves the state alone
+    assert!(state.get::<String>().is_none());
+    assert!(state.take::<String>().is_none());
+    assert_eq!(state.take::<Counter>().map(|v| v.count), Some(2));
+    assert!(!state.is_set());
+}
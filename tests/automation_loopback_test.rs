@@ -0,0 +1,19 @@
+#![cfg(feature = "automation")]
+
+use std::io::ErrorKind;
+
+use rusty_gui::automation::AutomationServer;
+
+
+/// Test that the automation server refuses to listen anywhere other machines could reach it
+#[test]
+fn automation_loopback_test(){
+    for address in ["0.0.0.0:0", "[::]:0"]{
+        match AutomationServer::start(address){
+            Err(e) => assert_eq!(e.kind(), ErrorKind::PermissionDenied),
+            Ok(server) => panic!("Expected {} to be refused, but the server is listening on {}", address, server.address()),
+        }
+    }
+    let server = AutomationServer::start("127.0.0.1:0").unwrap();
+    assert!(server.address().ip().is_loopback());
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "automation")]
+
+use std::io::{BufRead, BufReader, Write};
+
+use rusty_gui::automation::{AutomationRequest, AutomationServer, METHOD_NOT_FOUND, PARSE_ERROR};
+use winit::event::VirtualKeyCode;
+
+
+/// Test that JSON-RPC requests are parsed, and that bad requests get an error response over the socket
+#[test]
+fn automation_test(){
+    let (id, request) = AutomationRequest::parse(r#"{"jsonrpc": "2.0", "id": 1, "method": "click", "params": {"x": 10, "y": 20.5}}"#).unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(request, AutomationRequest::Click{ x: 10.0, y: 20.5 });
+
+    let (_, request) = AutomationRequest::parse(r#"{"jsonrpc": "2.0", "id": 2, "method": "key", "params": {"key": "enter"}}"#).unwrap();
+    assert_eq!(request, AutomationRequest::Key{ key: VirtualKeyCode::Return });
+    assert!(AutomationRequest::parse(r#"{"jsonrpc": "2.0", "id": 3, "method": "get_text", "params": {}}"#).is_err());
+
+    let error = AutomationRequest::parse(r#"{"jsonrpc": "2.0", "id": 4, "method": "explode"}"#).unwrap_err();
+    assert_eq!(error["error"]["code"], METHOD_NOT_FOUND);
+    assert_eq!(error["id"], 4);
+
+    // Invalid requests are answered without needing the main loop
+    let server = AutomationServer::start("127.0.0.1:0").unwrap();
+    let mut stream = std::net::TcpStream::connect(server.address()).unwrap();
+    writeln!(stream, "this is not json").unwrap();
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).unwrap();
+    let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+}
@@ -0,0 +1,32 @@
+use rusty_gui::{components::{Badge, BadgeCorner, Component}, rendering::Transform};
+
+mod common;
+
+
+/// Test that a badge sits on its host's corner and follows it, and hides while its count is zero
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn badge_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    let mut host = Transform::new(
+        cgmath::Vector3::new(100.0, 100.0, 0.0),
+        cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::new(40.0, 20.0, 1.0),
+        renderer.transform_pool()
+    );
+    let mut badge = Badge::new(&host, BadgeCorner::TopRight, 16.0, &renderer);
+    assert_eq!(badge.get_pos(), [120.0, 90.0]);
+    host.set_position(cgmath::Vector3::new(200.0, 50.0, 0.0));
+    assert_eq!(badge.get_pos(), [220.0, 40.0]);
+
+    assert!(!badge.is_visible());
+    badge.set_count(3);
+    assert!(badge.is_visible());
+    assert_eq!(badge.get_text(), Some("3"));
+    badge.set_max(9);
+    badge.set_count(120);
+    assert_eq!(badge.get_text(), Some("9+"));
+    badge.set_count(0);
+    assert!(!badge.is_visible());
+}
@@ -0,0 +1,24 @@
+use std::rc::Rc;
+
+mod common;
+
+
+/// Test that uniforms created with the same descriptor share one bind group layout
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn bind_group_cache_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    // The renderer's own tints and camera already made the fragment and vertex layouts
+    let cache = renderer.bind_group_cache();
+    let before = cache.len();
+    let (_, _, first) = cache.create_uniform(wgpu::ShaderStage::FRAGMENT, 0, &[1.0f32; 4], "First tint");
+    let (_, _, second) = cache.create_uniform(wgpu::ShaderStage::FRAGMENT, 0, &[0.5f32; 4], "Second tint");
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(cache.len(), before);
+
+    // A different descriptor gets its own layout
+    let other = cache.uniform_layout(1, wgpu::ShaderStage::FRAGMENT, false, None);
+    assert!(!Rc::ptr_eq(&first, &other));
+    assert_eq!(cache.len(), before + 1);
+}
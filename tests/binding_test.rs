@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use rusty_gui::{Observable, components::{Component, Label}};
+
+
+/// Test that bound labels follow their observable, picking up each change once on the next update
+#[test]
+fn binding_test(){
+    let name = Observable::new(String::from("Ada"));
+    let score = Observable::new(0.5f32);
+
+    let mut binding = score.bind();
+    assert_eq!(binding.poll(), Some(0.5));
+    assert_eq!(binding.poll(), None);
+
+    let mut label = Label::new("", 16.0, [0.0, 0.0]);
+    label.bind_text(&name);
+    let mut progress = Label::new("", 16.0, [0.0, 0.0]);
+    progress.bind_text_with(&score, |score| format!("{:.0}%", score * 100.0));
+
+    // The current value is applied on the first update
+    label.update(Duration::from_millis(16));
+    progress.update(Duration::from_millis(16));
+    assert_eq!(label.text(), "Ada");
+    assert_eq!(progress.text(), "50%");
+
+    // Text set by hand stays until the value changes again
+    label.set_text("Someone");
+    label.update(Duration::from_millis(16));
+    assert_eq!(label.text(), "Someone");
+
+    name.set(String::from("Grace"));
+    score.update(|score| *score = 1.0);
+    assert_eq!(binding.poll(), Some(1.0));
+    label.update(Duration::from_millis(16));
+    progress.update(Duration::from_millis(16));
+    assert_eq!(label.text(), "Grace");
+    assert_eq!(progress.text(), "100%");
+
+    label.unbind_text();
+    name.set(String::from("Linus"));
+    label.update(Duration::from_millis(16));
+    assert_eq!(label.text(), "Grace");
+}
@@ -0,0 +1,20 @@
+use rusty_gui::components::{ButtonBar, ButtonOrder, ButtonRole};
+
+
+/// Test that button bars order their buttons by platform
+#[test]
+fn button_bar_order_test(){
+    let mut bar = ButtonBar::new();
+    bar.add_button("Apply", ButtonRole::Apply, |_, _| {})
+        .add_button("OK", ButtonRole::Accept, |_, _| {})
+        .add_button("Help", ButtonRole::Other, |_, _| {})
+        .add_button("Cancel", ButtonRole::Cancel, |_, _| {});
+
+    bar.set_order(ButtonOrder::Windows);
+    let labels: Vec<&str> = bar.ordered().iter().map(|(text, _)| *text).collect();
+    assert_eq!(labels, vec!["Help", "OK", "Cancel", "Apply"]);
+
+    bar.set_order(ButtonOrder::MacOS);
+    let labels: Vec<&str> = bar.ordered().iter().map(|(text, _)| *text).collect();
+    assert_eq!(labels, vec!["Help", "Apply", "Cancel", "OK"]);
+}
@@ -0,0 +1,37 @@
+use rusty_gui::{Color, components::{Button, ButtonSkin, ButtonState, ButtonStyle}, rendering::{Texture, Transform}};
+
+mod common;
+
+
+/// Test that a skinned button draws its texture region, with its tint multiplied over it
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn button_skin_test(){
+    let mut renderer = common::headless_renderer((32, 32));
+
+    // A two pixel atlas - blue on the left, green on the right
+    let atlas = image::RgbaImage::from_raw(2, 1, vec![0, 0, 255, 255, 0, 255, 0, 255]).unwrap();
+    let texture = Texture::from_image(&atlas, &renderer).unwrap();
+    let green = texture.region([1, 0, 1, 1]);
+    assert_eq!(green.size(), (1, 1));
+    assert_eq!(green.uv(), [0.5, 0.0, 0.5, 1.0]);
+
+    let skin = ButtonSkin::new(green.clone());
+    assert_eq!(skin.region(ButtonState::Pressed), &green);
+
+    let transform = Transform::new(
+        cgmath::Vector3::<f32>::new(16.0, 16.0, 0.0),
+        cgmath::Quaternion::<f32>::new(0.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(16.0, 16.0, 1.0),
+        renderer.transform_pool()
+    );
+    let mut button = Button::new(transform, None, &renderer, None, 0.0);
+    button.set_style(ButtonStyle::from_tint(Color::WHITE), &renderer);
+    button.set_skin(Some(skin), &renderer);
+    renderer.layout.add_component(Box::new(button));
+
+    renderer.prepass();
+    renderer.render(Color::BLACK);
+    let pixel = renderer.capture_snapshot(Color::BLACK).get_pixel(16, 16);
+    assert!(pixel[0] < 8 && pixel[1] > 247 && pixel[2] < 8, "expected green, got {:?}", pixel);
+}
@@ -0,0 +1,21 @@
+use rusty_gui::{Color, components::{ButtonState, ButtonStyle}};
+
+
+/// Test that a style made from a tint is lighter when hovered, darker when pressed and faded when disabled
+#[test]
+fn button_style_test(){
+    let tint = Color::rgb(0.2, 0.4, 0.6);
+    let style = ButtonStyle::from_tint(tint);
+
+    assert_eq!(style.tint(ButtonState::Normal), tint);
+    let hovered = style.tint(ButtonState::Hovered);
+    assert!(hovered.r > tint.r && hovered.g > tint.g && hovered.b > tint.b);
+    let pressed = style.tint(ButtonState::Pressed);
+    assert!(pressed.r < tint.r && pressed.g < tint.g && pressed.b < tint.b);
+    assert!(style.tint(ButtonState::Disabled).a < tint.a);
+
+    // Mixing is clamped, so it never goes past either color
+    assert_eq!(tint.mix(Color::WHITE, 2.0), Color::WHITE);
+    assert_eq!(tint.mix(Color::WHITE, 0.0), tint);
+    assert_eq!(ButtonStyle::default().normal, Color::RED);
+}
@@ -0,0 +1,33 @@
+use std::time::{Duration, Instant};
+
+use rusty_gui::event::{ClickCounter, GuiEvent, MouseButton};
+
+
+/// Test that quick presses in the same place count up to a triple click, and slow, distant or different button presses don't
+#[test]
+fn click_count_test(){
+    let mut clicks = ClickCounter::with_limits(Duration::from_millis(500), 4.0);
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    assert_eq!(clicks.register(MouseButton::Left, (10.0, 10.0), at(0)), 1);
+    assert_eq!(clicks.register(MouseButton::Left, (12.0, 11.0), at(200)), 2);
+    assert_eq!(clicks.register(MouseButton::Left, (12.0, 11.0), at(400)), 3);
+    // The run starts again after a triple click
+    assert_eq!(clicks.register(MouseButton::Left, (12.0, 11.0), at(500)), 1);
+
+    // Too slow
+    assert_eq!(clicks.register(MouseButton::Left, (12.0, 11.0), at(1100)), 1);
+    // Too far
+    assert_eq!(clicks.register(MouseButton::Left, (30.0, 11.0), at(1200)), 1);
+    // A different button
+    assert_eq!(clicks.register(MouseButton::Right, (30.0, 11.0), at(1300)), 1);
+
+    // Events - only presses count
+    let mut clicks = ClickCounter::new();
+    let down = GuiEvent::MouseDown{ button: MouseButton::Left, pos: (5.0, 5.0) };
+    assert_eq!(clicks.click_event(&down), None);
+    assert_eq!(clicks.click_event(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (5.0, 5.0) }), None);
+    assert_eq!(clicks.click_event(&down), Some(GuiEvent::DoubleClick{ button: MouseButton::Left, pos: (5.0, 5.0) }));
+    assert_eq!(clicks.click_event(&down), Some(GuiEvent::TripleClick{ button: MouseButton::Left, pos: (5.0, 5.0) }));
+}
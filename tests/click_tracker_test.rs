@@ -0,0 +1,32 @@
+use rusty_gui::event::{ClickTracker, GuiEvent, MouseButton};
+
+
+/// Test that a click only completes when the left button is pressed and released in bounds
+#[test]
+fn click_tracker_test(){
+    let down = GuiEvent::MouseDown{ button: MouseButton::Left, pos: (0.0, 0.0) };
+    let up = GuiEvent::MouseUp{ button: MouseButton::Left, pos: (0.0, 0.0) };
+    let mut clicks = ClickTracker::new();
+
+    assert!(!clicks.update(&down, true));
+    assert!(clicks.is_pressed());
+    assert!(clicks.update(&up, true));
+    // Each click only completes once
+    assert!(!clicks.update(&up, true));
+
+    // Released outside, or pressed outside
+    clicks.update(&down, true);
+    assert!(!clicks.update(&up, false));
+    clicks.update(&down, false);
+    assert!(!clicks.update(&up, true));
+
+    // Other buttons don't click, and losing focus (or cancelling) forgets the press
+    let right = GuiEvent::MouseUp{ button: MouseButton::Right, pos: (0.0, 0.0) };
+    clicks.update(&down, true);
+    assert!(!clicks.update(&right, true));
+    clicks.update(&GuiEvent::Focused(false), true);
+    assert!(!clicks.update(&up, true));
+    clicks.update(&down, true);
+    clicks.cancel();
+    assert!(!clicks.is_pressed());
+}
@@ -0,0 +1,15 @@
+use rusty_gui::Clipboard;
+
+
+/// Test that an image put on the system clipboard comes back with the same pixels
+#[test]
+#[ignore = "needs a system clipboard"]
+fn clipboard_image_test(){
+    let clipboard = Clipboard::new();
+    let image = image::RgbaImage::from_fn(4, 2, |x, y| image::Rgba([x as u8 * 60, y as u8 * 120, 30, 255]));
+    clipboard.set_image(&image).unwrap();
+
+    let pasted = clipboard.get_image().unwrap();
+    assert_eq!(pasted.dimensions(), (4, 2));
+    assert_eq!(pasted.as_raw(), image.as_raw());
+}
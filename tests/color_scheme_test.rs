@@ -0,0 +1,22 @@
+use rusty_gui::{ColorScheme, Theme, event::{EventTranslator, GuiEvent}};
+use winit::event::WindowEvent;
+
+
+/// Test that schemes resolve to the light or dark theme, following the OS for `System`, and that the OS switching
+/// schemes is translated into an event
+#[test]
+fn color_scheme_test(){
+    assert_eq!(ColorScheme::Dark.resolve(ColorScheme::Light), ColorScheme::Dark);
+    assert_eq!(ColorScheme::System.resolve(ColorScheme::Dark), ColorScheme::Dark);
+    assert_eq!(ColorScheme::System.resolve(ColorScheme::Light), ColorScheme::Light);
+    assert!(matches!(ColorScheme::detect(), ColorScheme::Light | ColorScheme::Dark));
+
+    let dark = Theme::dark();
+    assert_eq!(ColorScheme::System.theme(ColorScheme::Dark).colors, dark.colors);
+    assert_eq!(ColorScheme::Light.theme(ColorScheme::Dark).colors, Theme::light().colors);
+    assert_ne!(dark.colors.background, Theme::light().colors.background);
+
+    let mut translator = EventTranslator::new();
+    let event = translator.translate(&WindowEvent::ThemeChanged(winit::window::Theme::Dark));
+    assert_eq!(event, Some(GuiEvent::ColorSchemeChanged(ColorScheme::Dark)));
+}
@@ -0,0 +1,25 @@
+use rusty_gui::Color;
+
+
+/// Test that hex parsing, HSV and linear conversions work as expected
+#[test]
+fn color_test(){
+    let color = Color::from_hex("#336699").unwrap();
+    assert_eq!(color.to_rgba8(), [0x33, 0x66, 0x99, 0xff]);
+    assert_eq!(Color::from_hex("369").unwrap(), color);
+    assert_eq!(Color::from_hex("#33669980").unwrap().to_hex(), "#33669980");
+    assert!(Color::from_hex("#12345").is_err());
+    assert!(Color::from_hex("#zzzzzz").is_err());
+    assert!(Color::from_hex("#+f+f+f").is_err());
+    assert!(Color::from_hex("+ff").is_err());
+
+    let (h, s, v) = color.to_hsv();
+    assert_eq!(Color::from_hsv(h, s, v).to_rgba8(), color.to_rgba8());
+    assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), Color::GREEN);
+
+    // Mid grey in sRGB is much darker in linear space
+    let linear = Color::GRAY.to_linear();
+    assert!((linear[0] - 0.214).abs() < 0.001);
+    let back = Color::from_linear(linear[0], linear[1], linear[2], linear[3]);
+    assert!((back.r - 0.5).abs() < 0.0001);
+}
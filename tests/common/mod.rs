@@ -0,0 +1,26 @@
+//! Helpers shared by the tests.
+//!
+//! Tests that draw need a graphics adapter, which CI machines often don't have, so they're marked
+//! `#[ignore = "needs a graphics adapter"]` and a plain `cargo test` skips them. Run them with `cargo test -- --ignored`
+//! on a machine that has one. Without a GPU, Mesa's lavapipe (a software Vulkan driver) works as the adapter, as
+//! wgpu 0.6 can't fall back to a software adapter by itself.
+
+// Each test uses the helpers it needs
+#![allow(dead_code)]
+
+use futures::executor::block_on;
+use rusty_gui::rendering::Renderer;
+
+/// Create a headless renderer that draws at `size` pixels (see `with_adapter`)
+pub fn headless_renderer(size: (u32, u32)) -> Renderer{
+    with_adapter(block_on(Renderer::new_headless(size)))
+}
+
+/// Unwrap the result of creating something that needs a graphics adapter (eg, a `TestHarness`). Panics if it failed,
+/// so a GPU test run without an adapter fails rather than passing without checking anything.
+pub fn with_adapter<T>(result: Result<T, &'static str>) -> T{
+    match result{
+        Ok(v) => v,
+        Err(e) => panic!("{} (this test needs a graphics adapter)", e),
+    }
+}
@@ -0,0 +1,50 @@
+use std::any::Any;
+
+use rusty_gui::{components::{Component, Label}, layout::Layout};
+
+// A component covering a rectangle
+struct Area{
+    rect: [f32; 4],
+}
+
+impl Component for Area{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+}
+
+/// Test that components outside the window or the layout's clip rect aren't drawn, and can't be hit outside the clip rect
+#[test]
+fn culling_test(){
+    let screen = (100, 100);
+    let mut layout = Layout::new();
+    let inside = layout.add_component(Box::new(Area{ rect: [10.0, 10.0, 20.0, 20.0] }));
+    let partly = layout.add_component(Box::new(Area{ rect: [90.0, 40.0, 20.0, 20.0] }));
+    let offscreen = layout.add_component(Box::new(Area{ rect: [10.0, 500.0, 20.0, 20.0] }));
+    // Labels without a rect aren't culled until they've been measured
+    let label = layout.add_component(Box::new(Label::new("Row", 16.0, [10.0, 900.0])));
+    let mut row = Label::new("Row", 16.0, [0.0, 0.0]);
+    row.set_rect(Some([10.0, 900.0, 80.0, 20.0]));
+    let row = layout.add_component(Box::new(row));
+
+    assert_eq!(layout.visible_components(screen), vec![inside, partly, label]);
+
+    // Scrolling the clip rect down shows the rows below, and hides the ones above
+    layout.set_clip_rect(Some([0.0, 480.0, 100.0, 100.0]));
+    assert_eq!(layout.visible_components((1000, 1000)), vec![offscreen, label]);
+    layout.set_clip_rect(Some([0.0, 880.0, 100.0, 100.0]));
+    assert_eq!(layout.visible_components((1000, 1000)), vec![label, row]);
+
+    // The cursor only hits components inside the clip rect
+    layout.set_clip_rect(Some([0.0, 0.0, 20.0, 100.0]));
+    assert_eq!(layout.component_at((15.0, 15.0), screen), Some(inside));
+    assert_eq!(layout.component_at((25.0, 15.0), screen), None);
+    layout.set_clip_rect(None);
+    assert_eq!(layout.component_at((25.0, 15.0), screen), Some(inside));
+}
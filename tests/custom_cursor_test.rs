@@ -0,0 +1,13 @@
+use rusty_gui::rendering::CustomCursor;
+
+
+/// Test that custom cursors check their data and hotspot, and line the hotspot up with the mouse
+#[test]
+fn custom_cursor_test(){
+    assert!(CustomCursor::from_rgba(4, 4, vec![0; 15], (0, 0)).is_err());
+    assert!(CustomCursor::from_rgba(4, 4, vec![0; 64], (4, 0)).is_err());
+
+    let cursor = CustomCursor::from_rgba(4, 4, vec![255; 64], (2, 1)).unwrap();
+    assert_eq!(cursor.size(), (4, 4));
+    assert_eq!(cursor.top_left((100.0, 50.0)), (98.0, 49.0));
+}
@@ -0,0 +1,26 @@
+use std::{cell::Cell, rc::Rc};
+
+use rusty_gui::Color;
+
+mod common;
+
+
+/// Test that a custom pass is called once a frame while it's set
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn custom_pass_test(){
+    let mut renderer = common::headless_renderer((32, 32));
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    renderer.set_custom_pass(Some(Box::new(move |_: &mut wgpu::RenderPass, _: &wgpu::Device| counter.set(counter.get() + 1))));
+
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    renderer.render(Color::WHITE);
+    assert_eq!(calls.get(), 2);
+
+    renderer.set_custom_pass(None);
+    renderer.render(Color::WHITE);
+    assert_eq!(calls.get(), 2);
+}
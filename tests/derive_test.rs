@@ -0,0 +1,69 @@
+#![cfg(feature = "derive")]
+
+use std::time::Duration;
+
+use rusty_gui::{components::Component, gui_component, layout::Layout, GuiComponent};
+
+#[derive(GuiComponent)]
+#[component(update)]
+struct Counter{
+    #[component(pos)]
+    pos: [f32; 2],
+    #[component(visible)]
+    visible: bool,
+    #[component(text)]
+    text: String,
+    ticks: u32,
+}
+
+impl Counter{
+    fn update(&mut self, _delta_time: Duration){
+        self.ticks += 1;
+        self.text = self.ticks.to_string();
+    }
+}
+
+#[derive(GuiComponent)]
+struct Wrapper<T>(#[component(enabled)] bool, T);
+
+struct Toggle{
+    focused: bool,
+}
+
+#[gui_component]
+impl Component for Toggle{
+    fn is_focusable(&self) -> bool{
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+}
+
+
+/// Test that derived components get `as_any`, the methods their marked fields stand in for and forwarded methods, and that
+/// `gui_component` fills in `as_any` for a hand-written impl
+#[test]
+fn derive_test(){
+    let mut layout = Layout::new();
+    let counter = layout.add_component(Box::new(Counter{ pos: [4.0, -2.0], visible: true, text: String::new(), ticks: 0 }));
+    let wrapper = layout.add_component(Box::new(Wrapper(false, 7u8)));
+    let toggle = layout.add_component(Box::new(Toggle{ focused: false }));
+
+    let component = layout.get_component_mut(counter).unwrap();
+    assert_eq!(component.get_pos(), [4.0, -2.0]);
+    component.update(Duration::from_millis(16));
+    component.update(Duration::from_millis(16));
+    component.set_visible(false);
+    assert!(!component.is_visible());
+    assert_eq!(component.get_text(), Some("2"));
+    assert_eq!(layout.borrow_component_as_type::<Counter>(counter).unwrap().ticks, 2);
+
+    let component = layout.get_component(wrapper).unwrap();
+    assert!(!component.is_enabled());
+    assert_eq!(layout.borrow_component_as_type::<Wrapper<u8>>(wrapper).unwrap().1, 7);
+
+    layout.get_component_mut(toggle).unwrap().set_focus(true);
+    assert!(layout.borrow_component_as_type::<Toggle>(toggle).unwrap().focused);
+}
@@ -0,0 +1,71 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent}, layout::Layout};
+
+// A component covering a rectangle, that can ask to be redrawn
+struct Area{
+    rect: [f32; 4],
+    animating: bool,
+}
+
+impl Area{
+    fn new(rect: [f32; 4]) -> Box<Self>{
+        Box::new(Self{ rect, animating: false })
+    }
+}
+
+impl Component for Area{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn needs_redraw(&self) -> bool{
+        self.animating
+    }
+}
+
+/// Test that the layout is only dirty after something it draws may have changed, and stays dirty while a component needs redrawing
+#[test]
+fn dirty_layout_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    assert!(layout.is_dirty());
+    let id = layout.add_component(Area::new([0.0, 0.0, 100.0, 100.0]));
+    assert!(layout.take_dirty());
+    assert!(!layout.is_dirty());
+
+    // Moving the cursor around without changing the hover, or reading components, changes nothing
+    layout.update_hover(Some((150.0, 150.0)), screen);
+    layout.update_hover(Some((160.0, 150.0)), screen);
+    assert!(layout.get_component(id).is_some());
+    assert!(!layout.is_dirty());
+
+    layout.update_hover(Some((10.0, 10.0)), screen);
+    assert!(layout.take_dirty());
+    layout.set_z(id, 1).unwrap();
+    assert!(layout.take_dirty());
+    assert!(!layout.take_dirty());
+
+    // Borrowing a component mutably may change it
+    layout.borrow_component_as_type_mut::<Area>(id).unwrap().animating = true;
+    assert!(layout.take_dirty());
+    // It stays dirty while the component wants frames
+    assert!(layout.take_dirty());
+    layout.borrow_component_as_type_mut::<Area>(id).unwrap().animating = false;
+    assert!(layout.take_dirty());
+    assert!(!layout.is_dirty());
+
+    layout.remove_component_by_id(id);
+    assert!(layout.is_dirty());
+}
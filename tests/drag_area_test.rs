@@ -0,0 +1,20 @@
+use rusty_gui::components::DragArea;
+
+
+/// Test that drag areas hit test points in window pixels
+#[test]
+fn drag_area_test(){
+    let strip = DragArea::TopStrip{ height: 30.0 };
+    assert!(strip.contains((500.0, 10.0), (800, 600)));
+    assert!(!strip.contains((500.0, 40.0), (800, 600)));
+    assert!(!strip.contains((900.0, 10.0), (800, 600)));
+
+    let rect = DragArea::Rect{ x: 10.0, y: 10.0, width: 100.0, height: 20.0 };
+    assert!(rect.contains((50.0, 15.0), (800, 600)));
+    assert!(!rect.contains((5.0, 15.0), (800, 600)));
+
+    // A component centered in the window, 200 by 150 pixels
+    let component = DragArea::Component{ position: [400.0, 300.0], scale: [200.0, 150.0] };
+    assert!(component.contains((400.0, 300.0), (800, 600)));
+    assert!(!component.contains((550.0, 300.0), (800, 600)));
+}
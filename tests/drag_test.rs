@@ -0,0 +1,80 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent, MouseButton}, layout::Layout};
+
+// A draggable handle that adds up how far it's been dragged
+struct Handle{
+    rect: [f32; 4],
+    dragging: bool,
+    moved: (f64, f64),
+}
+
+impl Component for Handle{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        true
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn on_drag_start(&mut self, button: MouseButton, _pos: (f64, f64)) -> bool{
+        self.dragging = button == MouseButton::Left;
+        self.dragging
+    }
+    fn on_drag_move(&mut self, _pos: (f64, f64), delta: (f64, f64)){
+        self.moved.0 += delta.0;
+        self.moved.1 += delta.1;
+    }
+    fn on_drag_end(&mut self, _pos: (f64, f64)){
+        self.dragging = false;
+    }
+}
+
+/// Test that pressing on a draggable component captures the mouse, reports movement deltas, and releases on button up
+#[test]
+fn drag_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    let handle = layout.add_component(Box::new(Handle{ rect: [0.0, 0.0, 20.0, 20.0], dragging: false, moved: (0.0, 0.0) }));
+    let moved = |layout: &Layout| layout.borrow_component_as_type::<Handle>(handle).unwrap().moved;
+
+    // Pressing outside of it, or with a button it doesn't drag with, does nothing
+    layout.update_hover(Some((100.0, 100.0)), screen);
+    assert_eq!(layout.update_drag(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (100.0, 100.0) }), None);
+    assert_eq!(layout.dragging(), None);
+    layout.update_hover(Some((10.0, 10.0)), screen);
+    layout.update_drag(&GuiEvent::MouseDown{ button: MouseButton::Right, pos: (10.0, 10.0) });
+    assert_eq!(layout.dragging(), None);
+
+    layout.update_drag(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 10.0) });
+    assert_eq!(layout.dragging(), Some(handle));
+
+    // Mouse events are captured, even outside of the handle. Keyboard events aren't.
+    assert_eq!(layout.update_drag(&GuiEvent::MouseMove{ pos: (15.0, 12.0) }), Some(handle));
+    assert_eq!(layout.update_drag(&GuiEvent::MouseMove{ pos: (150.0, 40.0) }), Some(handle));
+    assert_eq!(layout.update_drag(&GuiEvent::TextInput('a')), None);
+    assert_eq!(moved(&layout), (140.0, 30.0));
+
+    // Releasing another button doesn't end the drag
+    layout.update_drag(&GuiEvent::MouseUp{ button: MouseButton::Right, pos: (150.0, 40.0) });
+    assert_eq!(layout.dragging(), Some(handle));
+    assert_eq!(layout.update_drag(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (150.0, 40.0) }), Some(handle));
+    assert_eq!(layout.dragging(), None);
+    assert!(!layout.borrow_component_as_type::<Handle>(handle).unwrap().dragging);
+
+    // Losing focus ends a drag too
+    layout.update_drag(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 10.0) });
+    layout.update_drag(&GuiEvent::Focused(false));
+    assert_eq!(layout.dragging(), None);
+}
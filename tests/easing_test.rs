@@ -0,0 +1,30 @@
+use rusty_gui::easing::{self, Easing};
+
+
+/// Test that every easing curve starts at 0 and ends at 1, that the named curves match their functions, and that
+/// cubic bezier curves match the standard ones they approximate
+#[test]
+fn easing_test(){
+    let curves = [
+        Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut,
+        Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut,
+        Easing::ElasticIn, Easing::ElasticOut, Easing::ElasticInOut,
+        Easing::BounceIn, Easing::BounceOut, Easing::BounceInOut,
+        Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
+    ];
+    for curve in curves.iter(){
+        assert!(curve.apply(0.0).abs() < 1e-4, "{:?} doesn't start at 0", curve);
+        assert!((curve.apply(1.0) - 1.0).abs() < 1e-4, "{:?} doesn't end at 1", curve);
+    }
+
+    assert_eq!(Easing::QuadOut.apply(0.5), easing::quad_out(0.5));
+    assert_eq!(easing::quad_in(0.5), 0.25);
+    assert_eq!(easing::cubic_in_out(0.5), 0.5);
+    // Springs overshoot, bounces don't
+    assert!((0..100).any(|i| easing::elastic_out(i as f32 / 100.0) > 1.0));
+    assert!((0..=100).all(|i| easing::bounce_out(i as f32 / 100.0) <= 1.0));
+
+    // A linear bezier is linear, and the bezier for quad in is close to it
+    assert!((easing::cubic_bezier(0.0, 0.0, 1.0, 1.0, 0.3) - 0.3).abs() < 1e-3);
+    assert!((easing::cubic_bezier(0.33, 0.0, 0.67, 0.33, 0.5) - easing::quad_in(0.5)).abs() < 0.02);
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "egui")]
+
+use std::{cell::Cell, rc::Rc};
+
+use rusty_gui::{Color, components::EguiArea, egui};
+
+mod common;
+
+
+/// Test that an egui area runs its UI once a frame, and draws it without errors
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn egui_area_test(){
+    let mut renderer = common::headless_renderer((128, 128));
+
+    let frames = Rc::new(Cell::new(0));
+    let counter = frames.clone();
+    let area = renderer.layout.add_component(Box::new(EguiArea::new([16.0, 16.0, 96.0, 96.0], move |ctx: &egui::Context| {
+        counter.set(counter.get() + 1);
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Hello");
+            let _ = ui.button("Press");
+        });
+    })));
+
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    renderer.render(Color::WHITE);
+    assert_eq!(frames.get(), 2);
+    assert_eq!(renderer.layout.borrow_component_as_type::<EguiArea>(area).unwrap().rect(), [16.0, 16.0, 96.0, 96.0]);
+}
@@ -0,0 +1,40 @@
+use rusty_gui::{components::Label, rendering::{Renderer, Viewport}};
+
+mod common;
+
+
+/// Test that a renderer built on another renderer's device draws into a texture it doesn't own
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn embedded_renderer_test(){
+    // Borrow a device from a headless renderer, as a game would bring its own
+    let host = common::headless_renderer((64, 64));
+    let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+    let mut renderer = Renderer::from_raw(host.device.clone(), host.queue.clone(), format);
+    assert!(renderer.is_embedded());
+    assert!(!renderer.is_headless());
+    renderer.layout.add_component(Box::new(Label::new("Score", 16.0, [8.0, 8.0])));
+
+    let texture = host.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Game frame"),
+        size: wgpu::Extent3d { width: 64, height: 64, depth: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    // Draw into the bottom right quarter of the frame, as an overlay
+    let mut encoder = host.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Game encoder") });
+    renderer.prepass();
+    renderer.render_into(&mut encoder, &view, Viewport::new([32, 32, 32, 32], (64, 64)));
+    host.queue.submit(std::iter::once(encoder.finish()));
+    host.device.poll(wgpu::Maintain::Wait);
+
+    // The viewport is the layout's window
+    assert_eq!(renderer.frame_count(), 1);
+    assert_eq!((renderer.size.width, renderer.size.height), (32, 32));
+    assert_eq!(Viewport::full((64, 64)).rect, [0, 0, 64, 64]);
+}
@@ -0,0 +1,75 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent, ModifiersState, MouseButton, VirtualKeyCode}, layout::Layout};
+
+// A focusable component covering a rectangle
+struct Area{
+    rect: [f32; 4],
+    focused: bool,
+}
+
+impl Area{
+    fn new(rect: [f32; 4]) -> Box<Self>{
+        Box::new(Self{ rect, focused: false })
+    }
+}
+
+impl Component for Area{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        true
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn is_focusable(&self) -> bool{
+        true
+    }
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+}
+
+/// Test that events go to the hovered (or focused) component first, then the rest from the topmost down
+#[test]
+fn event_propagation_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    let back = layout.add_component(Area::new([0.0, 0.0, 200.0, 200.0]));
+    let middle = layout.add_component(Area::new([0.0, 0.0, 100.0, 100.0]));
+    let front = layout.add_component(Area::new([150.0, 150.0, 50.0, 50.0]));
+    let press = GuiEvent::MouseDown{ button: MouseButton::Left, pos: (50.0, 50.0) };
+    let key = GuiEvent::KeyDown{ key: VirtualKeyCode::A, scancode: 0, modifiers: ModifiersState::empty() };
+
+    // With nothing hovered or focused, the topmost component goes first
+    assert_eq!(layout.dispatch_order(&press), vec![front, middle, back]);
+
+    // The component under the cursor gets presses before the ones above it that aren't
+    layout.update_hover(Some((50.0, 50.0)), screen);
+    assert_eq!(layout.dispatch_order(&press), vec![middle, front, back]);
+    assert_eq!(layout.dispatch_order(&key), vec![front, middle, back]);
+
+    // The focused component gets keys first, but not presses
+    layout.set_focus(Some(back)).unwrap();
+    assert_eq!(layout.dispatch_order(&key), vec![back, front, middle]);
+    assert_eq!(layout.dispatch_order(&press), vec![middle, front, back]);
+
+    // Everything else just goes from the top down
+    assert_eq!(layout.dispatch_order(&GuiEvent::MouseMove{ pos: (50.0, 50.0) }), vec![front, middle, back]);
+    assert_eq!(EventResult::consumed_if(true), EventResult::Consumed);
+    assert!(!EventResult::Ignored.is_consumed());
+}
@@ -0,0 +1,70 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent, NavAction}, layout::Layout};
+
+// A focusable tile covering a rectangle
+struct Tile{
+    rect: [f32; 4],
+    focused: bool,
+}
+
+impl Tile{
+    fn new(x: f32, y: f32) -> Box<Self>{
+        Box::new(Self{ rect: [x, y, 40.0, 40.0], focused: false })
+    }
+}
+
+impl Component for Tile{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        true
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn is_focusable(&self) -> bool{
+        true
+    }
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+}
+
+/// Test that directional navigation moves focus to the nearest component in that direction, across a grid
+#[test]
+fn focus_direction_test(){
+    let screen = (400, 400);
+    let mut layout = Layout::new();
+    // A 2x2 grid, added out of order so the tab order doesn't match the layout
+    let bottom_right = layout.add_component(Tile::new(100.0, 100.0));
+    let top_left = layout.add_component(Tile::new(0.0, 0.0));
+    let top_right = layout.add_component(Tile::new(100.0, 0.0));
+    let bottom_left = layout.add_component(Tile::new(0.0, 100.0));
+
+    // With nothing focused, it falls back to the tab order
+    assert_eq!(layout.focus_direction(NavAction::Down, screen), Some(bottom_right));
+    layout.set_focus(Some(top_left)).unwrap();
+
+    assert_eq!(layout.focus_direction(NavAction::Right, screen), Some(top_right));
+    assert_eq!(layout.focus_direction(NavAction::Down, screen), Some(bottom_right));
+    assert_eq!(layout.focus_direction(NavAction::Left, screen), Some(bottom_left));
+    assert_eq!(layout.focus_direction(NavAction::Up, screen), Some(top_left));
+
+    // There's nothing further up, so focus stays put
+    assert_eq!(layout.focus_direction(NavAction::Up, screen), Some(top_left));
+    assert_eq!(layout.focus_direction(NavAction::Activate, screen), Some(top_left));
+}
@@ -0,0 +1,78 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent}, layout::Layout};
+
+// A component that only keeps track of its focus
+struct Focusable{
+    focusable: bool,
+    enabled: bool,
+    focused: bool,
+}
+
+impl Focusable{
+    fn new(focusable: bool, enabled: bool) -> Box<Self>{
+        Box::new(Self{ focusable, enabled, focused: false })
+    }
+}
+
+impl Component for Focusable{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn is_focusable(&self) -> bool{
+        self.focusable
+    }
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+    fn focus_rect(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some([10.0, 20.0, 30.0, 40.0])
+    }
+}
+
+/// Test that focus cycles through focusable, enabled components in both directions, skipping the rest
+#[test]
+fn focus_test(){
+    let mut layout = Layout::new();
+    let first = layout.add_component(Focusable::new(true, true));
+    layout.add_component(Focusable::new(false, true));
+    layout.add_component(Focusable::new(true, false));
+    let last = layout.add_component(Focusable::new(true, true));
+
+    assert_eq!(layout.focused(), None);
+    assert_eq!(layout.focus_rect((100, 100)), None);
+    assert_eq!(layout.focus_next(), Some(first));
+    assert_eq!(layout.focus_next(), Some(last));
+    assert_eq!(layout.focus_next(), Some(first));
+    assert_eq!(layout.focus_previous(), Some(last));
+    assert_eq!(layout.focus_rect((100, 100)), Some([10.0, 20.0, 30.0, 40.0]));
+
+    // Only one component holds focus at a time
+    assert!(layout.borrow_component(last).has_focus());
+    assert!(!layout.borrow_component(first).has_focus());
+
+    assert!(layout.set_focus(Some(1)).is_err());
+    assert!(layout.set_focus(Some(10)).is_err());
+    layout.set_focus(None).unwrap();
+    assert!(!layout.borrow_component(last).has_focus());
+
+    // Removing a component keeps focus on the same one
+    layout.set_focus(Some(last)).unwrap();
+    layout.remove_component_by_id(first);
+    assert_eq!(layout.focused(), Some(last));
+}
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+use rusty_gui::rendering::FrameStats;
+
+
+/// Test that the frames per second are derived from the frame time
+#[test]
+fn frame_stats_test(){
+    let mut stats = FrameStats::default();
+    assert_eq!(stats.fps(), 0.0);
+
+    stats.frame_time = Duration::from_millis(16);
+    assert!((stats.fps() - 62.5).abs() < 0.01);
+}
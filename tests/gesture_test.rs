@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+use rusty_gui::{event::{GuiEvent, MouseButton, TouchPhase}, gesture::{Gesture, GestureRecognizer, SwipeDirection}};
+
+
+/// Test that taps, long presses, swipes and pinches are recognized from mouse and touch input
+#[test]
+fn gesture_test(){
+    let mut gestures = GestureRecognizer::new();
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+    let touch = |id, phase, pos| GuiEvent::Touch{ id, phase, pos };
+
+    // A quick click is a tap
+    gestures.process(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 10.0) }, at(0));
+    assert_eq!(gestures.process(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (12.0, 10.0) }, at(100)), vec![Gesture::Tap{ pos: (12.0, 10.0) }]);
+
+    // Holding still is a long press, found by polling - and releasing it afterwards isn't a tap
+    gestures.process(&touch(1, TouchPhase::Started, (50.0, 50.0)), at(1000));
+    assert_eq!(gestures.next_deadline(), Some(at(1500)));
+    assert_eq!(gestures.poll(at(1200)), None);
+    assert_eq!(gestures.poll(at(1500)), Some(Gesture::LongPress{ pos: (50.0, 50.0) }));
+    assert_eq!(gestures.poll(at(1600)), None);
+    assert_eq!(gestures.process(&touch(1, TouchPhase::Ended, (50.0, 50.0)), at(1700)), vec![]);
+
+    // A quick move is a swipe, in the direction it mostly moved
+    gestures.process(&touch(2, TouchPhase::Started, (100.0, 100.0)), at(2000));
+    gestures.process(&touch(2, TouchPhase::Moved, (110.0, 40.0)), at(2100));
+    assert_eq!(gestures.poll(at(2600)), None);
+    assert_eq!(
+        gestures.process(&touch(2, TouchPhase::Ended, (110.0, 20.0)), at(2200)),
+        vec![Gesture::Swipe{ direction: SwipeDirection::Up, start: (100.0, 100.0), end: (110.0, 20.0) }]
+    );
+
+    // Two fingers moving apart pinch, and lifting them afterwards isn't anything else
+    gestures.process(&touch(3, TouchPhase::Started, (0.0, 0.0)), at(3000));
+    gestures.process(&touch(4, TouchPhase::Started, (100.0, 0.0)), at(3000));
+    assert_eq!(gestures.process(&touch(4, TouchPhase::Moved, (200.0, 0.0)), at(3100)), vec![Gesture::Pinch{ center: (100.0, 0.0), scale: 2.0, delta: 2.0 }]);
+    assert_eq!(gestures.process(&touch(4, TouchPhase::Moved, (300.0, 0.0)), at(3200)), vec![Gesture::Pinch{ center: (150.0, 0.0), scale: 3.0, delta: 1.5 }]);
+    assert_eq!(gestures.process(&touch(4, TouchPhase::Ended, (300.0, 0.0)), at(3300)), vec![]);
+    assert_eq!(gestures.process(&touch(3, TouchPhase::Ended, (0.0, 0.0)), at(3300)), vec![]);
+
+    // The mouse is ignored while a finger is down
+    gestures.process(&touch(5, TouchPhase::Started, (0.0, 0.0)), at(4000));
+    gestures.process(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (0.0, 0.0) }, at(4000));
+    assert_eq!(gestures.process(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (0.0, 0.0) }, at(4050)), vec![]);
+}
@@ -0,0 +1,33 @@
+use futures::executor::block_on;
+use rusty_gui::{Color, rendering::RendererBuilder};
+
+mod common;
+
+
+/// Test that registered glyphs are warmed a frame's budget at a time, and that whitespace is skipped
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn glyph_warmup_test(){
+    let mut renderer = common::with_adapter(block_on(RendererBuilder::new().warm_glyphs(&[48.0], "ab cd").set_glyph_warmup_budget(3).build_headless((32, 32))));
+
+    assert_eq!(renderer.pending_warm_glyphs(), 4);
+    assert!(renderer.needs_redraw());
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.pending_warm_glyphs(), 1);
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.pending_warm_glyphs(), 0);
+    assert!(!renderer.needs_redraw());
+
+    // Registering more later starts warming again, and a budget of 0 pauses it
+    renderer.set_glyph_warmup_budget(0);
+    renderer.warm_glyph_cache(wgpu_glyph::FontId(0), &[24.0, 32.0], "xyz");
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.pending_warm_glyphs(), 6);
+    renderer.set_glyph_warmup_budget(64);
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.pending_warm_glyphs(), 3);
+}
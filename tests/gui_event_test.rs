@@ -0,0 +1,37 @@
+use rusty_gui::event::{EventTranslator, GuiEvent, ModifiersState, MouseButton, VirtualKeyCode};
+use winit::{dpi::PhysicalPosition, event::{DeviceId, ElementState, Ime, KeyboardInput, WindowEvent}};
+
+
+/// Test that winit events are translated into GuiEvents, with clicks placed at the last cursor position
+#[test]
+#[allow(deprecated)]
+fn gui_event_test(){
+    let device_id = unsafe { DeviceId::dummy() };
+    let mut translator = EventTranslator::new();
+    let click = WindowEvent::MouseInput{ device_id, state: ElementState::Pressed, button: MouseButton::Left, modifiers: ModifiersState::empty() };
+
+    // Clicks can't be placed until the cursor position is known
+    assert_eq!(translator.translate(&click), None);
+
+    let moved = WindowEvent::CursorMoved{ device_id, position: PhysicalPosition::new(10.0, 20.0), modifiers: ModifiersState::empty() };
+    assert_eq!(translator.translate(&moved), Some(GuiEvent::MouseMove{ pos: (10.0, 20.0) }));
+    assert_eq!(translator.translate(&click), Some(GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 20.0) }));
+
+    // Key presses carry the modifiers held at the time, and both the logical and the physical key
+    translator.translate(&WindowEvent::ModifiersChanged(ModifiersState::CTRL));
+    let key = WindowEvent::KeyboardInput{
+        device_id,
+        input: KeyboardInput{ scancode: 46, state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::C), modifiers: ModifiersState::empty() },
+        is_synthetic: false,
+    };
+    assert_eq!(translator.translate(&key), Some(GuiEvent::KeyDown{ key: VirtualKeyCode::C, scancode: 46, modifiers: ModifiersState::CTRL }));
+
+    assert_eq!(translator.translate(&WindowEvent::ReceivedCharacter('a')), Some(GuiEvent::TextInput('a')));
+    // Input method composition, and turning the input method off, which drops the composition
+    assert_eq!(translator.translate(&WindowEvent::Ime(Ime::Preedit("ni".to_string(), Some((2, 2))))), Some(GuiEvent::ImePreedit("ni".to_string())));
+    assert_eq!(translator.translate(&WindowEvent::Ime(Ime::Commit("你".to_string()))), Some(GuiEvent::ImeCommit("你".to_string())));
+    assert_eq!(translator.translate(&WindowEvent::Ime(Ime::Disabled)), Some(GuiEvent::ImePreedit(String::new())));
+    assert_eq!(translator.translate(&WindowEvent::Ime(Ime::Enabled)), None);
+    assert_eq!(translator.translate(&WindowEvent::CursorLeft{ device_id }), Some(GuiEvent::MouseLeave));
+    assert_eq!(translator.cursor_position(), None);
+}
@@ -0,0 +1,14 @@
+use rusty_gui::test::TestHarness;
+
+mod common;
+
+
+/// Test that the harness draws its layout at its size, over the clear color
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn harness_render_test(){
+    let mut harness = common::with_adapter(TestHarness::new((16, 16)));
+    let frame = harness.render();
+    assert_eq!((frame.width(), frame.height()), (16, 16));
+    assert_eq!(frame.get_pixel(8, 8), [255, 255, 255, 255]);
+}
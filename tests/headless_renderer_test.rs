@@ -0,0 +1,24 @@
+use rusty_gui::Color;
+
+mod common;
+
+
+/// Test that a headless renderer draws without a window
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn headless_renderer_test(){
+    let mut renderer = common::headless_renderer((64, 32));
+    assert!(renderer.is_headless());
+
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.frame_count(), 1);
+
+    let snapshot = renderer.capture_snapshot(Color::from_rgba8(255, 0, 0, 255));
+    assert_eq!((snapshot.width(), snapshot.height()), (64, 32));
+    assert_eq!(snapshot.get_pixel(10, 10), [255, 0, 0, 255]);
+
+    renderer.resize(winit::dpi::PhysicalSize::new(16, 16));
+    renderer.render(Color::WHITE);
+    assert_eq!(renderer.capture_snapshot(Color::WHITE).width(), 16);
+}
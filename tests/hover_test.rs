@@ -0,0 +1,75 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent}, layout::Layout};
+
+// A component covering a rectangle, that counts how often it's hovered
+struct Area{
+    rect: [f32; 4],
+    enabled: bool,
+    hovered: bool,
+    enters: u32,
+}
+
+impl Area{
+    fn new(rect: [f32; 4]) -> Box<Self>{
+        Box::new(Self{ rect, enabled: true, hovered: false, enters: 0 })
+    }
+}
+
+impl Component for Area{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn on_hover_enter(&mut self){
+        self.hovered = true;
+        self.enters += 1;
+    }
+    fn on_hover_exit(&mut self){
+        self.hovered = false;
+    }
+}
+
+/// Test that the hit test hovers the topmost enabled component under the cursor, sending enter and exit only on changes
+#[test]
+fn hover_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    let back = layout.add_component(Area::new([0.0, 0.0, 100.0, 100.0]));
+    let front = layout.add_component(Area::new([50.0, 50.0, 100.0, 100.0]));
+    let area = |layout: &Layout, id| layout.borrow_component_as_type::<Area>(id).map(|v| (v.hovered, v.enters)).unwrap();
+
+    layout.update_hover(Some((10.0, 10.0)), screen);
+    assert_eq!(layout.hovered(), Some(back));
+    layout.update_hover(Some((20.0, 20.0)), screen);
+    assert_eq!(area(&layout, back), (true, 1));
+
+    // Where they overlap, the one added last is on top
+    layout.update_hover(Some((75.0, 75.0)), screen);
+    assert_eq!(layout.hovered(), Some(front));
+    assert_eq!(area(&layout, back), (false, 1));
+    assert_eq!(area(&layout, front), (true, 1));
+
+    // Disabled components can't be hovered
+    layout.borrow_component_as_type_mut::<Area>(front).unwrap().enabled = false;
+    assert_eq!(layout.component_at((75.0, 75.0), screen), Some(back));
+
+    layout.update_hover(None, screen);
+    assert_eq!(layout.hovered(), None);
+    assert_eq!(area(&layout, front), (false, 1));
+    assert_eq!(layout.component_at((190.0, 10.0), screen), None);
+}
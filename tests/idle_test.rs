@@ -0,0 +1,29 @@
+use rusty_gui::{Color, components::{Button, Label, Panel}, rendering::Transform};
+
+mod common;
+
+
+/// Test that a static layout stops asking for frames once it's been drawn, so an idle app does no work between events
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn idle_test(){
+    let mut renderer = common::headless_renderer((64, 64));
+
+    let mut panel = Panel::new([0.0, 0.0, 64.0, 64.0], &renderer);
+    panel.add_child(Box::new(Label::new("Idle", 16.0, [4.0, 4.0])));
+    renderer.layout.add_component(Box::new(panel));
+    let transform = Transform::new(
+        cgmath::Vector3::<f32>::new(24.0, 44.0, 0.0),
+        cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(20.0, 10.0, 1.0),
+        renderer.transform_pool()
+    );
+    renderer.layout.add_component(Box::new(Button::new(transform, None, &renderer, Some("Ok"), 16.0)));
+    assert!(renderer.needs_redraw());
+
+    for _ in 0..3{
+        renderer.prepass();
+        renderer.render(Color::WHITE);
+        assert!(!renderer.needs_redraw());
+    }
+}
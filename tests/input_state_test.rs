@@ -0,0 +1,33 @@
+use rusty_gui::{event::{GuiEvent, ModifiersState, MouseButton, VirtualKeyCode}, input::InputState};
+
+/// Test that the input state follows the keys (logical and physical), buttons and cursor, and forgets what's held when the window loses focus
+#[test]
+fn input_state_test(){
+    let mut input = InputState::new();
+    assert_eq!(input.cursor_position(), None);
+
+    input.update(&GuiEvent::MouseMove{ pos: (10.0, 20.0) });
+    input.update(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 20.0) });
+    input.update(&GuiEvent::KeyDown{ key: VirtualKeyCode::LShift, scancode: 42, modifiers: ModifiersState::SHIFT });
+    input.update(&GuiEvent::KeyDown{ key: VirtualKeyCode::W, scancode: 17, modifiers: ModifiersState::SHIFT });
+    assert_eq!(input.cursor_position(), Some((10.0, 20.0)));
+    assert!(input.is_mouse_down(MouseButton::Left));
+    assert!(!input.is_mouse_down(MouseButton::Right));
+    assert!(input.is_key_down(VirtualKeyCode::W));
+    assert!(input.is_scancode_down(17) && !input.is_scancode_down(30));
+    assert!(input.modifiers().shift());
+    assert_eq!(input.keys_down().count(), 2);
+
+    input.update(&GuiEvent::KeyUp{ key: VirtualKeyCode::W, scancode: 17, modifiers: ModifiersState::SHIFT });
+    input.update(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (10.0, 20.0) });
+    assert!(!input.is_key_down(VirtualKeyCode::W));
+    assert!(!input.is_scancode_down(17));
+    assert!(!input.is_mouse_down(MouseButton::Left));
+
+    input.update(&GuiEvent::Focused(false));
+    input.update(&GuiEvent::MouseLeave);
+    assert!(!input.is_key_down(VirtualKeyCode::LShift));
+    assert!(!input.is_scancode_down(42));
+    assert!(!input.modifiers().shift());
+    assert_eq!(input.cursor_position(), None);
+}
@@ -0,0 +1,24 @@
+use rusty_gui::components::Label;
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+
+/// Test that a label aligned in a rect is anchored at the point of the rect its alignment picks
+#[test]
+fn label_rect_test(){
+    let mut label = Label::new("OK", 16.0, [5.0, 6.0]);
+    assert_eq!(label.anchor(), [5.0, 6.0]);
+
+    label.set_rect(Some([100.0, 50.0, 80.0, 20.0]));
+    assert_eq!(label.anchor(), [100.0, 50.0]);
+
+    label.align_horizontal(HorizontalAlign::Center);
+    label.align_vertical(VerticalAlign::Center);
+    assert_eq!(label.anchor(), [140.0, 60.0]);
+
+    label.align_horizontal(HorizontalAlign::Right);
+    label.align_vertical(VerticalAlign::Bottom);
+    assert_eq!(label.anchor(), [180.0, 70.0]);
+
+    label.set_rect(None);
+    assert_eq!(label.anchor(), [5.0, 6.0]);
+}
@@ -0,0 +1,27 @@
+use rusty_gui::{color::Color, components::Label, test::TestHarness};
+
+mod common;
+
+
+/// Test that a label is drawn the same while nothing changes, and redrawn when a property its cached section depends on does
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn label_section_cache_test(){
+    let mut harness = common::with_adapter(TestHarness::new((64, 32)));
+    let id = harness.layout_mut().add_component(Box::new(Label::new("Hi", 16.0, [4.0, 4.0])));
+
+    let first = harness.render();
+    assert!(first.diff(&harness.render(), 0).is_empty());
+
+    label(&mut harness, id).set_color(Color::RED);
+    let recolored = harness.render();
+    assert!(!first.diff(&recolored, 0).is_empty());
+
+    label(&mut harness, id).set_pos([30.0, 4.0]);
+    assert!(!recolored.diff(&harness.render(), 0).is_empty());
+}
+
+// The label the test added
+fn label(harness: &mut TestHarness, id: usize) -> &mut Label{
+    harness.layout_mut().get_component_mut(id).unwrap().as_any_mut().downcast_mut::<Label>().unwrap()
+}
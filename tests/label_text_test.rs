@@ -0,0 +1,20 @@
+use rusty_gui::components::{Component, Label};
+
+
+/// Test that a label's text and size can be changed, and changing them clears its measured size
+#[test]
+fn label_text_test(){
+    let score = 10;
+    let mut label = Label::new(format!("Score: {}", score), 32.0, [0.0, 0.0]);
+    assert_eq!(label.text(), "Score: 10");
+    assert_eq!(label.get_text(), Some("Score: 10"));
+    assert_eq!(label.measured_size(), None);
+
+    label.set_text(String::from("Score: 11"));
+    assert_eq!(label.text(), "Score: 11");
+    assert_eq!(label.glyph_count(), 8);
+
+    label.set_size(16.0);
+    assert_eq!(label.get_size(), 16.0);
+    assert_eq!(label.measured_size(), None);
+}
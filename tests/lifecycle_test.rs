@@ -0,0 +1,64 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use rusty_gui::{components::Component, layout::Layout, rendering::Renderer};
+
+mod common;
+
+// Records its lifecycle, and creates a buffer while it's attached
+struct Lazy{
+    name: &'static str,
+    log: Rc<RefCell<Vec<String>>>,
+    buffer: Option<wgpu::Buffer>,
+}
+
+impl Component for Lazy{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn on_attach(&mut self, renderer: &Renderer){
+        self.buffer = Some(renderer.device.create_buffer(&wgpu::BufferDescriptor{
+            label: Some("Lazy buffer"),
+            size: 16,
+            usage: wgpu::BufferUsage::UNIFORM,
+            mapped_at_creation: false,
+        }));
+        self.log.borrow_mut().push(format!("attach {}", self.name));
+    }
+
+    fn on_detach(&mut self){
+        self.buffer = None;
+        self.log.borrow_mut().push(format!("detach {}", self.name));
+    }
+}
+
+
+/// Test that components are attached before the first frame they're in, once each, and detached when they're removed or
+/// their layout is replaced
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn lifecycle_test(){
+    let mut renderer = common::headless_renderer((32, 32));
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let lazy = |name| Box::new(Lazy{ name, log: log.clone(), buffer: None });
+
+    let first = renderer.layout.add_component(lazy("a"));
+    renderer.layout.add_component(lazy("b"));
+    assert!(renderer.layout.needs_attach());
+    renderer.prepass();
+    renderer.prepass();
+    assert!(!renderer.layout.needs_attach());
+    assert!(renderer.layout.borrow_component_as_type::<Lazy>(first).unwrap().buffer.is_some());
+
+    renderer.layout.remove_component_by_id(first);
+    let mut layout = Layout::new();
+    layout.add_component(lazy("c"));
+    renderer.layout = layout;
+    renderer.prepass();
+
+    assert_eq!(*log.borrow(), ["attach a", "attach b", "detach a", "detach b", "attach c"]);
+}
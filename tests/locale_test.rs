@@ -0,0 +1,37 @@
+use rusty_gui::{Locale, TextKey, components::{Component, Label}};
+
+
+/// Test that locales parse, look up keys with their arguments, and that labels showing a key are told when the locale changes
+#[test]
+fn locale_test(){
+    let french = Locale::parse("fr", "
+        # French
+        menu.quit = Quitter
+        greeting = Bonjour, {name} !
+        lines = Un\\nDeux
+    ").unwrap();
+    assert_eq!(french.language(), "fr");
+    assert_eq!(french.len(), 3);
+    assert_eq!(french.get("menu.quit"), Some("Quitter"));
+    assert_eq!(french.resolve(&TextKey::with_args("greeting", &[("name", "Ada")])), "Bonjour, Ada !");
+    assert_eq!(french.resolve(&"lines".into()), "Un\nDeux");
+    // Missing keys are shown as they are
+    assert_eq!(french.resolve(&TextKey::new("menu.open")), "menu.open");
+    assert!(Locale::parse("fr", "no equals sign").is_err());
+
+    let mut english = Locale::new("en");
+    english.insert("menu.quit", "Quit");
+    assert_eq!(english.resolve(&"menu.quit".into()), "Quit");
+
+    // Only labels showing a key care about the locale
+    let mut label = Label::new("Fixed", 16.0, [0.0, 0.0]);
+    assert!(!label.apply_locale(&french));
+    label.set_text_key("menu.quit");
+    assert_eq!(label.text_key().map(TextKey::key), Some("menu.quit"));
+    assert!(label.apply_locale(&french));
+
+    // Fixed text replaces the key
+    label.set_text("Fixed again");
+    assert_eq!(label.text_key(), None);
+    assert!(!label.apply_locale(&english));
+}
@@ -0,0 +1,24 @@
+use rusty_gui::message::Mailbox;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Message{
+    Increment,
+    Rename(String),
+}
+
+/// Test that messages sent through any clone of a mailbox are taken back out in order
+#[test]
+fn mailbox_test(){
+    let mailbox = Mailbox::new();
+    let sender = mailbox.clone();
+    assert!(mailbox.is_empty());
+
+    sender.send(Message::Increment);
+    mailbox.send(Message::Rename("Counter".to_string()));
+    sender.send(Message::Increment);
+    assert_eq!(mailbox.len(), 3);
+
+    assert_eq!(mailbox.take_all(), vec![Message::Increment, Message::Rename("Counter".to_string()), Message::Increment]);
+    assert!(sender.is_empty());
+    assert_eq!(sender.take_all(), vec![]);
+}
@@ -0,0 +1,16 @@
+use rusty_gui::rendering::{MemoryKind, MemoryStats, Tracked};
+
+
+/// Test that tracked allocations are counted while they live, and no longer once they're dropped
+#[test]
+fn memory_stats_test(){
+    let before = MemoryStats::current();
+    let texture = Tracked::new((), MemoryKind::Texture, 64);
+    let during = MemoryStats::current();
+    assert_eq!(during.textures.live(), before.textures.live() + 1);
+    assert_eq!(during.total_bytes(), before.total_bytes() + 64);
+    drop(texture);
+    let after = MemoryStats::current();
+    assert_eq!(after.textures.frees, before.textures.frees + 1);
+    assert_eq!(after.total_bytes(), before.total_bytes());
+}
@@ -0,0 +1,17 @@
+use rusty_gui::dialog::{Choice, MessageButtons};
+
+
+/// Test that message box buttons list their choices in order, with the right one picked when the box is closed
+#[test]
+fn message_box_test(){
+    assert_eq!(MessageButtons::default().choices(), &[Choice::Ok]);
+    assert_eq!(MessageButtons::OkCancel.choices(), &[Choice::Ok, Choice::Cancel]);
+    assert_eq!(MessageButtons::YesNoCancel.choices(), &[Choice::Yes, Choice::No, Choice::Cancel]);
+
+    assert_eq!(MessageButtons::Ok.dismiss(), Choice::Ok);
+    assert_eq!(MessageButtons::YesNo.dismiss(), Choice::No);
+    assert_eq!(MessageButtons::YesNoCancel.dismiss(), Choice::Cancel);
+
+    assert_eq!(Choice::Ok.label(), "OK");
+    assert_eq!(Choice::No.label(), "No");
+}
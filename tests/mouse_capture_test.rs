@@ -0,0 +1,60 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent, MouseButton}, layout::Layout};
+
+// A draggable area
+struct Area{
+    rect: [f32; 4],
+}
+
+impl Component for Area{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        true
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+    fn on_drag_start(&mut self, _button: MouseButton, _pos: (f64, f64)) -> bool{
+        true
+    }
+}
+
+/// Test that a component given the mouse with `set_mouse_capture` gets every mouse event until it's released
+#[test]
+fn mouse_capture_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    let left = layout.add_component(Box::new(Area{ rect: [0.0, 0.0, 100.0, 200.0] }));
+    let right = layout.add_component(Box::new(Area{ rect: [100.0, 0.0, 100.0, 200.0] }));
+
+    assert!(layout.set_mouse_capture(Some(5)).is_err());
+    layout.set_mouse_capture(Some(right)).unwrap();
+    assert_eq!(layout.mouse_capture(), Some(right));
+
+    // Pressing on another component doesn't start dragging it while the mouse is captured
+    layout.update_hover(Some((10.0, 10.0)), screen);
+    assert_eq!(layout.update_drag(&GuiEvent::MouseDown{ button: MouseButton::Left, pos: (10.0, 10.0) }), Some(right));
+    assert_eq!(layout.dragging(), None);
+    assert_eq!(layout.update_drag(&GuiEvent::MouseLeave), Some(right));
+    assert_eq!(layout.update_drag(&GuiEvent::Focused(true)), None);
+
+    // Removing a component before it keeps the capture on it
+    layout.remove_component_by_id(left);
+    assert_eq!(layout.mouse_capture(), Some(right));
+
+    layout.release_mouse_capture();
+    assert_eq!(layout.mouse_capture(), None);
+    assert_eq!(layout.update_drag(&GuiEvent::MouseMove{ pos: (10.0, 10.0) }), None);
+}
@@ -0,0 +1,46 @@
+use std::{path::Path, process::Command};
+
+use rusty_gui::notification::notify_command;
+
+
+// The program and arguments of `command`, as strings
+fn parts(command: &Command) -> (String, Vec<String>){
+    let args = command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    (command.get_program().to_string_lossy().into_owned(), args)
+}
+
+/// Test that notifications are posted with the OS's tool, with the title and body kept from being read as options or
+/// breaking out of the script that posts them (without posting one)
+#[test]
+fn notification_test(){
+    let icon = Path::new("icon.png");
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let (program, args) = parts(&notify_command("--title", "-body", Some(icon)));
+        assert_eq!(program, "notify-send");
+        assert_eq!(args, ["--icon", "icon.png", "--", "--title", "-body"]);
+        assert_eq!(parts(&notify_command("Title", "Body", None)).1, ["--", "Title", "Body"]);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let (program, args) = parts(&notify_command("Say \"hi\"", "C:\\path", Some(icon)));
+        assert_eq!(program, "osascript");
+        assert_eq!(args, ["-e", "display notification \"C:\\\\path\" with title \"Say \\\"hi\\\"\""]);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let command = notify_command("It\u{2019}s done'; exit", "Body", Some(icon));
+        let (program, args) = parts(&command);
+        assert_eq!(program, "powershell");
+        assert_eq!(&args[..4], ["-NoProfile", "-WindowStyle", "Hidden", "-Command"]);
+        // The text never goes into the script itself
+        assert!(!args[4].contains("done") && !args[4].contains("Body") && !args[4].contains("icon.png"));
+        let var = |name: &str| command.get_envs().find(|(key, _)| *key == name).and_then(|(_, value)| value).map(|v| v.to_string_lossy().into_owned());
+        assert_eq!(var("RUSTY_GUI_TITLE").as_deref(), Some("It\u{2019}s done'; exit"));
+        assert_eq!(var("RUSTY_GUI_BODY").as_deref(), Some("Body"));
+        assert!(Path::new(&var("RUSTY_GUI_ICON").unwrap()).is_absolute());
+    }
+}
@@ -0,0 +1,47 @@
+use rusty_gui::{Color, components::{Component, Label, Panel}};
+
+mod common;
+
+
+/// Test that a panel positions its children relative to itself, passes its enabled state and opacity on to them, and
+/// draws its background behind them
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn panel_test(){
+    let mut renderer = common::headless_renderer((64, 64));
+
+    let mut panel = Panel::new([16.0, 8.0, 32.0, 32.0], &renderer);
+    let label = panel.add_child(Box::new(Label::new("Hi", 12.0, [4.0, 4.0])));
+    let get_label = |panel: &Panel| panel.layout().borrow_component_as_type::<Label>(label).unwrap().get_pos();
+    assert_eq!(get_label(&panel), [20.0, 12.0]);
+
+    panel.set_pos([0.0, 0.0]);
+    assert_eq!(get_label(&panel), [4.0, 4.0]);
+    panel.set_rect([16.0, 16.0, 32.0, 32.0]);
+    assert_eq!(get_label(&panel), [20.0, 20.0]);
+
+    panel.disable();
+    panel.set_opacity(0.5);
+    let child = panel.layout().borrow_component_as_type::<Label>(label).unwrap();
+    assert!(!child.is_enabled());
+    assert_eq!(child.get_opacity(), 0.5);
+    panel.enable();
+    panel.set_opacity(1.0);
+    assert!(panel.layout().borrow_component(label).is_enabled());
+
+    // Children added to a disabled panel are disabled too
+    panel.disable();
+    let late = panel.add_child(Box::new(Label::new("Late", 12.0, [0.0, 0.0])));
+    assert!(!panel.layout().borrow_component(late).is_enabled());
+    panel.enable();
+
+    panel.set_background(Some(Color::BLUE));
+    renderer.layout.add_component(Box::new(panel));
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    let snapshot = renderer.capture_snapshot(Color::WHITE);
+    let inside = snapshot.get_pixel(32, 44);
+    let outside = snapshot.get_pixel(4, 4);
+    assert!(inside[2] > 200 && inside[0] < 50, "{:?}", inside);
+    assert_eq!(outside, [255, 255, 255, 255]);
+}
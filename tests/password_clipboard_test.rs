@@ -0,0 +1,35 @@
+use rusty_gui::{Clipboard, components::{PasswordInput, TextEdit}, event::{ModifiersState, VirtualKeyCode}};
+
+
+/// Test that the text of a password can't be copied or cut from the keyboard, but can still be pasted in, and is drawn
+/// masked
+#[test]
+fn password_clipboard_test(){
+    // Cmd is the shortcut modifier on macOS
+    let shortcut = if cfg!(target_os = "macos") { ModifiersState::LOGO } else { ModifiersState::CTRL };
+    let clipboard = Clipboard::in_memory();
+    clipboard.set_text("on the clipboard").unwrap();
+
+    // Set up like a `PasswordInput`'s
+    let mut edit = TextEdit::new(clipboard.clone());
+    edit.set_mask(Some(PasswordInput::DEFAULT_MASK), true);
+    edit.set_text("hunter2");
+    assert_eq!(edit.shown(), "\u{2022}".repeat(7));
+    edit.set_mask(Some('*'), true);
+    assert_eq!(edit.shown(), "*******");
+
+    // Copy and cut are still used up, but leave the clipboard and the text alone
+    assert!(edit.handle_key(VirtualKeyCode::C, shortcut));
+    assert!(edit.handle_key(VirtualKeyCode::X, shortcut));
+    assert_eq!(clipboard.get_text().unwrap(), "on the clipboard");
+    assert_eq!(edit.text(), "hunter2");
+
+    assert!(edit.handle_key(VirtualKeyCode::V, shortcut));
+    assert_eq!(edit.text(), "hunter2on the clipboard");
+
+    // Without the secret, they work as usual
+    edit.set_mask(None, false);
+    assert!(edit.handle_key(VirtualKeyCode::X, shortcut));
+    assert_eq!(clipboard.get_text().unwrap(), "hunter2on the clipboard");
+    assert_eq!(edit.text(), "");
+}
@@ -0,0 +1,26 @@
+use rusty_gui::components::{Component, PasswordInput};
+
+mod common;
+
+
+/// Test that a password input hides its text from screen readers until it's revealed, and from saved state even while
+/// it's revealed
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn password_input_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    let mut input = PasswordInput::new([16.0, 16.0], (32.0, 16.0), 12.0, &renderer);
+    input.set_text("hunter2");
+    assert_eq!(input.get_text(), "hunter2");
+    assert_eq!(input.access_info().unwrap().value.as_deref(), Some("\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}"));
+    assert_eq!(input.save_state().unwrap().text, None);
+
+    input.set_mask('*');
+    assert_eq!(input.access_info().unwrap().value.as_deref(), Some("*******"));
+    input.toggle_revealed();
+    assert!(input.is_revealed());
+    assert_eq!(input.access_info().unwrap().value.as_deref(), Some("hunter2"));
+    // Still a secret while it's shown
+    assert_eq!(input.save_state().unwrap().text, None);
+}
@@ -0,0 +1,26 @@
+use rusty_gui::Color;
+
+mod common;
+
+
+/// Test that the performance overlay can be toggled and drawn, and that the glyph cache is only counted while it's
+/// shown
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn perf_overlay_test(){
+    let mut renderer = common::headless_renderer((128, 128));
+
+    assert!(!renderer.perf_overlay_visible());
+    assert_eq!(renderer.frame_stats().glyph_cache_occupancy(), None);
+    renderer.toggle_perf_overlay();
+    assert!(renderer.perf_overlay_visible());
+
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    renderer.render(Color::WHITE);
+    assert!(renderer.frame_stats().glyph_cache_used.is_some());
+
+    renderer.toggle_perf_overlay();
+    assert!(!renderer.perf_overlay_visible());
+    assert_eq!(renderer.frame_stats().glyph_cache_used, None);
+}
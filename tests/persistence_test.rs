@@ -0,0 +1,46 @@
+use rusty_gui::{Color, components::Label, layout::Layout, persistence::{ComponentState, LayoutState}};
+
+
+/// Test that a layout's state can be saved, and put back into a layout built the same way
+#[test]
+fn persistence_test(){
+    let build = || {
+        let mut layout = Layout::new();
+        layout.add_component(Box::new(Label::new("Name", 16.0, [10.0, 10.0])));
+        layout.add_component(Box::new(Label::new("Status", 16.0, [10.0, 40.0])));
+        layout
+    };
+
+    let mut layout = build();
+    {
+        let status = layout.borrow_component_as_type_mut::<Label>(1).unwrap();
+        status.set_text("Saved");
+        status.set_color(Color::RED);
+        status.set_visible(false);
+    }
+    let state = layout.save_state();
+    assert_eq!(state.components.len(), 2);
+    let status = state.components[1].as_ref().unwrap();
+    assert_eq!(status.text.as_deref(), Some("Saved"));
+    assert_eq!(status.position, Some([10.0, 40.0]));
+    assert_eq!(status.visible, Some(false));
+
+    // Restoring into a fresh layout puts everything back
+    let mut restored = build();
+    restored.restore_state(&state);
+    assert_eq!(restored.save_state(), state);
+    assert_eq!(restored.borrow_component_as_type::<Label>(1).unwrap().text(), "Saved");
+
+    // Only the parts of a state that are there are changed, and states without a component are ignored
+    let partial = LayoutState{ components: vec![Some(ComponentState{ text: Some("Title".into()), ..ComponentState::default() }), None, None] };
+    restored.restore_state(&partial);
+    let name = restored.borrow_component(0).save_state().unwrap();
+    assert_eq!(name.text.as_deref(), Some("Title"));
+    assert_eq!(name.position, Some([10.0, 10.0]));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<LayoutState>(&json).unwrap(), state);
+    }
+}
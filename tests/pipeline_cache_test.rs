@@ -0,0 +1,28 @@
+use std::rc::Rc;
+
+use rusty_gui::rendering::{Blend, PipelineKey, Renderer};
+
+mod common;
+
+
+/// Test that a pipeline is only created the first time its key is asked for
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn pipeline_cache_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    // The renderer's own pipelines are already in the cache
+    let cache = renderer.pipeline_cache();
+    let format = renderer.sc_desc.format;
+    let before = cache.len();
+    assert!(cache.get(&PipelineKey::new("rusty_gui::texture", Blend::ALPHA, format)).is_some());
+    cache.get_or_create(PipelineKey::new("rusty_gui::texture", Blend::ALPHA, format), |_| panic!("The texture pipeline was created again"));
+    assert_eq!(cache.len(), before);
+
+    // A new key gets its own pipeline, which is handed out again after that
+    let key = PipelineKey::new("tests::tint", Blend::REPLACE, format);
+    let first = cache.get_or_create(key.clone(), |key| Renderer::create_render_pipeline(&renderer.device, key.format));
+    let second = cache.get_or_create(key, |_| panic!("The pipeline was created again"));
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(cache.len(), before + 1);
+}
@@ -0,0 +1,25 @@
+use cgmath::Vector4;
+use rusty_gui::rendering::Camera;
+
+
+/// Test that the camera maps pixels from the top left of the window onto clip space
+#[test]
+fn pixel_coordinates_test(){
+    let matrix = Camera::build_view_projection_matrix((800, 600));
+    let clip = |x: f32, y: f32| {
+        let point = matrix * Vector4::new(x, y, 0.0, 1.0);
+        [point.x / point.w, point.y / point.w]
+    };
+    let close = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5;
+
+    // The top left is the top left of clip space, and y goes down
+    assert!(close(clip(0.0, 0.0), [-1.0, 1.0]));
+    assert!(close(clip(800.0, 600.0), [1.0, -1.0]));
+    assert!(close(clip(400.0, 300.0), [0.0, 0.0]));
+    assert!(close(clip(200.0, 450.0), [-0.5, -0.5]));
+
+    // A minimized (zero sized) window still gets a usable matrix
+    let matrix = Camera::build_view_projection_matrix((0, 0));
+    let point = matrix * Vector4::new(0.0, 0.0, 0.0, 1.0);
+    assert!(point.x.is_finite() && point.y.is_finite());
+}
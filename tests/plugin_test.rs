@@ -0,0 +1,59 @@
+use rusty_gui::{components::Label, plugin::{ComponentPlugin, PluginContext}, rendering::Texture};
+
+mod common;
+
+// A pack with a font, a texture and a label factory. Failing packs register the same, then return an error.
+struct Pack{
+    name: &'static str,
+    fail: bool,
+}
+
+impl ComponentPlugin for Pack{
+    fn name(&self) -> &str{
+        self.name
+    }
+
+    fn register(&self, context: &mut PluginContext) -> Result<(), &'static str>{
+        let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!("../fonts/FingerPaint-Regular.ttf")).unwrap();
+        context.add_font(&format!("{}.font", self.name), font)?;
+        let image = image::RgbaImage::from_raw(1, 1, vec![255, 0, 0, 255]).unwrap();
+        let texture = Texture::from_image(&image, context.renderer()).unwrap();
+        context.add_texture(&format!("{}.dot", self.name), texture)?;
+        context.add_factory(&format!("{}.label", self.name), |_| Box::new(Label::new("From a plugin", 12.0, [0.0, 0.0])))?;
+        if self.fail{
+            return Err("Error, the pack failed!");
+        }
+        Ok(())
+    }
+}
+
+/// Test that plugins register their resources and factories, that names can't be reused, and that a failing plugin
+/// leaves nothing behind
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn plugin_test(){
+    let mut renderer = common::headless_renderer((32, 32));
+
+    renderer.add_plugin(Pack{ name: "forms", fail: false }).unwrap();
+    let plugins = renderer.plugins();
+    assert!(plugins.has_plugin("forms"));
+    assert_eq!(plugins.font("forms.font"), Some(wgpu_glyph::FontId(1)));
+    assert_eq!(plugins.texture("forms.dot").map(|texture| texture.size()), Some((1, 1)));
+    let label = renderer.create_component("forms.label").unwrap();
+    assert_eq!(label.get_text(), Some("From a plugin"));
+    assert!(renderer.create_component("forms.missing").is_none());
+
+    assert!(renderer.add_plugin(Pack{ name: "forms", fail: false }).is_err());
+
+    assert!(renderer.add_plugin(Pack{ name: "charts", fail: true }).is_err());
+    let plugins = renderer.plugins();
+    assert!(!plugins.has_plugin("charts"));
+    assert!(plugins.font("charts.font").is_none() && plugins.texture("charts.dot").is_none());
+    assert_eq!(plugins.factories().collect::<Vec<_>>(), vec!["forms.label"]);
+
+    // Fonts keep their IDs when the theme changes
+    renderer.set_theme(rusty_gui::Theme::dark());
+    assert_eq!(renderer.plugins().font("forms.font"), Some(wgpu_glyph::FontId(1)));
+    renderer.prepass();
+    renderer.render(rusty_gui::Color::WHITE);
+}
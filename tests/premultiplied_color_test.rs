@@ -0,0 +1,13 @@
+use rusty_gui::Color;
+
+
+/// Test that premultiplying scales the linear channels by alpha, and leaves opaque colors alone
+#[test]
+fn premultiplied_color_test(){
+    assert_eq!(Color::TRANSPARENT.premultiplied(), Color::TRANSPARENT);
+    assert_eq!(Color::RED.premultiplied().to_rgba8(), Color::RED.to_rgba8());
+
+    let color = Color::WHITE.with_alpha(0.5).premultiplied().to_linear();
+    assert!((color[0] - 0.5).abs() < 0.001);
+    assert!((color[3] - 0.5).abs() < 0.001);
+}
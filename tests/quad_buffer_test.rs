@@ -0,0 +1,20 @@
+use std::rc::Rc;
+
+use rusty_gui::components::Panel;
+
+mod common;
+
+
+/// Test that components share the renderer's quad buffer rather than creating their own
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn quad_buffer_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    let quad = renderer.quad_buffer();
+    let before = Rc::strong_count(&quad);
+    let panels: Vec<Panel> = (0..3).map(|_| Panel::new([0.0, 0.0, 8.0, 8.0], &renderer)).collect();
+    assert_eq!(Rc::strong_count(&quad), before + 3);
+    drop(panels);
+    assert_eq!(Rc::strong_count(&quad), before);
+}
@@ -0,0 +1,76 @@
+use std::any::Any;
+
+use rusty_gui::{accessibility::{AccessInfo, Role}, components::Component, layout::Layout};
+
+// A focusable text input, described as `name`
+struct Field{
+    name: &'static str,
+    focused: bool,
+}
+
+impl Field{
+    fn new(name: &'static str) -> Box<Self>{
+        Box::new(Self{ name, focused: false })
+    }
+}
+
+impl Component for Field{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_focusable(&self) -> bool{
+        true
+    }
+    fn set_focus(&mut self, focused: bool){
+        self.focused = focused;
+    }
+    fn has_focus(&self) -> bool{
+        self.focused
+    }
+    fn access_info(&self) -> Option<AccessInfo>{
+        Some(AccessInfo::named(Role::TextInput, self.name))
+    }
+}
+
+/// Test that the reading order is followed by Tab and the accessibility tree, and that given names and descriptions replace a component's own
+#[test]
+fn reading_order_test(){
+    let mut layout = Layout::new();
+    let password = layout.add_component(Field::new("password"));
+    let submit = layout.add_component(Field::new("submit"));
+    let email = layout.add_component(Field::new("email"));
+
+    assert!(layout.set_reading_order(&[email, 7]).is_err());
+    assert!(layout.set_reading_order(&[email, email]).is_err());
+    layout.set_reading_order(&[email, password]).unwrap();
+    assert_eq!(layout.reading_order(), vec![email, password, submit]);
+
+    // Tab follows the reading order, in both directions
+    assert_eq!(layout.focus_next(), Some(email));
+    assert_eq!(layout.focus_next(), Some(password));
+    assert_eq!(layout.focus_next(), Some(submit));
+    assert_eq!(layout.focus_next(), Some(email));
+    assert_eq!(layout.focus_previous(), Some(submit));
+
+    layout.set_accessible_name(email, Some("Email address")).unwrap();
+    layout.set_description(password, Some("At least 8 characters")).unwrap();
+    assert!(layout.set_accessible_name(7, Some("Nothing")).is_err());
+    let tree = layout.accessibility_tree((800, 600));
+    let names: Vec<_> = tree.iter().map(|node| node.name.as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["Email address", "password", "submit"]);
+    assert_eq!(tree[1].description.as_deref(), Some("At least 8 characters"));
+
+    layout.set_accessible_name(email, None).unwrap();
+    assert_eq!(layout.accessible_name(email), None);
+
+    // Removing a component keeps the rest in the same order, and its ID is given back without its description
+    layout.remove_component_by_id(password);
+    assert_eq!(layout.reading_order(), vec![email, submit]);
+    assert_eq!(layout.description(password), None);
+
+    layout.set_reading_order(&[]).unwrap();
+    assert_eq!(layout.reading_order(), vec![submit, email]);
+}
@@ -0,0 +1,32 @@
+use rusty_gui::{Color, components::Label};
+
+mod common;
+
+
+/// Test that a renderer can draw a frame into a texture it was given, at that texture's size
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn render_to_view_test(){
+    let mut renderer = common::headless_renderer((64, 64));
+    renderer.layout.add_component(Box::new(Label::new("Overlay", 16.0, [4.0, 4.0])));
+
+    let texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Overlay texture"),
+        size: wgpu::Extent3d { width: 128, height: 32, depth: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: renderer.sc_desc.format,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    renderer.prepass();
+    renderer.render_to_view(&view, (128, 32), Some(Color::TRANSPARENT));
+    renderer.render_to_view(&view, (128, 32), None);
+    assert_eq!(renderer.frame_count(), 2);
+
+    // The renderer's own target keeps its size, and still draws
+    assert_eq!((renderer.size.width, renderer.size.height), (64, 64));
+    assert_eq!(renderer.capture_snapshot(Color::WHITE).width(), 64);
+}
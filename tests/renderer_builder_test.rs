@@ -0,0 +1,15 @@
+use rusty_gui::rendering::{GlyphCachePolicy, RendererBuilder, MAX_GLYPH_CACHE_SIZE};
+
+
+/// Test that the glyph cache size is clamped, and that preallocating uses the largest cache
+#[test]
+fn renderer_builder_test(){
+    let mut builder = RendererBuilder::new();
+    assert_eq!(builder.get_glyph_cache_size(), (256, 256));
+
+    builder.set_glyph_cache_size((1024, 8192));
+    assert_eq!(builder.get_glyph_cache_size(), (1024, MAX_GLYPH_CACHE_SIZE));
+
+    builder.set_glyph_cache_policy(GlyphCachePolicy::Preallocate);
+    assert_eq!(builder.get_glyph_cache_size(), (MAX_GLYPH_CACHE_SIZE, MAX_GLYPH_CACHE_SIZE));
+}
@@ -0,0 +1,21 @@
+use rusty_gui::{components::Panel, rendering::MemoryStats};
+
+mod common;
+
+
+/// Test that the renderer's own resources are tracked, and that a component's buffers are freed with it
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn renderer_memory_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    // The renderer's own quad, uniforms, target and glyph cache
+    let before = MemoryStats::current();
+    assert!(before.vertex_buffers.live() > 0 && before.glyph_cache.live() > 0);
+    let panel = Panel::new([0.0, 0.0, 16.0, 16.0], &renderer);
+    assert!(MemoryStats::current().uniform_buffers.live() > before.uniform_buffers.live());
+    drop(panel);
+    let after = MemoryStats::current();
+    assert_eq!(after.total_live(), before.total_live());
+    assert_eq!(after.total_bytes(), before.total_bytes());
+}
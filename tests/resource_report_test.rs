@@ -0,0 +1,20 @@
+use rusty_gui::{layout::Layout, rendering::ResourceReport};
+
+
+/// Test that resource reports add up allocations by category
+#[test]
+fn resource_report_test(){
+    let mut report = ResourceReport::new();
+    Layout::new().report_resources(&mut report);
+    assert_eq!(report.total_components(), 0);
+
+    report.add_buffer(64);
+    report.add_buffer(16);
+    report.add_texture(32, 32, 4);
+    report.glyph_atlas.add(256 * 256);
+
+    assert_eq!(report.buffers.count, 2);
+    assert_eq!(report.buffers.bytes, 80);
+    assert_eq!(report.textures.bytes, 4096);
+    assert_eq!(report.total_gpu_bytes(), 80 + 4096 + 65536);
+}
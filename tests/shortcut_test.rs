@@ -0,0 +1,32 @@
+use rusty_gui::{event::{GuiEvent, ModifiersState, VirtualKeyCode}, shortcut::{Shortcut, ShortcutRegistry}};
+
+
+/// Test that shortcuts match exact modifiers, can't be registered twice, and are suppressed while typing
+#[test]
+fn shortcut_test(){
+    let save = Shortcut::new(ModifiersState::CTRL, VirtualKeyCode::S);
+    let delete = Shortcut::key(VirtualKeyCode::Delete);
+    let copy = Shortcut::new(ModifiersState::CTRL, VirtualKeyCode::C);
+
+    let mut registry = ShortcutRegistry::new();
+    registry.register(save, Box::new(|_, _| {})).unwrap();
+    registry.register(delete, Box::new(|_, _| {})).unwrap();
+    registry.register(copy, Box::new(|_, _| {})).unwrap();
+    assert!(registry.register(save, Box::new(|_, _| {})).is_err());
+    assert_eq!(save.to_string(), "Ctrl+S");
+
+    let press = |shortcut: Shortcut| GuiEvent::KeyDown{ key: shortcut.key, scancode: 0, modifiers: shortcut.modifiers };
+    assert_eq!(registry.matching(&press(save), false), Some(save));
+    assert_eq!(registry.matching(&press(save.with_shift()), false), None);
+    assert_eq!(registry.matching(&GuiEvent::KeyUp{ key: VirtualKeyCode::S, scancode: 0, modifiers: ModifiersState::CTRL }, false), None);
+
+    // A focused text field keeps plain keys and the editing shortcuts, but not Ctrl+S
+    assert_eq!(registry.matching(&press(save), true), Some(save));
+    assert_eq!(registry.matching(&press(delete), true), None);
+    assert_eq!(registry.matching(&press(copy), true), None);
+    assert_eq!(registry.matching(&press(copy), false), Some(copy));
+
+    assert!(registry.unregister(save));
+    assert!(!registry.unregister(save));
+    assert_eq!(registry.matching(&press(save), false), None);
+}
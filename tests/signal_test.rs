@@ -0,0 +1,54 @@
+use std::{cell::Cell, rc::Rc};
+
+use rusty_gui::signal::{create_effect, create_memo, create_signal, flush_effects};
+
+
+/// Test that effects run again once for a burst of changes to the signals they read, and that memos
+/// only set off their effects when the derived value changes
+#[test]
+fn signal_test(){
+    let count = create_signal(1);
+    let unrelated = create_signal(0);
+    let parity = {
+        let count = count.clone();
+        create_memo(move || count.get() % 2 == 0)
+    };
+
+    let (count_runs, parity_runs) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let count_effect = {
+        let (count, runs) = (count.clone(), count_runs.clone());
+        create_effect(move || {
+            count.get();
+            runs.set(runs.get() + 1);
+        })
+    };
+    let _parity_effect = {
+        let runs = parity_runs.clone();
+        create_effect(move || {
+            parity.get();
+            runs.set(runs.get() + 1);
+        })
+    };
+    // Effects run straight away when they're created
+    assert_eq!((count_runs.get(), parity_runs.get()), (1, 1));
+
+    // Changes are applied on the next flush, and only to the effects that read the signal
+    count.set(3);
+    count.set(5);
+    unrelated.set(1);
+    assert_eq!(count_runs.get(), 1);
+    flush_effects();
+    assert_eq!((count_runs.get(), parity_runs.get()), (2, 1));
+
+    // An even count changes the memo, so its effect runs too
+    count.update(|count| *count += 1);
+    flush_effects();
+    assert_eq!((count_runs.get(), parity_runs.get()), (3, 2));
+    assert_eq!(flush_effects(), 0);
+
+    // Dropped effects stop running
+    drop(count_effect);
+    count.set(8);
+    flush_effects();
+    assert_eq!(count_runs.get(), 3);
+}
@@ -0,0 +1,28 @@
+use rusty_gui::rendering::Snapshot;
+
+
+/// Test that snapshot diffs find the changed region
+#[test]
+fn snapshot_diff_test(){
+    let before = Snapshot::from_rgba(4, 4, vec![255; 4 * 4 * 4]);
+
+    let mut pixels = vec![255; 4 * 4 * 4];
+    // Change the pixels at (1, 1) and (2, 3)
+    pixels[(4 + 1) * 4] = 0;
+    pixels[(3 * 4 + 2) * 4 + 1] = 0;
+    let after = Snapshot::from_rgba(4, 4, pixels);
+
+    assert!(before.diff(&before, 0).is_empty());
+
+    let diff = before.diff(&after, 0);
+    assert_eq!(diff.changed_pixels(), 2);
+    assert_eq!(diff.bounds(), Some(([1, 1], [2, 3])));
+    assert!(diff.is_changed(1, 1) && !diff.is_changed(0, 0));
+
+    // A large enough tolerance ignores the change
+    assert!(before.diff(&after, 255).is_empty());
+
+    let overlay = diff.overlay(&after, [255, 0, 255, 255]);
+    assert_eq!(overlay.get_pixel(2, 3), [255, 0, 255, 255]);
+    assert_eq!(overlay.get_pixel(0, 0), [85, 85, 85, 255]);
+}
@@ -0,0 +1,54 @@
+use std::any::Any;
+
+use rusty_gui::{AppState, components::Component, event::{EventResult, GuiEvent}, layout::Layout};
+
+// A component that does nothing
+struct Blank;
+
+impl Component for Blank{
+    fn handle_event(&mut self, _event: &GuiEvent, _window: &winit::window::Window, _state: &mut AppState) -> EventResult{
+        EventResult::Ignored
+    }
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+}
+
+/// Test that component IDs stay the same when others are removed, reused IDs are drawn on top, and names find components
+#[test]
+fn stable_id_test(){
+    let mut layout = Layout::new();
+    let first = layout.add_component(Box::new(Blank));
+    let second = layout.add_component(Box::new(Blank));
+    let third = layout.add_component(Box::new(Blank));
+    layout.set_component_name(third, Some("status")).unwrap();
+    assert!(layout.set_component_name(first, Some("status")).is_err());
+    assert!(layout.set_component_name(9, Some("missing")).is_err());
+
+    layout.remove_component_by_id(second);
+    assert!(!layout.contains(second));
+    assert_eq!(layout.ids().collect::<Vec<_>>(), [first, third]);
+    assert_eq!(layout.find_component("status"), Some(third));
+    assert_eq!(layout.component_name(third), Some("status"));
+
+    // The freed ID is given to the next component, which is still drawn (and read) after the older ones
+    let fourth = layout.add_component(Box::new(Blank));
+    assert_eq!(fourth, second);
+    assert_eq!(layout.draw_order(), [first, third, fourth]);
+    assert_eq!(layout.reading_order(), [first, third, fourth]);
+
+    // Removing a named component frees its name
+    layout.remove_component_by_id(third);
+    assert_eq!(layout.find_component("status"), None);
+    layout.set_component_name(first, Some("status")).unwrap();
+    assert_eq!(layout.find_component("status"), Some(first));
+    layout.set_component_name(first, None).unwrap();
+    assert_eq!(layout.component_name(first), None);
+    assert_eq!(layout.len(), 2);
+}
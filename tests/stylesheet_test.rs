@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant};
+
+use rusty_gui::{Color, Stylesheet, Theme, components::Label, layout::Layout};
+
+
+/// Test that stylesheets are parsed into themes, that a watched stylesheet reloads when its file changes (keeping the
+/// last good theme if the change doesn't parse), and that only the components a theme changes count as restyled
+#[test]
+fn stylesheet_test(){
+    let dir = std::env::temp_dir().join(format!("rusty_gui_stylesheet_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("style.txt");
+    std::fs::write(&path, "# A dark theme\nbase = dark\naccent = #0000ff   # blue buttons\nspacing = 12\ntext_size.body = 20\n").unwrap();
+
+    let mut stylesheet = Stylesheet::load(&path).unwrap();
+    assert_eq!(stylesheet.theme().colors.background, Theme::dark().colors.background);
+    assert_eq!(stylesheet.theme().colors.accent, Color::BLUE);
+    assert_eq!(stylesheet.theme().spacing, 12.0);
+    assert_eq!(stylesheet.theme().text_sizes.body, 20.0);
+
+    // Nothing is checked until the interval is up, or reloaded while the file is unchanged
+    stylesheet.set_check_interval(Duration::from_millis(0));
+    assert!(stylesheet.poll(Instant::now()).is_none());
+
+    // Make sure the modification time moves on, even on file systems with coarse timestamps
+    std::fs::write(&path, "text = #ff0000\n").unwrap();
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() + Duration::from_secs(10)).unwrap();
+    let theme = stylesheet.poll(Instant::now()).unwrap().clone();
+    assert_eq!(theme.colors.text, Color::RED);
+    assert_eq!(theme.colors.background, Theme::light().colors.background);
+
+    // A broken change is reported, and the last theme kept
+    std::fs::write(&path, "colour = #ff0000\n").unwrap();
+    file.set_modified(std::time::SystemTime::now() + Duration::from_secs(20)).unwrap();
+    assert!(stylesheet.poll(Instant::now()).is_none());
+    assert!(stylesheet.error().is_some());
+    assert_eq!(stylesheet.theme().colors.text, Color::RED);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // Applying the same theme twice only restyles the components the first time
+    let mut layout = Layout::new();
+    layout.add_component(Box::new(Label::new("Hi", 16.0, [0.0, 0.0])));
+    assert!(layout.apply_theme(&theme));
+    assert!(!layout.apply_theme(&theme));
+}
@@ -0,0 +1,12 @@
+#![cfg(feature = "tasks")]
+
+use rusty_gui::task::Tasks;
+
+/// Test that tasks can't be spawned until they're connected to an event loop, as their results would have nowhere to go
+#[test]
+fn task_test(){
+    let mut tasks = Tasks::new();
+    assert!(tasks.spawn(async { 1 }).is_err());
+    assert!(tasks.spawn_with(async { "loaded" }, |_result, _window, _renderer| {}).is_err());
+    assert_eq!(tasks.pending(), 0);
+}
@@ -0,0 +1,44 @@
+use rusty_gui::{event::{GuiEvent, ModifiersState, MouseButton, VirtualKeyCode}, rendering::Snapshot, test::{self, SnapshotError}};
+
+
+/// Test that snapshots are compared with reference images within the tolerance (saving the reference when there
+/// isn't one, and the frame and diff when they differ), and that the input helpers build the expected events
+#[test]
+fn test_harness_test(){
+    let dir = std::env::temp_dir().join(format!("rusty_gui_harness_{}", std::process::id()));
+    let reference = dir.join("square.png");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let white = Snapshot::from_rgba(4, 4, vec![255; 4 * 4 * 4]);
+    test::compare_snapshot(&white, &reference, 0).unwrap();
+    assert!(reference.exists());
+    test::compare_snapshot(&white, &reference, 0).unwrap();
+
+    // A small change passes within the tolerance, and fails without it
+    let mut pixels = vec![255; 4 * 4 * 4];
+    pixels[(2 * 4 + 1) * 4] = 250;
+    let changed = Snapshot::from_rgba(4, 4, pixels);
+    test::compare_snapshot(&changed, &reference, 8).unwrap();
+    match test::compare_snapshot(&changed, &reference, 0){
+        Err(SnapshotError::Mismatch{ changed_pixels, bounds, actual, diff }) => {
+            assert_eq!(changed_pixels, 1);
+            assert_eq!(bounds, Some(([1, 2], [1, 2])));
+            assert_eq!(Snapshot::load(&actual).unwrap(), changed);
+            assert_eq!(Snapshot::load(&diff).unwrap().get_pixel(1, 2), [255, 0, 255, 255]);
+        }
+        other => panic!("Expected a mismatch, got {:?}", other),
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let pos = (10.0, 20.0);
+    assert_eq!(test::click(pos), vec![
+        GuiEvent::MouseMove{ pos },
+        GuiEvent::MouseDown{ button: MouseButton::Left, pos },
+        GuiEvent::MouseUp{ button: MouseButton::Left, pos },
+    ]);
+    let drag = test::drag((0.0, 0.0), (10.0, 0.0), 2);
+    assert_eq!(drag[2], GuiEvent::MouseMove{ pos: (5.0, 0.0) });
+    assert_eq!(drag.last(), Some(&GuiEvent::MouseUp{ button: MouseButton::Left, pos: (10.0, 0.0) }));
+    assert_eq!(test::key_press(VirtualKeyCode::A, ModifiersState::CTRL).len(), 2);
+    assert_eq!(test::type_text("hi"), vec![GuiEvent::TextInput('h'), GuiEvent::TextInput('i')]);
+}
@@ -0,0 +1,24 @@
+use rusty_gui::{Color, components::{Label, Panel}};
+
+mod common;
+
+
+/// Test that the text of a clipped layout is cut off at its clip rect
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn text_clip_test(){
+    let mut renderer = common::headless_renderer((64, 64));
+
+    // The label runs across the whole panel, but only its left part is inside the clip rect
+    let mut panel = Panel::new([0.0, 0.0, 64.0, 64.0], &renderer);
+    panel.add_child(Box::new(Label::new("MMMMMM", 32.0, [0.0, 16.0])));
+    panel.layout_mut().set_clip_rect(Some([0.0, 0.0, 24.0, 64.0]));
+    renderer.layout.add_component(Box::new(panel));
+    renderer.prepass();
+    renderer.render(Color::WHITE);
+    let snapshot = renderer.capture_snapshot(Color::WHITE);
+
+    let drawn = |from: u32, to: u32| (from..to).any(|x| (0..64).any(|y| snapshot.get_pixel(x, y) != [255, 255, 255, 255]));
+    assert!(drawn(0, 24));
+    assert!(!drawn(24, 64));
+}
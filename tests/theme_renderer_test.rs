@@ -0,0 +1,32 @@
+use rusty_gui::{Color, Theme, components::Button, rendering::{Renderer, Transform}};
+
+mod common;
+
+
+/// Test that components start out with the renderer's theme, and that changing the theme restyles them
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn theme_renderer_test(){
+    let mut theme = Theme::new();
+    theme.colors.text = Color::WHITE;
+    theme.colors.accent = Color::BLUE;
+
+    let mut renderer = common::headless_renderer((32, 32));
+    let transform = |renderer: &Renderer| Transform::new(
+        cgmath::Vector3::<f32>::new(16.0, 16.0, 0.0),
+        cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(16.0, 16.0, 1.0),
+        renderer.transform_pool()
+    );
+    let before = Button::new(transform(&renderer), None, &renderer, Some("OK"), 16.0);
+    assert_eq!(before.get_style().normal, Theme::new().colors.accent);
+    let before = renderer.layout.add_component(Box::new(before));
+
+    renderer.set_theme(theme);
+    let after = Button::new(transform(&renderer), None, &renderer, Some("OK"), 16.0);
+    assert_eq!(after.get_style().normal, Color::BLUE);
+    assert_eq!(after.label().unwrap().get_color(), Color::WHITE);
+
+    let before = renderer.layout.borrow_component_as_type::<Button>(before).unwrap();
+    assert_eq!(before.get_style().normal, Color::BLUE);
+}
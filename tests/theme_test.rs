@@ -0,0 +1,14 @@
+use rusty_gui::{Color, Theme, components::Label, layout::Layout};
+
+
+/// Test that applying a theme to a layout restyles its components
+#[test]
+fn theme_test(){
+    let mut theme = Theme::new();
+    theme.colors.text = Color::WHITE;
+
+    let mut layout = Layout::new();
+    let label = layout.add_component(Box::new(Label::new("Hi", 16.0, [0.0, 0.0])));
+    layout.apply_theme(&theme);
+    assert_eq!(layout.borrow_component_as_type::<Label>(label).unwrap().get_color(), Color::WHITE);
+}
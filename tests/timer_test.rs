@@ -0,0 +1,36 @@
+use std::time::{Duration, Instant};
+
+use rusty_gui::timer::{TimerId, Timers};
+
+/// Test that timeouts run once, intervals keep running (without catching up on missed runs), and cleared timers don't run
+#[test]
+fn timer_test(){
+    let mut timers: Timers<&'static str> = Timers::new();
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+    let run = |timers: &mut Timers<&'static str>, ms| {
+        let mut ran: Vec<&'static str> = Vec::new();
+        timers.poll(at(ms), |_, name| ran.push(*name));
+        ran
+    };
+
+    let interval = timers.set_interval(Duration::from_millis(100), "blink", start);
+    timers.set_timeout(Duration::from_millis(250), "toast", start);
+    let cleared: TimerId = timers.set_timeout(Duration::from_millis(50), "cleared", start);
+    assert!(timers.clear(cleared));
+    assert!(!timers.clear(cleared));
+    assert_eq!(timers.next_deadline(), Some(at(100)));
+
+    assert_eq!(run(&mut timers, 50), Vec::<&'static str>::new());
+    assert_eq!(run(&mut timers, 100), vec!["blink"]);
+    assert_eq!(timers.next_deadline(), Some(at(200)));
+
+    // Both are due, earliest first, and the interval only runs once for the time it missed
+    assert_eq!(run(&mut timers, 450), vec!["blink", "toast"]);
+    assert_eq!(timers.len(), 1);
+    assert_eq!(timers.next_deadline(), Some(at(550)));
+
+    assert!(timers.clear(interval));
+    assert!(timers.is_empty());
+    assert_eq!(timers.next_deadline(), None);
+}
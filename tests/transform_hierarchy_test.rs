@@ -0,0 +1,47 @@
+use rusty_gui::rendering::Transform;
+
+mod common;
+
+
+/// Test that a transform with a parent is placed relative to it, and follows it when it moves or scales
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn transform_hierarchy_test(){
+    let renderer = common::headless_renderer((32, 32));
+    let transform = |x: f32, y: f32, size: f32| Transform::new(
+        cgmath::Vector3::<f32>::new(x, y, 0.0),
+        cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(size, size, 1.0),
+        renderer.transform_pool()
+    );
+
+    let mut parent = transform(100.0, 100.0, 200.0);
+    let mut child = transform(10.0, 20.0, 10.0);
+    let mut grandchild = transform(5.0, 0.0, 2.0);
+    child.set_parent(Some(&parent)).unwrap();
+    grandchild.set_parent(Some(&child)).unwrap();
+    assert_eq!(child.rect(), [105.0, 115.0, 10.0, 10.0]);
+    assert_eq!(grandchild.world_position(), cgmath::Vector3::new(115.0, 120.0, 0.0));
+
+    // A transform can't be its own ancestor
+    assert!(parent.set_parent(Some(&grandchild)).is_err());
+    assert!(!parent.has_parent());
+
+    // Moving the parent moves its descendants, and their slots are rewritten when they're next drawn
+    child.write_uniform();
+    grandchild.write_uniform();
+    parent.set_position(cgmath::Vector3::new(0.0, 0.0, 0.0));
+    assert!(child.is_dirty() && grandchild.is_dirty());
+    assert_eq!(grandchild.world_position(), cgmath::Vector3::new(15.0, 20.0, 0.0));
+    grandchild.write_uniform();
+    assert!(!grandchild.is_dirty());
+
+    // The content scale scales children, but not the parent's own quad
+    parent.set_content_scale(2.0);
+    assert_eq!(parent.rect(), [-100.0, -100.0, 200.0, 200.0]);
+    assert_eq!(child.rect(), [10.0, 30.0, 20.0, 20.0]);
+
+    // Without a parent, the transform is relative to the window again
+    child.set_parent(None).unwrap();
+    assert_eq!(child.rect(), [5.0, 15.0, 10.0, 10.0]);
+}
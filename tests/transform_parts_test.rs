@@ -0,0 +1,25 @@
+use rusty_gui::rendering::TransformParts;
+
+
+/// Test that transform parts build the matrix a transform is drawn with - the quad sized and placed in pixels, relative to the parent's frame
+#[test]
+fn transform_parts_test(){
+    let parts = TransformParts{
+        parent: cgmath::Matrix4::from_translation(cgmath::Vector3::new(100.0, 50.0, 0.0)),
+        position: cgmath::Vector3::new(10.0, 20.0, 0.0),
+        rotation: cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        scale: cgmath::Vector3::new(40.0, 30.0, 1.0),
+    };
+    let matrix = parts.matrix();
+
+    // The quad spans -1 to 1 with y going up, so its top left corner is (-1, 1)
+    let top_left = matrix * cgmath::Vector4::new(-1.0, 1.0, 0.0, 1.0);
+    let bottom_right = matrix * cgmath::Vector4::new(1.0, -1.0, 0.0, 1.0);
+    assert_eq!((top_left.x, top_left.y), (90.0, 55.0));
+    assert_eq!((bottom_right.x, bottom_right.y), (130.0, 85.0));
+
+    // Without a parent, it's placed from the top left of the window
+    let parts = TransformParts{ parent: cgmath::Matrix4::from_scale(1.0), ..parts };
+    let center = parts.matrix() * cgmath::Vector4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!((center.x, center.y), (10.0, 20.0));
+}
@@ -0,0 +1,32 @@
+use rusty_gui::rendering::{Transform, TransformUniform, TRANSFORM_SLOT_SIZE};
+
+mod common;
+
+
+/// Test that transforms take slots from the renderer's pool, which grows when it's full and reuses freed slots
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn transform_pool_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    let pool = renderer.transform_pool();
+    let before = pool.len();
+    let capacity = pool.capacity();
+    let transforms: Vec<Transform> = (0..capacity).map(|i| Transform::new(
+        cgmath::Vector3::<f32>::new(i as f32, 0.0, 0.0),
+        cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(8.0, 8.0, 1.0),
+        pool
+    )).collect();
+    assert_eq!(pool.len(), before + capacity as usize);
+    assert!(pool.capacity() > capacity);
+    assert_eq!(pool.buffer_size(), pool.capacity() as u64 * TRANSFORM_SLOT_SIZE);
+
+    // Dropped transforms give their slots back, and new ones take them
+    drop(transforms);
+    assert_eq!(pool.len(), before);
+    let slot = pool.allocate(TransformUniform::new());
+    assert!((slot.offset() as u64).is_multiple_of(TRANSFORM_SLOT_SIZE));
+    assert!((slot.offset() as u64) < capacity as u64 * TRANSFORM_SLOT_SIZE);
+    pool.flush();
+}
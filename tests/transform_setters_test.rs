@@ -0,0 +1,27 @@
+use rusty_gui::rendering::Transform;
+
+mod common;
+
+
+/// Test that changing a transform marks it dirty, and that writing its buffer clears the flag
+#[test]
+#[ignore = "needs a graphics adapter"]
+fn transform_setters_test(){
+    let renderer = common::headless_renderer((32, 32));
+
+    let mut transform = Transform::new(
+        cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0),
+        cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+        cgmath::Vector3::<f32>::new(1.0, 1.0, 1.0),
+        renderer.transform_pool()
+    );
+    assert!(!transform.is_dirty());
+
+    transform.set_position(cgmath::Vector3::new(4.0, 2.0, 0.0)).set_scale(cgmath::Vector3::new(0.5, 0.5, 1.0));
+    assert!(transform.is_dirty());
+    assert_eq!(transform.position(), cgmath::Vector3::new(4.0, 2.0, 0.0));
+    assert_eq!(transform.scale(), cgmath::Vector3::new(0.5, 0.5, 1.0));
+
+    transform.write_uniform();
+    assert!(!transform.is_dirty());
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use rusty_gui::{Animator, AppState, Transition, components::{Component, Label}, layout::Layout};
+
+
+/// Test that show and hide transitions animate a component in and out, hiding it at the end of a hide and putting
+/// its property back, and that showing it part way through a hide goes back to how it was
+#[test]
+fn transition_test(){
+    let mut layout = Layout::new();
+    let mut label = Label::new("Menu", 16.0, [0.0, 0.0]);
+    label.set_visible(false);
+    let label = layout.add_component(Box::new(label));
+    let mut state = AppState::new();
+    let mut animator = Animator::new();
+    let frame = Duration::from_millis(50);
+    fn get(layout: &Layout) -> &Label{
+        layout.borrow_component_as_type::<Label>(0).unwrap()
+    }
+
+    // Fade in - visible straight away, from transparent
+    animator.show(&mut layout, label, Transition::Fade, Duration::from_millis(100));
+    assert!(get(&layout).is_visible());
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(get(&layout).get_opacity(), 0.0);
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(get(&layout).get_opacity(), 0.5);
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(get(&layout).get_opacity(), 1.0);
+    assert!(animator.is_empty());
+
+    // Slide out - hidden at the end, back where it was
+    animator.hide(&mut layout, label, Transition::Slide([0.0, 40.0]), Duration::from_millis(100));
+    animator.step(frame, &mut layout, &mut state);
+    animator.step(frame, &mut layout, &mut state);
+    assert_eq!(get(&layout).anchor(), [0.0, 20.0]);
+    assert!(get(&layout).is_visible());
+    animator.step(frame, &mut layout, &mut state);
+    assert!(!get(&layout).is_visible());
+    assert_eq!(get(&layout).anchor(), [0.0, 0.0]);
+
+    // Showing again part way through a hide slides back to where it was, not where the hide got to
+    animator.show(&mut layout, label, Transition::Slide([0.0, 40.0]), Duration::from_millis(100));
+    animator.step(frame, &mut layout, &mut state);
+    animator.hide(&mut layout, label, Transition::Slide([0.0, 40.0]), Duration::from_millis(100));
+    animator.step(frame, &mut layout, &mut state);
+    animator.step(frame, &mut layout, &mut state);
+    animator.show(&mut layout, label, Transition::Slide([0.0, 40.0]), Duration::from_millis(100));
+    for _ in 0..3{
+        animator.step(frame, &mut layout, &mut state);
+    }
+    assert!(get(&layout).is_visible());
+    assert_eq!(get(&layout).anchor(), [0.0, 0.0]);
+}
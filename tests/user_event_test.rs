@@ -0,0 +1,20 @@
+use rusty_gui::event::{GuiEvent, UserEvent};
+
+#[derive(Debug, PartialEq)]
+struct Downloaded{
+    bytes: usize,
+}
+
+/// Test that a user event gives its value back only as its own type, and is only equal to its clones
+#[test]
+fn user_event_test(){
+    let event = UserEvent::new(Downloaded{ bytes: 1024 });
+    assert!(event.is::<Downloaded>());
+    assert_eq!(event.get::<Downloaded>(), Some(&Downloaded{ bytes: 1024 }));
+    assert!(!event.is::<String>());
+    assert_eq!(event.get::<String>(), None);
+
+    let gui_event = GuiEvent::User(event.clone());
+    assert_eq!(gui_event, GuiEvent::User(event));
+    assert_ne!(gui_event, GuiEvent::User(UserEvent::new(Downloaded{ bytes: 1024 })));
+}
@@ -0,0 +1,27 @@
+use rusty_gui::rendering::{MonitorInfo, VideoModeInfo};
+
+
+/// Test that fullscreen video modes are picked from the monitor's supported modes
+#[test]
+fn video_mode_test(){
+    let mode = |size, refresh_rate| VideoModeInfo{ size, bit_depth: 32, refresh_rate };
+    let mut monitor = MonitorInfo{
+        name: Some(String::from("Test monitor")),
+        position: (0, 0),
+        size: (1920, 1080),
+        scale_factor: 1.0,
+        video_modes: vec![mode((1280, 720), 60), mode((1920, 1080), 60), mode((1920, 1080), 144), mode((2560, 1440), 60)],
+    };
+
+    // The current resolution at its highest refresh rate
+    assert_eq!(monitor.best_video_mode(), Some(mode((1920, 1080), 144)));
+    assert_eq!(monitor.find_video_mode((1280, 720), 60), Some(mode((1280, 720), 60)));
+    assert_eq!(monitor.find_video_mode((1280, 720), 144), None);
+
+    // If the current resolution isn't listed, the largest mode is used
+    monitor.size = (1024, 768);
+    assert_eq!(monitor.best_video_mode(), Some(mode((2560, 1440), 60)));
+
+    monitor.video_modes.clear();
+    assert_eq!(monitor.best_video_mode(), None);
+}
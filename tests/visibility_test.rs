@@ -0,0 +1,70 @@
+use std::any::Any;
+
+use rusty_gui::{components::Component, event::{GuiEvent, MouseButton}, layout::Layout};
+
+// A component covering a rectangle, with each flag settable
+struct Area{
+    rect: [f32; 4],
+    visible: bool,
+    enabled: bool,
+    hit_test: bool,
+}
+
+impl Area{
+    fn new(rect: [f32; 4]) -> Box<Self>{
+        Box::new(Self{ rect, visible: true, enabled: true, hit_test: true })
+    }
+}
+
+impl Component for Area{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+    fn is_visible(&self) -> bool{
+        self.visible
+    }
+    fn is_hit_testable(&self) -> bool{
+        self.hit_test
+    }
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+}
+
+/// Test that hidden components get no events and can't be hovered, and that components that aren't hit testable
+/// let the mouse through but still get other events
+#[test]
+fn visibility_test(){
+    let screen = (200, 200);
+    let mut layout = Layout::new();
+    let back = layout.add_component(Area::new([0.0, 0.0, 100.0, 100.0]));
+    let front = layout.add_component(Area::new([0.0, 0.0, 100.0, 100.0]));
+    let press = GuiEvent::MouseDown{ button: MouseButton::Left, pos: (50.0, 50.0) };
+    assert_eq!(layout.component_at((50.0, 50.0), screen), Some(front));
+
+    // The mouse goes through a component that isn't hit testable, but it still gets keyboard events
+    layout.borrow_component_as_type_mut::<Area>(front).unwrap().hit_test = false;
+    assert_eq!(layout.component_at((50.0, 50.0), screen), Some(back));
+    assert_eq!(layout.dispatch_order(&press), vec![back]);
+    assert_eq!(layout.dispatch_order(&GuiEvent::TextInput('a')), vec![front, back]);
+
+    // Hidden components get nothing at all
+    let area = layout.borrow_component_as_type_mut::<Area>(front).unwrap();
+    area.hit_test = true;
+    area.visible = false;
+    assert_eq!(layout.component_at((50.0, 50.0), screen), Some(back));
+    assert_eq!(layout.dispatch_order(&GuiEvent::TextInput('a')), vec![back]);
+
+    // Disabled components are still shown, but can't be hovered
+    let area = layout.borrow_component_as_type_mut::<Area>(front).unwrap();
+    area.visible = true;
+    area.enabled = false;
+    assert_eq!(layout.component_at((50.0, 50.0), screen), Some(back));
+    assert_eq!(layout.dispatch_order(&press), vec![front, back]);
+}
@@ -0,0 +1,12 @@
+use rusty_gui::rendering::WindowBuildError;
+
+
+/// Test that window build errors describe what went wrong
+#[test]
+fn window_build_error_test(){
+    assert_eq!(WindowBuildError::MonitorNotFound(2).to_string(), "Error, there's no monitor with index 2!");
+    assert_eq!(WindowBuildError::UnsupportedVideoMode{ size: (1920, 1080), refresh_rate: 144 }.to_string(), "Error, the monitor doesn't support 1920x1080 at 144Hz!");
+
+    let error: Box<dyn std::error::Error> = Box::new(WindowBuildError::EventLoopStarted);
+    assert!(error.source().is_none());
+}
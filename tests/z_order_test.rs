@@ -0,0 +1,59 @@
+use std::any::Any;
+
+use rusty_gui::{components::Component, layout::Layout};
+
+// A component covering a rect
+struct Panel{
+    rect: [f32; 4],
+}
+
+impl Component for Panel{
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn bounds(&self, _screen: (u32, u32)) -> Option<[f32; 4]>{
+        Some(self.rect)
+    }
+}
+
+
+/// Test that components are drawn and hit tested by z, then in the order they were added, and that
+/// `bring_to_front` and `send_to_back` move them above and below every other component
+#[test]
+fn z_order_test(){
+    let screen = (100, 100);
+    let mut layout = Layout::new();
+    let back = layout.add_component(Box::new(Panel{ rect: [0.0, 0.0, 50.0, 50.0] }));
+    let middle = layout.add_component(Box::new(Panel{ rect: [0.0, 0.0, 50.0, 50.0] }));
+    let front = layout.add_component(Box::new(Panel{ rect: [0.0, 0.0, 50.0, 50.0] }));
+    assert_eq!(layout.draw_order(), [back, middle, front]);
+    assert_eq!(layout.component_at((10.0, 10.0), screen), Some(front));
+
+    // Equal z values keep the order the components were added in
+    layout.set_z(back, 2).unwrap();
+    layout.set_z(middle, 2).unwrap();
+    assert_eq!(layout.draw_order(), [front, back, middle]);
+    assert_eq!(layout.component_at((10.0, 10.0), screen), Some(middle));
+
+    layout.bring_to_front(back).unwrap();
+    assert_eq!(layout.z(back), 3);
+    assert_eq!(layout.draw_order(), [front, middle, back]);
+    // Already on top, so nothing changes
+    layout.bring_to_front(back).unwrap();
+    assert_eq!(layout.z(back), 3);
+
+    layout.send_to_back(middle).unwrap();
+    assert_eq!(layout.z(middle), -1);
+    assert_eq!(layout.draw_order(), [middle, front, back]);
+    assert_eq!(layout.dispatch_order(&rusty_gui::event::GuiEvent::MouseEnter), [back, front, middle]);
+
+    // Removing a component keeps the others' z values
+    layout.remove_component_by_id(front);
+    assert_eq!(layout.draw_order(), [middle, back]);
+    assert!(layout.set_z(front, 0).is_err());
+}
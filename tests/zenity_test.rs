@@ -0,0 +1,24 @@
+use rusty_gui::dialog::{self, Choice, MessageButtons};
+
+
+/// Test that zenity message boxes are built with the right buttons, and that its exit status and output map to the
+/// button that was pressed (or to no choice when it couldn't show the dialog)
+#[test]
+fn zenity_test(){
+    let args = dialog::zenity_args("Save?", "Save the changes?", MessageButtons::YesNoCancel);
+    assert_eq!(&args[..5], &["--title", "Save?", "--text", "Save the changes?", "--no-markup"]);
+    assert_eq!(&args[5..], &["--question", "--ok-label", "Yes", "--cancel-label", "No", "--extra-button", "Cancel"]);
+    assert_eq!(dialog::zenity_args("", "", MessageButtons::Ok).last().map(String::as_str), Some("--info"));
+
+    let buttons = MessageButtons::YesNoCancel;
+    assert_eq!(dialog::zenity_choice(buttons, Some(0), "", ""), Some(Choice::Yes));
+    // The second button, or closing the dialog
+    assert_eq!(dialog::zenity_choice(buttons, Some(1), "", ""), Some(Choice::No));
+    assert_eq!(dialog::zenity_choice(MessageButtons::Ok, Some(1), "\n", ""), Some(Choice::Ok));
+    // The extra button prints its label
+    assert_eq!(dialog::zenity_choice(buttons, Some(1), "Cancel\n", ""), Some(Choice::Cancel));
+    // GTK couldn't start, so nothing was shown
+    assert_eq!(dialog::zenity_choice(buttons, Some(1), "", "(zenity:123): Gtk-WARNING **: cannot open display: :0\n"), None);
+    assert_eq!(dialog::zenity_choice(buttons, Some(255), "", ""), None);
+    assert_eq!(dialog::zenity_choice(buttons, None, "", ""), None);
+}